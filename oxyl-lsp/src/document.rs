@@ -0,0 +1,107 @@
+//! The documents open in the editor, and what the server knows about each of them.
+
+use crate::{line_index::LineIndex, names::Names};
+use lsp_types::{DiagnosticSeverity, Range};
+use oxylc::{
+    compile::{
+        diagnostic::{self, Diagnostic, Severity},
+        parser::{incremental::reparse, parse},
+    },
+    repr::fst::File,
+};
+
+/// A document open in the editor. Its text may be different to what's on the disk.
+#[derive(Clone, Debug)]
+pub struct Document {
+    pub text: String,
+
+    /// The version of the text, which increases with every change.
+    pub version: i32,
+
+    pub line_index: LineIndex,
+
+    /// The parsed text, or the syntax error that stopped it from being parsed.
+    pub parsed: Result<File, Diagnostic>,
+
+    /// The names in the document, if it parsed.
+    pub names: Option<Names>,
+}
+
+impl Document {
+    /// Creates a new `Document`, parsing its text.
+    pub fn new(text: String, version: i32) -> Self {
+        let parsed = parse(&text).map_err(|err| diagnostic::parse_error(&err));
+        let names = parsed.as_ref().ok().map(Names::new);
+
+        Self {
+            line_index: LineIndex::new(&text),
+            text,
+            version,
+            parsed,
+            names,
+        }
+    }
+
+    /// Applies a change from the editor. Changes without a range replace the whole text, and
+    /// changes with one are reparsed incrementally if the document parsed before.
+    pub fn change(self, range: Option<Range>, text: &str, version: i32) -> Self {
+        let Some(range) = range else {
+            return Self::new(text.to_string(), version);
+        };
+
+        let range = self.offset(range.start)..self.offset(range.end);
+        let mut edited = self.text;
+        edited.replace_range(range.clone(), text);
+
+        let parsed = match self.parsed {
+            Ok(file) => reparse(file, &edited, range, text),
+            Err(_) => parse(&edited),
+        }
+        .map_err(|err| diagnostic::parse_error(&err));
+        let names = parsed.as_ref().ok().map(Names::new);
+
+        Self {
+            line_index: LineIndex::new(&edited),
+            text: edited,
+            version,
+            parsed,
+            names,
+        }
+    }
+
+    /// Gets the LSP range of two offsets in the document.
+    pub fn range(&self, start: usize, end: usize) -> Range {
+        self.line_index.range(&self.text, start, end)
+    }
+
+    /// Gets the offset of an LSP position in the document.
+    pub fn offset(&self, position: lsp_types::Position) -> usize {
+        self.line_index.offset(&self.text, position)
+    }
+
+    /// Converts a diagnostic about this document into an LSP diagnostic. The diagnostic points at
+    /// its first label, and its notes are added to the end of its message.
+    pub fn to_lsp(&self, diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+        let range = diagnostic
+            .labels
+            .first()
+            .map_or_else(Range::default, |label| {
+                self.range(label.location.start(), label.location.end())
+            });
+        let message = std::iter::once(diagnostic.message.as_str())
+            .chain(diagnostic.notes.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        lsp_types::Diagnostic {
+            range,
+            severity: Some(match diagnostic.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+            }),
+            source: Some("oxyl".into()),
+            message,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use lsp_server::ProtocolError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    #[error("LSP protocol error: {0}")]
+    Protocol(#[from] ProtocolError),
+
+    #[error("The client disconnected")]
+    Disconnected,
+}
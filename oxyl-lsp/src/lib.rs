@@ -0,0 +1,14 @@
+//! The language server for Oxyl, which gives editors diagnostics, document symbols, hover, go to
+//! definition, find references, formatting and semantic highlighting over the Language Server
+//! Protocol. The server runs on any `lsp_server::Connection`, so it can be run over standard input
+//! and output by the `oxyl-lsp` binary, or in-process for testing.
+
+mod document;
+pub mod errors;
+mod line_index;
+mod loader;
+mod names;
+mod semantic_tokens;
+mod server;
+
+pub use server::{capabilities, run};
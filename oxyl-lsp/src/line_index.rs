@@ -0,0 +1,81 @@
+//! Converts between byte offsets, which the compiler uses, and LSP positions, which are a line
+//! and a column in UTF-16 code units.
+
+use lsp_types::{Position, Range};
+
+/// The lines of a document, for converting between offsets and positions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    /// The offset of the start of every line.
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Finds the lines in `text`.
+    pub fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    /// Gets the position of an offset in `text`. Offsets past the end are clamped to the end.
+    pub fn position(&self, text: &str, offset: usize) -> Position {
+        let offset = offset.min(text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        // NOTE: Offsets inside of a character count as the start of it.
+        let column = text[start..]
+            .char_indices()
+            .take_while(|(i, _)| start + i < offset)
+            .map(|(_, c)| c.len_utf16())
+            .sum::<usize>();
+
+        Position::new(line as u32, column as u32)
+    }
+
+    /// Gets the range of two offsets in `text`.
+    pub fn range(&self, text: &str, start: usize, end: usize) -> Range {
+        Range::new(self.position(text, start), self.position(text, end))
+    }
+
+    /// Gets the offset of a position in `text`. Positions past the end of their line are clamped
+    /// to the end of it, and lines past the end of the text to the end of the text.
+    pub fn offset(&self, text: &str, position: Position) -> usize {
+        let Some(&start) = self.line_starts.get(position.line as usize) else {
+            return text.len();
+        };
+        let line = text[start..].split('\n').next().unwrap_or("");
+
+        let mut column = 0;
+        for (i, c) in line.char_indices() {
+            if column >= position.character as usize {
+                return start + i;
+            }
+            column += c.len_utf16();
+        }
+
+        start + line.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_offsets_and_positions() {
+        let text = "let x = 1\nlet 𝔵é = 2\n";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.position(text, 0), Position::new(0, 0));
+        assert_eq!(index.position(text, 14), Position::new(1, 4));
+        assert_eq!(index.position(text, 20), Position::new(1, 7));
+        assert_eq!(index.position(text, text.len()), Position::new(2, 0));
+
+        assert_eq!(index.offset(text, Position::new(1, 4)), 14);
+        assert_eq!(index.offset(text, Position::new(1, 7)), 20);
+        assert_eq!(index.offset(text, Position::new(0, 99)), 9);
+        assert_eq!(index.offset(text, Position::new(9, 0)), text.len());
+    }
+}
@@ -0,0 +1,27 @@
+//! Loads the sources of modules for the module tree, preferring the text of open documents over
+//! what's on the disk, as the open documents may not be saved yet.
+
+use oxylc::compile::module::loader::{FsLoader, SourceLoader};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Loads open documents from memory, and everything else from the disk.
+pub struct OverlayLoader<'a> {
+    /// The text of every open document, by its path.
+    pub documents: &'a HashMap<PathBuf, String>,
+}
+
+impl SourceLoader for OverlayLoader<'_> {
+    fn load(&mut self, path: &Path) -> io::Result<(PathBuf, String)> {
+        // NOTE: New documents might not be on the disk at all, so they can't be canonicalized.
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+        match self.documents.get(&canonical) {
+            Some(text) => Ok((canonical, text.clone())),
+            None => FsLoader.load(path),
+        }
+    }
+}
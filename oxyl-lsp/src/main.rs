@@ -0,0 +1,20 @@
+use lsp_server::Connection;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+
+    let result = oxyl_lsp::run(connection);
+    let joined = io_threads.join();
+
+    match result
+        .map_err(|err| err.to_string())
+        .and_then(|()| joined.map_err(|err| err.to_string()))
+    {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
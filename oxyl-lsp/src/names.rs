@@ -0,0 +1,237 @@
+//! Finds what every name in a file refers to, for go to definition, find references and hover.
+//! This only knows about the names defined in the file itself: the top-level statements, the
+//! `let`s in blocks and the arguments of functions. The names are found by the compiler's own name
+//! resolution, on the AST lowered from the file.
+
+use oxylc::{
+    compile::{
+        infer::infer,
+        lower::lower,
+        resolve::{resolve, used_name},
+    },
+    repr::{
+        ast::{
+            self,
+            expr::{ExprId, ExprKind},
+            scope::DefKind,
+            stmt::StmtKind,
+        },
+        fst::{
+            statement::Statement,
+            visitor::{walk_statement, FstVisitor},
+            File,
+        },
+    },
+};
+use std::{collections::HashMap, ops::Range};
+
+/// Something a name was defined by.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DefinitionKind {
+    Let,
+    Argument,
+
+    /// A name bound by the pattern of an arm of a `match`.
+    Binding,
+    Mod,
+    Use,
+    Constructor,
+}
+
+/// Where a name was defined.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Definition {
+    pub name: String,
+    pub kind: DefinitionKind,
+
+    /// The location of the name itself.
+    pub location: Range<usize>,
+
+    /// The doc comments of the statement that defined the name, if it was one.
+    pub docs: Vec<String>,
+
+    /// Whether the definition is `pub`.
+    pub is_public: bool,
+
+    /// The arguments of the function the name was defined as, if it was one.
+    pub args: Option<Vec<String>>,
+
+    /// The type of the name, if it's a value.
+    pub ty: Option<String>,
+}
+
+/// Every definition and reference in a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Names {
+    pub definitions: Vec<Definition>,
+
+    /// The location of every reference, and the definition it refers to, if it could be found.
+    pub references: Vec<(Range<usize>, Option<usize>)>,
+}
+
+impl Names {
+    /// Finds the names in a file.
+    pub fn new(file: &File) -> Self {
+        let (ast, _) = lower(file);
+        let (mut resolution, _) = resolve(&ast);
+        let (types, _) = infer(&ast, &mut resolution);
+
+        let mut docs = Docs::default();
+        docs.visit_file(file);
+
+        // NOTE: Only the names written in the file are kept, so the prelude and packages aren't
+        // definitions here, and what uses them doesn't refer to anything.
+        let mut indices = HashMap::new();
+        let definitions = resolution
+            .defs
+            .iter()
+            .filter_map(|(id, def)| {
+                let (kind, statement) = match def.kind {
+                    DefKind::Let(id) => (DefinitionKind::Let, Some(&ast[id])),
+                    DefKind::Mod(id) => (DefinitionKind::Mod, Some(&ast[id])),
+                    DefKind::Import(id) => (DefinitionKind::Use, Some(&ast[id])),
+                    DefKind::Param(_) => (DefinitionKind::Argument, None),
+                    DefKind::Binding(..) => (DefinitionKind::Binding, None),
+                    DefKind::Constructor(id, _) => (DefinitionKind::Constructor, Some(&ast[id])),
+                    DefKind::Extern | DefKind::Builtin => return None,
+                };
+                indices.insert(id, indices.len());
+                let args = statement.and_then(|statement| match statement.kind {
+                    StmtKind::Let { value, .. } => params(&ast, value),
+                    _ => None,
+                });
+
+                Some(Definition {
+                    name: def.name.ident.to_string(),
+                    kind,
+                    location: def.name.location.start()..def.name.location.end(),
+                    docs: statement
+                        .and_then(|statement| {
+                            docs.0
+                                .get(&(statement.location.start()..statement.location.end()))
+                        })
+                        .cloned()
+                        .unwrap_or_default(),
+                    is_public: statement.is_some_and(|statement| statement.is_public),
+                    args,
+                    ty: types.of_def(id).map(|scheme| scheme.to_string()),
+                })
+            })
+            .collect();
+
+        let references = ast
+            .exprs
+            .iter()
+            .filter_map(|(id, _)| {
+                let name = used_name(&ast, id)?;
+                let definition = resolution
+                    .def_of(id)
+                    .and_then(|def| indices.get(&def).copied());
+                Some((name.location.start()..name.location.end(), definition))
+            })
+            .collect();
+
+        Self {
+            definitions,
+            references,
+        }
+    }
+
+    /// Gets the definition of the name at `offset`, whether it's the definition itself or a
+    /// reference to it.
+    pub fn definition_at(&self, offset: usize) -> Option<usize> {
+        let defined = self
+            .definitions
+            .iter()
+            .position(|definition| contains(&definition.location, offset));
+
+        defined.or_else(|| {
+            self.references
+                .iter()
+                .find(|(location, _)| contains(location, offset))
+                .and_then(|(_, definition)| *definition)
+        })
+    }
+
+    /// Gets the locations of every reference to a definition.
+    pub fn references_to(&self, definition: usize) -> impl Iterator<Item = &Range<usize>> {
+        self.references
+            .iter()
+            .filter(move |(_, to)| *to == Some(definition))
+            .map(|(location, _)| location)
+    }
+}
+
+/// Whether `offset` is in `range`, including its end, so that a cursor right after a name counts.
+fn contains(range: &Range<usize>, offset: usize) -> bool {
+    range.start <= offset && offset <= range.end
+}
+
+/// Gets the names of the parameters of the function `expr` is, if it is one. Functions with more
+/// than one parameter are lowered to a function for each, so this follows them all.
+fn params(ast: &ast::File, mut expr: ExprId) -> Option<Vec<String>> {
+    let ExprKind::Lambda { .. } = ast[expr].kind else {
+        return None;
+    };
+
+    let mut params = vec![];
+    while let ExprKind::Lambda {
+        param: Some(param),
+        body,
+    } = ast[expr].kind
+    {
+        params.push(param.ident.to_string());
+        expr = body;
+    }
+    Some(params)
+}
+
+/// The doc comments of every statement, by the location of the statement. The AST doesn't keep
+/// doc comments, but its statements have the same locations as the ones in the FST.
+#[derive(Default)]
+struct Docs(HashMap<Range<usize>, Vec<String>>);
+
+impl FstVisitor<()> for Docs {
+    fn visit_statement(&mut self, statement: &Statement) {
+        if !statement.docs.is_empty() {
+            let docs = statement.docs.iter().map(|doc| doc.content.clone());
+            let location = statement.location.start()..statement.location.end();
+            self.0.insert(location, docs.collect());
+        }
+        walk_statement(self, statement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxylc::compile::parser::parse;
+
+    #[test]
+    fn can_resolve_names() {
+        let source = "let f = |x| { let y = x\ny + g }\nlet g = f\n";
+        let names = Names::new(&parse(source).unwrap());
+        let name = |id: Option<usize>| id.map(|id| names.definitions[id].name.as_str());
+
+        // NOTE: `x` in the block is the argument, `g` is defined after `f` but still in scope.
+        assert_eq!(name(names.definition_at(22)), Some("x"));
+        assert_eq!(
+            names.definitions[names.definition_at(22).unwrap()].kind,
+            DefinitionKind::Argument
+        );
+        assert_eq!(name(names.definition_at(28)), Some("g"));
+        assert_eq!(name(names.definition_at(24)), Some("y"));
+        assert_eq!(names.definitions[0].args, Some(vec!["x".to_string()]));
+        assert_eq!(names.definitions[0].ty.as_deref(), Some("Add a => a -> a"));
+
+        let f = names.definition_at(4).unwrap();
+        assert_eq!(names.references_to(f).collect::<Vec<_>>(), [&(40..41)]);
+    }
+
+    #[test]
+    fn cant_resolve_unknown_names() {
+        let names = Names::new(&parse("let x = { let y = y\n1 } + z\n").unwrap());
+        assert_eq!(names.definition_at(18), None);
+        assert_eq!(names.definition_at(26), None);
+    }
+}
@@ -0,0 +1,172 @@
+//! Semantic tokens, which editors use to highlight code. The tokens come from the lexer, so they
+//! work even when the document doesn't parse, and identifiers are refined with the names in the
+//! document when it does.
+
+use crate::{
+    document::Document,
+    names::{Definition, DefinitionKind},
+};
+use lsp_types::{SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend};
+use oxylc::compile::lexer::{lex, token::TokenKind};
+use std::collections::HashMap;
+
+/// The token types the server uses, where a token's type is its index in this list.
+const TYPES: [SemanticTokenType; 10] = [
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::ENUM_MEMBER,
+];
+
+/// The token modifiers the server uses, where a modifier is the bit of its index in this list.
+const MODIFIERS: [SemanticTokenModifier; 2] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::DOCUMENTATION,
+];
+
+/// Gets the token types and modifiers, to tell the editor when it connects.
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TYPES.to_vec(),
+        token_modifiers: MODIFIERS.to_vec(),
+    }
+}
+
+/// Gets the semantic tokens of a document, in the relative format of the LSP.
+pub fn semantic_tokens(document: &Document) -> Vec<SemanticToken> {
+    let identifiers = identifiers(document);
+    let mut tokens = vec![];
+    let mut previous = (0, 0);
+
+    for token in lex(&document.text) {
+        let (token_type, modifiers) = match token.kind {
+            TokenKind::Keyword => (SemanticTokenType::KEYWORD, 0),
+            TokenKind::Identifier => identifiers
+                .get(&token.location.start())
+                .cloned()
+                .unwrap_or((SemanticTokenType::VARIABLE, 0)),
+            TokenKind::Number => (SemanticTokenType::NUMBER, 0),
+            TokenKind::String => (SemanticTokenType::STRING, 0),
+            TokenKind::Operator => (SemanticTokenType::OPERATOR, 0),
+            TokenKind::Comment => (SemanticTokenType::COMMENT, 0),
+            TokenKind::DocComment => (SemanticTokenType::COMMENT, modifier_bit(1)),
+            TokenKind::Punctuation
+            | TokenKind::Whitespace
+            | TokenKind::Newline
+            | TokenKind::Error => continue,
+        };
+        let token_type = TYPES
+            .iter()
+            .position(|known| *known == token_type)
+            .expect("every token type is in the legend") as u32;
+
+        // NOTE: Not every editor supports tokens over multiple lines, like block comments, so
+        // they're split into one token per line.
+        let mut start = token.location.start();
+        for line in token.text(&document.text).split_inclusive('\n') {
+            let text = line.trim_end_matches(['\r', '\n']);
+            let position = document.line_index.position(&document.text, start);
+            start += line.len();
+            if text.is_empty() {
+                continue;
+            }
+
+            let delta_line = position.line - previous.0;
+            let delta_start = match delta_line {
+                0 => position.character - previous.1,
+                _ => position.character,
+            };
+            tokens.push(SemanticToken {
+                delta_line,
+                delta_start,
+                length: text.encode_utf16().count() as u32,
+                token_type,
+                token_modifiers_bitset: modifiers,
+            });
+            previous = (position.line, position.character);
+        }
+    }
+
+    tokens
+}
+
+/// Gets the token type and modifiers of every identifier with a known definition, by its start.
+fn identifiers(document: &Document) -> HashMap<usize, (SemanticTokenType, u32)> {
+    let Some(names) = &document.names else {
+        return HashMap::new();
+    };
+
+    let definitions = names.definitions.iter().map(|definition| {
+        (
+            definition.location.start,
+            (classify(definition), modifier_bit(0)),
+        )
+    });
+    let references = names.references.iter().filter_map(|(location, id)| {
+        let definition = &names.definitions[(*id)?];
+        Some((location.start, (classify(definition), 0)))
+    });
+
+    definitions.chain(references).collect()
+}
+
+fn classify(definition: &Definition) -> SemanticTokenType {
+    match definition.kind {
+        DefinitionKind::Argument => SemanticTokenType::PARAMETER,
+        DefinitionKind::Mod => SemanticTokenType::NAMESPACE,
+        DefinitionKind::Constructor => SemanticTokenType::ENUM_MEMBER,
+        DefinitionKind::Let if definition.args.is_some() => SemanticTokenType::FUNCTION,
+        DefinitionKind::Let | DefinitionKind::Use | DefinitionKind::Binding => {
+            SemanticTokenType::VARIABLE
+        }
+    }
+}
+
+fn modifier_bit(index: usize) -> u32 {
+    1 << index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_get_semantic_tokens() {
+        use SemanticTokenType as T;
+
+        let document = Document::new("/** a\n b */\nlet f = |x| x + 1\n".into(), 1);
+        let tokens = semantic_tokens(&document)
+            .iter()
+            .map(|token| {
+                (
+                    token.delta_line,
+                    token.delta_start,
+                    token.length,
+                    TYPES[token.token_type as usize].clone(),
+                    token.token_modifiers_bitset,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            [
+                (0, 0, 5, T::COMMENT, 2),
+                (1, 0, 5, T::COMMENT, 2),
+                (1, 0, 3, T::KEYWORD, 0),
+                (0, 4, 1, T::FUNCTION, 1),
+                (0, 2, 1, T::OPERATOR, 0),
+                (0, 3, 1, T::PARAMETER, 1),
+                (0, 3, 1, T::PARAMETER, 0),
+                (0, 2, 1, T::OPERATOR, 0),
+                (0, 2, 1, T::NUMBER, 0),
+            ]
+        );
+    }
+}
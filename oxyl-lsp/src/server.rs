@@ -0,0 +1,464 @@
+//! The server itself, which answers the editor's requests and keeps track of its documents.
+
+use crate::{
+    document::Document,
+    errors::ServerError,
+    loader::OverlayLoader,
+    names::{Definition, DefinitionKind},
+    semantic_tokens::{legend, semantic_tokens},
+};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification, PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, Formatting, GotoDefinition, HoverRequest, RangeFormatting,
+        References, Request as LspRequest, SemanticTokensFullRequest,
+    },
+    DocumentFormattingParams, DocumentRangeFormattingParams, DocumentSymbol, DocumentSymbolParams,
+    DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf,
+    PublishDiagnosticsParams, ReferenceParams, SemanticTokens, SemanticTokensFullOptions,
+    SemanticTokensOptions, SemanticTokensParams, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, SymbolKind, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url,
+};
+use oxylc::{
+    compile::{
+        diagnostic::Diagnostic,
+        exhaustive,
+        formatter::{self, FormatConfig},
+        infer::infer,
+        lower::lower,
+        module::{errors::ModuleError, ModuleTree},
+        project::Project,
+        resolve::resolve_with_externs,
+    },
+    repr::{
+        ast::ident::Ident,
+        fst::{
+            expression::ExpressionKind,
+            statement::StatementKind,
+            value::{Value, ValueKind},
+        },
+    },
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+/// Gets what the server can do, to tell the editor when it connects.
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        // NOTE: Documents are small enough that the whole text is sent on every change.
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::INCREMENTAL,
+        )),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: legend(),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+/// Runs the server on a connection until the editor shuts it down.
+pub fn run(connection: Connection) -> Result<(), ServerError> {
+    let capabilities =
+        serde_json::to_value(capabilities()).expect("capabilities can always be serialized");
+    connection.initialize(capabilities)?;
+
+    Server {
+        connection,
+        documents: HashMap::new(),
+    }
+    .main_loop()
+}
+
+/// The state of the server.
+struct Server {
+    connection: Connection,
+
+    /// The documents open in the editor.
+    documents: HashMap<Url, Document>,
+}
+
+impl Server {
+    fn main_loop(&mut self) -> Result<(), ServerError> {
+        let receiver = self.connection.receiver.clone();
+
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.request(request);
+                    self.send(response.into())?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        // NOTE: The editor should always shut the server down before disconnecting.
+        Err(ServerError::Disconnected)
+    }
+
+    fn send(&self, message: Message) -> Result<(), ServerError> {
+        self.connection
+            .sender
+            .send(message)
+            .map_err(|_| ServerError::Disconnected)
+    }
+
+    fn request(&mut self, request: Request) -> Response {
+        match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => {
+                self.handle::<DocumentSymbolRequest>(request, Self::document_symbols)
+            }
+            HoverRequest::METHOD => self.handle::<HoverRequest>(request, Self::hover),
+            GotoDefinition::METHOD => self.handle::<GotoDefinition>(request, Self::definition),
+            References::METHOD => self.handle::<References>(request, Self::references),
+            Formatting::METHOD => self.handle::<Formatting>(request, Self::format),
+            RangeFormatting::METHOD => self.handle::<RangeFormatting>(request, Self::format_range),
+            SemanticTokensFullRequest::METHOD => {
+                self.handle::<SemanticTokensFullRequest>(request, Self::semantic_tokens)
+            }
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unknown method `{}`", request.method),
+            ),
+        }
+    }
+
+    /// Answers a request with `handler`, if its parameters are valid.
+    fn handle<R>(&self, request: Request, handler: fn(&Self, R::Params) -> R::Result) -> Response
+    where
+        R: LspRequest,
+        R::Params: DeserializeOwned,
+        R::Result: Serialize,
+    {
+        match serde_json::from_value(request.params) {
+            Ok(params) => Response::new_ok(request.id, handler(self, params)),
+            Err(err) => {
+                Response::new_err(request.id, ErrorCode::InvalidParams as i32, err.to_string())
+            }
+        }
+    }
+
+    fn notification(&mut self, notification: Notification) -> Result<(), ServerError> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                if let Ok(params) = notification
+                    .extract::<lsp_types::DidOpenTextDocumentParams>(DidOpenTextDocument::METHOD)
+                {
+                    let document = params.text_document;
+                    self.documents
+                        .insert(document.uri, Document::new(document.text, document.version));
+                    self.publish_all()?;
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                if let Ok(params) = notification.extract::<lsp_types::DidChangeTextDocumentParams>(
+                    DidChangeTextDocument::METHOD,
+                ) {
+                    let uri = params.text_document.uri;
+                    let version = params.text_document.version;
+                    // NOTE: Changes are applied in order, each to the text left by the last one.
+                    if let Some(mut document) = self.documents.remove(&uri) {
+                        for change in params.content_changes {
+                            document = document.change(change.range, &change.text, version);
+                        }
+                        self.documents.insert(uri, document);
+                        self.publish_all()?;
+                    }
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                if let Ok(params) = notification
+                    .extract::<lsp_types::DidCloseTextDocumentParams>(DidCloseTextDocument::METHOD)
+                {
+                    let uri = params.text_document.uri;
+                    self.documents.remove(&uri);
+                    self.publish(uri, vec![], None)?;
+                    self.publish_all()?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Publishes the diagnostics of every open document. A change to one document can change the
+    /// diagnostics of others, like by removing something another one uses.
+    fn publish_all(&self) -> Result<(), ServerError> {
+        for (uri, document) in &self.documents {
+            let diagnostics = self
+                .diagnostics(uri, document)
+                .iter()
+                .map(|diagnostic| document.to_lsp(diagnostic))
+                .collect();
+            self.publish(uri.clone(), diagnostics, Some(document.version))?;
+        }
+
+        Ok(())
+    }
+
+    fn publish(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+        version: Option<i32>,
+    ) -> Result<(), ServerError> {
+        let params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version,
+        };
+        self.send(Notification::new(PublishDiagnostics::METHOD.into(), params).into())
+    }
+
+    /// Gets the diagnostics of a document: its syntax error if it doesn't parse, and otherwise the
+    /// errors in it from loading the module tree it's part of, and from checking its names and
+    /// types.
+    fn diagnostics(&self, uri: &Url, document: &Document) -> Vec<Diagnostic> {
+        if let Err(diagnostic) = &document.parsed {
+            return vec![diagnostic.clone()];
+        }
+        let Ok(path) = uri.to_file_path() else {
+            return vec![];
+        };
+
+        let open = self
+            .documents
+            .iter()
+            .filter_map(|(uri, document)| {
+                let path = uri.to_file_path().ok()?;
+                let path = fs::canonicalize(&path).unwrap_or(path);
+                Some((path, document.text.clone()))
+            })
+            .collect::<HashMap<PathBuf, String>>();
+        let mut loader = OverlayLoader { documents: &open };
+
+        // NOTE: Files in a package are loaded as part of the whole package, so that `crate` and
+        // its dependencies resolve, while other files are loaded on their own.
+        let project = path
+            .parent()
+            .ok_or(())
+            .and_then(|dir| Project::find(dir).map_err(|_| ()))
+            .and_then(|dir| Project::open(&dir).map_err(|_| ()));
+        let (tree, errors) = match project {
+            Ok(project) => project.modules(&mut loader),
+            Err(()) => ModuleTree::load(&path, &mut loader),
+        };
+
+        let canonical = fs::canonicalize(&path).unwrap_or(path);
+        let is_this =
+            |file: &PathBuf| fs::canonicalize(file).unwrap_or_else(|_| file.clone()) == canonical;
+        let mut diagnostics = errors
+            .iter()
+            // NOTE: Syntax errors in other files are shown in those files when they're open.
+            .filter(|err| !matches!(err, ModuleError::Parse { .. }))
+            .map(Diagnostic::from)
+            .filter(|diagnostic| diagnostic.file.as_ref().is_some_and(is_this))
+            .collect::<Vec<_>>();
+
+        // NOTE: Names are resolved here rather than with the document's other names, as the
+        // packages a module can use are only known from the module tree.
+        if let Some((_, module)) = tree.modules().find(|(_, module)| is_this(&module.path)) {
+            let (ast, errors) = lower(&module.file);
+            let externs = module
+                .externs
+                .keys()
+                .map(|name| Ident::new(name))
+                .collect::<Vec<_>>();
+            let (mut resolution, unresolved) = resolve_with_externs(&ast, &externs);
+            let (_, type_errors) = infer(&ast, &mut resolution);
+            let pattern_errors = exhaustive::check(&ast, &resolution);
+
+            diagnostics.extend(errors.iter().map(Diagnostic::from));
+            diagnostics.extend(unresolved.iter().map(Diagnostic::from));
+            diagnostics.extend(type_errors.iter().map(Diagnostic::from));
+            diagnostics.extend(pattern_errors.iter().map(Diagnostic::from));
+        }
+        diagnostics
+    }
+
+    /// Gets the document and the definition of the name at a position in it, if there is one.
+    fn definition_at(
+        &self,
+        uri: &Url,
+        position: lsp_types::Position,
+    ) -> Option<(&Document, usize, &Definition)> {
+        let document = self.documents.get(uri)?;
+        let names = document.names.as_ref()?;
+        let id = names.definition_at(document.offset(position))?;
+        Some((document, id, &names.definitions[id]))
+    }
+
+    fn document_symbols(&self, params: DocumentSymbolParams) -> Option<DocumentSymbolResponse> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let file = document.parsed.as_ref().ok()?;
+
+        let symbols = file
+            .statements
+            .iter()
+            .filter_map(|statement| {
+                let (ident, kind) = match &statement.kind {
+                    StatementKind::Let {
+                        ident, expression, ..
+                    } => match &expression.kind {
+                        ExpressionKind::Value(Value {
+                            kind: ValueKind::Function(_),
+                            ..
+                        }) => (ident, SymbolKind::FUNCTION),
+                        _ => (ident, SymbolKind::VARIABLE),
+                    },
+                    StatementKind::Mod { ident } => (ident, SymbolKind::MODULE),
+                    StatementKind::Type { ident, .. } => (ident, SymbolKind::ENUM),
+                    StatementKind::Use { .. } | StatementKind::Impl { .. } => return None,
+                };
+
+                #[allow(deprecated)]
+                Some(DocumentSymbol {
+                    name: ident.name.to_string(),
+                    detail: None,
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    range: document.range(statement.location.start(), statement.location.end()),
+                    selection_range: document.range(ident.location.start(), ident.location.end()),
+                    children: None,
+                })
+            })
+            .collect();
+
+        Some(DocumentSymbolResponse::Nested(symbols))
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let (_, _, definition) =
+            self.definition_at(&position.text_document.uri, position.position)?;
+
+        let name = match &definition.ty {
+            Some(ty) => format!("{}: {}", definition.name, ty),
+            None => definition.name.clone(),
+        };
+        let signature = match &definition.args {
+            Some(args) => format!("let {} = |{}|", name, args.join(", ")),
+            None => match definition.kind {
+                DefinitionKind::Let => format!("let {}", name),
+                DefinitionKind::Argument
+                | DefinitionKind::Binding
+                | DefinitionKind::Constructor => name,
+                DefinitionKind::Mod => format!("mod {}", definition.name),
+                DefinitionKind::Use => format!("use {}", definition.name),
+            },
+        };
+        let signature = match definition.is_public {
+            true => format!("pub {}", signature),
+            false => signature,
+        };
+        let mut value = format!("```oxyl\n{}\n```", signature);
+        if !definition.docs.is_empty() {
+            let docs = definition
+                .docs
+                .iter()
+                .map(|doc| doc.trim())
+                .collect::<Vec<_>>()
+                .join("\n");
+            value = format!("{}\n\n{}", value, docs);
+        }
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params;
+        let uri = position.text_document.uri;
+        let (document, _, definition) = self.definition_at(&uri, position.position)?;
+        let range = document.range(definition.location.start, definition.location.end);
+
+        Some(GotoDefinitionResponse::Scalar(Location::new(uri, range)))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let position = params.text_document_position;
+        let uri = position.text_document.uri;
+        let (document, id, definition) = self.definition_at(&uri, position.position)?;
+        let names = document.names.as_ref()?;
+
+        let declaration = params
+            .context
+            .include_declaration
+            .then_some(&definition.location);
+        let locations = declaration
+            .into_iter()
+            .chain(names.references_to(id))
+            .map(|location| {
+                Location::new(uri.clone(), document.range(location.start, location.end))
+            })
+            .collect();
+
+        Some(locations)
+    }
+
+    fn format(&self, params: DocumentFormattingParams) -> Option<Vec<TextEdit>> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let config = FormatConfig::default().with_indent(params.options.tab_size as usize);
+        let formatted = formatter::format(&document.text, &config).ok()?;
+
+        if formatted == document.text {
+            return Some(vec![]);
+        }
+
+        Some(vec![TextEdit::new(
+            document.range(0, document.text.len()),
+            formatted,
+        )])
+    }
+
+    fn format_range(&self, params: DocumentRangeFormattingParams) -> Option<Vec<TextEdit>> {
+        let document = self.documents.get(&params.text_document.uri)?;
+        let config = FormatConfig::default().with_indent(params.options.tab_size as usize);
+        let range = document.offset(params.range.start)..document.offset(params.range.end);
+
+        let edit = formatter::format_range(&document.text, range, &config).ok()?;
+        let edits = edit
+            .filter(|edit| document.text[edit.range.clone()] != edit.text)
+            .map(|edit| TextEdit::new(document.range(edit.range.start, edit.range.end), edit.text))
+            .into_iter()
+            .collect();
+
+        Some(edits)
+    }
+
+    fn semantic_tokens(&self, params: SemanticTokensParams) -> Option<SemanticTokensResult> {
+        let document = self.documents.get(&params.text_document.uri)?;
+
+        Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data: semantic_tokens(document),
+        }))
+    }
+}
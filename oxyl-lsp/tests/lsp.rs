@@ -0,0 +1,388 @@
+//! Runs the server in-process, and talks to it like an editor would.
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, Formatting, GotoDefinition, HoverRequest, Initialize,
+        RangeFormatting, References, Shutdown,
+    },
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+    DocumentRangeFormattingParams, DocumentSymbolParams, DocumentSymbolResponse, FormattingOptions,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    InitializeParams, Location, Position, PublishDiagnosticsParams, Range, ReferenceContext,
+    ReferenceParams, SymbolKind, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+    TextDocumentItem, TextDocumentPositionParams, TextEdit, Url, VersionedTextDocumentIdentifier,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{fs, path::Path, thread, time::Duration};
+
+/// How long to wait for the server before giving up.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An editor connected to a server running on another thread.
+struct Client {
+    connection: Connection,
+    server: Option<thread::JoinHandle<Result<(), oxyl_lsp::errors::ServerError>>>,
+    next_id: i32,
+}
+
+impl Client {
+    /// Starts a server and initializes it.
+    fn new() -> Self {
+        let (client, server) = Connection::memory();
+        let server = thread::spawn(move || oxyl_lsp::run(server));
+        let mut client = Self {
+            connection: client,
+            server: Some(server),
+            next_id: 0,
+        };
+
+        client.request::<Initialize>(InitializeParams::default());
+        client.notify::<Initialized>(lsp_types::InitializedParams {});
+        client
+    }
+
+    fn send(&self, message: impl Into<Message>) {
+        self.connection.sender.send(message.into()).unwrap();
+    }
+
+    fn receive(&self) -> Message {
+        self.connection
+            .receiver
+            .recv_timeout(TIMEOUT)
+            .expect("the server didn't respond")
+    }
+
+    /// Sends a request, and waits for the response to it.
+    fn request<R>(&mut self, params: R::Params) -> R::Result
+    where
+        R: lsp_types::request::Request,
+        R::Params: Serialize,
+        R::Result: DeserializeOwned,
+    {
+        let response = self.request_raw(R::METHOD, serde_json::to_value(params).unwrap());
+        match response.error {
+            Some(err) => panic!("{} failed: {:?}", R::METHOD, err),
+            None => serde_json::from_value(response.result.unwrap_or_default()).unwrap(),
+        }
+    }
+
+    /// Sends a request with any method and parameters, and waits for the response to it.
+    fn request_raw(&mut self, method: &str, params: serde_json::Value) -> Response {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.send(Request::new(id.clone(), method.into(), params));
+
+        loop {
+            match self.receive() {
+                Message::Response(response) if response.id == id => return response,
+                _ => {}
+            }
+        }
+    }
+
+    fn notify<N>(&self, params: N::Params)
+    where
+        N: lsp_types::notification::Notification,
+        N::Params: Serialize,
+    {
+        self.send(Notification::new(N::METHOD.into(), params));
+    }
+
+    /// Waits for the diagnostics of a document to be published.
+    fn diagnostics(&self, uri: &Url) -> Vec<lsp_types::Diagnostic> {
+        loop {
+            if let Message::Notification(notification) = self.receive() {
+                if notification.method != PublishDiagnostics::METHOD {
+                    continue;
+                }
+                let params: PublishDiagnosticsParams =
+                    serde_json::from_value(notification.params).unwrap();
+                if &params.uri == uri {
+                    return params.diagnostics;
+                }
+            }
+        }
+    }
+
+    /// Opens a document, and waits for its diagnostics.
+    fn open(&self, uri: &Url, text: &str) -> Vec<lsp_types::Diagnostic> {
+        self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri.clone(), "oxyl".into(), 1, text.into()),
+        });
+        self.diagnostics(uri)
+    }
+
+    /// Shuts the server down, and checks that it stopped cleanly.
+    fn shutdown(mut self) {
+        self.request::<Shutdown>(());
+        self.notify::<Exit>(());
+        let server = self.server.take().unwrap();
+        server.join().unwrap().unwrap();
+    }
+}
+
+fn uri(dir: &Path, name: &str) -> Url {
+    Url::from_file_path(dir.join(name)).unwrap()
+}
+
+fn position(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+    TextDocumentPositionParams::new(
+        TextDocumentIdentifier::new(uri.clone()),
+        Position::new(line, character),
+    )
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+    Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+}
+
+const SOURCE: &str = "/// Adds one.\npub let inc = |x| x + 1\nlet y = inc\n";
+
+#[test]
+fn can_publish_syntax_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let mut client = Client::new();
+
+    let diagnostics = client.open(&uri, "let = 1\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range.start, Position::new(0, 4));
+    assert!(diagnostics[0].message.starts_with("Syntax error"));
+
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "let x = 1\n".into(),
+        }],
+    });
+    assert_eq!(client.diagnostics(&uri), vec![]);
+
+    let response = client.request_raw("oxyl/unknown", serde_json::Value::Null);
+    assert_eq!(
+        response.error.unwrap().code,
+        lsp_server::ErrorCode::MethodNotFound as i32
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn can_apply_incremental_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let mut client = Client::new();
+    client.open(&uri, SOURCE);
+
+    let change = |range, text: &str| TextDocumentContentChangeEvent {
+        range: Some(range),
+        range_length: None,
+        text: text.into(),
+    };
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 2),
+        content_changes: vec![
+            change(range((1, 22), (1, 23)), "10"),
+            change(range((2, 4), (2, 5)), "total"),
+        ],
+    });
+    assert_eq!(client.diagnostics(&uri), vec![]);
+
+    let Some(DocumentSymbolResponse::Nested(symbols)) =
+        client.request::<DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: TextDocumentIdentifier::new(uri.clone()),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    else {
+        panic!("expected nested document symbols");
+    };
+    assert_eq!(symbols[1].name, "total");
+    assert_eq!(symbols[1].selection_range, range((2, 4), (2, 9)));
+
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 3),
+        content_changes: vec![change(range((2, 15), (2, 15)), " +")],
+    });
+    let diagnostics = client.diagnostics(&uri);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.starts_with("Syntax error"));
+
+    client.shutdown();
+}
+
+#[test]
+fn can_publish_module_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("main.oxyl"), "").unwrap();
+    fs::write(dir.path().join("a.oxyl"), "let x = 1\n").unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let client = Client::new();
+
+    // NOTE: The open text is used rather than what's on the disk.
+    let diagnostics = client.open(&uri, "mod a\nuse a::y\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range, range((1, 7), (1, 8)));
+    assert_eq!(diagnostics[0].message, "Couldn't find `y` in `crate::a`");
+
+    client.shutdown();
+}
+
+#[test]
+fn can_publish_unresolved_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let client = Client::new();
+
+    let diagnostics = client.open(&uri, "let count = 1\nlet x = cuont\n");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range, range((1, 8), (1, 13)));
+    assert_eq!(
+        diagnostics[0].message,
+        "Cannot find `cuont` in this scope\nDid you mean `count`?"
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn can_list_document_symbols() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let mut client = Client::new();
+    client.open(&uri, SOURCE);
+
+    let Some(DocumentSymbolResponse::Nested(symbols)) =
+        client.request::<DocumentSymbolRequest>(DocumentSymbolParams {
+            text_document: TextDocumentIdentifier::new(uri.clone()),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    else {
+        panic!("expected nested document symbols");
+    };
+
+    let symbols = symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.selection_range))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        symbols,
+        [
+            ("inc", SymbolKind::FUNCTION, range((1, 8), (1, 11))),
+            ("y", SymbolKind::VARIABLE, range((2, 4), (2, 5))),
+        ]
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn can_hover_and_find_names() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let mut client = Client::new();
+    client.open(&uri, SOURCE);
+
+    let hover = client.request::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&uri, 2, 9),
+        work_done_progress_params: Default::default(),
+    });
+    let Some(Hover {
+        contents: HoverContents::Markup(markup),
+        ..
+    }) = hover
+    else {
+        panic!("expected markdown hover, got {:?}", hover);
+    };
+    assert_eq!(
+        markup.value,
+        "```oxyl\npub let inc: I64 -> I64 = |x|\n```\n\nAdds one."
+    );
+
+    let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+        text_document_position_params: position(&uri, 2, 9),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    assert_eq!(
+        definition,
+        Some(GotoDefinitionResponse::Scalar(Location::new(
+            uri.clone(),
+            range((1, 8), (1, 11))
+        )))
+    );
+
+    let references = client.request::<References>(ReferenceParams {
+        text_document_position: position(&uri, 1, 9),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: ReferenceContext {
+            include_declaration: true,
+        },
+    });
+    assert_eq!(
+        references,
+        Some(vec![
+            Location::new(uri.clone(), range((1, 8), (1, 11))),
+            Location::new(uri.clone(), range((2, 8), (2, 11))),
+        ])
+    );
+
+    // NOTE: Nothing is defined at the `+`.
+    let hover = client.request::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&uri, 1, 20),
+        work_done_progress_params: Default::default(),
+    });
+    assert_eq!(hover, None);
+
+    client.shutdown();
+}
+
+#[test]
+fn can_format_documents() {
+    let dir = tempfile::tempdir().unwrap();
+    let uri = uri(dir.path(), "main.oxyl");
+    let mut client = Client::new();
+    client.open(&uri, "let a=1\nlet  b=|x|{x}\n");
+    let options = FormattingOptions {
+        tab_size: 2,
+        insert_spaces: true,
+        ..Default::default()
+    };
+
+    let edits = client.request::<Formatting>(DocumentFormattingParams {
+        text_document: TextDocumentIdentifier::new(uri.clone()),
+        options: options.clone(),
+        work_done_progress_params: Default::default(),
+    });
+    assert_eq!(
+        edits,
+        Some(vec![TextEdit::new(
+            range((0, 0), (2, 0)),
+            "let a = 1\nlet b = |x| { x }\n".into()
+        )])
+    );
+
+    let edits = client.request::<RangeFormatting>(DocumentRangeFormattingParams {
+        text_document: TextDocumentIdentifier::new(uri.clone()),
+        range: range((1, 2), (1, 3)),
+        options,
+        work_done_progress_params: Default::default(),
+    });
+    assert_eq!(
+        edits,
+        Some(vec![TextEdit::new(
+            range((1, 0), (1, 13)),
+            "let b = |x| { x }".into()
+        )])
+    );
+
+    client.shutdown();
+}
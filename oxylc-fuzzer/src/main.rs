@@ -1,10 +1,13 @@
 use afl::fuzz;
-use oxylc::compile::parser;
+use oxylc::compile::{parser, printer};
 
 fn main() {
     fuzz!(|data: &[u8]| {
         if let Ok(s) = std::str::from_utf8(data) {
-            let _ = parser::parse(s);
+            // NOTE: The FST is lossless, so anything that parses must print back out the same.
+            if let Ok(file) = parser::parse(s) {
+                assert_eq!(printer::print(&file), s);
+            }
         }
     });
 }
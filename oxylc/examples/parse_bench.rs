@@ -0,0 +1,124 @@
+//! Measures how long parsing a large generated file takes, and how much memory it needs.
+//!
+//! ```sh
+//! cargo run --release --example parse_bench -- [megabytes]
+//! ```
+//!
+//! The file is made of `let` statements covering every kind of expression, including long
+//! application chains, and is `4` megabytes unless said otherwise.
+//!
+//! It then parses single application chains (`f a a a ...`) of doubling lengths, as a check that
+//! they take linear time: the time per argument should stay about the same as the chains get
+//! longer. When every argument cloned the chain before it, it doubled with the length instead.
+
+use oxylc::compile::parser::parse;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Write,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+/// The system allocator, but it keeps count of what it hands out.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        // SAFETY: We just pass the call along.
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        // SAFETY: We just pass the call along.
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Makes roughly `bytes` bytes of source.
+fn generate(bytes: usize) -> String {
+    let mut source = String::with_capacity(bytes + 1024);
+    let mut i = 0;
+    while source.len() < bytes {
+        // NOTE: `write!` into a `String` can't fail.
+        let _ = match i % 5 {
+            0 => writeln!(source, "let value_{i} = {i} + 2 * (value - {i}.5) / -3"),
+            1 => writeln!(source, "/// Doc for `apply_{i}`.\nlet apply_{i} = f a b c d e f g h i j k l m n o p"),
+            2 => writeln!(source, "let block_{i} = {{\n    // A comment.\n    let inner = x |> g |> h\n    inner.field::path\n}}"),
+            3 => writeln!(source, "let function_{i} = |x, y| {{\n    x * y\n}}"),
+            _ => writeln!(source, "pub let nested_{i} = f (g (h x) y) (k z) True False"),
+        };
+        i += 1;
+    }
+    source
+}
+
+/// Parses a single application chain with `args` arguments, and gets how long it took.
+fn chain(args: usize) -> std::time::Duration {
+    let source = format!("let x = f{}\n", " a".repeat(args));
+    let start = Instant::now();
+    parse(&source).expect("the generated chain should parse");
+    start.elapsed()
+}
+
+fn main() {
+    let megabytes: f64 = match std::env::args().nth(1) {
+        Some(arg) => arg
+            .parse()
+            .expect("the size should be a number of megabytes"),
+        None => 4.0,
+    };
+    let source = generate((megabytes * 1024.0 * 1024.0) as usize);
+
+    // NOTE: Only count what happens while parsing.
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let before = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+
+    let start = Instant::now();
+    let file = parse(&source).expect("the generated source should parse");
+    let elapsed = start.elapsed();
+
+    let mib = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "source:      {:.2} MiB, {} statements",
+        mib(source.len()),
+        file.statements.len()
+    );
+    println!(
+        "parse time:  {:.2?} ({:.2} MiB/s)",
+        elapsed,
+        mib(source.len()) / elapsed.as_secs_f64()
+    );
+    println!(
+        "allocations: {}",
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations
+    );
+    println!(
+        "memory:      {:.2} MiB at peak, {:.2} MiB kept for the FST",
+        mib(PEAK.load(Ordering::Relaxed) - before),
+        mib(CURRENT.load(Ordering::Relaxed) - before)
+    );
+
+    println!();
+    println!("application chains:");
+    for args in [1000, 2000, 4000, 8000] {
+        let elapsed = chain(args);
+        println!(
+            "  {:>5} arguments: {:>10.2?} ({:.2?} per argument)",
+            args,
+            elapsed,
+            elapsed / args as u32
+        );
+    }
+}
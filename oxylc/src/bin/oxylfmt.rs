@@ -0,0 +1,167 @@
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use oxylc::compile::{
+    diagnostic::{self, Diagnostic},
+    formatter::{self, errors::FormatError, FormatConfig},
+};
+use std::{
+    io::{self, Read},
+    ops::Range,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// The exit code when the files couldn't be formatted, or aren't formatted when checking.
+const EXIT_ERRORS: u8 = 1;
+
+/// The formatter for the Oxyl programming language.
+#[derive(Debug, Parser)]
+#[command(name = "oxylfmt", version, about)]
+#[command(after_help = "Exits with 0 on success, 1 if there were errors, and 2 on invalid usage.")]
+struct Args {
+    /// The files to format. Without any files, standard input is formatted to standard output.
+    files: Vec<PathBuf>,
+
+    /// Only check whether the files are formatted, without changing them.
+    #[arg(long)]
+    check: bool,
+
+    /// The number of columns code should fit in.
+    #[arg(long, default_value_t = FormatConfig::default().width)]
+    width: usize,
+
+    /// Only format the statements in this range of bytes, like `10..20`. This needs standard input
+    /// or a single file.
+    #[arg(long, value_parser = parse_range)]
+    range: Option<Range<usize>>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let config = FormatConfig::default().with_width(args.width);
+
+    let result = match (args.files.as_slice(), args.range) {
+        ([], range) => stdin(range, args.check, &config),
+        ([file], Some(range)) => format_file_range(file, range, args.check, &config),
+        (_, Some(_)) => Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "`--range` needs standard input or a single file",
+            )
+            .exit(),
+        (files, None) => {
+            let mut ok = true;
+            for file in files {
+                match formatter::format_path(file, &config, args.check) {
+                    Ok(true) if args.check => {
+                        report(&diagnostic::not_formatted(file));
+                        ok = false;
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        report(&Diagnostic::from(&err).with_file(file));
+                        ok = false;
+                    }
+                }
+            }
+            ok
+        }
+    };
+
+    match result {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::from(EXIT_ERRORS),
+    }
+}
+
+/// Formats standard input (or a range of it) to standard output.
+fn stdin(range: Option<Range<usize>>, check: bool, config: &FormatConfig) -> bool {
+    let mut source = String::new();
+    if let Err(err) = io::stdin().read_to_string(&mut source) {
+        report(&Diagnostic::error(format!(
+            "Couldn't read standard input: {}",
+            err
+        )));
+        return false;
+    }
+
+    match format_source(&source, range, config) {
+        Ok(formatted) if check => {
+            if formatted != source {
+                report(&Diagnostic::error("Standard input isn't formatted"));
+            }
+            formatted == source
+        }
+        Ok(formatted) => {
+            print!("{}", formatted);
+            true
+        }
+        Err(err) => {
+            report(&Diagnostic::from(&err));
+            false
+        }
+    }
+}
+
+/// Formats a range of a file, in place.
+fn format_file_range(file: &Path, range: Range<usize>, check: bool, config: &FormatConfig) -> bool {
+    let result = std::fs::read_to_string(file)
+        .map_err(|err| FormatError::Io {
+            path: file.to_path_buf(),
+            message: err.to_string(),
+        })
+        .and_then(|source| {
+            let formatted = format_source(&source, Some(range), config)?;
+            Ok((source, formatted))
+        });
+
+    match result {
+        Ok((source, formatted)) if formatted == source => true,
+        Ok(_) if check => {
+            report(&diagnostic::not_formatted(file));
+            false
+        }
+        Ok((_, formatted)) => match std::fs::write(file, formatted) {
+            Ok(()) => true,
+            Err(err) => {
+                report(&Diagnostic::error(format!(
+                    "Couldn't write `{}`: {}",
+                    file.display(),
+                    err
+                )));
+                false
+            }
+        },
+        Err(err) => {
+            report(&Diagnostic::from(&err).with_file(file));
+            false
+        }
+    }
+}
+
+/// Formats some source code, or only the statements in `range` of it.
+fn format_source(
+    source: &str,
+    range: Option<Range<usize>>,
+    config: &FormatConfig,
+) -> Result<String, FormatError> {
+    match range {
+        Some(range) => Ok(formatter::format_range(source, range, config)?
+            .map_or_else(|| source.to_string(), |edit| edit.apply(source))),
+        None => formatter::format(source, config),
+    }
+}
+
+/// Parses a range like `10..20`.
+fn parse_range(range: &str) -> Result<Range<usize>, String> {
+    let (start, end) = range
+        .split_once("..")
+        .ok_or_else(|| format!("`{}` isn't a range like `10..20`", range))?;
+    let start = start.trim().parse().map_err(|err| format!("{}", err))?;
+    let end = end.trim().parse().map_err(|err| format!("{}", err))?;
+    Ok(start..end)
+}
+
+/// Prints a diagnostic.
+fn report(diagnostic: &Diagnostic) {
+    eprintln!("{}", diagnostic.render(None));
+}
@@ -0,0 +1,415 @@
+//! Diagnostics, which are errors and warnings that can be shown to the user. These render like
+//! `rustc`'s diagnostics, with the line of source code they point at:
+//! ```text
+//! error: Couldn't find `y` in `crate::a`
+//!  --> src/main.oxyl:2:8
+//!   |
+//! 2 | use a::y
+//!   |        ^
+//! ```
+
+use super::{
+    exhaustive::errors::PatternError, formatter::errors::FormatError, infer::errors::TypeError,
+    lower::errors::LowerError, module::errors::ModuleError, parser::errors::ParserError,
+    project::errors::ProjectError, resolve::errors::ResolveError,
+};
+use errgonomic::parser::{errors::Error, input::Span};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// How bad a diagnostic is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A location in a file that a diagnostic points at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    /// The location being pointed at.
+    pub location: Span,
+
+    /// What's at the location. This can be empty.
+    pub message: String,
+}
+
+/// An error or warning to show to the user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+
+    /// What went wrong.
+    pub message: String,
+
+    /// The file the diagnostic is about, if there is one.
+    pub file: Option<PathBuf>,
+
+    /// The locations in `file` the diagnostic points at.
+    pub labels: Vec<Label>,
+
+    /// Extra information shown after everything else, like how to fix the problem.
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Creates a new diagnostic with nothing but a message.
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            file: None,
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    /// Creates a new error.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Creates a new warning.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    /// Sets the file the diagnostic is about.
+    pub fn with_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Points at a location in the diagnostic's file.
+    pub fn with_label(mut self, location: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            location,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Adds a note to the end of the diagnostic.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Renders the diagnostic as text, given the source of its file. Without the source, the labels
+    /// can't be shown, so only the file is.
+    pub fn render(&self, source: Option<&str>) -> String {
+        let mut out = String::new();
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        let _ = writeln!(out, "{}: {}", severity, self.message);
+
+        // NOTE: The gutter has to fit the largest line number shown.
+        let lines = match source {
+            Some(source) => self
+                .labels
+                .iter()
+                .map(|label| line_of(source, label.location.start()))
+                .collect::<Vec<_>>(),
+            None => vec![],
+        };
+        let width = lines
+            .iter()
+            .map(|(line, _)| (line + 1).to_string().len())
+            .max()
+            .unwrap_or(0);
+        let gutter = " ".repeat(width);
+
+        match (&self.file, lines.first()) {
+            (Some(file), Some((line, column))) => {
+                let _ = writeln!(
+                    out,
+                    "{}--> {}:{}:{}",
+                    gutter,
+                    file.display(),
+                    line + 1,
+                    column + 1
+                );
+            }
+            (Some(file), None) => {
+                let _ = writeln!(out, "{}--> {}", gutter, file.display());
+            }
+            (None, _) => {}
+        }
+
+        if let Some(source) = source.filter(|_| !self.labels.is_empty()) {
+            let _ = writeln!(out, "{} |", gutter);
+
+            let mut last_line = None;
+            for (label, (line, column)) in self.labels.iter().zip(&lines) {
+                let text = source.lines().nth(*line).unwrap_or("");
+                let start = label.location.start().min(source.len());
+                let end = label.location.end().clamp(start, source.len());
+
+                // NOTE: Labels spanning multiple lines are only underlined up to the end of the first.
+                let underlined = source[start..end]
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+                    .chars()
+                    .count()
+                    .max(1);
+
+                // NOTE: Labels one after the other on the same line share it.
+                if last_line != Some(*line) {
+                    let _ = writeln!(out, "{:>width$} | {}", line + 1, text);
+                }
+                last_line = Some(*line);
+                let _ = writeln!(
+                    out,
+                    "{} | {}{}{}{}",
+                    gutter,
+                    " ".repeat(*column),
+                    "^".repeat(underlined),
+                    if label.message.is_empty() { "" } else { " " },
+                    label.message
+                );
+            }
+        }
+
+        for note in &self.notes {
+            let _ = writeln!(out, "{} = note: {}", gutter, note);
+        }
+
+        out
+    }
+}
+
+impl From<&ModuleError> for Diagnostic {
+    fn from(err: &ModuleError) -> Self {
+        let diagnostic = Diagnostic::error(err.to_string());
+        let diagnostic = match err.location() {
+            Some((file, location)) => diagnostic.with_file(file).with_label(location, ""),
+            None => match err {
+                ModuleError::Io { path, .. } | ModuleError::Parse { path, .. } => {
+                    diagnostic.with_file(path)
+                }
+                _ => diagnostic,
+            },
+        };
+
+        match err {
+            ModuleError::Private { module, .. } => diagnostic.with_note(format!(
+                "Only things marked `pub` can be used from outside of `{}`",
+                module
+            )),
+            _ => diagnostic,
+        }
+    }
+}
+
+impl From<&LowerError> for Diagnostic {
+    fn from(err: &LowerError) -> Self {
+        Diagnostic::error(err.to_string()).with_label(err.location(), "")
+    }
+}
+
+impl From<&ResolveError> for Diagnostic {
+    fn from(err: &ResolveError) -> Self {
+        let diagnostic = Diagnostic::error(err.to_string());
+        match err {
+            ResolveError::Unresolved {
+                location,
+                suggestion,
+                ..
+            } => {
+                let diagnostic = diagnostic.with_label(*location, "not found in this scope");
+                match suggestion {
+                    Some(suggestion) => {
+                        diagnostic.with_note(format!("Did you mean `{}`?", suggestion))
+                    }
+                    None => diagnostic,
+                }
+            }
+            ResolveError::DuplicateBinding { location, .. } => diagnostic
+                .with_label(*location, "bound again here")
+                .with_note("Every name in a pattern has to be different"),
+        }
+    }
+}
+
+impl From<&TypeError> for Diagnostic {
+    fn from(err: &TypeError) -> Self {
+        let diagnostic = Diagnostic::error(err.to_string());
+        match err {
+            TypeError::Mismatch {
+                expected,
+                found,
+                location,
+                expected_at,
+            } => diagnostic
+                .with_label(*location, format!("found `{}` here", found))
+                .with_label(
+                    *expected_at,
+                    format!("expected `{}` because of this", expected),
+                ),
+            TypeError::Infinite {
+                location,
+                expected_at,
+                ..
+            } => diagnostic
+                .with_label(*location, "")
+                .with_label(*expected_at, "because of this")
+                .with_note("A type can't contain itself"),
+            TypeError::NotAFunction {
+                found,
+                location,
+                arg,
+            } => diagnostic
+                .with_label(*location, format!("this is `{}`", found))
+                .with_label(*arg, "but it's applied to this"),
+            TypeError::OutOfRange {
+                ty,
+                min,
+                max,
+                location,
+                ..
+            } => diagnostic
+                .with_label(*location, "")
+                .with_note(format!("`{}` goes from {} to {}", ty, min, max)),
+            TypeError::NoField { location, .. }
+            | TypeError::NotAConstructor { location, .. }
+            | TypeError::PatternArity { location, .. }
+            | TypeError::MissingInstance { location, .. }
+            | TypeError::UnknownTrait { location, .. }
+            | TypeError::UnknownType { location, .. }
+            | TypeError::BuiltinTrait { location, .. }
+            | TypeError::DuplicateType { location, .. }
+            | TypeError::DuplicateTypeParam { location, .. }
+            | TypeError::WrongArity { location, .. }
+            | TypeError::DuplicateInstance { location, .. } => diagnostic.with_label(*location, ""),
+        }
+    }
+}
+
+impl From<&PatternError> for Diagnostic {
+    fn from(err: &PatternError) -> Self {
+        match err {
+            PatternError::NonExhaustive { location, .. } => Diagnostic::error(err.to_string())
+                .with_label(*location, "")
+                .with_note(
+                    "Add an arm for every pattern that isn't covered, or an arm with `_` to match \
+                     the rest",
+                ),
+            PatternError::Unreachable { location } => Diagnostic::warning(err.to_string())
+                .with_label(*location, "")
+                .with_note("Everything it matches is matched by the arms before it"),
+        }
+    }
+}
+
+impl From<&ProjectError> for Diagnostic {
+    fn from(err: &ProjectError) -> Self {
+        Diagnostic::error(err.to_string())
+    }
+}
+
+impl From<&FormatError> for Diagnostic {
+    fn from(err: &FormatError) -> Self {
+        let diagnostic = Diagnostic::error(err.to_string());
+        match err {
+            FormatError::Io { path, .. } => diagnostic.with_file(path),
+            _ => diagnostic,
+        }
+    }
+}
+
+/// Creates the warning for a source file that isn't part of the module tree.
+pub fn unused_file(path: &Path) -> Diagnostic {
+    Diagnostic::warning(format!(
+        "`{}` isn't part of the module tree",
+        path.display()
+    ))
+    .with_note("Declare it with a `mod` statement to include it")
+}
+
+/// Creates the error for a file that couldn't be parsed, pointing at where parsing failed.
+pub fn parse_error(err: &Error<&str, ParserError>) -> Diagnostic {
+    Diagnostic::error("Syntax error")
+        .with_label(err.from().span(), "couldn't parse this")
+        .with_note(format!("{:?}", err.kind()))
+}
+
+/// Creates the error for a file that isn't formatted, when checking formatting.
+pub fn not_formatted(path: &Path) -> Diagnostic {
+    Diagnostic::error(format!("`{}` isn't formatted", path.display()))
+        .with_note("Run `oxylc fmt` to format it")
+}
+
+/// Gets the (0-based) line and column of a byte offset in some source code. The column is in
+/// characters.
+fn line_of(source: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_render_diagnostic() {
+        let diagnostic = Diagnostic::error("Couldn't find `y` in `crate::a`")
+            .with_file("main.oxyl")
+            .with_label((17..18).into(), "not found")
+            .with_note("Something helpful");
+        assert_eq!(
+            diagnostic.render(Some("let x = 1\nuse a::y\n")),
+            "error: Couldn't find `y` in `crate::a`\n --> main.oxyl:2:8\n  |\n2 | use a::y\n  |        ^ not found\n  = note: Something helpful\n"
+        );
+    }
+
+    #[test]
+    fn can_render_labels_on_the_same_line() {
+        let diagnostic = Diagnostic::error("Mismatched types: expected `Int`, found `Bool`")
+            .with_file("main.oxyl")
+            .with_label((12..16).into(), "found `Bool` here")
+            .with_label((8..9).into(), "expected `Int` because of this");
+        assert_eq!(
+            diagnostic.render(Some("let y = x + True\n")),
+            "error: Mismatched types: expected `Int`, found `Bool`\n --> main.oxyl:1:13\n  |\n1 | let y = x + True\n  |             ^^^^ found `Bool` here\n  |         ^ expected `Int` because of this\n"
+        );
+    }
+
+    #[test]
+    fn can_render_diagnostic_without_source() {
+        let diagnostic = Diagnostic::warning("Something's off")
+            .with_file("main.oxyl")
+            .with_label((0..3).into(), "");
+        assert_eq!(
+            diagnostic.render(None),
+            "warning: Something's off\n--> main.oxyl\n"
+        );
+        assert_eq!(
+            diagnostic.render(Some("let x = 1\n")),
+            "warning: Something's off\n --> main.oxyl:1:1\n  |\n1 | let x = 1\n  | ^^^\n"
+        );
+    }
+
+    #[test]
+    fn can_point_at_parse_errors() {
+        let err = crate::compile::parser::parse("let = 1\n").unwrap_err();
+        let diagnostic = parse_error(&err);
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.labels[0].location.start(), 4);
+    }
+
+    #[test]
+    fn can_find_line_and_column() {
+        assert_eq!(line_of("ab\ncd", 0), (0, 0));
+        assert_eq!(line_of("ab\ncd", 4), (1, 1));
+        assert_eq!(line_of("é\nx", 3), (1, 0));
+        assert_eq!(line_of("ab", 10), (0, 2));
+    }
+}
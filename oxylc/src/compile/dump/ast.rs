@@ -0,0 +1,185 @@
+//! Dumps the AST.
+
+use super::DumpNode;
+use crate::repr::ast::{
+    expr::{ExprId, ExprKind, Literal},
+    pattern::{Pattern, PatternKind},
+    stmt::{StmtId, StmtKind},
+    type_expr::TypeExpr,
+    File, Name,
+};
+
+/// Dumps a whole file.
+pub fn dump(file: &File) -> DumpNode {
+    DumpNode::new("File")
+        .with_span(file.location)
+        .with_children(file.statements.iter().map(|&id| statement(file, id)))
+}
+
+fn statement(file: &File, id: StmtId) -> DumpNode {
+    let stmt = &file[id];
+    let kind = match &stmt.kind {
+        StmtKind::Let { .. } => "Let",
+        StmtKind::Mod { .. } => "Mod",
+        StmtKind::Use { .. } => "Use",
+        StmtKind::Impl { .. } => "Impl",
+        StmtKind::Type { .. } => "Type",
+    };
+    let mut node = DumpNode::new(kind).with_span(stmt.location);
+
+    if stmt.is_public {
+        node = node.with_field("pub", true);
+    }
+
+    match &stmt.kind {
+        StmtKind::Let {
+            name,
+            is_mutable,
+            signature,
+            value,
+        } => {
+            let node = node
+                .with_field("name", name.ident.as_str())
+                .with_field("mutable", *is_mutable);
+            let node = match signature {
+                Some(signature) => node.with_child(
+                    DumpNode::new("Signature")
+                        .with_span(signature.location)
+                        .with_children(signature.params.iter().map(|param| {
+                            let bounds = param.bounds.iter().map(|bound| bound.ident.as_str());
+                            DumpNode::new("TypeParam")
+                                .with_span(param.name.location)
+                                .with_field("name", param.name.ident.as_str())
+                                .with_field("bounds", bounds.collect::<Vec<_>>().join(" + "))
+                        }))
+                        .with_child(type_expr(&signature.ty)),
+                ),
+                None => node,
+            };
+            node.with_child(expression(file, *value))
+        }
+        StmtKind::Mod { name } => node.with_field("name", name.ident.as_str()),
+        StmtKind::Use { imports } => node.with_children(imports.iter().map(|import| {
+            DumpNode::new("Import")
+                .with_field("path", path(&import.path))
+                .with_field("name", import.name.ident.as_str())
+        })),
+        StmtKind::Impl {
+            trait_name,
+            type_name,
+            value,
+        } => node
+            .with_field("trait", trait_name.ident.as_str())
+            .with_field("type", type_name.ident.as_str())
+            .with_child(expression(file, *value)),
+        StmtKind::Type {
+            name,
+            params,
+            constructors,
+        } => node
+            .with_field("name", name.ident.as_str())
+            .with_field(
+                "params",
+                params
+                    .iter()
+                    .map(|p| p.ident.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+            .with_children(constructors.iter().map(|constructor| {
+                DumpNode::new("Constructor")
+                    .with_span(constructor.location)
+                    .with_field("name", constructor.name.ident.as_str())
+                    .with_children(constructor.fields.iter().map(type_expr))
+            })),
+    }
+}
+
+fn type_expr(ty: &TypeExpr) -> DumpNode {
+    DumpNode::new("TypeExpr")
+        .with_span(ty.location)
+        .with_field("type", ty.to_string())
+}
+
+fn expression(file: &File, id: ExprId) -> DumpNode {
+    let expr = &file[id];
+    match &expr.kind {
+        ExprKind::Literal(Literal::Integer(i)) => DumpNode::new("Integer").with_field("value", *i),
+        ExprKind::Literal(Literal::Floating(f)) => {
+            DumpNode::new("Floating").with_field("value", *f)
+        }
+        ExprKind::Literal(Literal::Boolean(b)) => DumpNode::new("Boolean").with_field("value", *b),
+        ExprKind::Var(name) => DumpNode::new("Var").with_field("name", name.ident.as_str()),
+        ExprKind::Path(segments) => DumpNode::new("Path").with_field("path", path(segments)),
+        ExprKind::Lambda { param, body } => {
+            let node = DumpNode::new("Lambda");
+            match param {
+                Some(param) => node.with_field("param", param.ident.as_str()),
+                None => node,
+            }
+            .with_child(expression(file, *body))
+        }
+        ExprKind::Apply { function, arg } => DumpNode::new("Apply")
+            .with_child(expression(file, *function))
+            .with_child(expression(file, *arg)),
+        ExprKind::Binary { op, lhs, rhs, .. } => DumpNode::new("Binary")
+            .with_field("op", op.text())
+            .with_child(expression(file, *lhs))
+            .with_child(expression(file, *rhs)),
+        ExprKind::Unary { op, operand, .. } => DumpNode::new("Unary")
+            .with_field("op", op.text())
+            .with_child(expression(file, *operand)),
+        ExprKind::Field { expr, field } => DumpNode::new("Field")
+            .with_field("name", field.ident.as_str())
+            .with_child(expression(file, *expr)),
+        ExprKind::Block { statements, result } => DumpNode::new("Block")
+            .with_children(statements.iter().map(|&id| statement(file, id)))
+            .with_child(expression(file, *result)),
+        ExprKind::Tuple(items) => {
+            DumpNode::new("Tuple").with_children(items.iter().map(|&id| expression(file, id)))
+        }
+        ExprKind::Match { scrutinee, arms } => DumpNode::new("Match")
+            .with_child(expression(file, *scrutinee))
+            .with_children(arms.iter().map(|arm| {
+                DumpNode::new("Arm")
+                    .with_span(arm.location)
+                    .with_child(pattern(file, &arm.pattern))
+                    .with_child(expression(file, arm.body))
+            })),
+        ExprKind::Error => DumpNode::new("Error"),
+    }
+    .with_span(expr.location)
+}
+
+fn pattern(file: &File, pattern: &Pattern) -> DumpNode {
+    match &pattern.kind {
+        PatternKind::Wildcard => DumpNode::new("Wildcard"),
+        PatternKind::Binding(name) => {
+            DumpNode::new("Binding").with_field("name", name.ident.as_str())
+        }
+        PatternKind::Literal(id) => {
+            DumpNode::new("LiteralPattern").with_child(expression(file, *id))
+        }
+        PatternKind::Tuple(items) => DumpNode::new("TuplePattern")
+            .with_children(items.iter().map(|item| self::pattern(file, item))),
+        PatternKind::Constructor { constructor, args } => {
+            let name = match &file[*constructor].kind {
+                ExprKind::Var(name) => name.ident.as_str(),
+                _ => "",
+            };
+            DumpNode::new("ConstructorPattern")
+                .with_field("name", name)
+                .with_children(args.iter().map(|arg| self::pattern(file, arg)))
+        }
+    }
+    .with_span(pattern.location)
+}
+
+/// Writes a path like it is in the source, like `a::b`.
+fn path(segments: &[Name]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.ident.as_str())
+        .collect::<Vec<_>>()
+        .join("::")
+}
@@ -0,0 +1,300 @@
+//! Dumps the FST.
+
+use super::DumpNode;
+use crate::repr::fst::{
+    attribute::{Attribute, AttributeArgKind},
+    block::Block,
+    doc_comment::{DocComment, DocCommentKind},
+    expression::{Expression, ExpressionKind},
+    function::Function,
+    identifier::Identifier,
+    pattern::{Match, Pattern, PatternKind},
+    statement::{Statement, StatementKind},
+    type_expr::{TypeExpr, TypeExprKind, TypeParams},
+    use_tree::{UseTree, UseTreeKind},
+    value::{Value, ValueKind},
+    visitor::FstVisitor,
+    File,
+};
+
+/// Dumps a whole file.
+pub fn dump(file: &File) -> DumpNode {
+    FstDumper.visit_file(file)
+}
+
+/// Turns every node in the FST into a `DumpNode`.
+struct FstDumper;
+
+impl FstVisitor<DumpNode> for FstDumper {
+    fn visit_file(&mut self, file: &File) -> DumpNode {
+        DumpNode::new("File")
+            .with_span(file.location)
+            .with_children(file.statements.iter().map(|s| self.visit_statement(s)))
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) -> DumpNode {
+        let kind = match &statement.kind {
+            StatementKind::Let { .. } => "Let",
+            StatementKind::Mod { .. } => "Mod",
+            StatementKind::Use { .. } => "Use",
+            StatementKind::Impl { .. } => "Impl",
+            StatementKind::Type { .. } => "Type",
+        };
+        let mut node = DumpNode::new(kind)
+            .with_span(statement.location)
+            .with_children(statement.docs.iter().map(|d| self.visit_doc_comment(d)))
+            .with_children(statement.attributes.iter().map(|a| self.visit_attribute(a)));
+
+        if statement.visibility.is_public() {
+            node = node.with_field("pub", true);
+        }
+
+        match &statement.kind {
+            StatementKind::Let {
+                is_mutable,
+                ident,
+                signature,
+                expression,
+            } => {
+                let node = node
+                    .with_field("mutable", *is_mutable)
+                    .with_child(self.visit_ident(ident));
+                let node = match signature {
+                    Some(signature) => node.with_child(
+                        DumpNode::new("Signature")
+                            .with_span(signature.location)
+                            .with_children(
+                                signature.params.iter().map(|p| self.visit_type_params(p)),
+                            )
+                            .with_child(self.visit_type_expr(&signature.ty)),
+                    ),
+                    None => node,
+                };
+                node.with_child(self.visit_expression(expression))
+            }
+            StatementKind::Mod { ident } => node.with_child(self.visit_ident(ident)),
+            StatementKind::Use { tree } => node.with_child(self.visit_use_tree(tree)),
+            StatementKind::Impl {
+                trait_ident,
+                type_ident,
+                expression,
+            } => node
+                .with_child(self.visit_ident(trait_ident))
+                .with_child(self.visit_ident(type_ident))
+                .with_child(self.visit_expression(expression)),
+            StatementKind::Type {
+                ident,
+                params,
+                constructors,
+            } => node
+                .with_child(self.visit_ident(ident))
+                .with_children(params.iter().map(|p| self.visit_type_params(p)))
+                .with_children(constructors.iter().map(|constructor| {
+                    DumpNode::new("Constructor")
+                        .with_span(constructor.location)
+                        .with_child(self.visit_ident(&constructor.ident))
+                        .with_children(constructor.fields.iter().map(|f| self.visit_type_expr(f)))
+                })),
+        }
+    }
+
+    fn visit_type_expr(&mut self, ty: &TypeExpr) -> DumpNode {
+        match &ty.kind {
+            TypeExprKind::Named { ident, args } => DumpNode::new("NamedType")
+                .with_child(self.visit_ident(ident))
+                .with_children(args.iter().map(|arg| self.visit_type_expr(arg))),
+            TypeExprKind::Unit => DumpNode::new("UnitType"),
+            TypeExprKind::Parenthesized { inner, .. } => {
+                DumpNode::new("Parenthesized").with_child(self.visit_type_expr(inner))
+            }
+            TypeExprKind::Tuple { items, .. } => DumpNode::new("TupleType")
+                .with_children(items.iter().map(|item| self.visit_type_expr(item))),
+            TypeExprKind::Function { param, result, .. } => DumpNode::new("FunctionType")
+                .with_child(self.visit_type_expr(param))
+                .with_child(self.visit_type_expr(result)),
+        }
+        .with_span(ty.location)
+    }
+
+    fn visit_type_params(&mut self, params: &TypeParams) -> DumpNode {
+        DumpNode::new("TypeParams")
+            .with_span(params.location)
+            .with_children(params.params.iter().map(|param| {
+                DumpNode::new("TypeParam")
+                    .with_span(param.location)
+                    .with_child(self.visit_ident(&param.ident))
+                    .with_children(param.bounds.iter().map(|bound| self.visit_ident(bound)))
+            }))
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> DumpNode {
+        match &expression.kind {
+            ExpressionKind::Parenthesized { inner, .. } => DumpNode::new("Parenthesized")
+                .with_span(expression.location)
+                .with_child(self.visit_expression(inner)),
+            ExpressionKind::Tuple { items, .. } => DumpNode::new("Tuple")
+                .with_span(expression.location)
+                .with_children(items.iter().map(|item| self.visit_expression(item))),
+            ExpressionKind::Value(value) => self.visit_value(value),
+            ExpressionKind::Block(block) => self.visit_block(block),
+            ExpressionKind::Match(m) => self.visit_match(m),
+            ExpressionKind::Infix { operator, lhs, rhs } => DumpNode::new("Infix")
+                .with_span(expression.location)
+                .with_field("operator", format!("{:?}", operator.kind()))
+                .with_child(self.visit_expression(lhs))
+                .with_child(self.visit_expression(rhs)),
+            ExpressionKind::Prefix { operator, rhs } => DumpNode::new("Prefix")
+                .with_span(expression.location)
+                .with_field("operator", format!("{:?}", operator.kind()))
+                .with_child(self.visit_expression(rhs)),
+            ExpressionKind::Postfix { operator, lhs } => DumpNode::new("Postfix")
+                .with_span(expression.location)
+                .with_field("operator", format!("{:?}", operator.kind()))
+                .with_child(self.visit_expression(lhs)),
+            ExpressionKind::Application { function, arg } => DumpNode::new("Application")
+                .with_span(expression.location)
+                .with_child(self.visit_expression(function))
+                .with_child(self.visit_expression(arg)),
+        }
+    }
+
+    fn visit_match(&mut self, m: &Match) -> DumpNode {
+        DumpNode::new("Match")
+            .with_span(m.location)
+            .with_child(self.visit_expression(&m.scrutinee))
+            .with_children(m.arms.iter().map(|arm| {
+                DumpNode::new("Arm")
+                    .with_span(arm.location)
+                    .with_child(self.visit_pattern(&arm.pattern))
+                    .with_child(self.visit_expression(&arm.expression))
+            }))
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) -> DumpNode {
+        match &pattern.kind {
+            PatternKind::Named { ident, args } => DumpNode::new("NamedPattern")
+                .with_child(self.visit_ident(ident))
+                .with_children(args.iter().map(|arg| self.visit_pattern(arg))),
+            PatternKind::Value(value) => {
+                DumpNode::new("ValuePattern").with_child(self.visit_value(value))
+            }
+            PatternKind::Parenthesized { inner, .. } => {
+                DumpNode::new("Parenthesized").with_child(self.visit_pattern(inner))
+            }
+            PatternKind::Tuple { items, .. } => DumpNode::new("TuplePattern")
+                .with_children(items.iter().map(|item| self.visit_pattern(item))),
+        }
+        .with_span(pattern.location)
+    }
+
+    fn visit_value(&mut self, value: &Value) -> DumpNode {
+        match &value.kind {
+            ValueKind::Integer(i) => DumpNode::new("Integer").with_field("value", *i),
+            ValueKind::Floating(f) => DumpNode::new("Floating").with_field("value", *f),
+            ValueKind::Boolean(b) => DumpNode::new("Boolean").with_field("value", *b),
+            ValueKind::Identifier(ident) => self.visit_ident(ident),
+            ValueKind::Function(function) => self.visit_function(function),
+        }
+        .with_span(value.location)
+    }
+
+    fn visit_ident(&mut self, ident: &Identifier) -> DumpNode {
+        DumpNode::new("Ident")
+            .with_span(ident.location)
+            .with_field("name", ident.name.as_str())
+    }
+
+    fn visit_function(&mut self, function: &Function) -> DumpNode {
+        DumpNode::new("Function")
+            .with_span(function.location)
+            .with_children(function.args.iter().map(|arg| self.visit_ident(arg)))
+            .with_child(self.visit_expression(&function.expression))
+    }
+
+    fn visit_block(&mut self, block: &Block) -> DumpNode {
+        DumpNode::new("Block")
+            .with_span(block.location)
+            .with_children(block.statements.iter().map(|s| self.visit_statement(s)))
+            .with_child(self.visit_expression(&block.expression))
+    }
+
+    fn visit_attribute(&mut self, attribute: &Attribute) -> DumpNode {
+        let args = attribute.args.iter().map(|arg| match &arg.kind {
+            AttributeArgKind::String(string) => DumpNode::new("String")
+                .with_span(arg.location)
+                .with_field("value", string.clone()),
+            AttributeArgKind::Value(value) => self.visit_value(value),
+        });
+        let args = args.collect::<Vec<_>>();
+
+        DumpNode::new("Attribute")
+            .with_span(attribute.location)
+            .with_child(self.visit_ident(&attribute.name))
+            .with_children(args)
+    }
+
+    fn visit_doc_comment(&mut self, doc_comment: &DocComment) -> DumpNode {
+        let kind = match doc_comment.kind {
+            DocCommentKind::Line => "line",
+            DocCommentKind::Block => "block",
+        };
+
+        DumpNode::new("DocComment")
+            .with_span(doc_comment.location)
+            .with_field("kind", kind)
+            .with_field("content", doc_comment.content.clone())
+    }
+
+    fn visit_use_tree(&mut self, tree: &UseTree) -> DumpNode {
+        let path = tree
+            .path
+            .iter()
+            .map(|ident| ident.name.as_str())
+            .collect::<Vec<_>>()
+            .join("::");
+        let node = DumpNode::new("UseTree")
+            .with_span(tree.location)
+            .with_field("path", path);
+
+        match &tree.kind {
+            UseTreeKind::Simple { alias: Some(alias) } => node.with_child(
+                DumpNode::new("Alias")
+                    .with_span(alias.location)
+                    .with_child(self.visit_ident(alias)),
+            ),
+            UseTreeKind::Simple { alias: None } => node,
+            UseTreeKind::Group(trees) => {
+                node.with_children(trees.iter().map(|tree| self.visit_use_tree(tree)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::parser::parse;
+
+    #[test]
+    fn can_dump_fst() {
+        let file = parse("/// One.\npub let x = -(1 + y)\nuse a::{b as c}\n").unwrap();
+        assert_eq!(
+            dump(&file).to_text(),
+            r#"File 0..46
+  Let 13..30 mutable=false pub=true
+    DocComment 0..8 content=" One." kind="line"
+    Ident 17..18 name="x"
+    Prefix 21..29 operator="Dash"
+      Parenthesized 22..29
+        Infix 23..28 operator="Plus"
+          Integer 23..24 value=1
+          Ident 27..28 name="y"
+  Use 30..46
+    UseTree 34..45 path="a"
+      UseTree 38..44 path="b"
+        Alias 43..44
+          Ident 43..44 name="c"
+"#
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Dumps of the intermediate representations in the compiler, for debugging. Every stage is turned
+//! into a tree of `DumpNode`s, which can then be printed as indented text:
+//! ```text
+//! File 0..10
+//!   Let 0..9 mutable=false
+//!     Ident 4..5 name="x"
+//!     Integer 8..9 value=1
+//! ```
+//! or as JSON, where every node is `{ "kind": ..., "span": [start, end], "fields": { ... },
+//! "children": [ ... ] }`. Both formats are stable, so they can be used in tests and by tools.
+
+pub mod ast;
+pub mod fst;
+pub mod tokens;
+pub mod types;
+
+use errgonomic::parser::input::Span;
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt::Write};
+
+/// A node in the dump of some intermediate representation.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct DumpNode {
+    /// What kind of node it is, like `Let` or `Integer`.
+    pub kind: String,
+
+    /// The location of the node in the source, as `[start, end]`, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<[usize; 2]>,
+
+    /// The properties of the node that aren't other nodes, sorted by name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, Value>,
+
+    /// The nodes inside of this node, in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DumpNode>,
+}
+
+impl DumpNode {
+    /// Creates a new node without a location.
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            span: None,
+            fields: BTreeMap::new(),
+            children: vec![],
+        }
+    }
+
+    /// Sets the location of the node.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some([span.start(), span.end()]);
+        self
+    }
+
+    /// Adds a field to the node.
+    pub fn with_field(mut self, name: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.fields.insert(name.into(), value.into());
+        self
+    }
+
+    /// Adds a child to the end of the node's children.
+    pub fn with_child(mut self, child: DumpNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Adds children to the end of the node's children.
+    pub fn with_children(mut self, children: impl IntoIterator<Item = DumpNode>) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Renders the tree as indented text, with one node per line.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        self.write_text(&mut out, 0);
+        out
+    }
+
+    /// Renders the tree as (pretty-printed) JSON.
+    pub fn to_json(&self) -> String {
+        // NOTE: Every key is a string, so this can't fail.
+        serde_json::to_string_pretty(self).expect("dumps can always be serialized")
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        let _ = write!(out, "{}{}", "  ".repeat(depth), self.kind);
+
+        if let Some([start, end]) = self.span {
+            let _ = write!(out, " {}..{}", start, end);
+        }
+
+        for (name, value) in &self.fields {
+            // NOTE: JSON strings are quoted and escaped just like Rust's debug strings.
+            let _ = write!(out, " {}={}", name, value);
+        }
+
+        out.push('\n');
+
+        for child in &self.children {
+            child.write_text(out, depth + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> DumpNode {
+        DumpNode::new("Let")
+            .with_span((0..9).into())
+            .with_field("mutable", false)
+            .with_child(
+                DumpNode::new("Ident")
+                    .with_span((4..5).into())
+                    .with_field("name", "x\n"),
+            )
+            .with_child(DumpNode::new("Nothing"))
+    }
+
+    #[test]
+    fn can_dump_as_text() {
+        assert_eq!(
+            example().to_text(),
+            "Let 0..9 mutable=false\n  Ident 4..5 name=\"x\\n\"\n  Nothing\n"
+        );
+    }
+
+    #[test]
+    fn can_dump_as_json() {
+        let json = serde_json::from_str::<Value>(&example().to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "Let",
+                "span": [0, 9],
+                "fields": { "mutable": false },
+                "children": [
+                    { "kind": "Ident", "span": [4, 5], "fields": { "name": "x\n" } },
+                    { "kind": "Nothing" },
+                ],
+            })
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Dumps the tokens of the lexer.
+
+use super::DumpNode;
+use crate::compile::lexer::token::Token;
+
+/// Dumps the tokens of some source code, with the text of every token.
+pub fn dump(source: &str, tokens: &[Token]) -> DumpNode {
+    DumpNode::new("Tokens")
+        .with_span((0..source.len()).into())
+        .with_children(tokens.iter().map(|token| {
+            DumpNode::new(format!("{:?}", token.kind))
+                .with_span(token.location)
+                .with_field("text", token.text(source))
+        }))
+}
@@ -0,0 +1,42 @@
+//! Dumps the types of the names in a file.
+
+use super::DumpNode;
+use crate::{
+    compile::{infer::Types, resolve::Resolution},
+    repr::ast::{scope::DefKind, File},
+};
+
+/// Dumps the type of every `let`, function parameter and constructor in a file, in the order they're defined.
+pub fn dump(file: &File, resolution: &Resolution, types: &Types) -> DumpNode {
+    let mut defs = resolution
+        .defs
+        .iter()
+        .filter(|(id, _)| types.of_def(*id).is_some())
+        .collect::<Vec<_>>();
+    // NOTE: Top-level names are resolved before everything else, so they're sorted back into the
+    // order they're written in.
+    defs.sort_by_key(|(_, def)| def.name.location.start());
+
+    let defs = defs.into_iter().filter_map(|(id, def)| {
+        let kind = match def.kind {
+            DefKind::Let(_) => "Let",
+            DefKind::Param(_) => "Param",
+            DefKind::Binding(..) => "Binding",
+            DefKind::Constructor(..) => "Constructor",
+            DefKind::Mod(_) | DefKind::Import(_) | DefKind::Extern | DefKind::Builtin => {
+                return None
+            }
+        };
+        let scheme = types.of_def(id)?;
+        Some(
+            DumpNode::new(kind)
+                .with_span(def.name.location)
+                .with_field("name", def.name.ident.as_str())
+                .with_field("type", scheme.to_string()),
+        )
+    });
+
+    DumpNode::new("File")
+        .with_span(file.location)
+        .with_children(defs)
+}
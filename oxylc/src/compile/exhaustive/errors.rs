@@ -0,0 +1,45 @@
+use errgonomic::parser::input::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PatternError {
+    #[error("{}", not_covered(.patterns, *.more))]
+    NonExhaustive {
+        /// Some of the values that no arm matches, written as patterns.
+        patterns: Vec<String>,
+
+        /// Whether there are more values that no arm matches than the ones in `patterns`.
+        more: bool,
+
+        /// The location of the value being matched on.
+        location: Span,
+    },
+
+    #[error("This pattern is unreachable")]
+    Unreachable { location: Span },
+}
+
+impl PatternError {
+    /// Gets the location in the file where the error happened.
+    pub fn location(&self) -> Span {
+        match self {
+            PatternError::NonExhaustive { location, .. }
+            | PatternError::Unreachable { location } => *location,
+        }
+    }
+}
+
+/// Writes the message for patterns that aren't covered, like "Patterns `A` and `B` not covered".
+fn not_covered(patterns: &[String], more: bool) -> String {
+    let quoted = patterns
+        .iter()
+        .map(|pattern| format!("`{}`", pattern))
+        .collect::<Vec<_>>();
+
+    match (quoted.as_slice(), more) {
+        ([one], false) => format!("Pattern {} not covered", one),
+        ([init @ .., last], false) => {
+            format!("Patterns {} and {} not covered", init.join(", "), last)
+        }
+        (all, _) => format!("Patterns {} and more not covered", all.join(", ")),
+    }
+}
@@ -0,0 +1,445 @@
+//! Checks that every `match` has an arm for every value it could be given, and that every arm can
+//! be reached. This is Maranget's algorithm ("Warnings for pattern matching"), which asks whether a
+//! pattern is *useful* after some rows of patterns: whether there's a value it matches that none of
+//! the rows do.
+//! - A `match` is exhaustive if `_` isn't useful after all of its arms. If it is, the values that
+//!   make it useful are what's missing, and they're written as patterns for the error.
+//! - An arm is unreachable if its pattern isn't useful after the arms before it.
+//!
+//! A pattern is either `_` (which names also are, as they match anything) or a constructor applied
+//! to patterns. The constructors are `True` and `False`, tuples, the constructors of the types
+//! declared by `type`-statements and literals. A column of patterns covers every value once every
+//! constructor of its type is in it, which can't happen with numbers, so numbers always need a `_`
+//! (or a name) to be matched exhaustively.
+//!
+//! This runs after type inference, so the patterns in a `match` are expected to all be the same
+//! type. Patterns that couldn't be resolved or have the wrong number of fields have already been
+//! reported. An arm with a constructor that couldn't be resolved is skipped, as it can't be known
+//! what it would match, and a constructor with the wrong number of fields is checked as if it had
+//! the right number.
+
+pub mod errors;
+
+use crate::{
+    compile::resolve::Resolution,
+    repr::ast::{
+        expr::{ExprId, ExprKind, Literal},
+        pattern::{self, PatternKind},
+        scope::DefKind,
+        stmt::{StmtId, StmtKind},
+        File,
+    },
+};
+use errors::PatternError;
+
+/// The most patterns that are written in an error about missing ones.
+const SHOWN_MISSING: usize = 3;
+
+/// Checks every `match` in a file. Every pattern has to be resolved first, so that its
+/// constructors are known.
+pub fn check(file: &File, resolution: &Resolution) -> Vec<PatternError> {
+    let checker = Checker { file, resolution };
+
+    let mut errors = file
+        .exprs
+        .iter()
+        .filter_map(|(_, expr)| match &expr.kind {
+            ExprKind::Match { scrutinee, arms } => Some(checker.match_expr(*scrutinee, arms)),
+            _ => None,
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    // NOTE: Inner `match`es are lowered before the ones they're in, so the errors are sorted to be
+    // in the order they are in the file.
+    errors.sort_by_key(|error| error.location().start());
+    errors
+}
+
+/// A constructor that patterns can be made of.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Ctor {
+    Bool(bool),
+    Int(u64),
+
+    /// A floating-point literal, by its bits.
+    Float(u64),
+
+    /// A tuple with this many items.
+    Tuple(usize),
+
+    /// The `i`th constructor of the type declared by a `type`-statement.
+    Variant(StmtId, usize),
+}
+
+/// A pattern, as the algorithm sees it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+struct Checker<'a> {
+    file: &'a File,
+    resolution: &'a Resolution,
+}
+
+impl Checker<'_> {
+    fn match_expr(&self, scrutinee: ExprId, arms: &[pattern::Arm]) -> Vec<PatternError> {
+        let mut errors = vec![];
+        let mut rows: Vec<Vec<Pat>> = vec![];
+        let mut skipped = false;
+
+        for arm in arms {
+            // NOTE: If the arm is checked as if it were `_`, every arm after it would be
+            // unreachable, so it's skipped instead. Then what it was meant to match would seem
+            // missing, so the `match` isn't checked for that either.
+            let Some(pat) = self.lower(&arm.pattern) else {
+                skipped = true;
+                continue;
+            };
+            if self.useful(&rows, std::slice::from_ref(&pat)).is_empty() {
+                errors.push(PatternError::Unreachable {
+                    location: arm.pattern.location,
+                });
+            }
+            rows.push(vec![pat]);
+        }
+
+        let missing = match skipped {
+            true => vec![],
+            false => self.useful(&rows, &[Pat::Wild]),
+        };
+        if !missing.is_empty() {
+            errors.push(PatternError::NonExhaustive {
+                patterns: missing
+                    .iter()
+                    .take(SHOWN_MISSING)
+                    .map(|witness| self.write(&witness[0]))
+                    .collect(),
+                more: missing.len() > SHOWN_MISSING,
+                location: self.file[scrutinee].location,
+            });
+        }
+
+        errors
+    }
+
+    /// Turns a pattern of the AST into one the algorithm can use, unless it has a constructor that
+    /// couldn't be resolved.
+    fn lower(&self, pattern: &pattern::Pattern) -> Option<Pat> {
+        let pat = match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Binding(_) => Pat::Wild,
+            PatternKind::Literal(literal) => match self.file[*literal].kind {
+                ExprKind::Literal(Literal::Boolean(b)) => Pat::Ctor(Ctor::Bool(b), vec![]),
+                ExprKind::Literal(Literal::Integer(i)) => Pat::Ctor(Ctor::Int(i), vec![]),
+                ExprKind::Literal(Literal::Floating(f)) => Pat::Ctor(float(f), vec![]),
+                _ => Pat::Wild,
+            },
+            PatternKind::Tuple(items) => Pat::Ctor(
+                Ctor::Tuple(items.len()),
+                items
+                    .iter()
+                    .map(|item| self.lower(item))
+                    .collect::<Option<_>>()?,
+            ),
+            PatternKind::Constructor { constructor, args } => {
+                let (stmt, i) = self.variant(*constructor)?;
+                let mut args = args
+                    .iter()
+                    .map(|arg| self.lower(arg))
+                    .collect::<Option<Vec<_>>>()?;
+                args.resize(self.fields(stmt, i), Pat::Wild);
+                Pat::Ctor(Ctor::Variant(stmt, i), args)
+            }
+        };
+        Some(pat)
+    }
+
+    /// Gets the constructor a pattern uses, if it resolved to one.
+    fn variant(&self, constructor: ExprId) -> Option<(StmtId, usize)> {
+        let def = self.resolution.def_of(constructor)?;
+        match self.resolution.defs[def].kind {
+            DefKind::Constructor(stmt, i) => Some((stmt, i)),
+            _ => None,
+        }
+    }
+
+    /// Gets how many fields the `i`th constructor of a `type`-statement has.
+    fn fields(&self, stmt: StmtId, i: usize) -> usize {
+        match &self.file[stmt].kind {
+            StmtKind::Type { constructors, .. } => constructors[i].fields.len(),
+            _ => unreachable!("only `type`-statements have constructors"),
+        }
+    }
+
+    /// Gets how many patterns `ctor` is applied to.
+    fn arity(&self, ctor: Ctor) -> usize {
+        match ctor {
+            Ctor::Bool(_) | Ctor::Int(_) | Ctor::Float(_) => 0,
+            Ctor::Tuple(n) => n,
+            Ctor::Variant(stmt, i) => self.fields(stmt, i),
+        }
+    }
+
+    /// Finds the values that `q` matches, but none of `rows` do, written as rows of patterns. Only
+    /// a few more of them than are shown in an error are found, as there could be very many.
+    fn useful(&self, rows: &[Vec<Pat>], q: &[Pat]) -> Vec<Vec<Pat>> {
+        let Some((head, rest)) = q.split_first() else {
+            // NOTE: An empty row matches the one value there is, so it's only useful if no other
+            // row has matched it yet.
+            return match rows.is_empty() {
+                true => vec![vec![]],
+                false => vec![],
+            };
+        };
+
+        let mut witnesses = vec![];
+        match head {
+            Pat::Ctor(ctor, args) => {
+                let q = args.iter().chain(rest).cloned().collect::<Vec<_>>();
+                for witness in self.useful(&self.specialize(rows, *ctor), &q) {
+                    witnesses.push(rebuild(*ctor, args.len(), witness));
+                }
+            }
+            Pat::Wild => {
+                let (present, missing) = self.heads(rows);
+                for ctor in present {
+                    let arity = self.arity(ctor);
+                    let q = std::iter::repeat_n(Pat::Wild, arity)
+                        .chain(rest.iter().cloned())
+                        .collect::<Vec<_>>();
+                    for witness in self.useful(&self.specialize(rows, ctor), &q) {
+                        witnesses.push(rebuild(ctor, arity, witness));
+                    }
+                    if witnesses.len() > SHOWN_MISSING {
+                        break;
+                    }
+                }
+
+                // NOTE: The rows starting with a constructor can't match the missing ones, so only
+                // the rows starting with `_` are left.
+                if !missing.is_empty() && witnesses.len() <= SHOWN_MISSING {
+                    let defaults = rows
+                        .iter()
+                        .filter(|row| row[0] == Pat::Wild)
+                        .map(|row| row[1..].to_vec())
+                        .collect::<Vec<_>>();
+                    for witness in self.useful(&defaults, rest) {
+                        for head in &missing {
+                            let mut row = vec![head.clone()];
+                            row.extend(witness.iter().cloned());
+                            witnesses.push(row);
+                        }
+                    }
+                }
+            }
+        }
+
+        witnesses.truncate(SHOWN_MISSING + 1);
+        witnesses
+    }
+
+    /// Keeps the rows that match `ctor` at their head, with the head replaced by what's inside of
+    /// it. A `_` at the head matches anything inside of `ctor` too.
+    fn specialize(&self, rows: &[Vec<Pat>], ctor: Ctor) -> Vec<Vec<Pat>> {
+        let arity = self.arity(ctor);
+        rows.iter()
+            .filter_map(|row| {
+                let inner = match &row[0] {
+                    Pat::Wild => vec![Pat::Wild; arity],
+                    Pat::Ctor(head, args) if *head == ctor => args.clone(),
+                    Pat::Ctor(..) => return None,
+                };
+                Some(inner.into_iter().chain(row[1..].iter().cloned()).collect())
+            })
+            .collect()
+    }
+
+    /// Gets the constructors at the head of `rows` that have to be checked one by one, and
+    /// patterns for the values of their type that none of them match. When the constructors cover
+    /// their type, that's all of them, and nothing is missing.
+    fn heads(&self, rows: &[Vec<Pat>]) -> (Vec<Ctor>, Vec<Pat>) {
+        let mut ctors = vec![];
+        for row in rows {
+            match &row[0] {
+                Pat::Ctor(ctor, _) if !ctors.contains(ctor) => ctors.push(*ctor),
+                _ => {}
+            }
+        }
+
+        // NOTE: The patterns are all the same type, so the first constructor tells us which.
+        let all = match ctors.first() {
+            None => return (vec![], vec![Pat::Wild]),
+            Some(Ctor::Bool(_)) => vec![Ctor::Bool(false), Ctor::Bool(true)],
+            Some(&Ctor::Tuple(n)) => vec![Ctor::Tuple(n)],
+            Some(&Ctor::Variant(stmt, _)) => match &self.file[stmt].kind {
+                StmtKind::Type { constructors, .. } => (0..constructors.len())
+                    .map(|i| Ctor::Variant(stmt, i))
+                    .collect(),
+                _ => unreachable!("only `type`-statements have constructors"),
+            },
+            // NOTE: There are too many numbers for every one of them to be written out, so the
+            // smallest one that's missing stands for the rest.
+            Some(Ctor::Int(_)) => {
+                let smallest = (0..).find(|&i| !ctors.contains(&Ctor::Int(i)));
+                let missing = smallest.map(|i| Pat::Ctor(Ctor::Int(i), vec![]));
+                return (ctors, missing.into_iter().collect());
+            }
+            Some(Ctor::Float(_)) => return (ctors, vec![Pat::Wild]),
+        };
+
+        let missing = all
+            .iter()
+            .filter(|ctor| !ctors.contains(ctor))
+            .map(|&ctor| Pat::Ctor(ctor, vec![Pat::Wild; self.arity(ctor)]))
+            .collect::<Vec<_>>();
+        match missing.is_empty() {
+            true => (all, missing),
+            false => (ctors, missing),
+        }
+    }
+
+    /// Writes a pattern the way it would be written in the source.
+    fn write(&self, pat: &Pat) -> String {
+        let (ctor, args) = match pat {
+            Pat::Wild => return "_".to_string(),
+            Pat::Ctor(ctor, args) => (ctor, args),
+        };
+
+        match ctor {
+            Ctor::Bool(true) => "True".to_string(),
+            Ctor::Bool(false) => "False".to_string(),
+            Ctor::Int(i) => i.to_string(),
+            Ctor::Float(bits) => f64::from_bits(*bits).to_string(),
+            Ctor::Tuple(_) => {
+                let items = args.iter().map(|arg| self.write(arg)).collect::<Vec<_>>();
+                format!("({})", items.join(", "))
+            }
+            &Ctor::Variant(stmt, i) => {
+                let StmtKind::Type { constructors, .. } = &self.file[stmt].kind else {
+                    unreachable!("only `type`-statements have constructors");
+                };
+                let mut written = constructors[i].name.ident.to_string();
+                for arg in args {
+                    match arg {
+                        Pat::Ctor(Ctor::Variant(..), args) if !args.is_empty() => {
+                            written.push_str(&format!(" ({})", self.write(arg)))
+                        }
+                        _ => written.push_str(&format!(" {}", self.write(arg))),
+                    }
+                }
+                written
+            }
+        }
+    }
+}
+
+/// Gets the constructor of a floating-point literal. `-0.0` and `0.0` are equal, but their bits
+/// aren't, so `-0.0` becomes `0.0` first.
+fn float(f: f64) -> Ctor {
+    Ctor::Float((f + 0.0).to_bits())
+}
+
+/// Puts the first `arity` patterns of a witness back into `ctor`, undoing `specialize`.
+fn rebuild(ctor: Ctor, arity: usize, mut witness: Vec<Pat>) -> Vec<Pat> {
+    let rest = witness.split_off(arity);
+    let mut row = vec![Pat::Ctor(ctor, witness)];
+    row.extend(rest);
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{lower::lower, parser::parse, resolve::resolve};
+
+    /// Parses, lowers and resolves `source`, and checks its `match`es.
+    fn checked(source: &str) -> Vec<String> {
+        let (file, errors) = lower(&parse(source).unwrap());
+        assert_eq!(errors, []);
+        let (resolution, errors) = resolve(&file);
+        assert_eq!(errors, []);
+        check(&file, &resolution)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn can_accept_exhaustive_matches() {
+        let source = "type List[a] = Nil | Cons a (List a)\nlet len = |xs| match xs { Nil => 0, Cons _ rest => 1 }\nlet both = |p| match p { (True, True) => 1, (False, _) => 2, (_, False) => 3 }\nlet n = |x| match x { 0 => 1, n => n }\n";
+        assert_eq!(checked(source), Vec::<String>::new());
+    }
+
+    #[test]
+    fn can_find_missing_patterns() {
+        let source = "type Shape = Circle F64 | Rect F64 F64 | Dot\nlet area = |s| match s { Circle r => r }\nlet f = |b| match b { True => 1 }\nlet g = |x| match x { 0 => 1, 1 => 2 }\nlet h = |xs| match xs { (Dot, True) => 1, (Circle _, _) => 2 }\nlet i = |x| match x {}\n";
+        assert_eq!(
+            checked(source),
+            [
+                "Patterns `Rect _ _` and `Dot` not covered",
+                "Pattern `False` not covered",
+                "Pattern `2` not covered",
+                "Patterns `(Dot, False)` and `(Rect _ _, _)` not covered",
+                "Pattern `_` not covered",
+            ]
+        );
+    }
+
+    #[test]
+    fn can_find_nested_missing_patterns() {
+        let source = "type List[a] = Nil | Cons a (List a)\nlet f = |xs| match xs { Nil => 0, Cons _ Nil => 1 }\nlet g = |x| match x { (1, _) => 0, (_, 2) => 1 }\n";
+        assert_eq!(
+            checked(source),
+            [
+                "Pattern `Cons _ (Cons _ _)` not covered",
+                "Pattern `(0, 0)` not covered",
+            ]
+        );
+    }
+
+    #[test]
+    fn can_find_unreachable_arms() {
+        let source = "let f = |x| match x { _ => 0, 1 => 1 }\nlet g = |b| match b { True => 0, False => 1, True => 2 }\n";
+        let (file, errors) = lower(&parse(source).unwrap());
+        assert_eq!(errors, []);
+        let (resolution, _) = resolve(&file);
+        assert_eq!(
+            check(&file, &resolution),
+            [
+                PatternError::Unreachable {
+                    location: (30..31).into(),
+                },
+                PatternError::Unreachable {
+                    location: (84..88).into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_list_some_missing_patterns() {
+        let source =
+            "type Color = Red | Green | Blue | Black | White\nlet f = |c| match c { Red => 0 }\n";
+        assert_eq!(
+            checked(source),
+            ["Patterns `Green`, `Blue`, `Black` and more not covered"]
+        );
+    }
+
+    #[test]
+    fn can_skip_unresolved_constructors() {
+        let source = "type Shape = Circle | Dot\nlet f = |s| match s { Circel => 0, Dot => 1, Circle => 2 }\nlet g = |s| match s { (Dto, _) => 0, (Dot, True) => 1 }\n";
+        let (file, errors) = lower(&parse(source).unwrap());
+        assert_eq!(errors, []);
+        let (resolution, errors) = resolve(&file);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(check(&file, &resolution), []);
+    }
+
+    #[test]
+    fn can_compare_signed_zeros() {
+        assert_eq!(float(-0.0), float(0.0));
+        assert_ne!(float(1.0), float(-1.0));
+    }
+}
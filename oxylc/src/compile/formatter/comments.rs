@@ -0,0 +1,129 @@
+//! Puts the comments of a file back into the formatted code. Comments aren't part of the FST's
+//! nodes, so as the formatter lays out each node, it first takes every comment that was before it
+//! in the source.
+
+use super::doc::Doc;
+use crate::repr::fst::{
+    trivia::{Trivia, TriviaKind},
+    FstNode,
+};
+
+/// The comments of a file that haven't been formatted yet, in order.
+pub struct Comments<'a> {
+    source: &'a str,
+    comments: Vec<&'a Trivia>,
+    next: usize,
+}
+
+impl<'a> Comments<'a> {
+    /// Gets the comments out of the trivia of a file.
+    pub fn new(source: &'a str, trivia: &'a [Trivia]) -> Self {
+        Self {
+            source,
+            comments: trivia
+                .iter()
+                .filter(|trivia| trivia.kind != TriviaKind::Whitespace)
+                .collect(),
+            next: 0,
+        }
+    }
+
+    /// Where the next comment starts, if there is one.
+    pub fn peek(&self) -> Option<usize> {
+        self.comments
+            .get(self.next)
+            .map(|comment| comment.location().start())
+    }
+
+    /// Skips over the comments before `offset`, without formatting them.
+    pub fn skip_before(&mut self, offset: usize) {
+        while self.peek().is_some_and(|start| start < offset) {
+            self.next += 1;
+        }
+    }
+
+    /// Formats the comments before `offset`, to go before whatever is at `offset`. Comments on
+    /// their own line stay on their own line, as do the blank lines after them.
+    pub fn take_before(&mut self, offset: usize) -> Doc {
+        let mut docs = vec![];
+
+        while let Some(comment) = self.next_before(offset) {
+            docs.push(Doc::text(comment.text.as_str()));
+
+            let end = comment.location().end();
+            if comment.kind == TriviaKind::LineComment || self.is_own_line(comment) {
+                docs.push(Doc::HardLine);
+                if self.is_blank_between(end, self.peek().unwrap_or(offset).min(offset)) {
+                    docs.push(Doc::HardLine);
+                }
+            } else {
+                docs.push(Doc::text(" "));
+            }
+        }
+
+        Doc::Concat(docs)
+    }
+
+    /// Formats the comments before `offset`, to go after whatever came before them, like before a
+    /// closing `}` or at the end of the file.
+    pub fn take_trailing(&mut self, offset: usize) -> Doc {
+        let mut docs = vec![];
+
+        while let Some(comment) = self.next_before(offset) {
+            let start = comment.location().start();
+            match self.is_own_line(comment) {
+                true if self.is_blank_between(self.source[..start].trim_end().len(), start) => {
+                    docs.extend([Doc::HardLine, Doc::HardLine])
+                }
+                true => docs.push(Doc::HardLine),
+                false => docs.push(Doc::text(" ")),
+            }
+            docs.push(Doc::text(comment.text.as_str()));
+
+            if comment.kind == TriviaKind::LineComment {
+                docs.push(Doc::BreakParent);
+            }
+        }
+
+        Doc::Concat(docs)
+    }
+
+    /// Formats the comments on the same line as `offset`, to go after whatever ends at `offset`.
+    pub fn take_same_line(&mut self, offset: usize) -> Doc {
+        let line_end = self.source[offset..]
+            .find('\n')
+            .map_or(self.source.len(), |i| offset + i);
+        self.take_trailing(line_end)
+    }
+
+    /// Where the last comment that was formatted ends, if any were.
+    pub fn taken_end(&self) -> Option<usize> {
+        self.next
+            .checked_sub(1)
+            .map(|last| self.comments[last].location().end())
+    }
+
+    /// Whether there's a blank line between two offsets in the source.
+    pub fn is_blank_between(&self, start: usize, end: usize) -> bool {
+        self.source
+            .get(start..end)
+            .is_some_and(|between| between.matches('\n').count() >= 2)
+    }
+
+    fn next_before(&mut self, offset: usize) -> Option<&'a Trivia> {
+        let comment = *self.comments.get(self.next)?;
+        if comment.location().start() >= offset {
+            return None;
+        }
+
+        self.next += 1;
+        Some(comment)
+    }
+
+    /// Whether a comment is the first thing on its line.
+    fn is_own_line(&self, comment: &Trivia) -> bool {
+        let before = &self.source[..comment.location().start()];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        before[line_start..].trim().is_empty()
+    }
+}
@@ -0,0 +1,208 @@
+//! The document model the formatter lays code out with, after Wadler's "A prettier printer". A
+//! `Doc` describes every way some code could be printed: each `Group` is either printed flat, on
+//! one line, or broken, with every `Line` directly inside of it turned into a newline. Groups are
+//! only broken when they don't fit in the width, outermost first.
+
+/// A document to print.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Doc {
+    /// Nothing at all.
+    #[default]
+    Nil,
+
+    /// Some text. This should never have a newline in it, use `HardLine` instead.
+    Text(String),
+
+    /// A space, or a newline if the group it's in is broken.
+    Line,
+
+    /// Nothing, or a newline if the group it's in is broken.
+    SoftLine,
+
+    /// Always a newline. This breaks every group it's in.
+    HardLine,
+
+    /// Prints nothing, but breaks every group it's in, like after a `//` comment.
+    BreakParent,
+
+    /// Documents printed one after another.
+    Concat(Vec<Doc>),
+
+    /// A document where every newline is indented by some more spaces.
+    Nest(usize, Box<Doc>),
+
+    /// A document that is printed flat if it fits, or broken otherwise.
+    Group(Box<Doc>),
+
+    /// The first document if the group it's in is broken, or the second otherwise.
+    IfBreak(Box<Doc>, Box<Doc>),
+}
+
+impl Doc {
+    /// Creates a `Text` document.
+    pub fn text(text: impl Into<String>) -> Self {
+        Doc::Text(text.into())
+    }
+
+    /// Creates a `Concat` document.
+    pub fn concat(docs: impl IntoIterator<Item = Doc>) -> Self {
+        Doc::Concat(docs.into_iter().collect())
+    }
+
+    /// Puts `separator` between every one of `docs`.
+    pub fn join(docs: impl IntoIterator<Item = Doc>, separator: Doc) -> Self {
+        let mut joined = vec![];
+        for (i, doc) in docs.into_iter().enumerate() {
+            if i != 0 {
+                joined.push(separator.clone());
+            }
+            joined.push(doc);
+        }
+        Doc::Concat(joined)
+    }
+
+    /// Creates an `IfBreak` document.
+    pub fn if_break(broken: Doc, flat: Doc) -> Self {
+        Doc::IfBreak(Box::new(broken), Box::new(flat))
+    }
+
+    /// Indents the newlines in this document by `indent` more spaces.
+    pub fn nest(self, indent: usize) -> Self {
+        Doc::Nest(indent, Box::new(self))
+    }
+
+    /// Puts this document in a group.
+    pub fn group(self) -> Self {
+        Doc::Group(Box::new(self))
+    }
+
+    /// Prints the document, breaking groups so that it fits within `width` columns where it can.
+    /// Lines never end with spaces.
+    pub fn render(&self, width: usize) -> String {
+        let mut out = String::new();
+        let mut column = 0;
+        // NOTE: Indentation is only written once there's text on the line, so that blank lines
+        // stay empty.
+        let mut pending_indent = None;
+        let mut stack = vec![(0, Mode::Break, self)];
+
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Nil | Doc::BreakParent => {}
+                Doc::Text(text) => {
+                    if let Some(indent) = pending_indent.take() {
+                        out.push_str(&" ".repeat(indent));
+                    }
+                    out.push_str(text);
+                    column += text.chars().count();
+                }
+                Doc::Line if mode == Mode::Flat => {
+                    out.push(' ');
+                    column += 1;
+                }
+                Doc::SoftLine if mode == Mode::Flat => {}
+                Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                    let trimmed = out.trim_end_matches(' ').len();
+                    out.truncate(trimmed);
+                    out.push('\n');
+                    pending_indent = Some(indent);
+                    column = indent;
+                }
+                Doc::Concat(docs) => {
+                    stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+                }
+                Doc::Nest(more, doc) => stack.push((indent + more, mode, doc)),
+                Doc::Group(doc) => {
+                    let mode = match mode == Mode::Flat || fits(doc, width.saturating_sub(column)) {
+                        true => Mode::Flat,
+                        false => Mode::Break,
+                    };
+                    stack.push((indent, mode, doc));
+                }
+                Doc::IfBreak(broken, flat) => match mode {
+                    Mode::Break => stack.push((indent, mode, broken)),
+                    Mode::Flat => stack.push((indent, mode, flat)),
+                },
+            }
+        }
+
+        out
+    }
+}
+
+/// Whether a group is being printed flat or broken.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Whether a document fits in `width` columns when printed flat. Documents that can't be flat
+/// never fit.
+fn fits(doc: &Doc, mut width: usize) -> bool {
+    let mut stack = vec![doc];
+
+    while let Some(doc) = stack.pop() {
+        match doc {
+            Doc::Nil | Doc::SoftLine => {}
+            Doc::HardLine | Doc::BreakParent => return false,
+            Doc::Text(text) => match width.checked_sub(text.chars().count()) {
+                Some(left) => width = left,
+                None => return false,
+            },
+            Doc::Line => match width.checked_sub(1) {
+                Some(left) => width = left,
+                None => return false,
+            },
+            Doc::Concat(docs) => stack.extend(docs.iter().rev()),
+            Doc::Nest(_, doc) | Doc::Group(doc) => stack.push(doc),
+            Doc::IfBreak(_, flat) => stack.push(flat),
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list() -> Doc {
+        Doc::concat([
+            Doc::text("["),
+            Doc::concat([
+                Doc::SoftLine,
+                Doc::join(
+                    ["a", "b", "c"].map(Doc::text),
+                    Doc::concat([Doc::text(","), Doc::Line]),
+                ),
+                Doc::if_break(Doc::text(","), Doc::Nil),
+            ])
+            .nest(2),
+            Doc::SoftLine,
+            Doc::text("]"),
+        ])
+        .group()
+    }
+
+    #[test]
+    fn can_render_flat_group() {
+        assert_eq!(list().render(9), "[a, b, c]");
+    }
+
+    #[test]
+    fn can_render_broken_group() {
+        assert_eq!(list().render(8), "[\n  a,\n  b,\n  c,\n]");
+    }
+
+    #[test]
+    fn hard_lines_break_groups() {
+        let doc = Doc::concat([Doc::text("a"), Doc::Line, Doc::BreakParent]).group();
+        assert_eq!(doc.render(80), "a\n");
+
+        let doc = Doc::concat([Doc::text("a"), Doc::HardLine, Doc::HardLine, Doc::text("b")])
+            .nest(4)
+            .group();
+        assert_eq!(doc.render(80), "a\n\n    b");
+    }
+}
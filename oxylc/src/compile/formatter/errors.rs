@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FormatError {
+    #[error("Couldn't read `{}`: {message}", path.display())]
+    Io { path: PathBuf, message: String },
+
+    #[error("Couldn't parse the file to format: {0}")]
+    Parse(String),
+
+    #[error("Can't format {start}..{end}, as it isn't a range of characters in the file")]
+    InvalidRange { start: usize, end: usize },
+}
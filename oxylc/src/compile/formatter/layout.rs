@@ -0,0 +1,642 @@
+//! Lays out every node in the FST as a `Doc`.
+
+use super::{comments::Comments, doc::Doc, FormatConfig};
+use crate::{
+    compile::printer::tokens::operator_text,
+    repr::fst::{
+        attribute::{Attribute, AttributeArgKind},
+        block::Block,
+        doc_comment::{DocComment, DocCommentKind},
+        expression::{Expression, ExpressionKind, Operator, OperatorKind},
+        function::Function,
+        identifier::Identifier,
+        pattern::{Match, Pattern, PatternKind},
+        statement::{Statement, StatementKind, Visibility},
+        type_expr::{TypeExpr, TypeExprKind, TypeParams},
+        use_tree::{UseTree, UseTreeKind},
+        value::{Value, ValueKind},
+        visitor::FstVisitor,
+        File, FstNode,
+    },
+};
+
+/// Lays out the nodes of a file, along with its comments.
+pub struct Formatter<'a> {
+    pub comments: Comments<'a>,
+    indent: usize,
+}
+
+impl<'a> Formatter<'a> {
+    /// Creates a new `Formatter` for a file parsed from `source`.
+    pub fn new(source: &'a str, file: &'a File, config: &FormatConfig) -> Self {
+        Self {
+            comments: Comments::new(source, &file.trivia),
+            indent: config.indent,
+        }
+    }
+
+    /// Lays out statements one per line, keeping (single) blank lines between them.
+    pub fn statements(&mut self, statements: &[Statement]) -> Doc {
+        let mut docs = vec![];
+        let mut last_end = None;
+
+        for statement in statements {
+            if let Some(last_end) = last_end {
+                let start = leading_start(statement);
+                let start = self.comments.peek().map_or(start, |next| next.min(start));
+
+                docs.push(Doc::HardLine);
+                if self.comments.is_blank_between(last_end, start) {
+                    docs.push(Doc::HardLine);
+                }
+            }
+
+            let end = content_end(statement);
+            docs.push(self.visit_statement(statement));
+            docs.push(self.comments.take_same_line(end));
+            last_end = Some(end);
+        }
+
+        Doc::Concat(docs)
+    }
+
+    /// Lays out a chain of the same left-associative operator, like `a |> b |> c` or `f a b`, with
+    /// one operand per line if it doesn't fit.
+    fn chain(&mut self, expression: &Expression, kind: OperatorKind) -> Doc {
+        let mut rest = vec![];
+        let mut first = expression;
+
+        loop {
+            match &first.kind {
+                ExpressionKind::Infix { operator, lhs, rhs } if operator.kind() == kind => {
+                    rest.push((Some(*operator), rhs.as_ref()));
+                    first = lhs;
+                }
+                ExpressionKind::Application { function, arg }
+                    if kind == OperatorKind::Application =>
+                {
+                    rest.push((None, arg.as_ref()));
+                    first = function;
+                }
+                _ => break,
+            }
+        }
+
+        let first = self.visit_expression(first);
+        let rest = rest
+            .into_iter()
+            .rev()
+            .map(|(operator, operand)| {
+                let operator = match operator {
+                    Some(operator) if operator.kind() != OperatorKind::Application => {
+                        self.operator(&operator, true)
+                    }
+                    _ => Doc::Nil,
+                };
+                Doc::concat([Doc::Line, operator, self.visit_expression(operand)])
+            })
+            .collect::<Vec<_>>();
+
+        Doc::concat([first, Doc::Concat(rest).nest(self.indent)]).group()
+    }
+
+    /// Lays out an operator, with the comments before it.
+    fn operator(&mut self, operator: &Operator, space_after: bool) -> Doc {
+        Doc::concat([
+            self.comments.take_before(operator.location().start()),
+            Doc::text(operator_text(operator)),
+            match space_after {
+                true => Doc::text(" "),
+                false => Doc::Nil,
+            },
+        ])
+    }
+
+    /// Lays out a comma-separated list between `open` and `close`, which ends at `end`. If it
+    /// doesn't fit, it has one item per line, each with a trailing comma.
+    fn list(&mut self, open: &str, items: Vec<Doc>, end: usize, close: &str) -> Doc {
+        let trailing = self.comments.take_trailing(end);
+        if items.is_empty() {
+            return Doc::concat([Doc::text(open), trailing, Doc::text(close)]).group();
+        }
+
+        Doc::concat([
+            Doc::text(open),
+            Doc::concat([
+                Doc::SoftLine,
+                Doc::join(items, Doc::concat([Doc::text(","), Doc::Line])),
+                Doc::if_break(Doc::text(","), Doc::Nil),
+                trailing,
+            ])
+            .nest(self.indent),
+            Doc::SoftLine,
+            Doc::text(close),
+        ])
+        .group()
+    }
+}
+
+impl FstVisitor<Doc> for Formatter<'_> {
+    fn visit_file(&mut self, file: &File) -> Doc {
+        if file.statements.is_empty() {
+            return self.comments.take_before(usize::MAX);
+        }
+
+        Doc::concat([
+            self.statements(&file.statements),
+            self.comments.take_trailing(usize::MAX),
+            Doc::HardLine,
+        ])
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) -> Doc {
+        let mut docs = vec![];
+
+        for decoration in decorations(statement) {
+            match decoration {
+                Ok(doc) => {
+                    docs.push(self.comments.take_before(doc.location.start()));
+                    docs.push(self.visit_doc_comment(doc));
+                }
+                Err(attribute) => {
+                    docs.push(self.comments.take_before(attribute.location.start()));
+                    docs.push(self.visit_attribute(attribute));
+                }
+            }
+            docs.push(Doc::HardLine);
+        }
+
+        match statement.visibility {
+            Visibility::Public { location } => {
+                docs.push(self.comments.take_before(location.start()));
+                docs.push(Doc::text("pub "));
+            }
+            Visibility::Private => docs.push(self.comments.take_before(statement.location.start())),
+        }
+
+        match &statement.kind {
+            StatementKind::Let {
+                is_mutable,
+                ident,
+                signature,
+                expression,
+            } => {
+                docs.push(Doc::text(match is_mutable {
+                    true => "let mut ",
+                    false => "let ",
+                }));
+                docs.push(self.visit_ident(ident));
+                if let Some(signature) = signature {
+                    if let Some(params) = &signature.params {
+                        docs.push(self.visit_type_params(params));
+                    }
+                    docs.push(Doc::text(": "));
+                    docs.push(self.visit_type_expr(&signature.ty));
+                }
+                docs.push(Doc::text(" = "));
+                docs.push(self.visit_expression(expression));
+            }
+            StatementKind::Mod { ident } => {
+                docs.push(Doc::text("mod "));
+                docs.push(self.visit_ident(ident));
+            }
+            StatementKind::Use { tree } => {
+                docs.push(Doc::text("use "));
+                docs.push(self.visit_use_tree(tree));
+            }
+            StatementKind::Impl {
+                trait_ident,
+                type_ident,
+                expression,
+            } => {
+                docs.push(Doc::text("impl "));
+                docs.push(self.visit_ident(trait_ident));
+                docs.push(Doc::text(" for "));
+                docs.push(self.visit_ident(type_ident));
+                docs.push(Doc::text(" = "));
+                docs.push(self.visit_expression(expression));
+            }
+            StatementKind::Type {
+                ident,
+                params,
+                constructors,
+            } => {
+                docs.push(Doc::text("type "));
+                docs.push(self.visit_ident(ident));
+                if let Some(params) = params {
+                    docs.push(self.visit_type_params(params));
+                }
+                docs.push(Doc::text(" = "));
+
+                // NOTE: If they don't fit on one line, every constructor after the first one goes
+                // on its own line, starting with its `|`.
+                let mut constructors = constructors.iter().map(|constructor| {
+                    let fields = constructor
+                        .fields
+                        .iter()
+                        .map(|field| Doc::concat([Doc::text(" "), self.visit_type_expr(field)]))
+                        .collect::<Vec<_>>();
+                    Doc::concat([self.visit_ident(&constructor.ident), Doc::Concat(fields)])
+                });
+                let first = constructors.next().unwrap_or(Doc::Nil);
+                let rest = constructors
+                    .map(|constructor| Doc::concat([Doc::Line, Doc::text("| "), constructor]))
+                    .collect::<Vec<_>>();
+                docs.push(Doc::concat([first, Doc::Concat(rest).nest(self.indent)]).group());
+            }
+        }
+
+        Doc::Concat(docs)
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> Doc {
+        let comments = self.comments.take_before(expression.location.start());
+
+        let doc = match &expression.kind {
+            ExpressionKind::Parenthesized {
+                inner,
+                rparen_location,
+                ..
+            } => Doc::concat([
+                Doc::text("("),
+                Doc::concat([
+                    Doc::SoftLine,
+                    self.visit_expression(inner),
+                    self.comments.take_trailing(rparen_location.start()),
+                ])
+                .nest(self.indent),
+                Doc::SoftLine,
+                Doc::text(")"),
+            ])
+            .group(),
+            ExpressionKind::Tuple {
+                items,
+                rparen_location,
+                ..
+            } => {
+                let items = items
+                    .iter()
+                    .map(|item| self.visit_expression(item))
+                    .collect();
+                self.list("(", items, rparen_location.start(), ")")
+            }
+            ExpressionKind::Value(value) => self.visit_value(value),
+            ExpressionKind::Block(block) => self.visit_block(block),
+            ExpressionKind::Match(m) => self.visit_match(m),
+            ExpressionKind::Infix { operator, .. }
+                if matches!(
+                    operator.kind(),
+                    OperatorKind::Triangle | OperatorKind::Application
+                ) =>
+            {
+                self.chain(expression, operator.kind())
+            }
+            ExpressionKind::Application { .. } => self.chain(expression, OperatorKind::Application),
+            ExpressionKind::Infix { operator, lhs, rhs }
+                if matches!(
+                    operator.kind(),
+                    OperatorKind::Dot | OperatorKind::DoubleColon
+                ) =>
+            {
+                Doc::concat([
+                    self.visit_expression(lhs),
+                    self.operator(operator, false),
+                    self.visit_expression(rhs),
+                ])
+            }
+            ExpressionKind::Infix { operator, lhs, rhs } => Doc::concat([
+                self.visit_expression(lhs),
+                Doc::text(" "),
+                self.operator(operator, false),
+                Doc::concat([Doc::Line, self.visit_expression(rhs)]).nest(self.indent),
+            ])
+            .group(),
+            ExpressionKind::Prefix { operator, rhs } => {
+                Doc::concat([self.operator(operator, false), self.visit_expression(rhs)])
+            }
+            ExpressionKind::Postfix { operator, lhs } => {
+                Doc::concat([self.visit_expression(lhs), self.operator(operator, false)])
+            }
+        };
+
+        Doc::concat([comments, doc])
+    }
+
+    fn visit_type_expr(&mut self, ty: &TypeExpr) -> Doc {
+        let comments = self.comments.take_before(ty.location.start());
+
+        let doc = match &ty.kind {
+            TypeExprKind::Named { ident, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| Doc::concat([Doc::text(" "), self.visit_type_expr(arg)]))
+                    .collect::<Vec<_>>();
+                Doc::concat([self.visit_ident(ident), Doc::Concat(args)])
+            }
+            TypeExprKind::Unit => Doc::text("()"),
+            TypeExprKind::Parenthesized { inner, .. } => {
+                Doc::concat([Doc::text("("), self.visit_type_expr(inner), Doc::text(")")])
+            }
+            TypeExprKind::Tuple {
+                items,
+                rparen_location,
+                ..
+            } => {
+                let items = items
+                    .iter()
+                    .map(|item| self.visit_type_expr(item))
+                    .collect();
+                self.list("(", items, rparen_location.start(), ")")
+            }
+            TypeExprKind::Function { param, result, .. } => Doc::concat([
+                self.visit_type_expr(param),
+                Doc::text(" -> "),
+                self.visit_type_expr(result),
+            ]),
+        };
+
+        Doc::concat([comments, doc])
+    }
+
+    fn visit_type_params(&mut self, params: &TypeParams) -> Doc {
+        let items = params
+            .params
+            .iter()
+            .map(|param| {
+                let bounds = param
+                    .bounds
+                    .iter()
+                    .map(|bound| self.visit_ident(bound))
+                    .collect::<Vec<_>>();
+                let bounds = match bounds.is_empty() {
+                    true => Doc::Nil,
+                    false => Doc::concat([Doc::text(": "), Doc::join(bounds, Doc::text(" + "))]),
+                };
+                Doc::concat([self.visit_ident(&param.ident), bounds])
+            })
+            .collect();
+        self.list("[", items, params.location.end() - 1, "]")
+    }
+
+    fn visit_value(&mut self, value: &Value) -> Doc {
+        match &value.kind {
+            ValueKind::Identifier(ident) => self.visit_ident(ident),
+            ValueKind::Function(function) => self.visit_function(function),
+            kind => Doc::text(
+                value
+                    .raw
+                    .clone()
+                    .or_else(|| kind.literal())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &Identifier) -> Doc {
+        Doc::concat([
+            self.comments.take_before(ident.location.start()),
+            Doc::text(ident.name.as_str()),
+        ])
+    }
+
+    fn visit_function(&mut self, function: &Function) -> Doc {
+        let args = function
+            .args
+            .iter()
+            .map(|arg| self.visit_ident(arg))
+            .collect::<Vec<_>>();
+
+        Doc::concat([
+            Doc::text("|"),
+            Doc::join(args, Doc::text(", ")),
+            Doc::text("| "),
+            self.visit_expression(&function.expression),
+        ])
+    }
+
+    fn visit_block(&mut self, block: &Block) -> Doc {
+        let end = block.location.end() - 1;
+
+        if block.statements.is_empty() {
+            return Doc::concat([
+                Doc::text("{"),
+                Doc::concat([
+                    Doc::Line,
+                    self.visit_expression(&block.expression),
+                    self.comments.take_trailing(end),
+                ])
+                .nest(self.indent),
+                Doc::Line,
+                Doc::text("}"),
+            ])
+            .group();
+        }
+
+        let statements = self.statements(&block.statements);
+        let last_end = block.statements.last().map_or(0, content_end);
+        let start = block.expression.location.start();
+        let start = self.comments.peek().map_or(start, |next| next.min(start));
+        let blank = match self.comments.is_blank_between(last_end, start) {
+            true => Doc::HardLine,
+            false => Doc::Nil,
+        };
+
+        Doc::concat([
+            Doc::text("{"),
+            Doc::concat([
+                Doc::HardLine,
+                statements,
+                Doc::HardLine,
+                blank,
+                self.visit_expression(&block.expression),
+                self.comments.take_trailing(end),
+            ])
+            .nest(self.indent),
+            Doc::HardLine,
+            Doc::text("}"),
+        ])
+    }
+
+    fn visit_match(&mut self, m: &Match) -> Doc {
+        let end = m.location.end() - 1;
+        let scrutinee = Doc::concat([
+            Doc::text("match "),
+            self.visit_expression(&m.scrutinee),
+            Doc::text(" {"),
+        ]);
+        if m.arms.is_empty() {
+            let trailing = self.comments.take_trailing(end);
+            return Doc::concat([scrutinee, trailing, Doc::text("}")]).group();
+        }
+
+        // NOTE: If the arms don't fit on one line, each one goes on its own line, without a comma.
+        let arms = m
+            .arms
+            .iter()
+            .map(|arm| {
+                Doc::concat([
+                    self.visit_pattern(&arm.pattern),
+                    Doc::text(" => "),
+                    self.visit_expression(&arm.expression),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let separator = Doc::concat([Doc::if_break(Doc::Nil, Doc::text(",")), Doc::Line]);
+
+        Doc::concat([
+            scrutinee,
+            Doc::concat([
+                Doc::Line,
+                Doc::join(arms, separator),
+                self.comments.take_trailing(end),
+            ])
+            .nest(self.indent),
+            Doc::Line,
+            Doc::text("}"),
+        ])
+        .group()
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) -> Doc {
+        let comments = self.comments.take_before(pattern.location.start());
+
+        let doc = match &pattern.kind {
+            PatternKind::Named { ident, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| Doc::concat([Doc::text(" "), self.visit_pattern(arg)]))
+                    .collect::<Vec<_>>();
+                Doc::concat([self.visit_ident(ident), Doc::Concat(args)])
+            }
+            PatternKind::Value(value) => self.visit_value(value),
+            PatternKind::Parenthesized { inner, .. } => {
+                Doc::concat([Doc::text("("), self.visit_pattern(inner), Doc::text(")")])
+            }
+            PatternKind::Tuple {
+                items,
+                rparen_location,
+                ..
+            } => {
+                let items = items.iter().map(|item| self.visit_pattern(item)).collect();
+                self.list("(", items, rparen_location.start(), ")")
+            }
+        };
+
+        Doc::concat([comments, doc])
+    }
+
+    fn visit_attribute(&mut self, attribute: &Attribute) -> Doc {
+        let name = self.visit_ident(&attribute.name);
+        let args = match attribute.parens {
+            Some((_, rparen)) => {
+                let args = attribute
+                    .args
+                    .iter()
+                    .map(|arg| match &arg.kind {
+                        AttributeArgKind::String(string) => Doc::concat([
+                            self.comments.take_before(arg.location.start()),
+                            Doc::text(format!("\"{}\"", string)),
+                        ]),
+                        AttributeArgKind::Value(value) => Doc::concat([
+                            self.comments.take_before(value.location.start()),
+                            self.visit_value(value),
+                        ]),
+                    })
+                    .collect();
+                self.list("(", args, rparen.start(), ")")
+            }
+            None => Doc::Nil,
+        };
+
+        Doc::concat([Doc::text("#["), name, args, Doc::text("]")])
+    }
+
+    fn visit_doc_comment(&mut self, doc_comment: &DocComment) -> Doc {
+        match doc_comment.kind {
+            DocCommentKind::Line => Doc::text(format!("///{}", doc_comment.content)),
+            DocCommentKind::Block => Doc::text(format!("/**{}*/", doc_comment.content)),
+        }
+    }
+
+    fn visit_use_tree(&mut self, tree: &UseTree) -> Doc {
+        let mut docs = vec![];
+
+        match &tree.kind {
+            UseTreeKind::Simple { alias } => {
+                let path = tree
+                    .path
+                    .iter()
+                    .map(|ident| self.visit_ident(ident))
+                    .collect::<Vec<_>>();
+                docs.push(Doc::join(path, Doc::text("::")));
+
+                if let Some(alias) = alias {
+                    docs.push(Doc::text(" as "));
+                    docs.push(self.visit_ident(alias));
+                }
+            }
+            UseTreeKind::Group(trees) => {
+                for ident in &tree.path {
+                    docs.push(self.visit_ident(ident));
+                    docs.push(Doc::text("::"));
+                }
+
+                let items = trees.iter().map(|tree| self.visit_use_tree(tree)).collect();
+                docs.push(self.list("{", items, tree.location.end() - 1, "}"));
+            }
+        }
+
+        Doc::Concat(docs)
+    }
+}
+
+/// The doc comments and attributes of a statement, in the order they were written.
+fn decorations(statement: &Statement) -> Vec<Result<&DocComment, &Attribute>> {
+    let mut decorations = statement
+        .docs
+        .iter()
+        .map(Ok)
+        .chain(statement.attributes.iter().map(Err))
+        .collect::<Vec<_>>();
+    decorations.sort_by_key(|decoration| match decoration {
+        Ok(doc) => doc.location.start(),
+        Err(attribute) => attribute.location.start(),
+    });
+    decorations
+}
+
+/// Where a statement starts, including its doc comments and attributes.
+pub fn leading_start(statement: &Statement) -> usize {
+    let start = match statement.visibility {
+        Visibility::Public { location } => location.start(),
+        Visibility::Private => statement.location.start(),
+    };
+
+    decorations(statement)
+        .iter()
+        .map(|decoration| match decoration {
+            Ok(doc) => doc.location.start(),
+            Err(attribute) => attribute.location.start(),
+        })
+        .fold(start, usize::min)
+}
+
+/// Where the code of a statement ends. Unlike its location, this doesn't include the line ending.
+pub fn content_end(statement: &Statement) -> usize {
+    match &statement.kind {
+        StatementKind::Let { expression, .. } | StatementKind::Impl { expression, .. } => {
+            expression.location.end()
+        }
+        StatementKind::Mod { ident } => ident.location.end(),
+        StatementKind::Use { tree } => tree.location.end(),
+        StatementKind::Type {
+            ident,
+            constructors,
+            ..
+        } => constructors
+            .last()
+            .map_or(ident.location.end(), |constructor| {
+                constructor.location.end()
+            }),
+    }
+}
@@ -0,0 +1,341 @@
+//! The formatter, which lays out Oxyl code in one standard style. The FST of the code is turned
+//! into a `Doc` (see `doc`), which is then printed to fit within the configured width:
+//! ```
+//! use oxylc::compile::formatter::{format, FormatConfig};
+//!
+//! let source = "let   y = x|>f  // done\n";
+//! assert_eq!(format(source, &FormatConfig::default()).unwrap(), "let y = x |> f // done\n");
+//! ```
+//! The style is:
+//! - Every statement is on its own line, with at most one blank line between statements.
+//! - Blocks with statements have every statement on its own line, indented. Blocks without
+//!   statements stay on one line if they fit, like `{ x }`.
+//! - Pipelines (`|>`) and applications stay on one line if they fit, and otherwise have one
+//!   operand per line, indented.
+//! - Lists, like in `use a::{b, c}`, stay on one line if they fit, and otherwise have one item per
+//!   line, each with a trailing comma.
+//! - Comments stay where they were, relative to the code around them.
+
+mod comments;
+pub mod doc;
+pub mod errors;
+mod layout;
+
+use super::parser::parse;
+use crate::repr::fst::visitor::FstVisitor;
+use errors::FormatError;
+use layout::{content_end, leading_start, Formatter};
+use std::{fs, ops::Range, path::Path};
+
+/// How to format code.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// The number of columns code should fit in.
+    pub width: usize,
+
+    /// The number of spaces to indent by.
+    pub indent: usize,
+}
+
+impl FormatConfig {
+    /// Sets the number of columns code should fit in.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the number of spaces to indent by.
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            width: 100,
+            indent: 4,
+        }
+    }
+}
+
+/// A change to some source code: replacing the text in `range` with `text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    /// The range of bytes to replace.
+    pub range: Range<usize>,
+
+    /// What to replace them with.
+    pub text: String,
+}
+
+impl Edit {
+    /// Makes the change to `source`.
+    pub fn apply(&self, source: &str) -> String {
+        let mut changed = source.to_string();
+        changed.replace_range(self.range.clone(), &self.text);
+        changed
+    }
+}
+
+/// Formats a whole file.
+pub fn format(source: &str, config: &FormatConfig) -> Result<String, FormatError> {
+    let file = parse(source).map_err(|err| FormatError::Parse(format!("{:?}", err)))?;
+    let doc = Formatter::new(source, &file, config).visit_file(&file);
+
+    Ok(doc.render(config.width))
+}
+
+/// Formats the top-level statements that overlap with `range`, for formatting a selection in an
+/// editor. Returns `None` if there aren't any statements in the range.
+pub fn format_range(
+    source: &str,
+    range: Range<usize>,
+    config: &FormatConfig,
+) -> Result<Option<Edit>, FormatError> {
+    if range.start > range.end
+        || !source.is_char_boundary(range.start)
+        || !source.is_char_boundary(range.end)
+    {
+        return Err(FormatError::InvalidRange {
+            start: range.start,
+            end: range.end,
+        });
+    }
+
+    let file = parse(source).map_err(|err| FormatError::Parse(format!("{:?}", err)))?;
+    let overlapping = file
+        .statements
+        .iter()
+        .enumerate()
+        .filter(|(_, statement)| {
+            leading_start(statement) <= range.end && range.start <= content_end(statement)
+        })
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let (Some(&first), Some(&last)) = (overlapping.first(), overlapping.last()) else {
+        return Ok(None);
+    };
+    let statements = &file.statements[first..=last];
+
+    let start = leading_start(&statements[0]);
+    let mut formatter = Formatter::new(source, &file, config);
+    // NOTE: The comments before the first statement aren't part of the edit, so they're kept as
+    // they are.
+    formatter.comments.skip_before(start);
+    let doc = formatter.statements(statements);
+    let end = formatter
+        .comments
+        .taken_end()
+        .map_or(content_end(&statements[statements.len() - 1]), |end| {
+            end.max(content_end(&statements[statements.len() - 1]))
+        });
+
+    Ok(Some(Edit {
+        range: start..end,
+        text: doc.render(config.width),
+    }))
+}
+
+/// Formats a file on disk. If `check` is set, the file isn't changed. Returns whether the file
+/// wasn't formatted (and was changed, if `check` isn't set).
+pub fn format_path(path: &Path, config: &FormatConfig, check: bool) -> Result<bool, FormatError> {
+    let io_error = |err: std::io::Error| FormatError::Io {
+        path: path.to_path_buf(),
+        message: err.to_string(),
+    };
+
+    let source = fs::read_to_string(path).map_err(io_error)?;
+    let formatted = format(&source, config)?;
+    if formatted == source {
+        return Ok(false);
+    }
+
+    if !check {
+        fs::write(path, formatted).map_err(io_error)?;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compile::printer::tokens::tokens,
+        repr::fst::{trivia::TriviaKind, File},
+    };
+
+    fn fmt(source: &str) -> String {
+        format(source, &FormatConfig::default()).unwrap()
+    }
+
+    /// Checks that formatting `source` doesn't change what it means, loses no comments, and that
+    /// formatting it again doesn't change it.
+    fn assert_formats_well(source: &str, from: &str) {
+        let formatted = fmt(source);
+        let before = parse(source).unwrap();
+        let after = parse(&formatted)
+            .unwrap_or_else(|err| panic!("couldn't parse {from} once formatted: {err:?}"));
+
+        // NOTE: Trailing commas are added and removed by the formatter.
+        let code = |file| {
+            tokens(file)
+                .into_iter()
+                .filter(|token| token != ",")
+                .collect::<Vec<_>>()
+        };
+        let comments = |file: &File| {
+            file.trivia
+                .iter()
+                .filter(|trivia| trivia.kind != TriviaKind::Whitespace)
+                .map(|trivia| trivia.text.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(code(&before), code(&after), "in {from}");
+        assert_eq!(comments(&before), comments(&after), "in {from}");
+        assert_eq!(fmt(&formatted), formatted, "in {from}");
+    }
+
+    #[test]
+    fn can_format_statements() {
+        assert_eq!(
+            fmt("pub  let x=1+2\n\n\n\nmod   a\nuse a::{ b as c,d, }"),
+            "pub let x = 1 + 2\n\nmod a\nuse a::{b as c, d}\n"
+        );
+        assert_eq!(
+            fmt("/// Docs.\n#[ inline ]\n#[a( \"b\" ,1,)]\nlet x=1\n"),
+            "/// Docs.\n#[inline]\n#[a(\"b\", 1)]\nlet x = 1\n"
+        );
+        assert_eq!(
+            fmt("impl  Add for Bool=|a,b|a!=b\nlet x=1<=2\n"),
+            "impl Add for Bool = |a, b| a != b\nlet x = 1 <= 2\n"
+        );
+    }
+
+    #[test]
+    fn can_format_blocks_and_functions() {
+        assert_eq!(fmt("let f = |x,y,|{x}\n"), "let f = |x, y| { x }\n");
+        assert_eq!(
+            fmt("let f = || { let y = 1\n\n\n  y * (2) }\n"),
+            "let f = || {\n    let y = 1\n\n    y * (2)\n}\n"
+        );
+    }
+
+    #[test]
+    fn can_break_long_pipelines() {
+        let source = "let y = x |> first |> second |> third\n";
+        assert_eq!(fmt(source), source);
+
+        let config = FormatConfig::default().with_width(20);
+        assert_eq!(
+            format(source, &config).unwrap(),
+            "let y = x\n    |> first\n    |> second\n    |> third\n"
+        );
+        assert_eq!(
+            format(source, &config.with_indent(2)).unwrap(),
+            "let y = x\n  |> first\n  |> second\n  |> third\n"
+        );
+    }
+
+    #[test]
+    fn can_break_long_applications() {
+        let source = "let x = function first second\n";
+        assert_eq!(fmt(source), source);
+        assert_eq!(
+            format(source, &FormatConfig::default().with_width(20)).unwrap(),
+            "let x = function\n    first\n    second\n"
+        );
+        assert_eq!(fmt("let y = f   (g   x)  h\n"), "let y = f (g x) h\n");
+    }
+
+    #[test]
+    fn can_format_types() {
+        assert_eq!(
+            fmt("type  List[ a ,]=Nil|Cons a ( List a )\nlet id[a:Add+Eq]:a->(a)=|x|x\n"),
+            "type List[a] = Nil | Cons a (List a)\nlet id[a: Add + Eq]: a -> (a) = |x| x\n"
+        );
+
+        let source = "type Shape = Circle F64 | Rect F64 F64\n";
+        assert_eq!(fmt(source), source);
+        assert_eq!(
+            format(source, &FormatConfig::default().with_width(20)).unwrap(),
+            "type Shape = Circle F64\n    | Rect F64 F64\n"
+        );
+    }
+
+    #[test]
+    fn can_format_matches() {
+        assert_eq!(
+            fmt("let x = match ( a,b ,) {(0,_)=>1\nCons x  (Nil)=>x,}\n"),
+            "let x = match (a, b) { (0, _) => 1, Cons x (Nil) => x }\n"
+        );
+
+        let source = "let y = match shape { Circle r => r, Rect w h => w * h }\n";
+        assert_eq!(fmt(source), source);
+        assert_eq!(
+            format(source, &FormatConfig::default().with_width(30)).unwrap(),
+            "let y = match shape {\n    Circle r => r\n    Rect w h => w * h\n}\n"
+        );
+    }
+
+    #[test]
+    fn can_keep_comments() {
+        assert_eq!(
+            fmt("// One.\n\n// Two.\nlet x = /* three */ 1 +\n  // four\n  2\n// five\n"),
+            "// One.\n\n// Two.\nlet x = /* three */ 1 +\n    // four\n    2\n// five\n"
+        );
+        assert_eq!(
+            fmt("let f = { x // one\n}\nlet y = 2 // two"),
+            "let f = {\n    x // one\n}\nlet y = 2 // two\n"
+        );
+        assert_eq!(fmt("// Only comments.\n"), "// Only comments.\n");
+        assert_eq!(fmt(""), "");
+    }
+
+    #[test]
+    fn can_format_range() {
+        let source = "let a=1\n// b\nlet  b=2\nlet c=3\n";
+        let config = FormatConfig::default();
+
+        let edit = format_range(source, 14..15, &config).unwrap().unwrap();
+        assert_eq!(edit.apply(source), "let a=1\n// b\nlet b = 2\nlet c=3\n");
+
+        let edit = format_range(source, 0..source.len(), &config)
+            .unwrap()
+            .unwrap();
+        assert_eq!(edit.apply(source), fmt(source));
+
+        assert_eq!(format_range("\n\n", 1..1, &config), Ok(None));
+        assert_eq!(
+            format_range("let é = 1\n", 5..6, &config),
+            Err(FormatError::InvalidRange { start: 5, end: 6 })
+        );
+    }
+
+    #[test]
+    fn can_format_corpus() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../oxylc-fuzzer/in");
+
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            assert_formats_well(&source, &path.display().to_string());
+        }
+    }
+
+    #[test]
+    fn can_check_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.oxyl");
+        fs::write(&path, "let x=1\n").unwrap();
+        let config = FormatConfig::default();
+
+        assert_eq!(format_path(&path, &config, true), Ok(true));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "let x=1\n");
+        assert_eq!(format_path(&path, &config, false), Ok(true));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "let x = 1\n");
+        assert_eq!(format_path(&path, &config, true), Ok(false));
+    }
+}
@@ -0,0 +1,134 @@
+use errgonomic::parser::input::Span;
+
+/// NOTE: The types in these are already written out, so that the type variables in them have the
+/// same names as they would in the rest of the error.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TypeError {
+    #[error("Mismatched types: expected `{expected}`, found `{found}`")]
+    Mismatch {
+        expected: String,
+        found: String,
+        location: Span,
+
+        /// The location of what made `expected` be expected.
+        expected_at: Span,
+    },
+
+    #[error("Cannot construct the infinite type `{var} = {ty}`")]
+    Infinite {
+        var: String,
+        ty: String,
+        location: Span,
+
+        /// The location of what made `var` be `ty`.
+        expected_at: Span,
+    },
+
+    #[error("`{found}` isn't a function, so it can't be applied")]
+    NotAFunction {
+        found: String,
+        location: Span,
+
+        /// The location of the argument it's applied to.
+        arg: Span,
+    },
+
+    #[error("The literal `{literal}` doesn't fit into `{ty}`")]
+    OutOfRange {
+        literal: String,
+        ty: String,
+
+        /// The smallest number `ty` can hold.
+        min: String,
+
+        /// The largest number `ty` can hold.
+        max: String,
+
+        location: Span,
+    },
+
+    #[error("`{ty}` doesn't implement `{trait_name}`")]
+    MissingInstance {
+        trait_name: String,
+        ty: String,
+        location: Span,
+    },
+
+    #[error("Cannot find trait `{name}`")]
+    UnknownTrait { name: String, location: Span },
+
+    #[error("Cannot find type `{name}`")]
+    UnknownType { name: String, location: Span },
+
+    #[error("The type `{name}` is defined more than once")]
+    DuplicateType { name: String, location: Span },
+
+    #[error("The type parameter `{name}` is declared more than once")]
+    DuplicateTypeParam { name: String, location: Span },
+
+    #[error(
+        "`{name}` takes {expected} type {}, but {found} {} given",
+        if *.expected == 1 { "argument" } else { "arguments" },
+        if *.found == 1 { "was" } else { "were" }
+    )]
+    WrongArity {
+        name: String,
+        expected: usize,
+        found: usize,
+        location: Span,
+    },
+
+    #[error("`{trait_name}` is only implemented by the built-in types")]
+    BuiltinTrait { trait_name: String, location: Span },
+
+    #[error("`{ty}` already implements `{trait_name}`")]
+    DuplicateInstance {
+        trait_name: String,
+        ty: String,
+        location: Span,
+    },
+
+    #[error("`{name}` isn't a constructor, so it can't be matched on")]
+    NotAConstructor { name: String, location: Span },
+
+    #[error(
+        "`{name}` has {expected} {}, but the pattern has {found}",
+        if *.expected == 1 { "field" } else { "fields" }
+    )]
+    PatternArity {
+        name: String,
+        expected: usize,
+        found: usize,
+        location: Span,
+    },
+
+    #[error("`{ty}` has no field `{field}`")]
+    NoField {
+        ty: String,
+        field: String,
+        location: Span,
+    },
+}
+
+impl TypeError {
+    /// Gets the location in the file where the error happened.
+    pub fn location(&self) -> Span {
+        match self {
+            TypeError::Mismatch { location, .. }
+            | TypeError::Infinite { location, .. }
+            | TypeError::NotAFunction { location, .. }
+            | TypeError::OutOfRange { location, .. }
+            | TypeError::MissingInstance { location, .. }
+            | TypeError::UnknownTrait { location, .. }
+            | TypeError::UnknownType { location, .. }
+            | TypeError::DuplicateType { location, .. }
+            | TypeError::DuplicateTypeParam { location, .. }
+            | TypeError::WrongArity { location, .. }
+            | TypeError::BuiltinTrait { location, .. }
+            | TypeError::DuplicateInstance { location, .. }
+            | TypeError::NotAConstructor { location, .. }
+            | TypeError::PatternArity { location, .. }
+            | TypeError::NoField { location, .. } => *location,
+        }
+    }
+}
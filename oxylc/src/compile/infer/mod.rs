@@ -0,0 +1,1624 @@
+//! Type inference, which finds the type of every expression and name in a file without any of them
+//! being written down. This is Hindley-Milner type inference:
+//! - Every expression starts with an unknown type (a type variable), and what it's used for tells
+//!   us more about it. Two things that have to be the same type are unified, which makes the
+//!   unknowns in them the same, or fails with an error if they can't be.
+//! - A `let` can be used as many different types: once its value's type is found, the unknowns
+//!   left in it are generalized, so `let id = |x| x` can be used on both `1` and `True`.
+//! - The top-level `let`s can use each other in any order, so they're inferred in the order they
+//!   depend on each other, with the ones that depend on each other (like mutually recursive
+//!   functions) inferred together.
+//!
+//! Some unknowns are limited in what they can be:
+//! - An operator is a call of the method of its trait, so `a + b` is `add a b`, where `add` is the
+//!   method of `Add` (see `compile::prelude::method`). An unknown an operator is used on can only
+//!   be a type that implements the operator's trait, and a function that uses an operator on its
+//!   arguments can be used on any type that does, like `Add a => a -> a -> a`. It's an error for a
+//!   type that doesn't implement the trait to be found. The built-in types implement the traits
+//!   that make sense for them, and `impl`-statements can implement them for other types.
+//! - A literal like `1` can be any integer (`Integral`), and `2.5` can be any float
+//!   (`Fractional`). What they are is found from how they're used, but they're never generalized,
+//!   so that every use of `let x = 1` agrees on what `x` is. Once the whole file has been inferred,
+//!   the ones that still aren't known are defaulted to `I64` and `F64`, and then every literal is
+//!   checked to fit into its type.
+//!
+//! Types can also be written down:
+//! - A `type`-statement declares a type, like `type List[a] = Nil | Cons a (List a)`. Its
+//!   constructors are functions from their fields to the type, so `Cons` is
+//!   `a -> List a -> List a`. Types are declared before anything else, so they can be used anywhere
+//!   in the file, and every use of a type has to give it as many type arguments as it takes.
+//! - A `let` can have a signature, like `let id[a]: a -> a = |x| x`. Its value is checked with the
+//!   type parameters as rigid types, which are only the same as themselves, so the value has to
+//!   work for every type they could be. The `let` then has the type it was given, and nothing
+//!   more specific.
+//!
+//! A `match` has the type of its arms, which all have to be the same. The pattern of every arm has
+//! the type of the value being matched on, and a constructor in a pattern has to be given a pattern
+//! for every one of its fields. Whether the arms cover every value is checked once the types are
+//! known (see `compile::exhaustive`).
+//!
+//! The types are filled into the scopes of the `Resolution` of the file, and the type of every
+//! expression is returned as `Types`.
+
+pub mod errors;
+
+use crate::{
+    compile::{prelude, resolve::Resolution},
+    repr::ast::{
+        expr::{ExprId, ExprKind, Literal, UnaryOp},
+        ident::Ident,
+        pattern::{Pattern, PatternKind},
+        scope::{DefId, DefKind},
+        stmt::{StmtId, StmtKind},
+        type_expr::{TypeExpr, TypeExprKind},
+        types::{display_together, Class, IntType, RigidVar, Scheme, Trait, Type, TypeVar},
+        File,
+    },
+};
+use errgonomic::parser::input::Span;
+use errors::TypeError;
+use std::collections::{HashMap, HashSet};
+
+/// The types of everything in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Types {
+    /// The type of every expression, by its index.
+    exprs: Vec<Type>,
+
+    /// The type of every definition.
+    defs: HashMap<DefId, Scheme>,
+}
+
+impl Types {
+    /// Gets the type of an expression.
+    pub fn of(&self, expr: ExprId) -> &Type {
+        &self.exprs[expr.index()]
+    }
+
+    /// Gets the type of a definition.
+    pub fn of_def(&self, def: DefId) -> Option<&Scheme> {
+        self.defs.get(&def)
+    }
+}
+
+/// Infers the types in a file, filling them into the scopes of its `Resolution`. Type errors don't
+/// stop inference, so every error in the file is returned.
+pub fn infer(file: &File, resolution: &mut Resolution) -> (Types, Vec<TypeError>) {
+    let mut lets = HashMap::new();
+    let mut params = HashMap::new();
+    let mut bindings = HashMap::new();
+    let mut constructors = vec![];
+    for (id, def) in resolution.defs.iter() {
+        match def.kind {
+            DefKind::Let(stmt) => {
+                lets.insert(stmt, id);
+            }
+            DefKind::Param(lambda) => {
+                params.insert(lambda, id);
+            }
+            DefKind::Binding(m, i) => {
+                bindings.insert((m, i, def.name.ident), id);
+            }
+            DefKind::Constructor(stmt, i) => constructors.push((id, stmt, i)),
+            DefKind::Mod(_) | DefKind::Import(_) | DefKind::Extern | DefKind::Builtin => {}
+        }
+    }
+    let builtins = resolution
+        .defs
+        .iter()
+        .filter(|(_, def)| def.kind == DefKind::Builtin)
+        .filter_map(|(id, def)| Some((id, prelude::type_of(def.name.ident.as_str())?)))
+        .collect::<Vec<_>>();
+
+    let mut inferer = Inferer {
+        file,
+        resolution,
+        lets,
+        params,
+        bindings,
+        vars: vec![],
+        level: 0,
+        defs: HashMap::new(),
+        exprs: vec![None; file.exprs.len()],
+        instances: prelude::instances().into_iter().collect(),
+        impls: HashMap::new(),
+        types: HashMap::new(),
+        signatures: HashMap::new(),
+        rigids: 0,
+        errors: vec![],
+    };
+
+    // NOTE: Types and implementations can be used anywhere in the file, even before (or outside
+    // of) the block they're in, so they're all declared first.
+    for (stmt, _) in file.stmts.iter() {
+        inferer.declare_type(stmt);
+    }
+    for (def, stmt, i) in constructors {
+        let scheme = inferer.constructor_scheme(stmt, i);
+        inferer.defs.insert(def, scheme);
+    }
+    for (stmt, _) in file.stmts.iter() {
+        inferer.declare_impl(stmt);
+        inferer.declare_signature(stmt);
+    }
+
+    // NOTE: The type variables in the prelude's types aren't ours, so they're made again.
+    for (def, scheme) in builtins {
+        inferer.level += 1;
+        let ty = inferer.instantiate(&scheme);
+        inferer.level -= 1;
+        let scheme = inferer.generalize(&ty);
+        inferer.defs.insert(def, scheme);
+    }
+
+    for group in inferer.top_level_groups() {
+        inferer.let_group(&group);
+    }
+    for &stmt in &file.statements {
+        inferer.impl_statement(stmt);
+    }
+    inferer.default_literals();
+
+    // NOTE: The types of parameters and of what they're used with may have been found after they
+    // were recorded, so everything is looked up one last time.
+    let exprs = inferer
+        .exprs
+        .iter()
+        .map(|ty| match ty {
+            Some(ty) => inferer.zonk(ty),
+            None => Type::Unit,
+        })
+        .collect();
+    let defs = inferer
+        .defs
+        .iter()
+        .map(|(&def, scheme)| {
+            let scheme = Scheme {
+                ty: inferer.zonk(&scheme.ty),
+                ..scheme.clone()
+            };
+            (def, scheme)
+        })
+        .collect::<HashMap<_, _>>();
+
+    for (&id, scheme) in &defs {
+        let def = &inferer.resolution.defs[id];
+        let (ident, scope) = (def.name.ident, def.scope);
+        // NOTE: Only the definition a name refers to in its scope gets its type there, as names
+        // defined twice at the top level keep their first definition.
+        if inferer.resolution.scopes[scope].get(ident) == Some(id) {
+            inferer.resolution.scopes[scope].set_type(ident, scheme.clone());
+        }
+    }
+
+    let types = Types { exprs, defs };
+    let mut errors = inferer.errors;
+    errors.extend(check_literals(file, &types));
+    (types, errors)
+}
+
+/// What's known about a type variable.
+#[derive(Debug, Clone)]
+struct VarInfo {
+    /// The type it's been unified with, if it has been.
+    binding: Option<Type>,
+
+    /// How many `let`s deep it was made. Only the variables made inside of a `let` can be
+    /// generalized when it's done, as the others may still be found to be something else.
+    level: u32,
+
+    /// The class of numbers it's limited to, if it's the type of a literal.
+    class: Option<Class>,
+
+    /// The traits it has to implement, in order.
+    traits: Vec<Trait>,
+
+    /// Whether it's been generalized, so that it's in a `Scheme` and could stand for anything.
+    generalized: bool,
+}
+
+/// Why two types couldn't be unified.
+enum Clash {
+    Mismatch,
+    Infinite(TypeVar, Type),
+
+    /// The types could be unified, but a type variable that has to implement the trait was found
+    /// to be the type, which doesn't.
+    MissingInstance(Trait, Type),
+}
+
+/// Walks the AST, inferring types as it goes.
+struct Inferer<'a> {
+    file: &'a File,
+    resolution: &'a mut Resolution,
+
+    /// The definition of every `let`.
+    lets: HashMap<StmtId, DefId>,
+
+    /// The definition of the parameter of every function that has one.
+    params: HashMap<ExprId, DefId>,
+
+    /// The definition of every name bound by a pattern, by the `match`, the index of the arm and
+    /// the name.
+    bindings: HashMap<(ExprId, usize, Ident), DefId>,
+
+    vars: Vec<VarInfo>,
+
+    /// How many `let`s deep we are.
+    level: u32,
+
+    /// The type of every definition that's been found so far.
+    defs: HashMap<DefId, Scheme>,
+
+    exprs: Vec<Option<Type>>,
+
+    /// The traits every type implements, both built-in and from `impl`-statements.
+    instances: HashSet<(Trait, Type)>,
+
+    /// The trait and the type of every `impl`-statement that could be declared.
+    impls: HashMap<StmtId, (Trait, Type)>,
+
+    /// The number of type arguments every type declared by a `type`-statement takes, and the
+    /// statement that declares it.
+    types: HashMap<Ident, (usize, StmtId)>,
+
+    /// The type every `let` with a signature is checked against, with its type parameters as
+    /// `Type::Rigid`s, and the `Scheme` it's declared to have.
+    signatures: HashMap<StmtId, (Type, Scheme)>,
+
+    /// The number of `RigidVar`s made so far.
+    rigids: u32,
+
+    errors: Vec<TypeError>,
+}
+
+impl Inferer<'_> {
+    /// Makes a new type variable, which could be anything.
+    fn fresh(&mut self) -> Type {
+        self.fresh_in(None)
+    }
+
+    /// Makes a new type variable, which could be anything in `class`.
+    fn fresh_in(&mut self, class: Option<Class>) -> Type {
+        self.vars.push(VarInfo {
+            binding: None,
+            level: self.level,
+            class,
+            traits: vec![],
+            generalized: false,
+        });
+        Type::Var(TypeVar(self.vars.len() as u32 - 1))
+    }
+
+    /// Follows the bindings of `ty` until it's no longer a bound type variable.
+    fn prune(&self, ty: &Type) -> Type {
+        let mut ty = ty.clone();
+        while let Type::Var(TypeVar(var)) = ty {
+            match &self.vars[var as usize].binding {
+                Some(binding) => ty = binding.clone(),
+                None => break,
+            }
+        }
+        ty
+    }
+
+    /// Replaces every bound type variable in `ty` with what it's bound to.
+    fn zonk(&self, ty: &Type) -> Type {
+        match self.prune(ty) {
+            Type::Function(param, result) => Type::function(self.zonk(&param), self.zonk(&result)),
+            Type::Named(ident, args) => {
+                Type::Named(ident, args.iter().map(|arg| self.zonk(arg)).collect())
+            }
+            Type::Tuple(items) => Type::Tuple(items.iter().map(|item| self.zonk(item)).collect()),
+            ty => ty,
+        }
+    }
+
+    fn info(&mut self, var: TypeVar) -> &mut VarInfo {
+        &mut self.vars[var.0 as usize]
+    }
+
+    fn unify(&mut self, a: &Type, b: &Type) -> Result<(), Clash> {
+        match (self.prune(a), self.prune(b)) {
+            (Type::Var(a), Type::Var(b)) if a == b => Ok(()),
+            (Type::Var(var), ty) | (ty, Type::Var(var)) => self.bind(var, ty),
+            (Type::Function(p1, r1), Type::Function(p2, r2)) => {
+                self.unify(&p1, &p2)?;
+                self.unify(&r1, &r2)
+            }
+            (Type::Named(a, a_args), Type::Named(b, b_args))
+                if a == b && a_args.len() == b_args.len() =>
+            {
+                for (a, b) in a_args.iter().zip(&b_args) {
+                    self.unify(a, b)?;
+                }
+                Ok(())
+            }
+            (Type::Tuple(a), Type::Tuple(b)) if a.len() == b.len() => {
+                for (a, b) in a.iter().zip(&b) {
+                    self.unify(a, b)?;
+                }
+                Ok(())
+            }
+            (a, b) if a == b => Ok(()),
+            _ => Err(Clash::Mismatch),
+        }
+    }
+
+    /// Binds an unbound type variable to `ty`.
+    fn bind(&mut self, var: TypeVar, ty: Type) -> Result<(), Clash> {
+        let ty = self.zonk(&ty);
+        let vars = ty.vars();
+        if vars.contains(&var) {
+            return Err(Clash::Infinite(var, ty));
+        }
+
+        let info = self.info(var);
+        let (level, class, traits) = (info.level, info.class, info.traits.clone());
+        let mut missing = None;
+        match &ty {
+            Type::Var(other) => {
+                let info = self.info(*other);
+                info.class = match (class, info.class) {
+                    (Some(a), Some(b)) if a != b => return Err(Clash::Mismatch),
+                    (a, b) => a.or(b),
+                };
+                info.traits.extend(traits);
+                info.traits.sort_unstable();
+                info.traits.dedup();
+            }
+            ty => {
+                if class.is_some_and(|class| !class.contains(ty)) {
+                    return Err(Clash::Mismatch);
+                }
+                // NOTE: A type without the trait is still the type it was found to be, so it's
+                // bound anyway, and the rest of the file doesn't report the same error again.
+                missing = traits.into_iter().find(|&t| !self.implements(t, ty));
+            }
+        }
+        // NOTE: `var` might have been made outside of a `let` that the variables in `ty` were made
+        // in, and now they have to be the same, so they can't be generalized by that `let`.
+        for other in vars {
+            let info = self.info(other);
+            info.level = info.level.min(level);
+        }
+
+        self.info(var).binding = Some(ty.clone());
+        match missing {
+            Some(t) => Err(Clash::MissingInstance(t, ty)),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether `ty` implements `t`. Implementations are for a type with any type arguments, so
+    /// they're looked up without them.
+    fn implements(&self, t: Trait, ty: &Type) -> bool {
+        let ty = match ty {
+            Type::Named(ident, _) => Type::Named(*ident, vec![]),
+            ty => ty.clone(),
+        };
+        self.instances.contains(&(t, ty))
+    }
+
+    /// Unifies what was `found` at `location` with what was `expected` because of what's at
+    /// `expected_at`, reporting an error if they can't be.
+    fn expect(&mut self, expected: &Type, expected_at: Span, found: &Type, location: Span) {
+        // NOTE: Some of the types may have been bound before the clash, so they're written as they
+        // were before unifying them.
+        let (expected_before, found_before) = (self.zonk(expected), self.zonk(found));
+        let clash = match self.unify(expected, found) {
+            Ok(()) => return,
+            Err(clash) => clash,
+        };
+
+        let error = match clash {
+            Clash::Mismatch => {
+                let [expected, found] = self
+                    .display(&[&expected_before, &found_before])
+                    .try_into()
+                    .expect("2 types were written");
+                TypeError::Mismatch {
+                    expected,
+                    found,
+                    location,
+                    expected_at,
+                }
+            }
+            Clash::Infinite(var, ty) => {
+                let [var, ty] = self
+                    .display(&[&Type::Var(var), &ty])
+                    .try_into()
+                    .expect("2 types were written");
+                TypeError::Infinite {
+                    var,
+                    ty,
+                    location,
+                    expected_at,
+                }
+            }
+            Clash::MissingInstance(t, ty) => {
+                let [ty] = self.display(&[&ty]).try_into().expect("1 type was written");
+                TypeError::MissingInstance {
+                    trait_name: t.name().to_string(),
+                    ty,
+                    location,
+                }
+            }
+        };
+        self.errors.push(error);
+    }
+
+    /// Gets a type for a use of the method of `t`, which has to have one.
+    fn method(&mut self, t: Trait) -> Type {
+        let (_, scheme) = prelude::method(t).expect("operators only call traits with methods");
+        self.instantiate(&scheme)
+    }
+
+    /// Writes types for an error, with the unknowns that are limited to a class of numbers written
+    /// as that class.
+    fn display(&self, types: &[&Type]) -> Vec<String> {
+        let classes = types
+            .iter()
+            .flat_map(|ty| ty.vars())
+            .filter_map(|var| Some((var, self.vars[var.0 as usize].class?)))
+            .collect();
+        display_together(types, &classes)
+    }
+
+    /// Turns the unknowns in `ty` that were made inside of the current `let` into a `Scheme`.
+    fn generalize(&mut self, ty: &Type) -> Scheme {
+        let ty = self.zonk(ty);
+        let (mut vars, mut constraints) = (vec![], vec![]);
+        for var in ty.vars() {
+            let level = self.level;
+            let info = self.info(var);
+            if info.level <= level {
+                continue;
+            }
+
+            match info.class {
+                // NOTE: Literals aren't generalized, so they now belong to the outer `let`.
+                Some(_) => info.level = level,
+                None => {
+                    info.generalized = true;
+                    vars.push(var);
+                    constraints.extend(info.traits.iter().map(|&t| (t, var)));
+                }
+            }
+        }
+        Scheme {
+            vars,
+            constraints,
+            ty,
+        }
+    }
+
+    /// Gives the literals whose types weren't found the default type for them.
+    fn default_literals(&mut self) {
+        for info in &mut self.vars {
+            if let (None, Some(class), false) = (&info.binding, info.class, info.generalized) {
+                info.binding = Some(class.default_type());
+            }
+        }
+    }
+
+    /// Makes a type from a `Scheme`, with new unknowns for its variables.
+    fn instantiate(&mut self, scheme: &Scheme) -> Type {
+        let mut replacements = HashMap::new();
+        for &var in &scheme.vars {
+            let fresh = self.fresh();
+            if let Type::Var(fresh) = fresh {
+                self.info(fresh).traits = scheme
+                    .constraints
+                    .iter()
+                    .filter(|(_, constrained)| *constrained == var)
+                    .map(|&(t, _)| t)
+                    .collect();
+            }
+            replacements.insert(var, fresh);
+        }
+        substitute(&scheme.ty, &replacements)
+    }
+
+    /// Gets a type for a use of `def`.
+    fn def_type(&mut self, def: DefId) -> Type {
+        if let Some(scheme) = self.defs.get(&def).cloned() {
+            return self.instantiate(&scheme);
+        }
+
+        // NOTE: Modules, imports and packages are checked through the module tree, so their types
+        // aren't known here. They're given an unknown type that's the same everywhere in the file,
+        // so the file at least agrees with itself about them.
+        let outer = std::mem::replace(&mut self.level, 0);
+        let ty = self.fresh();
+        self.level = outer;
+        self.defs.insert(def, Scheme::mono(ty.clone()));
+        ty
+    }
+
+    /// Groups the top-level `let`s by which ones use each other, in the order they have to be
+    /// inferred: a group only uses itself and the groups before it. This is Tarjan's algorithm.
+    fn top_level_groups(&self) -> Vec<Vec<StmtId>> {
+        let lets = self
+            .file
+            .statements
+            .iter()
+            .copied()
+            .filter(|stmt| self.lets.contains_key(stmt))
+            .collect::<Vec<_>>();
+        let index_of = lets
+            .iter()
+            .enumerate()
+            .map(|(i, stmt)| (self.lets[stmt], i))
+            .collect::<HashMap<_, _>>();
+
+        let uses = lets
+            .iter()
+            .map(|&stmt| {
+                let StmtKind::Let { value, .. } = self.file[stmt].kind else {
+                    unreachable!("only `let`s have been kept");
+                };
+                let mut used = vec![];
+                self.uses_in(value, &mut used);
+                used.into_iter()
+                    .filter_map(|def| index_of.get(&def).copied())
+                    .collect()
+            })
+            .collect::<Vec<Vec<usize>>>();
+
+        let mut tarjan = Tarjan {
+            uses: &uses,
+            index: vec![None; lets.len()],
+            low: vec![0; lets.len()],
+            stack: vec![],
+            on_stack: vec![false; lets.len()],
+            next: 0,
+            groups: vec![],
+        };
+        for node in 0..lets.len() {
+            if tarjan.index[node].is_none() {
+                tarjan.connect(node);
+            }
+        }
+
+        tarjan
+            .groups
+            .into_iter()
+            .map(|group| group.into_iter().map(|node| lets[node]).collect())
+            .collect()
+    }
+
+    /// Collects every definition used inside of `expr`.
+    fn uses_in(&self, expr: ExprId, used: &mut Vec<DefId>) {
+        if let Some(def) = self.resolution.def_of(expr) {
+            used.push(def);
+        }
+
+        match &self.file[expr].kind {
+            ExprKind::Literal(_) | ExprKind::Var(_) | ExprKind::Path(_) | ExprKind::Error => {}
+            ExprKind::Lambda { body, .. } => self.uses_in(*body, used),
+            ExprKind::Apply { function, arg } => {
+                self.uses_in(*function, used);
+                self.uses_in(*arg, used);
+            }
+            ExprKind::Binary { lhs, rhs, .. } => {
+                self.uses_in(*lhs, used);
+                self.uses_in(*rhs, used);
+            }
+            ExprKind::Unary { operand, .. } => self.uses_in(*operand, used),
+            ExprKind::Field { expr, .. } => self.uses_in(*expr, used),
+            ExprKind::Block { statements, result } => {
+                for &stmt in statements {
+                    if let StmtKind::Let { value, .. } | StmtKind::Impl { value, .. } =
+                        self.file[stmt].kind
+                    {
+                        self.uses_in(value, used);
+                    }
+                }
+                self.uses_in(*result, used);
+            }
+            ExprKind::Tuple(items) => {
+                for &item in items {
+                    self.uses_in(item, used);
+                }
+            }
+            // NOTE: Patterns only use constructors, which aren't `let`s.
+            ExprKind::Match { scrutinee, arms } => {
+                self.uses_in(*scrutinee, used);
+                for arm in arms {
+                    self.uses_in(arm.body, used);
+                }
+            }
+        }
+    }
+
+    /// Infers a group of `let`s that can use each other.
+    fn let_group(&mut self, group: &[StmtId]) {
+        // NOTE: Inside of the group, the `let`s are only ever used as one type each, as their
+        // types aren't known yet, so they can't be generalized until the whole group is done.
+        // The ones with a signature already have their type, so they can be used as any type.
+        self.level += 1;
+        let tys = group
+            .iter()
+            .map(|stmt| {
+                let ty = self.fresh();
+                let scheme = match self.signatures.get(stmt) {
+                    Some((_, declared)) => declared.clone(),
+                    None => Scheme::mono(ty.clone()),
+                };
+                self.defs.insert(self.lets[stmt], scheme);
+                ty
+            })
+            .collect::<Vec<_>>();
+
+        for (stmt, ty) in group.iter().zip(&tys) {
+            let StmtKind::Let { name, value, .. } = self.file[*stmt].kind else {
+                unreachable!("only `let`s are grouped");
+            };
+            let found = self.expression(value);
+            let location = self.file[value].location;
+            match self.signatures.get(stmt).cloned() {
+                Some((rigid, _)) => self.expect_signature(*stmt, &rigid, &found, location),
+                None => self.expect(ty, name.location, &found, location),
+            }
+        }
+        self.level -= 1;
+
+        for (stmt, ty) in group.iter().zip(&tys) {
+            if !self.signatures.contains_key(stmt) {
+                let scheme = self.let_scheme(*stmt, ty);
+                self.defs.insert(self.lets[stmt], scheme);
+            }
+        }
+    }
+
+    /// Infers a statement in a block. A `let` in a block can only be used after it.
+    fn block_statement(&mut self, stmt: StmtId) {
+        let StmtKind::Let { value, .. } = self.file[stmt].kind else {
+            return self.impl_statement(stmt);
+        };
+
+        self.level += 1;
+        let ty = self.expression(value);
+        let signature = self.signatures.get(&stmt).cloned();
+        if let Some((rigid, _)) = &signature {
+            let location = self.file[value].location;
+            self.expect_signature(stmt, rigid, &ty, location);
+        }
+        self.level -= 1;
+
+        let scheme = match signature {
+            Some((_, declared)) => declared,
+            None => self.let_scheme(stmt, &ty),
+        };
+        if let Some(&def) = self.lets.get(&stmt) {
+            self.defs.insert(def, scheme);
+        }
+    }
+
+    /// Checks the value of a `let` against the type in its signature.
+    fn expect_signature(&mut self, stmt: StmtId, rigid: &Type, found: &Type, location: Span) {
+        let file = self.file;
+        let StmtKind::Let {
+            signature: Some(signature),
+            ..
+        } = &file[stmt].kind
+        else {
+            unreachable!("only `let`s with a signature have one");
+        };
+        self.expect(rigid, signature.ty.location, found, location);
+    }
+
+    /// Declares the type of a `type`-statement, so that it can be used by name.
+    fn declare_type(&mut self, stmt: StmtId) {
+        let file = self.file;
+        let StmtKind::Type { name, params, .. } = &file[stmt].kind else {
+            return;
+        };
+
+        let mut seen = HashSet::new();
+        for param in params {
+            if !seen.insert(param.ident) {
+                self.errors.push(TypeError::DuplicateTypeParam {
+                    name: param.ident.to_string(),
+                    location: param.location,
+                });
+            }
+        }
+
+        let is_builtin = prelude::type_named(name.ident.as_str()).is_some();
+        if is_builtin || self.types.contains_key(&name.ident) {
+            self.errors.push(TypeError::DuplicateType {
+                name: name.ident.to_string(),
+                location: name.location,
+            });
+            return;
+        }
+        self.types.insert(name.ident, (params.len(), stmt));
+    }
+
+    /// Gets the `Scheme` of the `i`th constructor of a `type`-statement, which is a function from
+    /// its fields to the type, like `a -> List a -> List a`.
+    fn constructor_scheme(&mut self, stmt: StmtId, i: usize) -> Scheme {
+        let file = self.file;
+        let StmtKind::Type {
+            name,
+            params,
+            constructors,
+        } = &file[stmt].kind
+        else {
+            unreachable!("only `type`-statements have constructors");
+        };
+
+        self.level += 1;
+        let vars = params
+            .iter()
+            .map(|param| (param.ident, self.fresh()))
+            .collect::<HashMap<_, _>>();
+        let args = params.iter().map(|param| vars[&param.ident].clone());
+        let ty = Type::Named(name.ident, args.collect());
+        let fields = constructors[i]
+            .fields
+            .iter()
+            .map(|field| self.type_from(field, &vars))
+            .collect::<Vec<_>>();
+        self.level -= 1;
+
+        let ty = fields
+            .into_iter()
+            .rev()
+            .fold(ty, |ty, field| Type::function(field, ty));
+        self.generalize(&ty)
+    }
+
+    /// Finds the type in the signature of a `let`, if it has one.
+    fn declare_signature(&mut self, stmt: StmtId) {
+        let file = self.file;
+        let StmtKind::Let {
+            signature: Some(signature),
+            ..
+        } = &file[stmt].kind
+        else {
+            return;
+        };
+
+        // NOTE: The declared type is made with type variables, which are generalized into its
+        // `Scheme`, and then they're replaced with rigid types to check the value against.
+        self.level += 1;
+        let (mut vars, mut rigids) = (HashMap::new(), HashMap::new());
+        for param in &signature.params {
+            let ident = param.name.ident;
+            if vars.contains_key(&ident) {
+                self.errors.push(TypeError::DuplicateTypeParam {
+                    name: ident.to_string(),
+                    location: param.name.location,
+                });
+                continue;
+            }
+
+            let var = self.fresh();
+            let rigid = Type::Rigid(RigidVar {
+                ident,
+                id: self.rigids,
+            });
+            self.rigids += 1;
+
+            for bound in &param.bounds {
+                let Some(t) = Trait::named(bound.ident.as_str()) else {
+                    self.errors.push(TypeError::UnknownTrait {
+                        name: bound.ident.to_string(),
+                        location: bound.location,
+                    });
+                    continue;
+                };
+                if let Type::Var(var) = var {
+                    self.info(var).traits.push(t);
+                }
+                self.instances.insert((t, rigid.clone()));
+            }
+
+            if let Type::Var(var) = var {
+                rigids.insert(var, rigid);
+            }
+            vars.insert(ident, var);
+        }
+        let ty = self.type_from(&signature.ty, &vars);
+        self.level -= 1;
+
+        let rigid = substitute(&ty, &rigids);
+        let declared = self.generalize(&ty);
+        self.signatures.insert(stmt, (rigid, declared));
+    }
+
+    /// Makes the `Type` written as `ty`, where the type parameters in scope are `params`. Types
+    /// that can't be found, or aren't given as many type arguments as they take, are reported and
+    /// become unknown.
+    fn type_from(&mut self, ty: &TypeExpr, params: &HashMap<Ident, Type>) -> Type {
+        let (name, args) = match &ty.kind {
+            TypeExprKind::Named { name, args } => (name, args),
+            TypeExprKind::Unit => return Type::Unit,
+            TypeExprKind::Tuple(items) => {
+                let items = items.iter().map(|item| self.type_from(item, params));
+                return Type::Tuple(items.collect());
+            }
+            TypeExprKind::Function(param, result) => {
+                let param = self.type_from(param, params);
+                return Type::function(param, self.type_from(result, params));
+            }
+        };
+
+        let (arity, found) = if let Some(param) = params.get(&name.ident) {
+            (0, param.clone())
+        } else if let Some(builtin) = prelude::type_named(name.ident.as_str()) {
+            (0, builtin)
+        } else if let Some(&(arity, _)) = self.types.get(&name.ident) {
+            (arity, Type::Named(name.ident, vec![]))
+        } else {
+            self.errors.push(TypeError::UnknownType {
+                name: name.ident.to_string(),
+                location: name.location,
+            });
+            return self.fresh();
+        };
+
+        if args.len() != arity {
+            self.errors.push(TypeError::WrongArity {
+                name: name.ident.to_string(),
+                expected: arity,
+                found: args.len(),
+                location: ty.location,
+            });
+            return self.fresh();
+        }
+        match found {
+            Type::Named(ident, _) => {
+                let args = args.iter().map(|arg| self.type_from(arg, params));
+                Type::Named(ident, args.collect())
+            }
+            found => found,
+        }
+    }
+
+    /// Finds the trait and the type of an `impl`-statement, and adds them to the instances.
+    fn declare_impl(&mut self, stmt: StmtId) {
+        let StmtKind::Impl {
+            trait_name,
+            type_name,
+            ..
+        } = self.file[stmt].kind
+        else {
+            return;
+        };
+
+        let Some(t) = Trait::named(trait_name.ident.as_str()) else {
+            self.errors.push(TypeError::UnknownTrait {
+                name: trait_name.ident.to_string(),
+                location: trait_name.location,
+            });
+            return;
+        };
+        let declared = self
+            .types
+            .contains_key(&type_name.ident)
+            .then(|| Type::Named(type_name.ident, vec![]));
+        let Some(ty) = prelude::type_named(type_name.ident.as_str()).or(declared) else {
+            self.errors.push(TypeError::UnknownType {
+                name: type_name.ident.to_string(),
+                location: type_name.location,
+            });
+            return;
+        };
+
+        let error = if prelude::method(t).is_none() {
+            TypeError::BuiltinTrait {
+                trait_name: t.name().to_string(),
+                location: trait_name.location,
+            }
+        } else if !self.instances.insert((t, ty.clone())) {
+            TypeError::DuplicateInstance {
+                trait_name: t.name().to_string(),
+                ty: ty.to_string(),
+                location: type_name.location,
+            }
+        } else {
+            self.impls.insert(stmt, (t, ty));
+            return;
+        };
+        self.errors.push(error);
+    }
+
+    /// Infers the value of an `impl`-statement, which has to be the trait's method for the type.
+    fn impl_statement(&mut self, stmt: StmtId) {
+        let StmtKind::Impl {
+            trait_name, value, ..
+        } = self.file[stmt].kind
+        else {
+            return;
+        };
+
+        // NOTE: The value is inferred even if the implementation couldn't be declared, so that the
+        // errors in it are still found.
+        self.level += 1;
+        let found = self.expression(value);
+        self.level -= 1;
+
+        let Some((t, ty)) = self.impls.get(&stmt).cloned() else {
+            return;
+        };
+        let (_, scheme) = prelude::method(t).expect("only traits with methods are implemented");
+        // NOTE: The method has to work for the type with any type arguments, so they're rigid.
+        let ty = match ty {
+            Type::Named(ident, _) => {
+                let file = self.file;
+                let (_, declared_by) = self.types[&ident];
+                let StmtKind::Type { params, .. } = &file[declared_by].kind else {
+                    unreachable!("types are declared by `type`-statements");
+                };
+                let args = params.iter().map(|param| {
+                    self.rigids += 1;
+                    Type::Rigid(RigidVar {
+                        ident: param.ident,
+                        id: self.rigids - 1,
+                    })
+                });
+                Type::Named(ident, args.collect())
+            }
+            ty => ty,
+        };
+        let replacements = scheme.vars.iter().map(|&var| (var, ty.clone())).collect();
+        let expected = substitute(&scheme.ty, &replacements);
+        self.expect(
+            &expected,
+            trait_name.location,
+            &found,
+            self.file[value].location,
+        );
+    }
+
+    /// Gets the `Scheme` of a `let` whose value is `ty`.
+    fn let_scheme(&mut self, stmt: StmtId, ty: &Type) -> Scheme {
+        match self.file[stmt].kind {
+            // NOTE: A mutable `let` could be changed to a value of any of the types it's
+            // generalized to, which wouldn't be safe, so it only ever has one type. The unknowns
+            // in it belong to the outer `let` now, so they aren't generalized by any other.
+            StmtKind::Let {
+                is_mutable: true, ..
+            } => {
+                let ty = self.zonk(ty);
+                for var in ty.vars() {
+                    let level = self.level;
+                    let info = self.info(var);
+                    info.level = info.level.min(level);
+                }
+                Scheme::mono(ty)
+            }
+            _ => self.generalize(ty),
+        }
+    }
+
+    fn expression(&mut self, expr: ExprId) -> Type {
+        let ty = match &self.file[expr].kind {
+            ExprKind::Literal(Literal::Integer(_)) => self.fresh_in(Some(Class::Integral)),
+            ExprKind::Literal(Literal::Floating(_)) => self.fresh_in(Some(Class::Fractional)),
+            ExprKind::Literal(Literal::Boolean(_)) => Type::Bool,
+            ExprKind::Var(_) => match self.resolution.def_of(expr) {
+                Some(def) => self.def_type(def),
+                // NOTE: Names that couldn't be resolved have already been reported.
+                None => self.fresh(),
+            },
+            // NOTE: The rest of a path is looked up through the module tree, so what it leads to
+            // isn't known here.
+            ExprKind::Path(_) => self.fresh(),
+            ExprKind::Lambda { param, body } => {
+                let param_ty = match param {
+                    Some(_) => {
+                        let ty = self.fresh();
+                        if let Some(&def) = self.params.get(&expr) {
+                            self.defs.insert(def, Scheme::mono(ty.clone()));
+                        }
+                        ty
+                    }
+                    None => Type::Unit,
+                };
+                let body = self.expression(*body);
+                Type::function(param_ty, body)
+            }
+            &ExprKind::Apply { function, arg } => self.apply(function, arg),
+            &ExprKind::Binary {
+                op,
+                op_location,
+                lhs,
+                rhs,
+            } => {
+                let (lhs_ty, rhs_ty) = (self.expression(lhs), self.expression(rhs));
+                let (lhs_at, rhs_at) = (self.file[lhs].location, self.file[rhs].location);
+                let Type::Function(lhs_param, rest) = self.method(op.method_trait()) else {
+                    unreachable!("binary operators call methods taking 2 arguments");
+                };
+                let Type::Function(rhs_param, result) = *rest else {
+                    unreachable!("binary operators call methods taking 2 arguments");
+                };
+                self.expect(&lhs_param, op_location, &lhs_ty, lhs_at);
+                // NOTE: Both sides are the same type, so the right one is expected to be the left
+                // one's type, which is easier to read in an error than the method's.
+                self.expect(&rhs_param, lhs_at, &rhs_ty, rhs_at);
+                *result
+            }
+            &ExprKind::Unary {
+                op,
+                op_location,
+                operand,
+            } => {
+                let ty = self.expression(operand);
+                let at = self.file[operand].location;
+                let Type::Function(param, result) = self.method(op.method_trait()) else {
+                    unreachable!("unary operators call methods taking 1 argument");
+                };
+                self.expect(&param, op_location, &ty, at);
+                *result
+            }
+            ExprKind::Field { expr, field } => {
+                let ty = self.expression(*expr);
+                let ty = self.zonk(&ty);
+                self.errors.push(TypeError::NoField {
+                    ty: ty.to_string(),
+                    field: field.ident.to_string(),
+                    location: field.location,
+                });
+                self.fresh()
+            }
+            ExprKind::Block { statements, result } => {
+                for &stmt in statements {
+                    self.block_statement(stmt);
+                }
+                self.expression(*result)
+            }
+            ExprKind::Tuple(items) => {
+                Type::Tuple(items.iter().map(|&item| self.expression(item)).collect())
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                let scrutinee_ty = self.expression(*scrutinee);
+                let scrutinee_at = self.file[*scrutinee].location;
+
+                // NOTE: Every arm is expected to be the type of the first one.
+                let mut first: Option<(Type, Span)> = None;
+                for (i, arm) in arms.iter().enumerate() {
+                    let pattern_ty = self.pattern(&arm.pattern, expr, i);
+                    self.expect(
+                        &scrutinee_ty,
+                        scrutinee_at,
+                        &pattern_ty,
+                        arm.pattern.location,
+                    );
+
+                    let body_ty = self.expression(arm.body);
+                    let body_at = self.file[arm.body].location;
+                    match &first {
+                        Some((first_ty, first_at)) => {
+                            self.expect(&first_ty.clone(), *first_at, &body_ty, body_at)
+                        }
+                        None => first = Some((body_ty, body_at)),
+                    }
+                }
+                // NOTE: A `match` without arms never has a value, so it can be anything.
+                match first {
+                    Some((ty, _)) => ty,
+                    None => self.fresh(),
+                }
+            }
+            // NOTE: The error for this has already been reported, so it can be anything.
+            ExprKind::Error => self.fresh(),
+        };
+
+        self.exprs[expr.index()] = Some(ty.clone());
+        ty
+    }
+
+    /// Infers the type of a value that the pattern of the `i`th arm of `m` matches, and gives the
+    /// names it binds their types.
+    fn pattern(&mut self, pattern: &Pattern, m: ExprId, i: usize) -> Type {
+        match &pattern.kind {
+            PatternKind::Wildcard => self.fresh(),
+            PatternKind::Binding(name) => {
+                let ty = self.fresh();
+                // NOTE: A name bound twice has only been defined by its first binding.
+                let def = self.bindings.get(&(m, i, name.ident)).copied();
+                if let Some(def) =
+                    def.filter(|&def| self.resolution.defs[def].name.location == name.location)
+                {
+                    self.defs.insert(def, Scheme::mono(ty.clone()));
+                }
+                ty
+            }
+            PatternKind::Literal(literal) => self.expression(*literal),
+            PatternKind::Tuple(items) => {
+                Type::Tuple(items.iter().map(|item| self.pattern(item, m, i)).collect())
+            }
+            PatternKind::Constructor { constructor, args } => {
+                let ty = self.expression(*constructor);
+                let location = self.file[*constructor].location;
+                let name = match self.file[*constructor].kind {
+                    ExprKind::Var(name) => name.ident.to_string(),
+                    _ => unreachable!("constructors in patterns are `Var`s"),
+                };
+
+                // NOTE: Names that couldn't be resolved have already been reported.
+                let def = self.resolution.def_of(*constructor);
+                let is_constructor = def.map(|def| self.resolution.defs[def].kind);
+                if !matches!(is_constructor, Some(DefKind::Constructor(..))) {
+                    if is_constructor.is_some() {
+                        self.errors
+                            .push(TypeError::NotAConstructor { name, location });
+                    }
+                    for arg in args {
+                        self.pattern(arg, m, i);
+                    }
+                    return self.fresh();
+                }
+
+                // NOTE: A constructor's type is a function from its fields to the type it makes.
+                let mut fields = vec![];
+                let mut result = ty;
+                while let Type::Function(field, rest) = result {
+                    fields.push(*field);
+                    result = *rest;
+                }
+
+                if fields.len() != args.len() {
+                    self.errors.push(TypeError::PatternArity {
+                        name,
+                        expected: fields.len(),
+                        found: args.len(),
+                        location: pattern.location,
+                    });
+                    for arg in args {
+                        self.pattern(arg, m, i);
+                    }
+                    return result;
+                }
+                for (field, arg) in fields.iter().zip(args) {
+                    let arg_ty = self.pattern(arg, m, i);
+                    self.expect(field, location, &arg_ty, arg.location);
+                }
+                result
+            }
+        }
+    }
+
+    fn apply(&mut self, function: ExprId, arg: ExprId) -> Type {
+        let function_ty = self.expression(function);
+        let arg_ty = self.expression(arg);
+        let (function_at, arg_at) = (self.file[function].location, self.file[arg].location);
+
+        match self.prune(&function_ty) {
+            Type::Function(param, result) => {
+                self.expect(&param, function_at, &arg_ty, arg_at);
+                *result
+            }
+            Type::Var(var) if self.vars[var.0 as usize].class.is_none() => {
+                let result = self.fresh();
+                let expected = Type::function(arg_ty, result.clone());
+                self.expect(&expected, arg_at, &function_ty, function_at);
+                result
+            }
+            ty => {
+                let [found] = self.display(&[&ty]).try_into().expect("1 type was written");
+                self.errors.push(TypeError::NotAFunction {
+                    found,
+                    location: function_at,
+                    arg: arg_at,
+                });
+                self.fresh()
+            }
+        }
+    }
+}
+
+/// Checks that every number literal fits into the type it was found to be.
+fn check_literals(file: &File, types: &Types) -> Vec<TypeError> {
+    let mut errors = vec![];
+
+    // NOTE: `-128` is `128` negated, which fits into an `I8` even though `128` on its own doesn't,
+    // so negated literals are checked with their sign.
+    let mut negated = HashSet::new();
+    for (id, expr) in file.exprs.iter() {
+        let ExprKind::Unary {
+            op: UnaryOp::Neg,
+            operand,
+            ..
+        } = expr.kind
+        else {
+            continue;
+        };
+
+        let (ExprKind::Literal(Literal::Integer(value)), Type::Int(int)) =
+            (&file[operand].kind, types.of(id))
+        else {
+            continue;
+        };
+        // NOTE: Unsigned integers don't implement `Neg`, which has already been reported.
+        if int.is_signed() {
+            errors.extend(out_of_range(-i128::from(*value), *int, expr.location));
+        }
+        negated.insert(operand);
+    }
+
+    for (id, expr) in file.exprs.iter() {
+        match (&expr.kind, types.of(id)) {
+            (ExprKind::Literal(Literal::Integer(value)), Type::Int(int))
+                if !negated.contains(&id) =>
+            {
+                errors.extend(out_of_range(i128::from(*value), *int, expr.location));
+            }
+            (ExprKind::Literal(Literal::Floating(value)), Type::Float(float))
+                if value.abs() > float.max() =>
+            {
+                errors.push(TypeError::OutOfRange {
+                    literal: value.to_string(),
+                    ty: float.name().to_string(),
+                    min: format!("{:e}", -float.max()),
+                    max: format!("{:e}", float.max()),
+                    location: expr.location,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    errors
+}
+
+/// Gets the error for an integer literal that doesn't fit into `int`, if it doesn't.
+fn out_of_range(value: i128, int: IntType, location: Span) -> Option<TypeError> {
+    match (int.min()..=int.max()).contains(&value) {
+        true => None,
+        false => Some(TypeError::OutOfRange {
+            literal: value.to_string(),
+            ty: int.name().to_string(),
+            min: int.min().to_string(),
+            max: int.max().to_string(),
+            location,
+        }),
+    }
+}
+
+/// Replaces the type variables in `ty` that are in `replacements`.
+fn substitute(ty: &Type, replacements: &HashMap<TypeVar, Type>) -> Type {
+    match ty {
+        Type::Var(var) => replacements.get(var).cloned().unwrap_or(Type::Var(*var)),
+        Type::Function(param, result) => Type::function(
+            substitute(param, replacements),
+            substitute(result, replacements),
+        ),
+        Type::Named(ident, args) => Type::Named(
+            *ident,
+            args.iter()
+                .map(|arg| substitute(arg, replacements))
+                .collect(),
+        ),
+        Type::Tuple(items) => Type::Tuple(
+            items
+                .iter()
+                .map(|item| substitute(item, replacements))
+                .collect(),
+        ),
+        ty => ty.clone(),
+    }
+}
+
+/// The state of Tarjan's strongly connected components algorithm, which finds the groups of nodes
+/// that can all reach each other. The groups are found in the order they can be reached in, with
+/// the ones that can be reached from a group always found before it.
+struct Tarjan<'a> {
+    /// The nodes each node uses.
+    uses: &'a [Vec<usize>],
+
+    /// The order each node was visited in.
+    index: Vec<Option<usize>>,
+
+    /// The smallest index reachable from each node.
+    low: Vec<usize>,
+
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    next: usize,
+    groups: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn connect(&mut self, node: usize) {
+        self.index[node] = Some(self.next);
+        self.low[node] = self.next;
+        self.next += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &used in &self.uses[node] {
+            match self.index[used] {
+                None => {
+                    self.connect(used);
+                    self.low[node] = self.low[node].min(self.low[used]);
+                }
+                Some(index) if self.on_stack[used] => {
+                    self.low[node] = self.low[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low[node]) == self.index[node] {
+            let mut group = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack[member] = false;
+                group.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            // NOTE: Keep the group in the order it's written in.
+            group.sort_unstable();
+            self.groups.push(group);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compile::{lower::lower, parser::parse, resolve::resolve},
+        repr::ast::ident::Ident,
+    };
+
+    /// Parses, lowers, resolves and infers `source`.
+    fn inferred(source: &str) -> (Resolution, Vec<TypeError>) {
+        let (file, errors) = lower(&parse(source).unwrap());
+        assert_eq!(errors, []);
+        let (mut resolution, errors) = resolve(&file);
+        assert_eq!(errors, []);
+        let (_, errors) = infer(&file, &mut resolution);
+        (resolution, errors)
+    }
+
+    /// Gets the type of a top-level name.
+    fn type_of(resolution: &Resolution, name: &str) -> String {
+        resolution.scopes[resolution.root]
+            .type_of(Ident::new(name))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn can_infer_literals_and_functions() {
+        let source = "let a = 1\nlet b = 2.5\nlet c = True\nlet add = |x, y| x + y\nlet three = add 1 2\nlet neg = |x| -x\nlet zero = || 0\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(errors, []);
+        assert_eq!(type_of(&resolution, "a"), "I64");
+        assert_eq!(type_of(&resolution, "b"), "F64");
+        assert_eq!(type_of(&resolution, "c"), "Bool");
+        assert_eq!(type_of(&resolution, "add"), "Add a => a -> a -> a");
+        assert_eq!(type_of(&resolution, "three"), "I64");
+        assert_eq!(type_of(&resolution, "neg"), "Neg a => a -> a");
+        assert_eq!(type_of(&resolution, "zero"), "() -> I64");
+    }
+
+    #[test]
+    fn can_generalize_lets() {
+        let source = "let id = |x| x\nlet a = id 1\nlet b = id True\nlet c = {\n    let k = |x, y| x\n    k 1.5 True\n}\nlet apply = |f, x| f x\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(errors, []);
+        assert_eq!(type_of(&resolution, "id"), "a -> a");
+        assert_eq!(type_of(&resolution, "a"), "I64");
+        assert_eq!(type_of(&resolution, "b"), "Bool");
+        assert_eq!(type_of(&resolution, "c"), "F64");
+        assert_eq!(type_of(&resolution, "apply"), "(a -> b) -> a -> b");
+
+        // NOTE: The `let` in the block gets its type in its own scope.
+        let k = resolution
+            .scopes
+            .iter()
+            .find_map(|(_, scope)| scope.type_of(Ident::new("k")))
+            .unwrap();
+        assert_eq!(k.to_string(), "a -> b -> a");
+    }
+
+    #[test]
+    fn can_infer_recursive_lets() {
+        // NOTE: `even` and `odd` use each other, so they're inferred together, while `twice` only
+        // uses `inc`, which is written after it.
+        let source = "let even = |n| odd (n - 1)\nlet odd = |n| even (n - 1)\nlet twice = |x| inc (inc x)\nlet inc = |x| x + 1\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(errors, []);
+        assert_eq!(type_of(&resolution, "even"), "I64 -> a");
+        assert_eq!(type_of(&resolution, "odd"), "I64 -> a");
+        assert_eq!(type_of(&resolution, "twice"), "I64 -> I64");
+    }
+
+    #[test]
+    fn can_report_mismatches() {
+        let (_, errors) = inferred("let x = 1\nlet y = x + True\n");
+        assert_eq!(
+            errors,
+            [TypeError::Mismatch {
+                expected: "{integer}".into(),
+                found: "Bool".into(),
+                location: (22..26).into(),
+                expected_at: (18..19).into(),
+            }]
+        );
+
+        let (_, errors) = inferred("let id = |x| x\nlet f = |g| g 1 + g True\n");
+        assert_eq!(
+            errors,
+            [TypeError::Mismatch {
+                expected: "{integer}".into(),
+                found: "Bool".into(),
+                location: (35..39).into(),
+                expected_at: (33..34).into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn can_report_other_type_errors() {
+        let source = "let a = 1 2\nlet b = |f| f f\nlet c = True + False\nlet add = |x, y| x + y\nlet d = add True False\nlet e = c.field\n";
+        let (_, errors) = inferred(source);
+        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "`{integer}` isn't a function, so it can't be applied",
+                "Cannot construct the infinite type `a = a -> b`",
+                "`Bool` doesn't implement `Add`",
+                "`Bool` doesn't implement `Add`",
+                "`Bool` has no field `field`",
+            ]
+        );
+    }
+
+    #[test]
+    fn can_infer_literals_from_context() {
+        // NOTE: `x` isn't generalized, so using it as a `U8` makes it one everywhere.
+        let source = "let x = 1\nlet y = x + to_u8 2\nlet z = 2.5\nlet w = to_f32 x + z\nlet inc = |n| n + 1\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(errors, []);
+        assert_eq!(type_of(&resolution, "x"), "U8");
+        assert_eq!(type_of(&resolution, "y"), "U8");
+        assert_eq!(type_of(&resolution, "z"), "F32");
+        assert_eq!(type_of(&resolution, "w"), "F32");
+        assert_eq!(type_of(&resolution, "inc"), "I64 -> I64");
+        assert_eq!(
+            resolution.scopes[resolution.prelude]
+                .type_of(Ident::new("to_u8"))
+                .unwrap()
+                .to_string(),
+            "Num a => a -> U8"
+        );
+    }
+
+    #[test]
+    fn can_check_literals() {
+        let source = "let a = to_u8 0 + 256\nlet b = to_i8 0 + (-128)\nlet c = to_i8 0 + (-129)\nlet d = -(to_u8 1)\nlet e = 1 + 2.5\n";
+        let (_, errors) = inferred(source);
+        assert_eq!(
+            errors,
+            [
+                TypeError::MissingInstance {
+                    trait_name: "Neg".into(),
+                    ty: "U8".into(),
+                    location: (82..89).into(),
+                },
+                TypeError::Mismatch {
+                    expected: "{integer}".into(),
+                    found: "{float}".into(),
+                    location: (103..106).into(),
+                    expected_at: (99..100).into(),
+                },
+                TypeError::OutOfRange {
+                    literal: "-129".into(),
+                    ty: "I8".into(),
+                    min: "-128".into(),
+                    max: "127".into(),
+                    location: (66..70).into(),
+                },
+                TypeError::OutOfRange {
+                    literal: "256".into(),
+                    ty: "U8".into(),
+                    min: "0".into(),
+                    max: "255".into(),
+                    location: (18..21).into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_check_the_largest_literals() {
+        let source = "let max: U64 = 18446744073709551615\nlet min: I64 = -9223372036854775808\nlet over: I64 = 9223372036854775808\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(type_of(&resolution, "max"), "U64");
+        assert_eq!(type_of(&resolution, "min"), "I64");
+        assert_eq!(
+            errors,
+            [TypeError::OutOfRange {
+                literal: "9223372036854775808".into(),
+                ty: "I64".into(),
+                min: "-9223372036854775808".into(),
+                max: "9223372036854775807".into(),
+                location: (88..107).into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn can_infer_traits() {
+        let source = "let eq = |a, b| a == b\nlet between = |x, lo, hi| lo <= x\nlet yes = eq True False\nimpl Add for Bool = |a, b| a == b\nlet both = True + False\nlet sum = |a, b| -(a + b) * a\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(errors, []);
+        assert_eq!(type_of(&resolution, "eq"), "Eq a => a -> a -> Bool");
+        assert_eq!(
+            type_of(&resolution, "between"),
+            "Ord a => a -> a -> b -> Bool"
+        );
+        assert_eq!(type_of(&resolution, "yes"), "Bool");
+        assert_eq!(type_of(&resolution, "both"), "Bool");
+        assert_eq!(
+            type_of(&resolution, "sum"),
+            "(Add a, Mul a, Neg a) => a -> a -> a"
+        );
+    }
+
+    #[test]
+    fn can_report_trait_errors() {
+        let source = "impl Add for Bool = |a| a\nimpl Num for Bool = 1\nimpl Eq for I64 = |a, b| True\nimpl Show for Bool = 1\nimpl Add for Text = 1\nlet f = (|x| x) + (|x| x)\n";
+        let (_, errors) = inferred(source);
+        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "`Num` is only implemented by the built-in types",
+                "`I64` already implements `Eq`",
+                "Cannot find trait `Show`",
+                "Cannot find type `Text`",
+                "`a -> a` doesn't implement `Add`",
+                "Mismatched types: expected `Bool -> Bool -> Bool`, found `a -> a`",
+            ]
+        );
+    }
+
+    #[test]
+    fn can_infer_type_declarations() {
+        let source = "type List[a] = Nil | Cons a (List a)\ntype Shape = Circle F64 | Rect F64 F64\nlet xs = Cons 1 (Cons 2 Nil)\nlet map[a, b]: (a -> b) -> List a -> List b = |f, xs| Nil\nlet ys = map (|x| x == 1) xs\nlet twice[a: Add]: a -> a = |x| x + x\nlet shape = twice 2.5 |> Circle\n";
+        let (resolution, errors) = inferred(source);
+        assert_eq!(errors, []);
+        assert_eq!(type_of(&resolution, "Nil"), "List a");
+        assert_eq!(type_of(&resolution, "Cons"), "a -> List a -> List a");
+        assert_eq!(type_of(&resolution, "Rect"), "F64 -> F64 -> Shape");
+        assert_eq!(type_of(&resolution, "xs"), "List I64");
+        assert_eq!(type_of(&resolution, "map"), "(a -> b) -> List a -> List b");
+        assert_eq!(type_of(&resolution, "ys"), "List Bool");
+        assert_eq!(type_of(&resolution, "twice"), "Add a => a -> a");
+        assert_eq!(type_of(&resolution, "shape"), "Shape");
+    }
+
+    #[test]
+    fn can_report_kind_errors() {
+        let source = "type List[a] = Nil | Cons a (List a)\ntype Pair[a, a] = Pair a a\ntype Bool = Yes | No\nlet a: List I64 I64 = Nil\nlet b: List = Nil\nlet c[t]: t I64 -> t = |x| x\nlet d: Tree = 1\nlet e[t]: t -> I64 = |x| x\nlet f[t: Show]: t -> t = |x| x + x\n";
+        let (_, errors) = inferred(source);
+        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            errors,
+            [
+                "The type parameter `a` is declared more than once",
+                "The type `Bool` is defined more than once",
+                "`List` takes 1 type argument, but 2 were given",
+                "`List` takes 1 type argument, but 0 were given",
+                "`t` takes 0 type arguments, but 1 was given",
+                "Cannot find type `Tree`",
+                "Cannot find trait `Show`",
+                "Mismatched types: expected `t -> I64`, found `a -> a`",
+                "`t` doesn't implement `Add`",
+            ]
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! The lexer, which splits source code into classified tokens for syntax highlighting and other
+//! tools that don't need a whole syntax tree:
+//! ```
+//! use oxylc::compile::lexer::{lex, token::TokenKind};
+//!
+//! let kinds = lex("let x = 1").iter().map(|token| token.kind).collect::<Vec<_>>();
+//! assert_eq!(kinds[..3], [TokenKind::Keyword, TokenKind::Whitespace, TokenKind::Identifier]);
+//! ```
+//! The lexer never fails: anything it doesn't understand becomes an `Error` token, so it works on
+//! code that's still being written. It's also lossless, as every character of the source is in
+//! exactly one token, including whitespace and comments. The tokens that aren't trivia line up
+//! with the tokens the parser reads, so the lexer is also used to find the trivia of a file.
+
+pub mod token;
+
+use token::{Token, TokenKind};
+
+/// The identifiers which are keywords.
+pub const KEYWORDS: [&str; 11] = [
+    "let", "mod", "pub", "use", "as", "True", "False", "impl", "for", "type", "match",
+];
+
+/// The operators, longest first so that `|>` isn't lexed as `|` and then `>`.
+const OPERATORS: [&str; 16] = [
+    "|>", "::", "==", "!=", "<=", ">=", "->", "=>", "+", "-", "*", "/", ".", "=", "<", ">",
+];
+
+/// The punctuation, longest first. `:` comes after the operators, so `::` is still one token.
+const PUNCTUATION: [&str; 10] = ["#[", "(", ")", "{", "}", "[", "]", ",", "|", ":"];
+
+/// Splits source code into tokens.
+pub fn lex(source: &str) -> Vec<Token> {
+    Lexer::new(source).collect()
+}
+
+/// Checks if a comment is a doc comment. Like Rust, `////` and `/***` are regular comments, and so
+/// is the empty `/**/`.
+pub fn is_doc_comment(comment: &str) -> bool {
+    (comment.starts_with("///") && !comment.starts_with("////"))
+        || (comment.starts_with("/**") && !comment.starts_with("/***") && comment != "/**/")
+}
+
+/// Lexes tokens from some source code, one at a time.
+#[derive(Clone, Debug)]
+pub struct Lexer<'a> {
+    source: &'a str,
+
+    /// Where the next token starts.
+    offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+    /// Creates a new `Lexer` at the start of `source`.
+    pub fn new(source: &'a str) -> Self {
+        Self { source, offset: 0 }
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let rest = &self.source[self.offset..];
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (kind, len) = next_token(rest);
+        let token = Token::new((self.offset..self.offset + len).into(), kind);
+        self.offset += len;
+        Some(token)
+    }
+}
+
+/// Gets the kind and length of the token at the start of `rest`, which can't be empty.
+fn next_token(rest: &str) -> (TokenKind, usize) {
+    let first = rest.chars().next().expect("there's always a next token");
+
+    if rest.starts_with("\r\n") {
+        (TokenKind::Newline, 2)
+    } else if first == '\n' {
+        (TokenKind::Newline, 1)
+    } else if first.is_whitespace() {
+        let len = rest
+            .char_indices()
+            .find(|&(i, c)| c == '\n' || !c.is_whitespace() || rest[i..].starts_with("\r\n"))
+            .map_or(rest.len(), |(i, _)| i);
+        (TokenKind::Whitespace, len)
+    } else if rest.starts_with("//") {
+        // NOTE: The line ending isn't part of the comment, so that it's a newline like any other.
+        let len = rest.find('\n').unwrap_or(rest.len());
+        let len = match rest[..len].ends_with('\r') {
+            true => len - 1,
+            false => len,
+        };
+        (comment_kind(&rest[..len]), len)
+    } else if let Some(comment) = rest.strip_prefix("/*") {
+        match comment.find("*/") {
+            Some(end) => (comment_kind(&rest[..end + 4]), end + 4),
+            None => (TokenKind::Error, rest.len()),
+        }
+    } else if let Some(string) = rest.strip_prefix('"') {
+        // NOTE: There are no escapes (yet), so the string ends at the next `"`.
+        match string.find('"') {
+            Some(end) => (TokenKind::String, end + 2),
+            None => (TokenKind::Error, rest.len()),
+        }
+    } else if first.is_ascii_digit() {
+        let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let len = digits(rest);
+        let len = match rest[len..].starts_with('.') {
+            true => len + 1 + digits(&rest[len + 1..]),
+            false => len,
+        };
+        (TokenKind::Number, len)
+    } else if first.is_ascii_alphabetic() || first == '_' {
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        match KEYWORDS.contains(&&rest[..len]) {
+            true => (TokenKind::Keyword, len),
+            false => (TokenKind::Identifier, len),
+        }
+    } else if let Some(operator) = OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+        (TokenKind::Operator, operator.len())
+    } else if let Some(punctuation) = PUNCTUATION.iter().find(|p| rest.starts_with(*p)) {
+        (TokenKind::Punctuation, punctuation.len())
+    } else {
+        (TokenKind::Error, first.len_utf8())
+    }
+}
+
+fn comment_kind(comment: &str) -> TokenKind {
+    match is_doc_comment(comment) {
+        true => TokenKind::DocComment,
+        false => TokenKind::Comment,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{parser::parse, printer::tokens::tokens};
+    use std::fs;
+
+    fn kinds(source: &str) -> Vec<(TokenKind, &str)> {
+        lex(source)
+            .iter()
+            .map(|token| (token.kind, token.text(source)))
+            .collect()
+    }
+
+    #[test]
+    fn can_lex_tokens() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds("/// Docs.\n#[a(\"b\")]\npub let f = |x_1| x_1 |> g::h // c\r\nlet y = 1.5*-2 /* d */"),
+            [
+                (DocComment, "/// Docs."),
+                (Newline, "\n"),
+                (Punctuation, "#["),
+                (Identifier, "a"),
+                (Punctuation, "("),
+                (String, "\"b\""),
+                (Punctuation, ")"),
+                (Punctuation, "]"),
+                (Newline, "\n"),
+                (Keyword, "pub"),
+                (Whitespace, " "),
+                (Keyword, "let"),
+                (Whitespace, " "),
+                (Identifier, "f"),
+                (Whitespace, " "),
+                (Operator, "="),
+                (Whitespace, " "),
+                (Punctuation, "|"),
+                (Identifier, "x_1"),
+                (Punctuation, "|"),
+                (Whitespace, " "),
+                (Identifier, "x_1"),
+                (Whitespace, " "),
+                (Operator, "|>"),
+                (Whitespace, " "),
+                (Identifier, "g"),
+                (Operator, "::"),
+                (Identifier, "h"),
+                (Whitespace, " "),
+                (Comment, "// c"),
+                (Newline, "\r\n"),
+                (Keyword, "let"),
+                (Whitespace, " "),
+                (Identifier, "y"),
+                (Whitespace, " "),
+                (Operator, "="),
+                (Whitespace, " "),
+                (Number, "1.5"),
+                (Operator, "*"),
+                (Operator, "-"),
+                (Number, "2"),
+                (Whitespace, " "),
+                (Comment, "/* d */"),
+            ]
+        );
+    }
+
+    #[test]
+    fn never_fails() {
+        use TokenKind::*;
+
+        assert_eq!(
+            kinds("let é = $ \"open"),
+            [
+                (Keyword, "let"),
+                (Whitespace, " "),
+                (Error, "é"),
+                (Whitespace, " "),
+                (Operator, "="),
+                (Whitespace, " "),
+                (Error, "$"),
+                (Whitespace, " "),
+                (Error, "\"open"),
+            ]
+        );
+        assert_eq!(kinds("/* open"), [(Error, "/* open")]);
+        assert_eq!(
+            kinds("//// x\n/**/"),
+            [(Comment, "//// x"), (Newline, "\n"), (Comment, "/**/")]
+        );
+        assert_eq!(kinds(""), []);
+    }
+
+    #[test]
+    fn can_lex_corpus() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../oxylc-fuzzer/in");
+
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            let lexed = lex(&source);
+
+            let text = lexed
+                .iter()
+                .map(|token| token.text(&source))
+                .collect::<String>();
+            assert_eq!(text, source, "in {}", path.display());
+
+            // NOTE: The tokens that aren't trivia are exactly the tokens the parser reads.
+            let significant = lexed
+                .iter()
+                .filter(|token| !token.is_trivia())
+                .map(|token| token.text(&source))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                significant,
+                tokens(&parse(&source).unwrap()),
+                "in {}",
+                path.display()
+            );
+        }
+    }
+}
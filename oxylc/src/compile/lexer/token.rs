@@ -0,0 +1,70 @@
+use errgonomic::parser::input::Span;
+
+/// A token: a classified piece of the source. Every character of the source is in exactly one
+/// token, so the tokens of a file can always be put back together into its source.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    /// The kind of token it is.
+    pub kind: TokenKind,
+
+    /// The location of the token in the source.
+    pub location: Span,
+}
+
+impl Token {
+    /// Creates a new `Token`.
+    pub fn new(location: Span, kind: TokenKind) -> Self {
+        Self { kind, location }
+    }
+
+    /// Gets the text of the token in the source it was lexed from.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.location.start()..self.location.end()]
+    }
+
+    /// Whether the token is trivia, which the parser skips over. Note that doc comments aren't
+    /// trivia, as they're attached to statements.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::Newline | TokenKind::Comment
+        )
+    }
+}
+
+/// The kinds of tokens we can have.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A keyword, like `let` or `True`.
+    Keyword,
+
+    /// An identifier that isn't a keyword.
+    Identifier,
+
+    /// An integer or floating-point number, like `1` or `1.5`.
+    Number,
+
+    /// A string, like `"a"`.
+    String,
+
+    /// An operator, like `+`, `|>` or `=`.
+    Operator,
+
+    /// Punctuation, like `(`, `,` or `#[`.
+    Punctuation,
+
+    /// A `//` or `/* */` comment. Line comments don't include their line ending.
+    Comment,
+
+    /// A `///` or `/** */` doc comment.
+    DocComment,
+
+    /// A run of whitespace, not including newlines.
+    Whitespace,
+
+    /// A line ending, either `\n` or `\r\n`.
+    Newline,
+
+    /// Something that isn't valid Oxyl, like an unknown character or an unterminated string.
+    Error,
+}
@@ -0,0 +1,32 @@
+use crate::repr::ast::ident::Ident;
+use errgonomic::parser::input::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LowerError {
+    #[error("Expected a name after `{operator}`")]
+    ExpectedName {
+        operator: &'static str,
+        location: Span,
+    },
+
+    #[error("`{operator}` can't be used as a {position} operator")]
+    InvalidOperator {
+        operator: &'static str,
+        position: &'static str,
+        location: Span,
+    },
+
+    #[error("`{name}` isn't a constructor, so it can't be applied to patterns")]
+    ExpectedConstructor { name: Ident, location: Span },
+}
+
+impl LowerError {
+    /// Gets the location in the file where the error happened.
+    pub fn location(&self) -> Span {
+        match self {
+            LowerError::ExpectedName { location, .. }
+            | LowerError::InvalidOperator { location, .. }
+            | LowerError::ExpectedConstructor { location, .. } => *location,
+        }
+    }
+}
@@ -0,0 +1,541 @@
+//! Lowers the FST into the AST. This is where the sugar of the language goes away:
+//! - Parentheses are dropped, as the tree already says how things are grouped.
+//! - Functions with more than 1 argument become nested functions of 1 argument each, so
+//!   `|a, b| e` becomes `|a| |b| e`.
+//! - `x |> f` becomes the application `f x`.
+//! - Chains of `.` are turned around, as they're parsed as right-associative, but `a.b.c` gets `c`
+//!   out of `a.b`. Chains of `::` become a single path.
+//! - `use`-trees are flattened into a list of imports.
+//! - Parentheses in types are dropped, like the ones in expressions.
+//! - Names in patterns are sorted out: `_` matches anything, names starting with a lowercase letter
+//!   bind what they match, and the rest are constructors.
+//!
+//! Every node in the AST keeps the location of the FST node it came from. Nodes that were made up
+//! while desugaring get the location of what they stand for, so the inner function of `|a, b| e`
+//! covers `b| e`.
+
+pub mod errors;
+
+use crate::{
+    compile::printer::tokens::operator_text,
+    repr::{
+        ast::{
+            self,
+            expr::{BinaryOp, Expr, ExprId, ExprKind, Literal, UnaryOp},
+            pattern::{self, Arm},
+            stmt::{Import, Stmt, StmtId, StmtKind},
+            type_expr::{self, Constructor, Signature, TypeParam},
+            Name,
+        },
+        fst::{
+            block::Block,
+            expression::{Expression, ExpressionKind, Operator, OperatorKind},
+            function::Function,
+            identifier::Identifier,
+            pattern::{Match, Pattern, PatternKind},
+            statement::{Statement, StatementKind},
+            type_expr::{TypeExpr, TypeExprKind},
+            use_tree::{UseTree, UseTreeKind},
+            value::{Value, ValueKind},
+            File, FstNode,
+        },
+    },
+};
+use errgonomic::parser::input::Span;
+use errors::LowerError;
+
+/// Lowers a whole file. Anything that can't be lowered becomes an `ExprKind::Error`, so there is
+/// always a complete AST, and the errors for those nodes are returned along with it.
+pub fn lower(file: &File) -> (ast::File, Vec<LowerError>) {
+    let mut lowerer = Lowerer {
+        ast: ast::File::new(file.location),
+        errors: vec![],
+    };
+
+    for statement in &file.statements {
+        let id = lowerer.statement(statement);
+        lowerer.ast.statements.push(id);
+    }
+
+    (lowerer.ast, lowerer.errors)
+}
+
+/// Builds up the AST of a file.
+struct Lowerer {
+    ast: ast::File,
+    errors: Vec<LowerError>,
+}
+
+impl Lowerer {
+    fn alloc(&mut self, location: Span, kind: ExprKind) -> ExprId {
+        self.ast.exprs.alloc(Expr::new(location, kind))
+    }
+
+    fn statement(&mut self, statement: &Statement) -> StmtId {
+        let kind = match &statement.kind {
+            StatementKind::Let {
+                is_mutable,
+                ident,
+                signature,
+                expression,
+            } => StmtKind::Let {
+                name: name(ident),
+                is_mutable: *is_mutable,
+                signature: signature.as_ref().map(|signature| Signature {
+                    params: signature
+                        .params
+                        .iter()
+                        .flat_map(|params| &params.params)
+                        .map(|param| TypeParam {
+                            name: name(&param.ident),
+                            bounds: param.bounds.iter().map(name).collect(),
+                        })
+                        .collect(),
+                    ty: type_expr(&signature.ty),
+                    location: signature.location,
+                }),
+                value: self.expression(expression),
+            },
+            StatementKind::Mod { ident } => StmtKind::Mod { name: name(ident) },
+            StatementKind::Use { tree } => {
+                let mut imports = vec![];
+                flatten_use_tree(tree, &[], &mut imports);
+                StmtKind::Use { imports }
+            }
+            StatementKind::Impl {
+                trait_ident,
+                type_ident,
+                expression,
+            } => StmtKind::Impl {
+                trait_name: name(trait_ident),
+                type_name: name(type_ident),
+                value: self.expression(expression),
+            },
+            StatementKind::Type {
+                ident,
+                params,
+                constructors,
+            } => StmtKind::Type {
+                name: name(ident),
+                params: params
+                    .iter()
+                    .flat_map(|params| &params.params)
+                    .map(|param| name(&param.ident))
+                    .collect(),
+                constructors: constructors
+                    .iter()
+                    .map(|constructor| Constructor {
+                        name: name(&constructor.ident),
+                        fields: constructor.fields.iter().map(type_expr).collect(),
+                        location: constructor.location,
+                    })
+                    .collect(),
+            },
+        };
+
+        self.ast.stmts.alloc(Stmt::new(
+            statement.location,
+            kind,
+            statement.visibility.is_public(),
+        ))
+    }
+
+    fn expression(&mut self, expression: &Expression) -> ExprId {
+        let kind = match &expression.kind {
+            ExpressionKind::Parenthesized { inner, .. } => return self.expression(inner),
+            ExpressionKind::Tuple { items, .. } => {
+                ExprKind::Tuple(items.iter().map(|item| self.expression(item)).collect())
+            }
+            ExpressionKind::Value(value) => return self.value(value),
+            ExpressionKind::Block(block) => self.block(block),
+            ExpressionKind::Match(m) => self.match_expr(m),
+            ExpressionKind::Infix { operator, lhs, rhs } => match operator.kind() {
+                OperatorKind::Plus => self.binary(BinaryOp::Add, operator, lhs, rhs),
+                OperatorKind::Dash => self.binary(BinaryOp::Sub, operator, lhs, rhs),
+                OperatorKind::Star => self.binary(BinaryOp::Mul, operator, lhs, rhs),
+                OperatorKind::FSlash => self.binary(BinaryOp::Div, operator, lhs, rhs),
+                OperatorKind::DoubleEquals => self.binary(BinaryOp::Eq, operator, lhs, rhs),
+                OperatorKind::BangEquals => self.binary(BinaryOp::Ne, operator, lhs, rhs),
+                OperatorKind::LeftAngle => self.binary(BinaryOp::Lt, operator, lhs, rhs),
+                OperatorKind::LeftAngleEquals => self.binary(BinaryOp::Le, operator, lhs, rhs),
+                OperatorKind::RightAngle => self.binary(BinaryOp::Gt, operator, lhs, rhs),
+                OperatorKind::RightAngleEquals => self.binary(BinaryOp::Ge, operator, lhs, rhs),
+                OperatorKind::Triangle => {
+                    // NOTE: The argument comes first in the source, so it's lowered first.
+                    let arg = self.expression(lhs);
+                    let function = self.expression(rhs);
+                    ExprKind::Apply { function, arg }
+                }
+                OperatorKind::Dot => return self.field(expression),
+                OperatorKind::DoubleColon => self.path(expression),
+                OperatorKind::Application => self.invalid(operator, "infix"),
+            },
+            ExpressionKind::Prefix { operator, rhs } => match operator.kind() {
+                OperatorKind::Dash => ExprKind::Unary {
+                    op: UnaryOp::Neg,
+                    op_location: *operator.location(),
+                    operand: self.expression(rhs),
+                },
+                _ => self.invalid(operator, "prefix"),
+            },
+            ExpressionKind::Postfix { operator, .. } => self.invalid(operator, "postfix"),
+            ExpressionKind::Application { function, arg } => ExprKind::Apply {
+                function: self.expression(function),
+                arg: self.expression(arg),
+            },
+        };
+
+        self.alloc(expression.location, kind)
+    }
+
+    fn binary(
+        &mut self,
+        op: BinaryOp,
+        operator: &Operator,
+        lhs: &Expression,
+        rhs: &Expression,
+    ) -> ExprKind {
+        ExprKind::Binary {
+            op,
+            op_location: *operator.location(),
+            lhs: self.expression(lhs),
+            rhs: self.expression(rhs),
+        }
+    }
+
+    fn invalid(&mut self, operator: &Operator, position: &'static str) -> ExprKind {
+        self.errors.push(LowerError::InvalidOperator {
+            operator: operator_text(operator),
+            position,
+            location: *operator.location(),
+        });
+        ExprKind::Error
+    }
+
+    /// Lowers a chain of `.`, like `a.b.c`. It's parsed as `a.(b.c)`, but it means `(a.b).c`, so
+    /// it's flattened and then folded from the left.
+    fn field(&mut self, expression: &Expression) -> ExprId {
+        let mut operands = vec![];
+        flatten_infix(expression, OperatorKind::Dot, &mut operands);
+
+        let mut expr = self.expression(operands[0]);
+        for operand in &operands[1..] {
+            let location = (self.ast[expr].location.start()..operand.location.end()).into();
+            let kind = match identifier(operand) {
+                Some(field) => ExprKind::Field {
+                    expr,
+                    field: name(field),
+                },
+                None => {
+                    self.errors.push(LowerError::ExpectedName {
+                        operator: ".",
+                        location: operand.location,
+                    });
+                    ExprKind::Error
+                }
+            };
+            expr = self.alloc(location, kind);
+        }
+
+        expr
+    }
+
+    /// Lowers a chain of `::`, like `a::b::c`, into a single path.
+    fn path(&mut self, expression: &Expression) -> ExprKind {
+        let mut operands = vec![];
+        flatten_infix(expression, OperatorKind::DoubleColon, &mut operands);
+
+        let mut segments = vec![];
+        for operand in operands {
+            match identifier(operand) {
+                Some(segment) => segments.push(name(segment)),
+                None => {
+                    self.errors.push(LowerError::ExpectedName {
+                        operator: "::",
+                        location: operand.location,
+                    });
+                    return ExprKind::Error;
+                }
+            }
+        }
+
+        ExprKind::Path(segments)
+    }
+
+    fn value(&mut self, value: &Value) -> ExprId {
+        let kind = match &value.kind {
+            ValueKind::Integer(i) => ExprKind::Literal(Literal::Integer(*i)),
+            ValueKind::Floating(f) => ExprKind::Literal(Literal::Floating(*f)),
+            ValueKind::Boolean(b) => ExprKind::Literal(Literal::Boolean(*b)),
+            ValueKind::Identifier(ident) => ExprKind::Var(name(ident)),
+            ValueKind::Function(function) => return self.function(function),
+        };
+
+        self.alloc(value.location, kind)
+    }
+
+    /// Lowers a function into one function per argument, from the inside out, so the innermost
+    /// one takes the last argument.
+    fn function(&mut self, function: &Function) -> ExprId {
+        let mut body = self.expression(&function.expression);
+
+        let Some((first, rest)) = function.args.split_first() else {
+            return self.alloc(function.location, ExprKind::Lambda { param: None, body });
+        };
+
+        for arg in rest.iter().rev() {
+            let location = (arg.location.start()..function.location.end()).into();
+            let param = Some(name(arg));
+            body = self.alloc(location, ExprKind::Lambda { param, body });
+        }
+
+        let param = Some(name(first));
+        self.alloc(function.location, ExprKind::Lambda { param, body })
+    }
+
+    fn block(&mut self, block: &Block) -> ExprKind {
+        let statements = block
+            .statements
+            .iter()
+            .map(|statement| self.statement(statement))
+            .collect();
+
+        ExprKind::Block {
+            statements,
+            result: self.expression(&block.expression),
+        }
+    }
+
+    fn match_expr(&mut self, m: &Match) -> ExprKind {
+        let scrutinee = self.expression(&m.scrutinee);
+        let arms = m
+            .arms
+            .iter()
+            .map(|arm| Arm {
+                pattern: self.pattern(&arm.pattern),
+                body: self.expression(&arm.expression),
+                location: arm.location,
+            })
+            .collect();
+
+        ExprKind::Match { scrutinee, arms }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) -> pattern::Pattern {
+        let kind = match &pattern.kind {
+            PatternKind::Named { ident, args } => {
+                let is_constructor = ident.name.as_str().starts_with(|c: char| c.is_uppercase());
+                match (is_constructor, ident.name.as_str()) {
+                    (true, _) => pattern::PatternKind::Constructor {
+                        constructor: self.alloc(ident.location, ExprKind::Var(name(ident))),
+                        args: args.iter().map(|arg| self.pattern(arg)).collect(),
+                    },
+                    (false, _) if !args.is_empty() => {
+                        self.errors.push(LowerError::ExpectedConstructor {
+                            name: ident.ident(),
+                            location: ident.location,
+                        });
+                        pattern::PatternKind::Wildcard
+                    }
+                    (false, "_") => pattern::PatternKind::Wildcard,
+                    (false, _) => pattern::PatternKind::Binding(name(ident)),
+                }
+            }
+            PatternKind::Value(value) => pattern::PatternKind::Literal(self.value(value)),
+            PatternKind::Parenthesized { inner, .. } => return self.pattern(inner),
+            PatternKind::Tuple { items, .. } => {
+                pattern::PatternKind::Tuple(items.iter().map(|item| self.pattern(item)).collect())
+            }
+        };
+
+        pattern::Pattern::new(pattern.location, kind)
+    }
+}
+
+fn name(ident: &Identifier) -> Name {
+    Name::new(ident.location, ident.ident())
+}
+
+/// Lowers a type, dropping its parentheses.
+fn type_expr(ty: &TypeExpr) -> type_expr::TypeExpr {
+    let kind = match &ty.kind {
+        TypeExprKind::Named { ident, args } => type_expr::TypeExprKind::Named {
+            name: name(ident),
+            args: args.iter().map(type_expr).collect(),
+        },
+        TypeExprKind::Unit => type_expr::TypeExprKind::Unit,
+        TypeExprKind::Parenthesized { inner, .. } => return type_expr(inner),
+        TypeExprKind::Tuple { items, .. } => {
+            type_expr::TypeExprKind::Tuple(items.iter().map(type_expr).collect())
+        }
+        TypeExprKind::Function { param, result, .. } => type_expr::TypeExprKind::Function(
+            Box::new(type_expr(param)),
+            Box::new(type_expr(result)),
+        ),
+    };
+    type_expr::TypeExpr::new(ty.location, kind)
+}
+
+/// Gets the identifier an expression is made of, if it's just an identifier.
+fn identifier(expression: &Expression) -> Option<&Identifier> {
+    match &expression.kind {
+        ExpressionKind::Value(Value {
+            kind: ValueKind::Identifier(ident),
+            ..
+        }) => Some(ident),
+        _ => None,
+    }
+}
+
+/// Gets the operands of a chain of the same infix operator, in order.
+fn flatten_infix<'e>(
+    expression: &'e Expression,
+    kind: OperatorKind,
+    out: &mut Vec<&'e Expression>,
+) {
+    match &expression.kind {
+        ExpressionKind::Infix { operator, lhs, rhs } if operator.kind() == kind => {
+            flatten_infix(lhs, kind, out);
+            flatten_infix(rhs, kind, out);
+        }
+        _ => out.push(expression),
+    }
+}
+
+/// Gets every import in a `use`-tree, where `prefix` is the path of the groups it's in.
+fn flatten_use_tree(tree: &UseTree, prefix: &[Name], out: &mut Vec<Import>) {
+    let mut path = prefix.to_vec();
+    path.extend(tree.path.iter().map(name));
+
+    match &tree.kind {
+        UseTreeKind::Simple { alias } => {
+            if let Some(imported) = alias.as_ref().map(name).or(path.last().copied()) {
+                out.push(Import {
+                    path,
+                    name: imported,
+                });
+            }
+        }
+        UseTreeKind::Group(trees) => {
+            for tree in trees {
+                flatten_use_tree(tree, &path, out);
+            }
+        }
+    }
+}
+
+// NOTE: The tests compare dumps of the AST, which need the `serde` feature.
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::compile::{dump, parser::parse};
+
+    fn lowered(source: &str) -> (String, Vec<LowerError>) {
+        let (ast, errors) = lower(&parse(source).unwrap());
+        (dump::ast::dump(&ast).to_text(), errors)
+    }
+
+    #[test]
+    fn can_lower_functions() {
+        let (ast, errors) = lowered("let f = |a, b| (a + b)\nlet g = || 1\n");
+        assert_eq!(errors, []);
+        assert_eq!(
+            ast,
+            r#"File 0..36
+  Let 0..23 mutable=false name="f"
+    Lambda 8..22 param="a"
+      Lambda 12..22 param="b"
+        Binary 16..21 op="+"
+          Var 16..17 name="a"
+          Var 20..21 name="b"
+  Let 23..36 mutable=false name="g"
+    Lambda 31..35
+      Integer 34..35 value=1
+"#
+        );
+    }
+
+    #[test]
+    fn can_lower_pipes_and_applications() {
+        let (ast, errors) = lowered("let y = x |> f a |> -g\n");
+        assert_eq!(errors, []);
+        assert_eq!(
+            ast,
+            r#"File 0..23
+  Let 0..23 mutable=false name="y"
+    Apply 8..22
+      Unary 20..22 op="-"
+        Var 21..22 name="g"
+      Apply 8..16
+        Apply 13..16
+          Var 13..14 name="f"
+          Var 15..16 name="a"
+        Var 8..9 name="x"
+"#
+        );
+    }
+
+    #[test]
+    fn can_lower_fields_and_paths() {
+        let (ast, errors) = lowered("let z = a::b.c.d\n");
+        assert_eq!(errors, []);
+        assert_eq!(
+            ast,
+            r#"File 0..17
+  Let 0..17 mutable=false name="z"
+    Field 8..16 name="d"
+      Field 8..14 name="c"
+        Path 8..12 path="a::b"
+"#
+        );
+    }
+
+    #[test]
+    fn can_lower_blocks_and_uses() {
+        let (ast, errors) =
+            lowered("pub use a::{b, c::d as e}\nlet x = {\n    let y = 1\n    y * 2.5\n}\n");
+        assert_eq!(errors, []);
+        assert_eq!(
+            ast,
+            r#"File 0..64
+  Use 4..26 pub=true
+    Import name="b" path="a::b"
+    Import name="e" path="a::c::d"
+  Let 26..64 mutable=false name="x"
+    Block 34..63
+      Let 40..50 mutable=false name="y"
+        Integer 48..49 value=1
+      Binary 54..61 op="*"
+        Var 54..55 name="y"
+        Floating 58..61 value=2.5
+"#
+        );
+    }
+
+    #[test]
+    fn cant_lower_bad_names() {
+        let (ast, errors) = lowered("let z = a.1 + b::(c)\n");
+        assert_eq!(
+            errors,
+            [
+                LowerError::ExpectedName {
+                    operator: ".",
+                    location: (10..11).into(),
+                },
+                LowerError::ExpectedName {
+                    operator: "::",
+                    location: (17..20).into(),
+                },
+            ]
+        );
+        assert_eq!(
+            ast,
+            r#"File 0..21
+  Let 0..21 mutable=false name="z"
+    Binary 8..20 op="+"
+      Error 8..11
+      Error 14..20
+"#
+        );
+    }
+}
@@ -1 +1,14 @@
+pub mod diagnostic;
+#[cfg(feature = "serde")]
+pub mod dump;
+pub mod exhaustive;
+pub mod formatter;
+pub mod infer;
+pub mod lexer;
+pub mod lower;
+pub mod module;
 pub mod parser;
+pub mod prelude;
+pub mod printer;
+pub mod project;
+pub mod resolve;
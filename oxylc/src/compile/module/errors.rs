@@ -0,0 +1,90 @@
+use errgonomic::parser::input::Span;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ModuleError {
+    #[error("Couldn't read `{}`: {message}", path.display())]
+    Io { path: PathBuf, message: String },
+
+    #[error("Couldn't parse `{}`: {message}", path.display())]
+    Parse { path: PathBuf, message: String },
+
+    #[error("Couldn't find the file for module `{name}`, expected `{}` or `{}`", candidates[0].display(), candidates[1].display())]
+    MissingFile {
+        name: String,
+        file: PathBuf,
+        location: Span,
+        candidates: [PathBuf; 2],
+    },
+
+    #[error("The module `{name}` has a file at both `{}` and `{}`", candidates[0].display(), candidates[1].display())]
+    AmbiguousFile {
+        name: String,
+        file: PathBuf,
+        location: Span,
+        candidates: [PathBuf; 2],
+    },
+
+    #[error("The module `{name}` includes itself (through `{}`)", path.display())]
+    CyclicModule {
+        name: String,
+        file: PathBuf,
+        location: Span,
+        path: PathBuf,
+    },
+
+    #[error("The name `{name}` is defined more than once in `{module}`")]
+    Duplicate {
+        name: String,
+        module: String,
+        file: PathBuf,
+        location: Span,
+    },
+
+    #[error("Couldn't find `{name}` in `{module}`")]
+    Unresolved {
+        name: String,
+        module: String,
+        file: PathBuf,
+        location: Span,
+    },
+
+    #[error("`{name}` isn't a module")]
+    NotAModule {
+        name: String,
+        file: PathBuf,
+        location: Span,
+    },
+
+    #[error("`{name}` is private to `{module}`")]
+    Private {
+        name: String,
+        module: String,
+        file: PathBuf,
+        location: Span,
+    },
+
+    #[error("The import of `{name}` is cyclic")]
+    CyclicImport {
+        name: String,
+        file: PathBuf,
+        location: Span,
+    },
+}
+
+impl ModuleError {
+    /// Gets the file and location in the file where the error happened, if there is one.
+    pub fn location(&self) -> Option<(&Path, Span)> {
+        match self {
+            ModuleError::Io { .. } | ModuleError::Parse { .. } => None,
+            ModuleError::MissingFile { file, location, .. }
+            | ModuleError::AmbiguousFile { file, location, .. }
+            | ModuleError::CyclicModule { file, location, .. }
+            | ModuleError::Duplicate { file, location, .. }
+            | ModuleError::Unresolved { file, location, .. }
+            | ModuleError::NotAModule { file, location, .. }
+            | ModuleError::Private { file, location, .. }
+            | ModuleError::CyclicImport { file, location, .. } => Some((file, *location)),
+        }
+    }
+}
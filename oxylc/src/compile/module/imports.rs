@@ -0,0 +1,339 @@
+//! Resolves the `use`-statements in a `ModuleTree`, building the namespace of every module.
+
+use super::{errors::ModuleError, Binding, Entry, ModuleId, ModuleTree};
+use crate::repr::{
+    ast::ident::Ident,
+    fst::{
+        identifier::Identifier,
+        statement::StatementKind,
+        use_tree::{UseTree, UseTreeKind},
+        FstNode,
+    },
+};
+use std::collections::HashMap;
+
+/// Something we can look up names in the namespaces of modules with. Looking up a name may fail
+/// with an error, or with `None` if the error was already reported.
+pub(super) trait Namespaces {
+    fn entry(&mut self, module: ModuleId, name: &str)
+        -> Result<Option<Entry>, Option<ModuleError>>;
+}
+
+/// The namespaces of a tree whose imports are already resolved.
+pub(super) struct Resolved<'a>(pub &'a ModuleTree);
+
+impl Namespaces for Resolved<'_> {
+    fn entry(
+        &mut self,
+        module: ModuleId,
+        name: &str,
+    ) -> Result<Option<Entry>, Option<ModuleError>> {
+        Ok(self.0.lookup(module, name).cloned())
+    }
+}
+
+/// A single import. Note that a `use`-statement can have many imports, like `use a::{b, c}`.
+#[derive(Clone, Debug)]
+struct Import {
+    module: ModuleId,
+    path: Vec<Identifier>,
+    name: Identifier,
+    is_public: bool,
+}
+
+#[derive(Clone, Debug)]
+enum ImportState {
+    Unresolved,
+    Resolving,
+    Resolved(Option<Binding>),
+}
+
+/// Resolves every import in the tree, returning the namespace of every module.
+pub(super) fn resolve(
+    tree: &ModuleTree,
+    errors: &mut Vec<ModuleError>,
+) -> Vec<HashMap<String, Entry>> {
+    let mut resolver = Resolver {
+        tree,
+        locals: vec![HashMap::new(); tree.modules.len()],
+        imports: vec![],
+        import_names: HashMap::new(),
+        states: vec![],
+        errors,
+    };
+
+    for (id, module) in tree.modules() {
+        // NOTE: Packages are used just like private modules declared in the root.
+        let mut externs = module.externs.iter().collect::<Vec<_>>();
+        externs.sort();
+
+        for (name, package) in externs {
+            let ident = Identifier::new((0..0).into(), Ident::new(name));
+            resolver.define(id, &ident, Binding::Module(*package), false);
+        }
+
+        for statement in &module.file.statements {
+            let is_public = statement.visibility.is_public();
+
+            match &statement.kind {
+                StatementKind::Let { ident, .. } => {
+                    let binding = Binding::Item {
+                        module: id,
+                        name: ident.name.to_string(),
+                    };
+                    resolver.define(id, ident, binding, is_public);
+                }
+                StatementKind::Mod { ident } => {
+                    // NOTE: If the module couldn't be loaded, that's already been reported.
+                    if let Some(child) = module.children.get(ident.name.as_str()) {
+                        resolver.define(id, ident, Binding::Module(*child), is_public);
+                    }
+                }
+                StatementKind::Use { tree: use_tree } => {
+                    let mut flattened = vec![];
+                    flatten(use_tree, &[], &mut flattened);
+
+                    for (path, name) in flattened {
+                        resolver.declare_import(Import {
+                            module: id,
+                            path,
+                            name,
+                            is_public,
+                        });
+                    }
+                }
+                StatementKind::Type { constructors, .. } => {
+                    for constructor in constructors {
+                        let binding = Binding::Item {
+                            module: id,
+                            name: constructor.ident.name.to_string(),
+                        };
+                        resolver.define(id, &constructor.ident, binding, is_public);
+                    }
+                }
+                // NOTE: Implementations don't define any names.
+                StatementKind::Impl { .. } => {}
+            }
+        }
+    }
+
+    for i in 0..resolver.imports.len() {
+        let _ = resolver.resolve_import(i);
+    }
+
+    let mut namespaces = resolver.locals;
+
+    for (import, state) in resolver.imports.iter().zip(resolver.states) {
+        if let ImportState::Resolved(Some(binding)) = state {
+            namespaces[import.module.0].insert(
+                import.name.name.to_string(),
+                Entry {
+                    binding,
+                    is_public: import.is_public,
+                    location: *import.name.location(),
+                },
+            );
+        }
+    }
+
+    namespaces
+}
+
+/// Resolves a path, like `a::b::c`, from inside `from`.
+pub(super) fn resolve_path(
+    tree: &ModuleTree,
+    from: ModuleId,
+    path: &[Identifier],
+    namespaces: &mut impl Namespaces,
+) -> Result<Binding, Option<ModuleError>> {
+    let file = &tree.get(from).path;
+    let mut current = Binding::Module(from);
+
+    for (i, segment) in path.iter().enumerate() {
+        let module = match current {
+            Binding::Module(module) => module,
+            Binding::Item { name, .. } => {
+                return Err(Some(ModuleError::NotAModule {
+                    name,
+                    file: file.clone(),
+                    location: *path[i - 1].location(),
+                }));
+            }
+        };
+        let unresolved = || ModuleError::Unresolved {
+            name: segment.name.to_string(),
+            module: tree.path_of(module),
+            file: file.clone(),
+            location: *segment.location(),
+        };
+
+        current = match segment.name.as_str() {
+            "crate" if i == 0 => Binding::Module(tree.package_root(from)),
+            "self" if i == 0 => Binding::Module(from),
+            "super" if path[..i].iter().all(|s| s.name.as_str() == "super") => {
+                match tree.get(module).parent {
+                    Some(parent) => Binding::Module(parent),
+                    None => return Err(Some(unresolved())),
+                }
+            }
+            name => {
+                let entry = namespaces
+                    .entry(module, name)?
+                    .ok_or_else(|| Some(unresolved()))?;
+
+                // NOTE: Everything is visible inside of its own module, and that module's children.
+                if !entry.is_public && !tree.is_within(from, module) {
+                    return Err(Some(ModuleError::Private {
+                        name: name.to_string(),
+                        module: tree.path_of(module),
+                        file: file.clone(),
+                        location: *segment.location(),
+                    }));
+                }
+
+                entry.binding
+            }
+        };
+    }
+
+    Ok(current)
+}
+
+/// Flattens a use-tree into its individual imports, as pairs of the full path being imported and
+/// the name it's imported as.
+fn flatten(tree: &UseTree, prefix: &[Identifier], out: &mut Vec<(Vec<Identifier>, Identifier)>) {
+    let mut path = prefix.to_vec();
+    path.extend(tree.path.iter().cloned());
+
+    match &tree.kind {
+        UseTreeKind::Simple { alias } => {
+            // NOTE: The parser makes sure simple trees always have at least 1 segment.
+            let name = alias
+                .clone()
+                .unwrap_or_else(|| path[path.len() - 1].clone());
+            out.push((path, name));
+        }
+        UseTreeKind::Group(trees) => {
+            for tree in trees {
+                flatten(tree, &path, out);
+            }
+        }
+    }
+}
+
+struct Resolver<'a> {
+    tree: &'a ModuleTree,
+
+    /// The names defined in every module, without the imports.
+    locals: Vec<HashMap<String, Entry>>,
+
+    imports: Vec<Import>,
+
+    /// The imports in every module, by the name they're imported as.
+    import_names: HashMap<(ModuleId, String), usize>,
+
+    states: Vec<ImportState>,
+
+    errors: &'a mut Vec<ModuleError>,
+}
+
+impl Resolver<'_> {
+    /// Checks if a name is already defined (or imported) in a module, and reports it if it is.
+    fn is_duplicate(&mut self, module: ModuleId, ident: &Identifier) -> bool {
+        let is_duplicate = self.locals[module.0].contains_key(ident.name.as_str())
+            || self
+                .import_names
+                .contains_key(&(module, ident.name.to_string()));
+
+        if is_duplicate {
+            self.errors.push(ModuleError::Duplicate {
+                name: ident.name.to_string(),
+                module: self.tree.path_of(module),
+                file: self.tree.get(module).path.clone(),
+                location: *ident.location(),
+            });
+        }
+
+        is_duplicate
+    }
+
+    fn define(&mut self, module: ModuleId, ident: &Identifier, binding: Binding, is_public: bool) {
+        if !self.is_duplicate(module, ident) {
+            self.locals[module.0].insert(
+                ident.name.to_string(),
+                Entry {
+                    binding,
+                    is_public,
+                    location: *ident.location(),
+                },
+            );
+        }
+    }
+
+    fn declare_import(&mut self, import: Import) {
+        if !self.is_duplicate(import.module, &import.name) {
+            self.import_names.insert(
+                (import.module, import.name.name.to_string()),
+                self.imports.len(),
+            );
+            self.imports.push(import);
+            self.states.push(ImportState::Unresolved);
+        }
+    }
+
+    fn resolve_import(&mut self, i: usize) -> Result<Binding, Option<ModuleError>> {
+        match &self.states[i] {
+            ImportState::Resolved(Some(binding)) => return Ok(binding.clone()),
+            ImportState::Resolved(None) => return Err(None),
+            ImportState::Resolving => {
+                let import = &self.imports[i];
+                return Err(Some(ModuleError::CyclicImport {
+                    name: import.name.name.to_string(),
+                    file: self.tree.get(import.module).path.clone(),
+                    location: *import.name.location(),
+                }));
+            }
+            ImportState::Unresolved => {}
+        }
+
+        self.states[i] = ImportState::Resolving;
+        let import = self.imports[i].clone();
+
+        match resolve_path(self.tree, import.module, &import.path, self) {
+            Ok(binding) => {
+                self.states[i] = ImportState::Resolved(Some(binding.clone()));
+                Ok(binding)
+            }
+            Err(err) => {
+                self.errors.extend(err);
+                self.states[i] = ImportState::Resolved(None);
+                Err(None)
+            }
+        }
+    }
+}
+
+impl Namespaces for Resolver<'_> {
+    fn entry(
+        &mut self,
+        module: ModuleId,
+        name: &str,
+    ) -> Result<Option<Entry>, Option<ModuleError>> {
+        if let Some(entry) = self.locals[module.0].get(name) {
+            return Ok(Some(entry.clone()));
+        }
+
+        match self.import_names.get(&(module, name.to_string())) {
+            Some(&i) => {
+                let binding = self.resolve_import(i)?;
+                let import = &self.imports[i];
+                Ok(Some(Entry {
+                    binding,
+                    is_public: import.is_public,
+                    location: *import.name.location(),
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Something that can load source files for the module system. This way, modules can be loaded
+/// from anywhere, like the disk (with `FsLoader`), or straight from memory (with a `HashMap`).
+pub trait SourceLoader {
+    /// Loads the source at `path`, returning its canonical path and its contents. The canonical
+    /// path is used to find modules which include themselves. If there is no file at `path`, this
+    /// must return an error with the kind `io::ErrorKind::NotFound`.
+    fn load(&mut self, path: &Path) -> io::Result<(PathBuf, String)>;
+}
+
+/// Loads source files from the disk.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FsLoader;
+
+impl SourceLoader for FsLoader {
+    fn load(&mut self, path: &Path) -> io::Result<(PathBuf, String)> {
+        let canonical = fs::canonicalize(path)?;
+        let source = fs::read_to_string(&canonical)?;
+        Ok((canonical, source))
+    }
+}
+
+impl SourceLoader for HashMap<PathBuf, String> {
+    fn load(&mut self, path: &Path) -> io::Result<(PathBuf, String)> {
+        match self.get(path) {
+            Some(source) => Ok((path.to_path_buf(), source.clone())),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("`{}` doesn't exist", path.display()),
+            )),
+        }
+    }
+}
+
+/// Makes an in-memory set of files to load modules from, as pairs of paths and sources.
+#[cfg(test)]
+pub fn files(files: &[(&str, &str)]) -> HashMap<PathBuf, String> {
+    files
+        .iter()
+        .map(|(path, source)| (PathBuf::from(path), source.to_string()))
+        .collect()
+}
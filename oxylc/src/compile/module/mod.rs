@@ -0,0 +1,394 @@
+//! The module system. Starting from a root file, this loads every module declared with `mod` into
+//! a `ModuleTree`, and then resolves all of the `use`-statements in it. This happens before name
+//! resolution, which can then look up names (and paths like `a::b`) through the tree.
+//!
+//! A `mod foo` declared in `dir/main.oxyl` (the root) or `dir/mod.oxyl` loads `dir/foo.oxyl` or
+//! `dir/foo/mod.oxyl`, and one declared in `dir/bar.oxyl` loads `dir/bar/foo.oxyl` or
+//! `dir/bar/foo/mod.oxyl`, just like Rust.
+//!
+//! Paths (in `use`-statements, etc.) start from the module they're written in, unless they start
+//! with `crate` (the root module), `self` (the current module) or `super` (the parent module).
+//! Anything not marked `pub` is only visible inside of its module, and that module's children.
+//!
+//! A tree can also contain other packages, which are loaded as `Extern`s. Each of them has its own
+//! root module, so `crate` inside of one of them refers to that package, and the package it's
+//! loaded into can only use what it makes `pub`.
+
+pub mod errors;
+pub mod loader;
+
+mod imports;
+mod tests;
+
+use crate::{
+    compile::parser,
+    repr::fst::{identifier::Identifier, statement::StatementKind, File, FstNode},
+};
+use errgonomic::parser::input::Span;
+use errors::ModuleError;
+use loader::SourceLoader;
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// The ID of a module in a `ModuleTree`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModuleId(usize);
+
+/// A single module, which is a single file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Module {
+    /// The name of the module. The root module is called `crate`.
+    pub name: String,
+
+    /// The (canonical) path to the module's file.
+    pub path: PathBuf,
+
+    /// The source code of the module.
+    pub source: String,
+
+    /// The parsed module. If the module failed to parse, this is empty.
+    pub file: File,
+
+    /// The parent of the module, if it isn't the root module.
+    pub parent: Option<ModuleId>,
+
+    /// The children of the module, declared with `mod`.
+    pub children: HashMap<String, ModuleId>,
+
+    /// The root modules of the packages this module depends on. Only root modules have these.
+    pub externs: HashMap<String, ModuleId>,
+
+    /// The directory that the files of the children are in.
+    dir: PathBuf,
+}
+
+/// A package that a module tree depends on, like a crate passed to `rustc` with `--extern`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Extern {
+    /// The name the package is used with.
+    pub name: String,
+
+    /// The path to the root file of the package.
+    pub root: PathBuf,
+
+    /// The packages that this package depends on.
+    pub externs: Vec<Extern>,
+}
+
+/// Something that a name in a module refers to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Binding {
+    /// A module.
+    Module(ModuleId),
+
+    /// A top-level `let` in a module.
+    Item { module: ModuleId, name: String },
+}
+
+/// A name in the namespace of a module, along with whether it can be seen from outside.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// What the name refers to.
+    pub binding: Binding,
+
+    /// Whether the name is `pub`.
+    pub is_public: bool,
+
+    /// The location where the name was defined (or imported) in the module.
+    pub location: Span,
+}
+
+/// The tree of modules in a program, with all of their imports resolved.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleTree {
+    /// The modules, indexed by their `ModuleId`. The root is always first.
+    modules: Vec<Module>,
+
+    /// The namespace of every module, indexed by their `ModuleId`. This includes imports.
+    namespaces: Vec<HashMap<String, Entry>>,
+}
+
+impl ModuleTree {
+    /// Loads the module tree starting at the root file `root`, and resolves all the imports in it.
+    /// Note that this always returns a tree, even if there were errors, so that as much as possible
+    /// can be checked.
+    pub fn load(root: &Path, loader: &mut impl SourceLoader) -> (Self, Vec<ModuleError>) {
+        Self::load_with_externs(root, &[], loader)
+    }
+
+    /// Like `load`, but the root module can also use the packages in `externs` by their names.
+    pub fn load_with_externs(
+        root: &Path,
+        externs: &[Extern],
+        loader: &mut impl SourceLoader,
+    ) -> (Self, Vec<ModuleError>) {
+        let mut tree = Self {
+            modules: vec![],
+            namespaces: vec![],
+        };
+        let mut errors = vec![];
+
+        if tree
+            .load_package("crate".into(), root, externs, loader, &mut errors)
+            .is_some()
+        {
+            tree.namespaces = imports::resolve(&tree, &mut errors);
+        }
+
+        (tree, errors)
+    }
+
+    /// Gets the root module.
+    pub fn root(&self) -> ModuleId {
+        ModuleId(0)
+    }
+
+    /// Gets the root module of the package that a module is in.
+    pub fn package_root(&self, id: ModuleId) -> ModuleId {
+        // NOTE: `ancestors` always includes the module itself, so this can't be empty.
+        self.ancestors(id).last().unwrap()
+    }
+
+    /// Gets a module in the tree.
+    pub fn get(&self, id: ModuleId) -> &Module {
+        &self.modules[id.0]
+    }
+
+    /// Iterates over all the modules in the tree.
+    pub fn modules(&self) -> impl Iterator<Item = (ModuleId, &Module)> {
+        self.modules
+            .iter()
+            .enumerate()
+            .map(|(i, module)| (ModuleId(i), module))
+    }
+
+    /// Looks up a name in the namespace of a module. This includes the items defined in the module,
+    /// its children and everything it imports.
+    pub fn lookup(&self, module: ModuleId, name: &str) -> Option<&Entry> {
+        self.namespaces.get(module.0)?.get(name)
+    }
+
+    /// Gets the full path of a module, like `crate::a::b`. Modules in other packages start with the
+    /// name of the package instead.
+    pub fn path_of(&self, id: ModuleId) -> String {
+        let module = self.get(id);
+        match module.parent {
+            Some(parent) => format!("{}::{}", self.path_of(parent), module.name),
+            None => module.name.clone(),
+        }
+    }
+
+    /// Checks if `module` is `ancestor`, or is inside of it.
+    pub fn is_within(&self, module: ModuleId, ancestor: ModuleId) -> bool {
+        self.ancestors(module).any(|id| id == ancestor)
+    }
+
+    /// Resolves a path, like `a::b::c`, from inside `from`, checking that everything along the way
+    /// is visible from `from`.
+    pub fn resolve_path(
+        &self,
+        from: ModuleId,
+        path: &[Identifier],
+    ) -> Result<Binding, ModuleError> {
+        // NOTE: The errors are always there, as every import is already resolved.
+        imports::resolve_path(self, from, path, &mut imports::Resolved(self))
+            .map_err(|err| err.expect("imports are already resolved"))
+    }
+
+    /// Loads a package, with its root module called `name`, along with all of its children and the
+    /// packages it depends on. A package that has already been loaded (by the canonical path of its
+    /// root) isn't loaded again, so that packages depending on the same one share it, and its root
+    /// module keeps the name it was first loaded with.
+    fn load_package(
+        &mut self,
+        name: String,
+        root: &Path,
+        externs: &[Extern],
+        loader: &mut impl SourceLoader,
+        errors: &mut Vec<ModuleError>,
+    ) -> Option<ModuleId> {
+        let id = match loader.load(root) {
+            Ok((path, source)) => {
+                let loaded = self
+                    .modules()
+                    .find(|(_, module)| module.parent.is_none() && module.path == path);
+                if let Some((loaded, _)) = loaded {
+                    return Some(loaded);
+                }
+
+                let dir = root.parent().map(Path::to_path_buf).unwrap_or_default();
+                self.load_module(name, path, source, dir, None, errors)
+            }
+            Err(err) => {
+                errors.push(ModuleError::Io {
+                    path: root.to_path_buf(),
+                    message: err.to_string(),
+                });
+                return None;
+            }
+        };
+
+        self.load_children(id, loader, errors);
+
+        for package in externs {
+            let loaded = self.load_package(
+                package.name.clone(),
+                &package.root,
+                &package.externs,
+                loader,
+                errors,
+            );
+
+            if let Some(extern_id) = loaded {
+                self.modules[id.0]
+                    .externs
+                    .insert(package.name.clone(), extern_id);
+            }
+        }
+
+        Some(id)
+    }
+
+    fn load_module(
+        &mut self,
+        name: String,
+        path: PathBuf,
+        source: String,
+        dir: PathBuf,
+        parent: Option<ModuleId>,
+        errors: &mut Vec<ModuleError>,
+    ) -> ModuleId {
+        let file = match parser::parse(&source) {
+            Ok(file) => file,
+            Err(err) => {
+                errors.push(ModuleError::Parse {
+                    path: path.clone(),
+                    message: format!("{:?}", err),
+                });
+                File::new((0..0).into(), vec![])
+            }
+        };
+        let id = ModuleId(self.modules.len());
+
+        self.modules.push(Module {
+            name,
+            path,
+            source,
+            file,
+            parent,
+            children: HashMap::new(),
+            externs: HashMap::new(),
+            dir,
+        });
+
+        id
+    }
+
+    /// Loads all the children declared with `mod` in a module, and their children, and so on.
+    fn load_children(
+        &mut self,
+        id: ModuleId,
+        loader: &mut impl SourceLoader,
+        errors: &mut Vec<ModuleError>,
+    ) {
+        let declared = self
+            .get(id)
+            .file
+            .statements
+            .iter()
+            .filter_map(|statement| match &statement.kind {
+                StatementKind::Mod { ident } => Some(ident.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for ident in declared {
+            let module = self.get(id);
+            let file = module.path.clone();
+
+            // NOTE: The duplicate is reported when resolving imports, along with everything else.
+            if module.children.contains_key(ident.name.as_str()) {
+                continue;
+            }
+
+            let candidates = [
+                module.dir.join(format!("{}.oxyl", ident.name)),
+                module.dir.join(ident.name.as_str()).join("mod.oxyl"),
+            ];
+            let (path, source, dir) = match (
+                load_if_exists(loader, &candidates[0]),
+                load_if_exists(loader, &candidates[1]),
+            ) {
+                (Ok(Some((path, source))), Ok(None)) => {
+                    (path, source, module.dir.join(ident.name.as_str()))
+                }
+                (Ok(None), Ok(Some((path, source)))) => {
+                    (path, source, candidates[1].parent().unwrap().to_path_buf())
+                }
+                (Ok(None), Ok(None)) => {
+                    errors.push(ModuleError::MissingFile {
+                        name: ident.name.to_string(),
+                        file,
+                        location: *ident.location(),
+                        candidates,
+                    });
+                    continue;
+                }
+                (Ok(Some(_)), Ok(Some(_))) => {
+                    errors.push(ModuleError::AmbiguousFile {
+                        name: ident.name.to_string(),
+                        file,
+                        location: *ident.location(),
+                        candidates,
+                    });
+                    continue;
+                }
+                (Err((path, err)), _) | (_, Err((path, err))) => {
+                    errors.push(ModuleError::Io {
+                        path,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            // NOTE: A module can only include itself through one of its ancestors.
+            if let Some(ancestor) = self.ancestors(id).find(|a| self.get(*a).path == path) {
+                errors.push(ModuleError::CyclicModule {
+                    name: ident.name.to_string(),
+                    file,
+                    location: *ident.location(),
+                    path: self.get(ancestor).path.clone(),
+                });
+                continue;
+            }
+
+            let child =
+                self.load_module(ident.name.to_string(), path, source, dir, Some(id), errors);
+            self.modules[id.0]
+                .children
+                .insert(ident.name.to_string(), child);
+            self.load_children(child, loader, errors);
+        }
+    }
+
+    /// Iterates over a module and all of its ancestors, from the module up to the root.
+    fn ancestors(&self, id: ModuleId) -> impl Iterator<Item = ModuleId> + '_ {
+        std::iter::successors(Some(id), |id| self.get(*id).parent)
+    }
+}
+
+/// Loads a file if it exists, returning `None` if it doesn't.
+fn load_if_exists(
+    loader: &mut impl SourceLoader,
+    path: &Path,
+) -> Result<Option<(PathBuf, String)>, (PathBuf, io::Error)> {
+    match loader.load(path) {
+        Ok(loaded) => Ok(Some(loaded)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err((path.to_path_buf(), err)),
+    }
+}
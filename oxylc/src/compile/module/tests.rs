@@ -0,0 +1,190 @@
+#![cfg(test)]
+
+use super::*;
+use loader::files;
+
+#[test]
+fn can_load_module_tree() {
+    let mut loader = files(&[
+        ("src/main.oxyl", "mod a\nmod c\n"),
+        ("src/a.oxyl", "pub mod b\n"),
+        ("src/a/b.oxyl", "pub let x = 1\n"),
+        ("src/c/mod.oxyl", "mod d\n"),
+        ("src/c/d.oxyl", "let y = 2\n"),
+    ]);
+    let (tree, errors) = ModuleTree::load(Path::new("src/main.oxyl"), &mut loader);
+    assert_eq!(errors, []);
+
+    let paths = tree
+        .modules()
+        .map(|(id, module)| (tree.path_of(id), module.path.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            ("crate".to_string(), PathBuf::from("src/main.oxyl")),
+            ("crate::a".to_string(), PathBuf::from("src/a.oxyl")),
+            ("crate::a::b".to_string(), PathBuf::from("src/a/b.oxyl")),
+            ("crate::c".to_string(), PathBuf::from("src/c/mod.oxyl")),
+            ("crate::c::d".to_string(), PathBuf::from("src/c/d.oxyl")),
+        ]
+    );
+}
+
+#[test]
+fn can_report_missing_module_file() {
+    let mut loader = files(&[("main.oxyl", "mod a\n")]);
+    let (_, errors) = ModuleTree::load(Path::new("main.oxyl"), &mut loader);
+    assert_eq!(
+        errors,
+        [ModuleError::MissingFile {
+            name: "a".into(),
+            file: "main.oxyl".into(),
+            location: (4..5).into(),
+            candidates: ["a.oxyl".into(), "a/mod.oxyl".into()],
+        }]
+    );
+}
+
+#[test]
+fn can_resolve_imports() {
+    let mut loader = files(&[
+        (
+            "main.oxyl",
+            "mod a\nuse a::b::{x, y as z}\nuse self::a::b\n",
+        ),
+        ("a.oxyl", "pub mod b\n"),
+        ("a/b.oxyl", "pub let x = 1\npub let y = 2\n"),
+    ]);
+    let (tree, errors) = ModuleTree::load(Path::new("main.oxyl"), &mut loader);
+    assert_eq!(errors, []);
+
+    let b = tree.get(tree.get(tree.root()).children["a"]).children["b"];
+    let binding = |name: &str| tree.lookup(tree.root(), name).map(|e| e.binding.clone());
+    assert_eq!(
+        binding("x"),
+        Some(Binding::Item {
+            module: b,
+            name: "x".into()
+        })
+    );
+    assert_eq!(
+        binding("z"),
+        Some(Binding::Item {
+            module: b,
+            name: "y".into()
+        })
+    );
+    assert_eq!(binding("b"), Some(Binding::Module(b)));
+    assert_eq!(binding("y"), None);
+}
+
+#[test]
+fn can_report_private_access() {
+    let mut loader = files(&[
+        ("main.oxyl", "mod a\nuse a::x\n"),
+        ("a.oxyl", "let x = 1\nmod b\n"),
+        ("a/b.oxyl", "use super::x\n"),
+    ]);
+    let (_, errors) = ModuleTree::load(Path::new("main.oxyl"), &mut loader);
+
+    // NOTE: `a::b` can see `x`, as it's inside of `a`.
+    assert_eq!(
+        errors,
+        [ModuleError::Private {
+            name: "x".into(),
+            module: "crate::a".into(),
+            file: "main.oxyl".into(),
+            location: (13..14).into(),
+        }]
+    );
+}
+
+#[test]
+fn can_report_cyclic_imports() {
+    let mut loader = files(&[
+        ("main.oxyl", "mod a\nmod b\n"),
+        ("a.oxyl", "pub use crate::b::x\n"),
+        ("b.oxyl", "pub use crate::a::x\n"),
+    ]);
+    let (_, errors) = ModuleTree::load(Path::new("main.oxyl"), &mut loader);
+    assert_eq!(
+        errors,
+        [ModuleError::CyclicImport {
+            name: "x".into(),
+            file: "a.oxyl".into(),
+            location: (18..19).into(),
+        }]
+    );
+}
+
+#[test]
+fn can_report_duplicates_and_unresolved() {
+    let mut loader = files(&[("main.oxyl", "let x = 1\nlet x = 2\nuse y\nuse x::z\n")]);
+    let (_, errors) = ModuleTree::load(Path::new("main.oxyl"), &mut loader);
+    assert_eq!(
+        errors,
+        [
+            ModuleError::Duplicate {
+                name: "x".into(),
+                module: "crate".into(),
+                file: "main.oxyl".into(),
+                location: (14..15).into(),
+            },
+            ModuleError::CyclicImport {
+                name: "y".into(),
+                file: "main.oxyl".into(),
+                location: (24..25).into(),
+            },
+            ModuleError::NotAModule {
+                name: "x".into(),
+                file: "main.oxyl".into(),
+                location: (30..31).into(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn can_use_extern_packages() {
+    let mut loader = files(&[
+        ("app/main.oxyl", "mod a\nuse lib::b::x\n"),
+        ("app/a.oxyl", "use crate::lib::b::y\n"),
+        ("lib/lib.oxyl", "pub mod b\nuse crate::b::x as z\n"),
+        ("lib/b.oxyl", "pub let x = 1\nlet y = 2\n"),
+    ]);
+    let externs = [Extern {
+        name: "lib".into(),
+        root: "lib/lib.oxyl".into(),
+        externs: vec![],
+    }];
+    let (tree, errors) =
+        ModuleTree::load_with_externs(Path::new("app/main.oxyl"), &externs, &mut loader);
+
+    let lib = tree.get(tree.root()).externs["lib"];
+    let b = tree.get(lib).children["b"];
+    assert_eq!(tree.path_of(b), "lib::b");
+    assert_eq!(
+        tree.lookup(tree.root(), "x").map(|e| e.binding.clone()),
+        Some(Binding::Item {
+            module: b,
+            name: "x".into()
+        })
+    );
+    assert_eq!(
+        tree.lookup(lib, "z").map(|e| e.binding.clone()),
+        Some(Binding::Item {
+            module: b,
+            name: "x".into()
+        })
+    );
+    assert_eq!(
+        errors,
+        [ModuleError::Private {
+            name: "y".into(),
+            module: "lib::b".into(),
+            file: "app/a.oxyl".into(),
+            location: (19..20).into(),
+        }]
+    );
+}
@@ -0,0 +1,118 @@
+use super::{
+    errors::ParserError,
+    ident::ident,
+    utils::{comma_separated, parenthesized, ww},
+    value::value,
+};
+use crate::repr::fst::{
+    attribute::{Attribute, AttributeArg, AttributeArgKind},
+    FstNode,
+};
+use errgonomic::{
+    combinators::{any, commit, is, maybe, take_until},
+    parser::{errors::Result, state::State, Parser},
+};
+
+/// Parses an attribute.
+/// ```bnf
+/// <attribute> ::= "#[" <ident> ( "(" (<attribute_arg> ",")* <attribute_arg>? ","? ")" )? "]"
+/// ```
+pub fn attribute(state: State<&str, ParserError>) -> Result<&str, Attribute, ParserError> {
+    is("#[")
+        // NOTE: commit on the rest of the attribute, as nothing else starts with `#[`.
+        .then(commit(
+            ww(ident)
+                .then(maybe(parenthesized(comma_separated(ww(attribute_arg)))))
+                .then(ww(is("]"))),
+        ))
+        .map(|(start, ((name, args), end))| {
+            let location = start.span().union_between(end.span());
+
+            match args {
+                Some((lparen, (args, trailing_comma), rparen)) => {
+                    Attribute::new(location, name, args)
+                        .with_parens(Some((lparen.span(), rparen.span())))
+                        .with_trailing_comma(trailing_comma)
+                }
+                None => Attribute::new(location, name, vec![]),
+            }
+        })
+        .process(state)
+}
+
+/// Parses an argument to an attribute.
+/// ```bnf
+/// <attribute_arg> ::= <string> | <value>
+/// ```
+fn attribute_arg(state: State<&str, ParserError>) -> Result<&str, AttributeArg, ParserError> {
+    any((
+        string,
+        value.map(|v| AttributeArg::new(*v.location(), AttributeArgKind::Value(v))),
+    ))
+    .process(state)
+}
+
+/// Parses a string. Note that there are no escapes (yet), so the string ends at the first `"`.
+/// ```bnf
+/// <string> ::= "\"" [^"]* "\""
+/// ```
+fn string(state: State<&str, ParserError>) -> Result<&str, AttributeArg, ParserError> {
+    is("\"")
+        .then(take_until(is("\"")))
+        .map(|(start, (contents, end))| {
+            AttributeArg::new(
+                start.span().union_between(end.span()),
+                AttributeArgKind::String(contents.as_inner().to_string()),
+            )
+        })
+        .process(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repr::fst::{
+        identifier::Identifier,
+        value::{Value, ValueKind},
+    };
+
+    #[test]
+    fn can_parse_bare_attribute() {
+        let (state, parsed) = attribute.process("#[inline]".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            parsed,
+            Attribute::new(
+                (0..9).into(),
+                Identifier::new((2..8).into(), "inline".into()),
+                vec![],
+            )
+        );
+    }
+
+    #[test]
+    fn can_parse_attribute_with_args() {
+        let (state, parsed) = attribute
+            .process("#[ deprecated(\"use bar\", 3 ,) ]".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            parsed,
+            Attribute::new(
+                (0..31).into(),
+                Identifier::new((3..13).into(), "deprecated".into()),
+                vec![
+                    AttributeArg::new((14..23).into(), AttributeArgKind::String("use bar".into())),
+                    AttributeArg::new(
+                        (25..26).into(),
+                        AttributeArgKind::Value(Value::new((25..26).into(), ValueKind::Integer(3)))
+                    ),
+                ],
+            )
+            .with_parens(Some(((13..14).into(), (28..29).into())))
+            .with_trailing_comma(Some((27..28).into()))
+        );
+    }
+}
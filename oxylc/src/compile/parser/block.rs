@@ -12,12 +12,9 @@ use errgonomic::{
 pub fn block(state: State<&str, ParserError>) -> Result<&str, Block, ParserError> {
     is("{")
         .then(commit(many(statement).then(ww(expression)).then(is("}"))))
-        .map_with_state(|state, (open_curly, ((statements, expression), _))| {
-            let location = state.as_input().span().union_between(open_curly.span());
-            (
-                state,
-                Block::new(location, statements, Box::new(expression)),
-            )
+        .map(|(open_curly, ((statements, expression), close_curly))| {
+            let location = open_curly.span().union_between(close_curly.span());
+            Block::new(location, statements, Box::new(expression))
         })
         .process(state)
 }
@@ -72,6 +69,7 @@ mod tests {
                 StatementKind::Let {
                     is_mutable: false,
                     ident: Identifier::new((6..7).into(), "x".into()),
+                    signature: None,
                     expression: Expression::new(
                         (10..11).into(),
                         ExpressionKind::Value(Value::new((10..11).into(), ValueKind::Integer(3))),
@@ -87,4 +85,26 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn can_parse_names_starting_with_keywords() {
+        let sources = [
+            "{ model }",
+            "{ user }",
+            "{ used + 1 }",
+            "{ letter }",
+            "{ let model = 1\n    used + model }",
+            "{ use a::b\n    modules }",
+            "{ implies }",
+            "{ impl_count }",
+            "{ types }",
+            "{ type_of x }",
+        ];
+        for source in sources {
+            let (state, parsed) = block.process(source.into()).unwrap();
+            assert!(state.is_ok());
+            assert_eq!(state.as_input().as_inner(), "", "in {}", source);
+            assert_eq!(parsed.location(), &Span::from(0..source.len()));
+        }
+    }
 }
@@ -1,31 +1,94 @@
 use super::{errors::ParserError, utils::line_ending};
+use crate::compile::lexer::is_doc_comment;
+use crate::repr::fst::doc_comment::{DocComment, DocCommentKind};
 use errgonomic::{
     combinators::{any, is, take_until},
     parser::{errors::Result, input::Input, state::State, Parser},
 };
 
-/// A comment.
+/// A comment. Note that this does *not* parse doc comments, as those are attached to whatever
+/// follows them instead of being thrown away.
 pub fn comment(state: State<&str, ParserError>) -> Result<&str, Input<&str>, ParserError> {
     any((single_line_comment, multi_line_comment)).process(state)
 }
 
+/// A doc comment.
+/// ```bnf
+/// <doc_comment> ::= ( "///" [^\n]* <line_ending> ) | ( "/**" .* "*/" )
+/// ```
+pub fn doc_comment(state: State<&str, ParserError>) -> Result<&str, DocComment, ParserError> {
+    any((single_line_doc_comment, multi_line_doc_comment)).process(state)
+}
+
 fn single_line_comment(state: State<&str, ParserError>) -> Result<&str, Input<&str>, ParserError> {
     is("//")
         .then(take_until(line_ending))
-        .map(|(x, (y, z))| x.join(&y).join(&z))
+        .map_res(|(x, (y, z))| {
+            let comment = x.join(&y).join(&z);
+            match is_doc_comment(comment.as_inner()) {
+                true => Err(ParserError::DocCommentNotComment),
+                false => Ok(comment),
+            }
+        })
         .process(state)
 }
 
 fn multi_line_comment(state: State<&str, ParserError>) -> Result<&str, Input<&str>, ParserError> {
     is("/*")
         .then(take_until(is("*/")))
-        .map(|(x, (y, z))| x.join(&y).join(&z))
+        .map_res(|(x, (y, z))| {
+            let comment = x.join(&y).join(&z);
+            match is_doc_comment(comment.as_inner()) {
+                true => Err(ParserError::DocCommentNotComment),
+                false => Ok(comment),
+            }
+        })
+        .process(state)
+}
+
+fn single_line_doc_comment(
+    state: State<&str, ParserError>,
+) -> Result<&str, DocComment, ParserError> {
+    is("///")
+        .then(take_until(line_ending))
+        // NOTE: The line ending isn't part of the doc comment, even though we consume it.
+        .map_res(|(x, (content, _))| {
+            let comment = x.join(&content);
+            match is_doc_comment(comment.as_inner()) {
+                true => Ok(DocComment::new(
+                    comment.span(),
+                    DocCommentKind::Line,
+                    content.as_inner().to_string(),
+                )),
+                false => Err(ParserError::NotDocComment),
+            }
+        })
+        .process(state)
+}
+
+fn multi_line_doc_comment(
+    state: State<&str, ParserError>,
+) -> Result<&str, DocComment, ParserError> {
+    is("/**")
+        .then(take_until(is("*/")))
+        .map_res(|(x, (content, z))| {
+            let comment = x.join(&content).join(&z);
+            match is_doc_comment(comment.as_inner()) {
+                true => Ok(DocComment::new(
+                    comment.span(),
+                    DocCommentKind::Block,
+                    content.as_inner().to_string(),
+                )),
+                false => Err(ParserError::NotDocComment),
+            }
+        })
         .process(state)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use errgonomic::parser::errors::ErrorKind;
 
     #[test]
     fn can_parse_single_line_comment() {
@@ -47,6 +110,77 @@ mod tests {
         assert_eq!(state.as_input().as_inner(), "");
     }
 
+    #[test]
+    fn cant_parse_doc_comment_as_comment() {
+        assert!(comment.process("/// hello\n".into()).is_err());
+        assert!(comment.process("/** hello */".into()).is_err());
+
+        let (state, parsed) = comment.process("//// hello".into()).unwrap();
+        assert_eq!(parsed.as_inner(), "//// hello");
+        assert!(state.is_ok());
+
+        let (state, parsed) = comment.process("/**/".into()).unwrap();
+        assert_eq!(parsed.as_inner(), "/**/");
+        assert!(state.is_ok());
+    }
+
+    #[test]
+    fn cant_parse_regular_comment_as_doc_comment() {
+        for source in ["//// hello\n", "/*** hello */", "/**/"] {
+            let state = doc_comment.process(source.into()).unwrap_err();
+            assert_eq!(state.as_input().as_inner(), source);
+        }
+
+        let state = single_line_doc_comment
+            .process("//// hello\n".into())
+            .unwrap_err();
+        assert_eq!(
+            state.errors().kind(),
+            &ErrorKind::custom(ParserError::NotDocComment)
+        );
+
+        let state = single_line_comment
+            .process("/// hello\n".into())
+            .unwrap_err();
+        assert_eq!(
+            state.errors().kind(),
+            &ErrorKind::custom(ParserError::DocCommentNotComment)
+        );
+
+        let state = multi_line_comment
+            .process("/** hello */".into())
+            .unwrap_err();
+        assert_eq!(
+            state.errors().kind(),
+            &ErrorKind::custom(ParserError::DocCommentNotComment)
+        );
+    }
+
+    #[test]
+    fn can_parse_doc_comments() {
+        let (state, parsed) = doc_comment.process("/// # Hello\n".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            parsed,
+            DocComment::new((0..11).into(), DocCommentKind::Line, " # Hello".into())
+        );
+
+        let (state, parsed) = doc_comment
+            .process("/** *Hello*\n  world */".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            parsed,
+            DocComment::new(
+                (0..22).into(),
+                DocCommentKind::Block,
+                " *Hello*\n  world ".into()
+            )
+        );
+    }
+
     #[test]
     fn can_parse_multi_line_comment() {
         let (state, parsed) = multi_line_comment.process("/* hello */".into()).unwrap();
@@ -9,6 +9,26 @@ pub enum ParserError {
 
     #[error("Error parsing floating number: {0}")]
     ParseFloat(#[from] ParseFloatError),
+
+    /// Comments like `///` and `/**`, which are doc comments, where a regular comment was expected.
+    #[error("Expected a regular comment, found a doc comment")]
+    DocCommentNotComment,
+
+    /// Comments like `////` and `/***` look like doc comments, but are regular comments.
+    #[error("Expected a doc comment, found a regular comment")]
+    NotDocComment,
+
+    /// A name that starts with a keyword, like `letter`, where the keyword was expected.
+    #[error("Expected the keyword `{0}`, found a name that starts with it")]
+    NameNotKeyword(&'static str),
+
+    /// A keyword, like `let`, where a name was expected.
+    #[error("`{0}` is a keyword, so it can't be used as a name")]
+    KeywordAsName(String),
+
+    /// A `/` that starts a comment, where a division was expected.
+    #[error("Expected `/`, found the start of a comment")]
+    CommentNotDivision,
 }
 
 impl CustomError for ParserError {}
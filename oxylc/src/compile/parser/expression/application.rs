@@ -1,61 +1,59 @@
-use super::atom;
+use super::{primary, unblocked};
 use crate::{
-    compile::parser::{errors::ParserError, utils::wnnw},
+    compile::parser::errors::ParserError,
     repr::fst::{
         expression::{Expression, ExpressionKind},
         FstNode,
     },
 };
 use errgonomic::{
+    combinators::many,
     parser::Parser,
     prelude::{Result, State},
 };
 
-/// Takes an atom, and tries to apply it to the next one.
-/// TODO: Eugh, not an actual parser. Somehow fix that at some point?
-/// WARN: This should be parsed last, since otherwise it'll just recurse forever!
-pub fn application(
+/// Parses a chain of applications, like `f a b`, into `((f a) b)`.
+/// ```bnf
+/// <application> ::= <primary> <primary>*
+/// ```
+/// NOTE: The arguments are folded onto the function left to right as they come in, so a chain of
+/// `n` arguments takes `n` steps and never has to rebuild (or clone) the part already parsed.
+pub fn application(state: State<&str, ParserError>) -> Result<&str, Expression, ParserError> {
+    primary
+        .then(many(primary))
+        .map(|(function, args)| args.into_iter().fold(function, apply))
+        .process(state)
+}
+
+/// Parses a chain of applications whose arguments can't be blocks, like the `f x` in
+/// `match f x { ... }`.
+/// ```bnf
+/// <unblocked_application> ::= <primary> <unblocked>*
+/// ```
+pub fn unblocked_application(
     state: State<&str, ParserError>,
-    left: Expression,
 ) -> Result<&str, Expression, ParserError> {
-    let (state, right) = wnnw(atom).process(state)?;
-    Ok((state, fix_application(left, right)))
+    primary
+        .then(many(unblocked))
+        .map(|(function, args)| args.into_iter().fold(function, apply))
+        .process(state)
 }
 
-/// NOTE: We assume that we are to create an application between `left` and `right`.
-/// In an ideal world, we have `((h g) f) a)` from `h g f a`, and to enforce this we use an
-/// inductive approach:
-///     Base case: <expr> <expr>
-///         1. just make `appl { <expr> <expr> }`
-///     Inductive case: <expr> <appl>, asm. <appl> is "correct" (e.g. `((g f) a)` from `g f a`), so
-///     like `h g f a`:
-///         1. unwrap into `h, g, (f ...)`
-fn fix_application(left: Expression, right: Expression) -> Expression {
-    match right.kind {
-        ExpressionKind::Application { function, arg } => fix_application(
-            Expression::new(
-                left.location().union_between(*function.location()),
-                ExpressionKind::Application {
-                    function: Box::new(left),
-                    arg: function.clone(), // TODO: Less expensive please!
-                },
-            ),
-            *arg,
-        ),
-        _ => Expression::new(
-            left.location().union_between(*right.location()),
-            ExpressionKind::Application {
-                function: Box::new(left),
-                arg: Box::new(right),
-            },
-        ),
-    }
+/// Applies `function` to `arg`.
+fn apply(function: Expression, arg: Expression) -> Expression {
+    Expression::new(
+        function.location().union_between(*arg.location()),
+        ExpressionKind::Application {
+            function: Box::new(function),
+            arg: Box::new(arg),
+        },
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        compile::parser::expression::expression,
+        compile::parser::expression::{atom, expression},
         repr::fst::{
             identifier::Identifier,
             value::{Value, ValueKind},
@@ -78,14 +76,14 @@ mod tests {
                         (0..1).into(),
                         ExpressionKind::Value(Value::new(
                             (0..1).into(),
-                            ValueKind::Identifier(Identifier::new((0..1).into(), "f".to_string())),
+                            ValueKind::Identifier(Identifier::new((0..1).into(), "f".into())),
                         )),
                     )),
                     arg: Box::new(Expression::new(
                         (2..3).into(),
                         ExpressionKind::Value(Value::new(
                             (2..3).into(),
-                            ValueKind::Identifier(Identifier::new((2..3).into(), "g".to_string())),
+                            ValueKind::Identifier(Identifier::new((2..3).into(), "g".into())),
                         )),
                     )),
                 },
@@ -94,8 +92,6 @@ mod tests {
     }
 
     #[test]
-    // NOTE: This requires that `application` is in invoked within `atom`, as it requires recursive
-    // calls to `atom`.
     fn can_parse_app_2_args() {
         let (state, parsed) = atom.process("f g h".into()).unwrap();
         assert!(state.is_ok());
@@ -114,7 +110,7 @@ mod tests {
                                     (0..1).into(),
                                     ValueKind::Identifier(Identifier::new(
                                         (0..1).into(),
-                                        "f".to_string()
+                                        "f".into()
                                     )),
                                 )),
                             )),
@@ -124,7 +120,7 @@ mod tests {
                                     (2..3).into(),
                                     ValueKind::Identifier(Identifier::new(
                                         (2..3).into(),
-                                        "g".to_string()
+                                        "g".into()
                                     )),
                                 )),
                             )),
@@ -134,11 +130,28 @@ mod tests {
                         (4..5).into(),
                         ExpressionKind::Value(Value::new(
                             (4..5).into(),
-                            ValueKind::Identifier(Identifier::new((4..5).into(), "h".to_string())),
+                            ValueKind::Identifier(Identifier::new((4..5).into(), "h".into())),
                         )),
                     )),
                 }
             )
         )
     }
+
+    #[test]
+    fn can_parse_app_tighter_than_operators() {
+        let (state, parsed) = expression.process("f x + g (h y) z".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        let ExpressionKind::Infix { lhs, rhs, .. } = parsed.kind else {
+            panic!("expected an infix expression, got {:?}", parsed.kind);
+        };
+        assert!(matches!(lhs.kind, ExpressionKind::Application { .. }));
+        assert_eq!(*lhs.location(), (0..3).into());
+        let ExpressionKind::Application { function, arg } = rhs.kind else {
+            panic!("expected an application, got {:?}", rhs.kind);
+        };
+        assert_eq!(*function.location(), (6..13).into());
+        assert_eq!(*arg.location(), (14..15).into());
+    }
 }
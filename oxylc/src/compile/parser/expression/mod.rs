@@ -2,10 +2,13 @@ mod application;
 mod tests;
 mod utils;
 
+use application::{application, unblocked_application};
+
 use super::{
     block::block,
     errors::ParserError,
-    utils::{parenthesized, wnnw},
+    pattern::match_expr,
+    utils::{parenthesized, tuple, wnnw},
     value::value,
 };
 use crate::{
@@ -16,8 +19,8 @@ use crate::{
     },
 };
 use errgonomic::{
-    combinators::any,
-    parser::{errors::Result, state::State, Parser},
+    combinators::{any, maybe},
+    parser::{errors::Result, input::Input, state::State, Parser},
     prelude::{is, Associativity, Pratt},
 };
 use utils::*;
@@ -30,16 +33,50 @@ pub fn expression(state: State<&str, ParserError>) -> Result<&str, Expression, P
     pratt.process(state)
 }
 
-/// Parses an atomic `Expression` object.
+/// Parses an atomic `Expression` object, which is the operand of the operators in `pratt`. As
+/// application binds tighter than any operator, this is a (possibly trivial) application chain.
 /// ```bnf
-/// <atom> ::= ( "(" <expression> ")" ) | <value>
+/// <atom> ::= <application>
 /// ```
 pub fn atom(state: State<&str, ParserError>) -> Result<&str, Expression, ParserError> {
+    application.process(state)
+}
+
+/// Parses a single `Expression` object that can be applied or applied to.
+/// ```bnf
+/// <primary> ::= <block> | <unblocked>
+/// ```
+fn primary(state: State<&str, ParserError>) -> Result<&str, Expression, ParserError> {
+    any((
+        wnnw(block.map(|block| Expression::new(*block.location(), ExpressionKind::Block(block)))),
+        unblocked,
+    ))
+    .process(state)
+}
+
+/// Parses a single `Expression` object that can be applied or applied to, other than a block.
+/// ```bnf
+/// <unblocked> ::= <match> | <value> | <tuple> | ( "(" <expression> ")" )
+/// ```
+fn unblocked(state: State<&str, ParserError>) -> Result<&str, Expression, ParserError> {
     // NOTE: Don't do `ww(expression)` in the `any`, as we simply recurse forever if we never
-    // encounter an expression. Therefore, `ww` every individual kind of expression
+    // encounter an expression. Therefore, `ww` every individual kind of expression. A `match` is
+    // tried before a value, as `match` is also an identifier, and a tuple is tried before a
+    // parenthesized expression, as that commits after the `(`.
     any((
+        wnnw(match_expr.map(|m| Expression::new(m.location, ExpressionKind::Match(m)))),
         wnnw(value.map(|value| Expression::new(*value.location(), ExpressionKind::Value(value)))),
-        wnnw(block.map(|block| Expression::new(*block.location(), ExpressionKind::Block(block)))),
+        wnnw(tuple(expression)).map(|(lparen, items, trailing_comma, rparen)| {
+            Expression::new(
+                lparen.span().union_between(rparen.span()),
+                ExpressionKind::Tuple {
+                    lparen_location: lparen.span(),
+                    rparen_location: rparen.span(),
+                    items,
+                    trailing_comma,
+                },
+            )
+        }),
         wnnw(parenthesized(expression)).map(|(p1, expr, p2)| {
             Expression::new(
                 p1.span().union_between(p2.span()),
@@ -54,14 +91,34 @@ pub fn atom(state: State<&str, ParserError>) -> Result<&str, Expression, ParserE
     .process(state)
 }
 
+/// Parses the expression a `match` is on. It's like any other expression, except that nothing in
+/// it can be applied to a block without parentheses, as the `{` after it starts the arms.
+/// ```bnf
+/// <scrutinee> ::= <expression>
+/// ```
+pub fn scrutinee(state: State<&str, ParserError>) -> Result<&str, Expression, ParserError> {
+    operators(unblocked_application, state)
+}
+
 /// The pratt parser we are using.
 fn pratt(state: State<&str, ParserError>) -> Result<&str, Expression, ParserError> {
+    operators(atom, state)
+}
+
+/// Parses the operators of an expression, with `atom` parsing what they're used on.
+fn operators<'a, A>(
+    atom: A,
+    state: State<&'a str, ParserError>,
+) -> Result<&'a str, Expression, ParserError>
+where
+    A: Fn(State<&'a str, ParserError>) -> Result<&'a str, Expression, ParserError>,
+{
     Pratt::new(&atom, cons_prefix, cons_infix, cons_postfix)
         .with_prefix_op(ww(is("-")).map(|op| Operator::new(op.span(), OperatorKind::Dash)))
         // NOTE: We want `::` on top as it "binds tighter" than `.`, so out of an expression
         // `a::b.c` we get `(a::b).c`.
         .with_infix_op(
-            ww(is("::")).map(|op| Operator::new(op.span(), OperatorKind::Dot)),
+            ww(is("::")).map(|op| Operator::new(op.span(), OperatorKind::DoubleColon)),
             Associativity::Right,
         )
         // NOTE: We want `.` on top as it "binds tighter" than `|>`, so out of an expression
@@ -79,7 +136,7 @@ fn pratt(state: State<&str, ParserError>) -> Result<&str, Expression, ParserErro
             Associativity::Left,
         )
         .with_infix_op(
-            ww(is("/")).map(|op| Operator::new(op.span(), OperatorKind::FSlash)),
+            ww(fslash).map(|op| Operator::new(op.span(), OperatorKind::FSlash)),
             Associativity::Left,
         )
         .with_infix_op(
@@ -90,5 +147,35 @@ fn pratt(state: State<&str, ParserError>) -> Result<&str, Expression, ParserErro
             ww(is("-")).map(|op| Operator::new(op.span(), OperatorKind::Dash)),
             Associativity::Left,
         )
+        // NOTE: We want comparisons at the bottom, so out of an expression `a + b == c` we get
+        // `(a + b) == c`.
+        .with_infix_op(ww(comparison), Associativity::Left)
+        .process(state)
+}
+
+/// The comparison operators, which all bind just as tightly. `<=` and `>=` are tried before `<`
+/// and `>`, so that they aren't parsed as `<` or `>` followed by a `=`.
+fn comparison(state: State<&str, ParserError>) -> Result<&str, Operator, ParserError> {
+    let op = |text, kind| is(text).map(move |op: Input<&str>| Operator::new(op.span(), kind));
+    any((
+        op("==", OperatorKind::DoubleEquals),
+        op("!=", OperatorKind::BangEquals),
+        op("<=", OperatorKind::LeftAngleEquals),
+        op(">=", OperatorKind::RightAngleEquals),
+        op("<", OperatorKind::LeftAngle),
+        op(">", OperatorKind::RightAngle),
+    ))
+    .process(state)
+}
+
+/// The `/` operator. We can't just use `is("/")` for this, as doc comments aren't eaten by `ww`, so
+/// the start of a doc comment after an expression would be parsed as a division.
+fn fslash(state: State<&str, ParserError>) -> Result<&str, Input<&str>, ParserError> {
+    is("/")
+        .then(maybe(any((is("/"), is("*")))))
+        .map_res(|(slash, comment)| match comment {
+            Some(_) => Err(ParserError::CommentNotDivision),
+            None => Ok(slash),
+        })
         .process(state)
 }
@@ -6,6 +6,7 @@ use crate::repr::fst::{
     identifier::Identifier,
     value::{Value, ValueKind},
 };
+use errgonomic::parser::errors::ErrorKind;
 
 #[test]
 fn can_parse_value_expression() {
@@ -157,3 +158,41 @@ fn can_parse_triangle() {
     assert_eq!(state.as_input().as_inner(), "");
     assert!(state.is_ok());
 }
+
+#[test]
+fn cant_parse_doc_comment_as_division() {
+    let (state, expr) = expression.process("123\n/// Docs!".into()).unwrap();
+    assert_eq!(
+        expr.kind,
+        ExpressionKind::Value(Value::new((0..3).into(), ValueKind::Integer(123)))
+    );
+    assert_eq!(state.as_input().as_inner(), "\n/// Docs!");
+
+    let state = fslash.process("// Not a division".into()).unwrap_err();
+    assert_eq!(
+        state.errors().kind(),
+        &ErrorKind::custom(ParserError::CommentNotDivision)
+    );
+}
+
+#[test]
+fn can_parse_double_colon() {
+    let (state, actual) = expression.process("a::b".into()).unwrap();
+    let ExpressionKind::Infix { operator, .. } = actual.kind else {
+        panic!("expected an infix expression, got {:#?}", actual);
+    };
+    assert_eq!(operator.kind(), OperatorKind::DoubleColon);
+    assert_eq!(state.as_input().as_inner(), "");
+}
+
+#[test]
+fn can_parse_comparison() {
+    let (state, actual) = expression.process("a + 1 <= b".into()).unwrap();
+    let ExpressionKind::Infix { operator, lhs, .. } = actual.kind else {
+        panic!("expected an infix expression, got {:#?}", actual);
+    };
+    assert_eq!(operator.kind(), OperatorKind::LeftAngleEquals);
+    assert_eq!(*operator.location(), (6..8).into());
+    assert!(matches!(lhs.kind, ExpressionKind::Infix { .. }));
+    assert_eq!(state.as_input().as_inner(), "");
+}
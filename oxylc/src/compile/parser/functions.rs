@@ -1,7 +1,12 @@
-use super::{errors::ParserError, expression::expression, ident::ident, utils::ww};
-use crate::repr::fst::function::Function;
+use super::{
+    errors::ParserError,
+    expression::expression,
+    ident::ident,
+    utils::{comma_separated, wnnw, ww},
+};
+use crate::repr::fst::{function::Function, FstNode};
 use errgonomic::{
-    combinators::{is, separated},
+    combinators::is,
     parser::{errors::Result, state::State, Parser},
 };
 
@@ -11,12 +16,14 @@ use errgonomic::{
 /// ```
 pub fn function(state: State<&str, ParserError>) -> Result<&str, Function, ParserError> {
     is("|")
-        .then(separated(ww(ident), is(","), true))
+        .then(comma_separated(ww(ident)))
         .then(ww(is("|")))
-        .then(ww(expression))
-        .map_with_state(|state, (((start, idents), _), expression)| {
-            let location = start.span().union_between(state.as_input().span());
-            (state, Function::new(location, idents, expression))
+        // NOTE: Don't eat the newlines after the expression, as whatever the function is in (e.g. a
+        // `let`-statement) may need them.
+        .then(wnnw(expression))
+        .map(|(((start, (idents, trailing_comma)), _), expression)| {
+            let location = start.span().union_between(*expression.location());
+            Function::new(location, idents, expression).with_trailing_comma(trailing_comma)
         })
         .process(state)
 }
@@ -34,11 +41,11 @@ mod tests {
     fn can_parse_fn_no_arg() {
         let (state, parsed) = function.process("|   \n\r\n| 123 \n".into()).unwrap();
         assert!(state.is_ok());
-        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(state.as_input().as_inner(), "\n");
         assert_eq!(
             parsed,
             Function::new(
-                (0..14).into(),
+                (0..12).into(),
                 vec![],
                 Expression::new(
                     (9..12).into(),
@@ -52,12 +59,12 @@ mod tests {
     fn can_parse_fn_single_arg() {
         let (state, parsed) = function.process("|  x \n\n| 123 \n".into()).unwrap();
         assert!(state.is_ok());
-        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(state.as_input().as_inner(), "\n");
         assert_eq!(
             parsed,
             Function::new(
-                (0..14).into(),
-                vec![Identifier::new((3..4).into(), "x".to_string(),)],
+                (0..12).into(),
+                vec![Identifier::new((3..4).into(), "x".into(),)],
                 Expression::new(
                     (9..12).into(),
                     ExpressionKind::Value(Value::new((9..12).into(), ValueKind::Integer(123),)),
@@ -72,14 +79,14 @@ mod tests {
             .process("|  x ,\n\t\r\ny_1\n\n| 123 \n".into())
             .unwrap();
         assert!(state.is_ok());
-        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(state.as_input().as_inner(), "\n");
         assert_eq!(
             parsed,
             Function::new(
-                (0..22).into(),
+                (0..20).into(),
                 vec![
-                    Identifier::new((3..4).into(), "x".to_string(),),
-                    Identifier::new((10..13).into(), "y_1".to_string(),)
+                    Identifier::new((3..4).into(), "x".into(),),
+                    Identifier::new((10..13).into(), "y_1".into(),)
                 ],
                 Expression::new(
                     (17..20).into(),
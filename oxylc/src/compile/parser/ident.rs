@@ -1,29 +1,49 @@
 use super::errors::ParserError;
-use crate::repr::fst::identifier::Identifier;
+use crate::{
+    compile::lexer::KEYWORDS,
+    repr::{ast::ident::Ident, fst::identifier::Identifier},
+};
 use errgonomic::{
-    combinators::{alphabetic, alphanumeric, any, is, many},
-    parser::{errors::Result, state::State, Parser},
+    combinators::{alphabetic, alphanumeric, any, is, many, maybe},
+    parser::{errors::Result, input::Input, state::State, Parser},
 };
 
-/// Parses an ident.
+/// Parses an ident. Keywords (see `lexer::KEYWORDS`) aren't idents.
 /// ```bnf
 /// <ident> ::= ( [a-zA-Z] | "_" ) ( [a-zA-Z0-9] | "_" )*
 /// ```
 pub fn ident(state: State<&str, ParserError>) -> Result<&str, Identifier, ParserError> {
     any((alphabetic, is("_")))
         .then(many(any((alphanumeric, is("_")))))
-        .map(|(part1, part2s)| {
+        .map_res(|(part1, part2s)| {
             let part2 = part2s.into_iter().reduce(|acc, x| acc.join(&x));
             let ident = match part2 {
                 Some(x) => part1.join(&x),
                 None => part1,
             };
 
-            Identifier::new(ident.span(), ident.as_inner().to_string())
+            match KEYWORDS.contains(&ident.as_inner()) {
+                true => Err(ParserError::KeywordAsName(ident.as_inner().to_string())),
+                false => Ok(Identifier::new(ident.span(), Ident::new(ident.as_inner()))),
+            }
         })
         .process(state)
 }
 
+/// Parses a keyword, like `let`. Unlike `is`, this doesn't parse the start of a name that begins
+/// with the keyword, like the `let` in `letter`, so parsers can `commit` right after it.
+/// ```bnf
+/// <keyword> ::= your_keyword_here !( [a-zA-Z0-9] | "_" )
+/// ```
+pub fn keyword<'a>(kwd: &'static str) -> impl Parser<&'a str, Input<&'a str>, ParserError> {
+    is(kwd)
+        .then(maybe(any((alphanumeric, is("_")))))
+        .map_res(move |(parsed, rest)| match rest {
+            Some(_) => Err(ParserError::NameNotKeyword(kwd)),
+            None => Ok(parsed),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -36,8 +56,34 @@ mod tests {
         let result = ident(state);
         assert!(result.is_ok());
         let (state, ident) = result.unwrap();
-        assert_eq!(ident.name, "abc");
+        assert_eq!(ident.name.as_str(), "abc");
         assert_eq!(ident.location(), &(0..3).into());
         assert_eq!(state.as_input().as_inner(), "");
     }
+
+    #[test]
+    fn cant_parse_keyword_as_ident() {
+        for source in ["let", "match", "True"] {
+            let state = ident.process(source.into()).unwrap_err();
+            assert_eq!(state.as_input().as_inner(), source);
+        }
+
+        let (_, parsed) = ident.process("matches".into()).unwrap();
+        assert_eq!(parsed.name.as_str(), "matches");
+    }
+
+    #[test]
+    fn can_parse_keyword() {
+        let (state, parsed) = keyword("mod").process("mod a".into()).unwrap();
+        assert_eq!(parsed.as_inner(), "mod");
+        assert_eq!(state.as_input().as_inner(), " a");
+
+        let (state, _) = keyword("use").process("use".into()).unwrap();
+        assert_eq!(state.as_input().as_inner(), "");
+
+        for source in ["model", "user", "use_it", "mod2"] {
+            let state = keyword(&source[..3]).process(source.into()).unwrap_err();
+            assert_eq!(state.as_input().as_inner(), source);
+        }
+    }
 }
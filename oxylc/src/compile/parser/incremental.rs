@@ -0,0 +1,621 @@
+//! Reparses a file after an edit, without parsing all of it again. Only the smallest block or
+//! top-level statement around the edit is parsed, and the result is spliced into the old FST, with
+//! the spans of everything after it moved. If there's no such node, or it doesn't parse on its
+//! own, the whole file is parsed instead, so the result is always the same as `parse`'s.
+
+use super::{
+    block::block, errors::ParserError, parse, statement::statement, syntax::statement_start, trivia,
+};
+use crate::{
+    compile::lexer::lex,
+    repr::fst::{
+        attribute::{Attribute, AttributeArgKind},
+        block::Block,
+        expression::{Expression, ExpressionKind, Operator},
+        function::Function,
+        identifier::Identifier,
+        pattern::{Match, Pattern, PatternKind},
+        statement::{Statement, StatementKind, Visibility},
+        type_expr::{TypeExpr, TypeExprKind, TypeParams},
+        use_tree::{UseTree, UseTreeKind},
+        value::{Value, ValueKind},
+        File, FstNode,
+    },
+};
+use errgonomic::parser::{errors::Error, input::Span, Parser};
+use std::ops::Range;
+
+/// Reparses `file` after `range` of its source was replaced with `text`, giving `source`.
+pub fn reparse<'a>(
+    mut file: File,
+    source: &'a str,
+    range: Range<usize>,
+    text: &str,
+) -> Result<File, Error<&'a str, ParserError>> {
+    let delta = text.len() as isize - range.len() as isize;
+    let Some((index, region)) = region(&file, &range) else {
+        return parse(source);
+    };
+
+    let old = region.location();
+    let input = &source[old.start()..moved(old.end(), delta)];
+    let mut splice = Splice::new(old.end(), delta);
+
+    match region {
+        Region::Block(_) => {
+            let Some(mut new) = parse_all(block, input) else {
+                return parse(source);
+            };
+            Splice::new(0, old.start() as isize).block(&mut new);
+            splice.block = Some(new);
+            splice.statement(&mut file.statements[index]);
+        }
+        Region::Statement(_) => {
+            let Some(mut new) = parse_all(statement, input) else {
+                return parse(source);
+            };
+            Splice::new(0, old.start() as isize).statement(&mut new);
+            file.statements[index] = new;
+        }
+    }
+
+    for statement in &mut file.statements[index + 1..] {
+        splice.statement(statement);
+    }
+    splice.span(&mut file.location);
+    relex_trivia(&mut file, source, old, delta);
+
+    Ok(file)
+}
+
+/// What gets reparsed after an edit, along with where it was before the edit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Region {
+    /// A top-level statement, with its doc comments, attributes and visibility.
+    Statement(Span),
+
+    Block(Span),
+}
+
+impl Region {
+    fn location(&self) -> Span {
+        match self {
+            Region::Statement(location) | Region::Block(location) => *location,
+        }
+    }
+}
+
+/// Finds the smallest region around `range`, and the index of the top-level statement it's in.
+/// The edit can't touch the start or the end of the region, as that could change where it ends
+/// (or whether it's still a block at all).
+fn region(file: &File, range: &Range<usize>) -> Option<(usize, Region)> {
+    let index = file.statements.iter().position(|statement| {
+        statement_start(statement) < range.start && range.end < statement.location.end()
+    })?;
+    let statement = &file.statements[index];
+
+    let region = match block_in_statement(statement, range) {
+        Some(block) => Region::Block(block.location),
+        None => Region::Statement((statement_start(statement)..statement.location.end()).into()),
+    };
+    Some((index, region))
+}
+
+fn block_in_statement<'a>(statement: &'a Statement, range: &Range<usize>) -> Option<&'a Block> {
+    let attributes = statement
+        .attributes
+        .iter()
+        .flat_map(|attribute| &attribute.args)
+        .find_map(|arg| match &arg.kind {
+            AttributeArgKind::Value(value) => block_in_value(value, range),
+            AttributeArgKind::String(_) => None,
+        });
+
+    attributes.or_else(|| match &statement.kind {
+        StatementKind::Let { expression, .. } | StatementKind::Impl { expression, .. } => {
+            block_in_expression(expression, range)
+        }
+        StatementKind::Mod { .. } | StatementKind::Use { .. } | StatementKind::Type { .. } => None,
+    })
+}
+
+fn block_in_expression<'a>(expression: &'a Expression, range: &Range<usize>) -> Option<&'a Block> {
+    match &expression.kind {
+        ExpressionKind::Parenthesized { inner, .. }
+        | ExpressionKind::Prefix { rhs: inner, .. }
+        | ExpressionKind::Postfix { lhs: inner, .. } => block_in_expression(inner, range),
+        ExpressionKind::Infix { lhs, rhs, .. }
+        | ExpressionKind::Application {
+            function: lhs,
+            arg: rhs,
+        } => block_in_expression(lhs, range).or_else(|| block_in_expression(rhs, range)),
+        ExpressionKind::Tuple { items, .. } => items
+            .iter()
+            .find_map(|item| block_in_expression(item, range)),
+        ExpressionKind::Value(value) => block_in_value(value, range),
+        ExpressionKind::Block(block) => block_in_block(block, range),
+        ExpressionKind::Match(m) => block_in_expression(&m.scrutinee, range).or_else(|| {
+            m.arms
+                .iter()
+                .find_map(|arm| block_in_expression(&arm.expression, range))
+        }),
+    }
+}
+
+fn block_in_value<'a>(value: &'a Value, range: &Range<usize>) -> Option<&'a Block> {
+    match &value.kind {
+        ValueKind::Function(function) => block_in_expression(&function.expression, range),
+        _ => None,
+    }
+}
+
+fn block_in_block<'a>(block: &'a Block, range: &Range<usize>) -> Option<&'a Block> {
+    // NOTE: The edit mustn't touch the curly brackets.
+    if !(block.location.start() < range.start && range.end < block.location.end()) {
+        return None;
+    }
+
+    block
+        .statements
+        .iter()
+        .find_map(|statement| block_in_statement(statement, range))
+        .or_else(|| block_in_expression(&block.expression, range))
+        .or(Some(block))
+}
+
+/// Runs `parser` on `input`, only succeeding if it's all parsed without errors.
+fn parse_all<'a, T>(parser: impl Parser<&'a str, T, ParserError>, input: &'a str) -> Option<T> {
+    let (state, node) = parser.process(input.into()).ok()?;
+    (state.is_ok() && state.as_input().as_inner().is_empty()).then_some(node)
+}
+
+/// Replaces the trivia in the region `old` with the trivia in it after the edit. Trivia that
+/// only partly overlaps the region (like the blank lines after a statement) is lexed again too,
+/// so that it's merged the same way as it would be by `parse`.
+fn relex_trivia(file: &mut File, source: &str, old: Span, delta: isize) {
+    let overlaps = |location: &Span| location.start() < old.end() && old.start() < location.end();
+    let (start, end) = file
+        .trivia
+        .iter()
+        .filter(|trivia| overlaps(&trivia.location))
+        .fold((old.start(), old.end()), |(start, end), trivia| {
+            (
+                start.min(trivia.location.start()),
+                end.max(trivia.location.end()),
+            )
+        });
+
+    file.trivia.retain(|trivia| !overlaps(&trivia.location));
+    let splice = Splice::new(end, delta);
+    for trivia in &mut file.trivia {
+        splice.span(&mut trivia.location);
+    }
+
+    let input = &source[start..moved(end, delta)];
+    let mut relexed = trivia::collect(input, &lex(input));
+    let shift = Splice::new(0, start as isize);
+    for trivia in &mut relexed {
+        shift.span(&mut trivia.location);
+    }
+
+    let index = file
+        .trivia
+        .partition_point(|trivia| trivia.location.start() < start);
+    file.trivia.splice(index..index, relexed);
+}
+
+/// Moves `offset` by `delta`.
+fn moved(offset: usize, delta: isize) -> usize {
+    offset
+        .checked_add_signed(delta)
+        .expect("nothing is moved before the start of the file")
+}
+
+/// Moves the spans in an FST after an edit. Spans after the end of the edited region move by
+/// `delta`, spans around it grow (or shrink) by `delta`, and spans before it stay where they are.
+/// If `block` is given, it replaces the block that starts at the same place.
+struct Splice {
+    end: usize,
+    delta: isize,
+    block: Option<Block>,
+}
+
+impl Splice {
+    fn new(end: usize, delta: isize) -> Self {
+        Self {
+            end,
+            delta,
+            block: None,
+        }
+    }
+
+    fn span(&self, span: &mut Span) {
+        if span.start() >= self.end {
+            *span = (moved(span.start(), self.delta)..moved(span.end(), self.delta)).into();
+        } else if span.end() >= self.end {
+            *span = (span.start()..moved(span.end(), self.delta)).into();
+        }
+    }
+
+    fn statement(&mut self, statement: &mut Statement) {
+        self.span(&mut statement.location);
+        if let Visibility::Public { location } = &mut statement.visibility {
+            self.span(location);
+        }
+        for doc in &mut statement.docs {
+            self.span(&mut doc.location);
+        }
+        for attribute in &mut statement.attributes {
+            self.attribute(attribute);
+        }
+
+        match &mut statement.kind {
+            StatementKind::Let {
+                ident,
+                signature,
+                expression,
+                ..
+            } => {
+                self.ident(ident);
+                if let Some(signature) = signature {
+                    self.span(&mut signature.location);
+                    if let Some(params) = &mut signature.params {
+                        self.type_params(params);
+                    }
+                    self.type_expr(&mut signature.ty);
+                }
+                self.expression(expression);
+            }
+            StatementKind::Mod { ident } => self.ident(ident),
+            StatementKind::Use { tree } => self.use_tree(tree),
+            StatementKind::Impl {
+                trait_ident,
+                type_ident,
+                expression,
+            } => {
+                self.ident(trait_ident);
+                self.ident(type_ident);
+                self.expression(expression);
+            }
+            StatementKind::Type {
+                ident,
+                params,
+                constructors,
+            } => {
+                self.ident(ident);
+                if let Some(params) = params {
+                    self.type_params(params);
+                }
+                for constructor in constructors {
+                    self.span(&mut constructor.location);
+                    self.ident(&mut constructor.ident);
+                    for field in &mut constructor.fields {
+                        self.type_expr(field);
+                    }
+                }
+            }
+        }
+    }
+
+    fn type_params(&self, params: &mut TypeParams) {
+        self.span(&mut params.location);
+        for param in &mut params.params {
+            self.span(&mut param.location);
+            self.ident(&mut param.ident);
+            for bound in &mut param.bounds {
+                self.ident(bound);
+            }
+        }
+        if let Some(comma) = &mut params.trailing_comma {
+            self.span(comma);
+        }
+    }
+
+    fn type_expr(&self, ty: &mut TypeExpr) {
+        self.span(&mut ty.location);
+        match &mut ty.kind {
+            TypeExprKind::Named { ident, args } => {
+                self.ident(ident);
+                for arg in args {
+                    self.type_expr(arg);
+                }
+            }
+            TypeExprKind::Unit => {}
+            TypeExprKind::Parenthesized {
+                lparen_location,
+                rparen_location,
+                inner,
+            } => {
+                self.span(lparen_location);
+                self.span(rparen_location);
+                self.type_expr(inner);
+            }
+            TypeExprKind::Tuple {
+                lparen_location,
+                rparen_location,
+                items,
+                trailing_comma,
+            } => {
+                self.span(lparen_location);
+                self.span(rparen_location);
+                for item in items {
+                    self.type_expr(item);
+                }
+                if let Some(comma) = trailing_comma {
+                    self.span(comma);
+                }
+            }
+            TypeExprKind::Function {
+                arrow_location,
+                param,
+                result,
+            } => {
+                self.span(arrow_location);
+                self.type_expr(param);
+                self.type_expr(result);
+            }
+        }
+    }
+
+    fn attribute(&mut self, attribute: &mut Attribute) {
+        self.span(&mut attribute.location);
+        self.ident(&mut attribute.name);
+        for arg in &mut attribute.args {
+            self.span(&mut arg.location);
+            if let AttributeArgKind::Value(value) = &mut arg.kind {
+                self.value(value);
+            }
+        }
+        if let Some((lparen, rparen)) = &mut attribute.parens {
+            self.span(lparen);
+            self.span(rparen);
+        }
+        if let Some(comma) = &mut attribute.trailing_comma {
+            self.span(comma);
+        }
+    }
+
+    fn use_tree(&mut self, tree: &mut UseTree) {
+        self.span(&mut tree.location);
+        for ident in &mut tree.path {
+            self.ident(ident);
+        }
+        match &mut tree.kind {
+            UseTreeKind::Simple { alias } => {
+                if let Some(alias) = alias {
+                    self.ident(alias);
+                }
+            }
+            UseTreeKind::Group(trees) => {
+                for tree in trees {
+                    self.use_tree(tree);
+                }
+            }
+        }
+        if let Some(comma) = &mut tree.trailing_comma {
+            self.span(comma);
+        }
+    }
+
+    fn ident(&self, ident: &mut Identifier) {
+        self.span(&mut ident.location);
+    }
+
+    fn expression(&mut self, expression: &mut Expression) {
+        self.span(&mut expression.location);
+
+        match &mut expression.kind {
+            ExpressionKind::Parenthesized {
+                lparen_location,
+                rparen_location,
+                inner,
+            } => {
+                self.span(lparen_location);
+                self.span(rparen_location);
+                self.expression(inner);
+            }
+            ExpressionKind::Tuple {
+                lparen_location,
+                rparen_location,
+                items,
+                trailing_comma,
+            } => {
+                self.span(lparen_location);
+                self.span(rparen_location);
+                for item in items {
+                    self.expression(item);
+                }
+                if let Some(comma) = trailing_comma {
+                    self.span(comma);
+                }
+            }
+            ExpressionKind::Value(value) => self.value(value),
+            ExpressionKind::Block(block) => self.block(block),
+            ExpressionKind::Match(m) => self.match_expr(m),
+            ExpressionKind::Infix { operator, lhs, rhs } => {
+                self.operator(operator);
+                self.expression(lhs);
+                self.expression(rhs);
+            }
+            ExpressionKind::Prefix { operator, rhs } => {
+                self.operator(operator);
+                self.expression(rhs);
+            }
+            ExpressionKind::Postfix { operator, lhs } => {
+                self.operator(operator);
+                self.expression(lhs);
+            }
+            ExpressionKind::Application { function, arg } => {
+                self.expression(function);
+                self.expression(arg);
+            }
+        }
+    }
+
+    fn match_expr(&mut self, m: &mut Match) {
+        self.span(&mut m.location);
+        self.expression(&mut m.scrutinee);
+        for arm in &mut m.arms {
+            self.span(&mut arm.location);
+            self.pattern(&mut arm.pattern);
+            self.span(&mut arm.arrow_location);
+            self.expression(&mut arm.expression);
+            if let Some(comma) = &mut arm.comma {
+                self.span(comma);
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &mut Pattern) {
+        self.span(&mut pattern.location);
+        match &mut pattern.kind {
+            PatternKind::Named { ident, args } => {
+                self.ident(ident);
+                for arg in args {
+                    self.pattern(arg);
+                }
+            }
+            PatternKind::Value(value) => self.value(value),
+            PatternKind::Parenthesized {
+                lparen_location,
+                rparen_location,
+                inner,
+            } => {
+                self.span(lparen_location);
+                self.span(rparen_location);
+                self.pattern(inner);
+            }
+            PatternKind::Tuple {
+                lparen_location,
+                rparen_location,
+                items,
+                trailing_comma,
+            } => {
+                self.span(lparen_location);
+                self.span(rparen_location);
+                for item in items {
+                    self.pattern(item);
+                }
+                if let Some(comma) = trailing_comma {
+                    self.span(comma);
+                }
+            }
+        }
+    }
+
+    fn operator(&self, operator: &mut Operator) {
+        let mut location = *operator.location();
+        self.span(&mut location);
+        *operator = Operator::new(location, operator.kind());
+    }
+
+    fn value(&mut self, value: &mut Value) {
+        self.span(&mut value.location);
+        match &mut value.kind {
+            ValueKind::Identifier(ident) => self.ident(ident),
+            ValueKind::Function(function) => self.function(function),
+            ValueKind::Integer(_) | ValueKind::Floating(_) | ValueKind::Boolean(_) => {}
+        }
+    }
+
+    fn function(&mut self, function: &mut Function) {
+        self.span(&mut function.location);
+        for arg in &mut function.args {
+            self.ident(arg);
+        }
+        self.expression(&mut function.expression);
+        if let Some(comma) = &mut function.trailing_comma {
+            self.span(comma);
+        }
+    }
+
+    fn block(&mut self, block: &mut Block) {
+        // NOTE: Blocks around the replaced one start before it, and blocks in it start after it.
+        if let Some(new) = self
+            .block
+            .take_if(|new| new.location.start() == block.location.start())
+        {
+            *block = new;
+            return;
+        }
+
+        self.span(&mut block.location);
+        for statement in &mut block.statements {
+            self.statement(statement);
+        }
+        self.expression(&mut block.expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "/// Docs.\n#[inline]\nlet f = |x| {\n    // Double it.\n    let y = x * 2\n    { y + 1 }\n}\n\nlet g = 1\n";
+
+    /// Checks that reparsing after replacing `range` with `text` is the same as parsing it all.
+    fn check(range: Range<usize>, text: &str) {
+        let mut source = SOURCE.to_string();
+        source.replace_range(range.clone(), text);
+
+        let parsed = parse(&source).expect("the edit should parse");
+        let reparsed = reparse(parse(SOURCE).unwrap(), &source, range, text);
+        assert_eq!(reparsed.ok(), Some(parsed), "after the edit:\n{}", source);
+    }
+
+    fn offset(text: &str) -> usize {
+        SOURCE.find(text).unwrap()
+    }
+
+    #[test]
+    fn can_find_smallest_region() {
+        let file = parse(SOURCE).unwrap();
+        let inner = offset("{ y");
+        let outer = offset("{\n");
+
+        let at = |start: usize, len: usize| region(&file, &(start..start + len));
+        assert_eq!(
+            at(inner + 2, 1),
+            Some((0, Region::Block((inner..inner + 9).into())))
+        );
+        assert_eq!(
+            at(offset("2\n"), 1),
+            Some((0, Region::Block((outer..offset("}\n\n") + 1).into())))
+        );
+        assert_eq!(
+            at(offset("f ="), 1),
+            Some((0, Region::Statement((0..offset("let g")).into())))
+        );
+        // NOTE: Edits touching the start of a statement could join it to the one before.
+        assert_eq!(at(offset("let g"), 1), None);
+        assert_eq!(at(offset("let g"), 0), None);
+    }
+
+    #[test]
+    fn can_reparse_like_parse() {
+        // NOTE: In blocks.
+        check(offset("2\n")..offset("2\n") + 1, "20");
+        check(offset("y + 1") + 4..offset("y + 1") + 5, "x");
+        check(offset("let y")..offset("let y"), "let z = 3\n    ");
+        check(offset("    // Double")..offset("    let y"), "");
+
+        // NOTE: In top-level statements.
+        check(offset("f =")..offset("f =") + 1, "foo");
+        check(offset("Docs")..offset("Docs"), "More ");
+        check(offset("inline")..offset("inline") + 6, "cold(1)");
+
+        // NOTE: Around statements, which needs the whole file to be parsed.
+        check(offset("let g")..offset("let g"), "let h = 2\n");
+        check(SOURCE.len()..SOURCE.len(), "mod a");
+        check(0..SOURCE.len(), "");
+    }
+
+    #[test]
+    fn can_reparse_errors() {
+        let range = offset("* 2")..offset("* 2") + 1;
+        let mut source = SOURCE.to_string();
+        source.replace_range(range.clone(), "*)");
+
+        assert!(reparse(parse(SOURCE).unwrap(), &source, range, "*)").is_err());
+    }
+}
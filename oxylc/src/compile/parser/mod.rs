@@ -2,30 +2,45 @@
 //! (includes comments and other things).
 //!
 
+mod attribute;
 mod block;
 mod comments;
 mod expression;
 mod functions;
 mod ident;
+pub mod incremental;
+mod pattern;
 mod statement;
+pub mod syntax;
+mod trivia;
+mod type_expr;
+mod use_tree;
 mod utils;
 mod value;
 
 pub mod errors;
 
-use crate::repr::fst::File;
+use crate::{compile::lexer::lex, repr::fst::File};
 use errgonomic::{
-    combinators::many,
+    combinators::{eoi, many},
     parser::{errors::Error, Parser},
 };
 use errors::ParserError;
 use statement::statement;
+use utils::ww;
 
+/// Parses a whole file, along with its trivia.
 pub fn parse(input: &str) -> Result<File, Error<&str, ParserError>> {
-    many(statement)
+    // NOTE: The whole input has to be parsed, or the FST wouldn't have everything in the source.
+    let file = many(statement)
+        .then(ww(eoi))
+        .map(|(stmts, _)| stmts)
         .map_with_state(|state, stmts| {
             let location = state.as_input().span().union_between((0..1).into());
             (state, File::new(location, stmts))
         })
-        .parse(input)
+        .parse(input)?;
+    let trivia = trivia::collect(input, &lex(input));
+
+    Ok(file.with_trivia(trivia))
 }
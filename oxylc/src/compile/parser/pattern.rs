@@ -0,0 +1,185 @@
+use super::{
+    errors::ParserError,
+    expression::{expression, scrutinee},
+    ident::{ident, keyword},
+    utils::{parenthesized, tuple, wnnw, ww},
+    value::literal,
+};
+use crate::repr::fst::{
+    identifier::Identifier,
+    pattern::{Arm, Match, Pattern, PatternKind},
+};
+use errgonomic::{
+    combinators::{any, commit, is, many, maybe},
+    parser::{errors::Result, state::State, Parser},
+};
+
+/// Parses a `match` expression.
+/// ```bnf
+/// <match> ::= "match" <expression> "{" <arm>* "}"
+/// ```
+/// NOTE: The expression being matched on can't apply anything to a block, as the `{` after it
+/// starts the arms (see `scrutinee`).
+pub fn match_expr(state: State<&str, ParserError>) -> Result<&str, Match, ParserError> {
+    keyword("match")
+        .then(commit(
+            ww(scrutinee)
+                .then(is("{"))
+                .then(many(ww(arm)))
+                .then(is("}")),
+        ))
+        .map(|(match_kwd, (((scrutinee, _), arms), rcurly))| {
+            Match::new(
+                match_kwd.span().union_between(rcurly.span()),
+                scrutinee,
+                arms,
+            )
+        })
+        .process(state)
+}
+
+/// Parses an arm of a `match`. Arms are separated by new lines, or by commas.
+/// ```bnf
+/// <arm> ::= <pattern> "=>" <expression> ","?
+/// ```
+fn arm(state: State<&str, ParserError>) -> Result<&str, Arm, ParserError> {
+    pattern
+        .then(ww(is("=>")))
+        .then(commit(expression)) // NOTE: alr. wrapped in whitespace
+        .then(maybe(ww(is(","))))
+        .map(|(((pattern, arrow), expression), comma)| {
+            Arm::new(pattern, arrow.span(), expression).with_comma(comma.map(|comma| comma.span()))
+        })
+        .process(state)
+}
+
+/// Parses a pattern.
+/// ```bnf
+/// <pattern> ::= <literal> | ( <ident> <pattern_atom>* ) | <pattern_atom>
+/// ```
+pub fn pattern(state: State<&str, ParserError>) -> Result<&str, Pattern, ParserError> {
+    // NOTE: Literals are tried first, as `True` and `False` are also identifiers.
+    any((
+        wnnw(literal).map(|value| Pattern::new(value.location, PatternKind::Value(value))),
+        wnnw(ident)
+            .then(many(pattern_atom))
+            .map(|(ident, args)| named(ident, args)),
+        pattern_atom,
+    ))
+    .process(state)
+}
+
+/// Parses a pattern that can be an argument of a constructor without parentheses around it.
+/// ```bnf
+/// <pattern_atom> ::= <literal> | <ident> | <tuple> | ( "(" <pattern> ")" )
+/// ```
+fn pattern_atom(state: State<&str, ParserError>) -> Result<&str, Pattern, ParserError> {
+    // NOTE: A tuple is tried before a parenthesized pattern, as that commits after the `(`.
+    any((
+        wnnw(literal).map(|value| Pattern::new(value.location, PatternKind::Value(value))),
+        wnnw(ident).map(|ident| named(ident, vec![])),
+        wnnw(tuple(pattern)).map(|(lparen, items, trailing_comma, rparen)| {
+            Pattern::new(
+                lparen.span().union_between(rparen.span()),
+                PatternKind::Tuple {
+                    lparen_location: lparen.span(),
+                    rparen_location: rparen.span(),
+                    items,
+                    trailing_comma,
+                },
+            )
+        }),
+        wnnw(parenthesized(ww(pattern))).map(|(lparen, inner, rparen)| {
+            Pattern::new(
+                lparen.span().union_between(rparen.span()),
+                PatternKind::Parenthesized {
+                    lparen_location: lparen.span(),
+                    rparen_location: rparen.span(),
+                    inner: Box::new(inner),
+                },
+            )
+        }),
+    ))
+    .process(state)
+}
+
+/// Makes a pattern of a name, applied to `args`.
+fn named(ident: Identifier, args: Vec<Pattern>) -> Pattern {
+    let location = match args.last() {
+        Some(last) => ident.location.union_between(last.location),
+        None => ident.location,
+    };
+    Pattern::new(location, PatternKind::Named { ident, args })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repr::fst::value::ValueKind;
+
+    /// Writes a pattern back out, with parentheses around every constructor applied to something,
+    /// so that the tests can check how it was grouped.
+    fn grouped(pattern: &Pattern) -> String {
+        match &pattern.kind {
+            PatternKind::Named { ident, args } if args.is_empty() => ident.name.to_string(),
+            PatternKind::Named { ident, args } => {
+                let args = args.iter().map(grouped).collect::<Vec<_>>();
+                format!("({} {})", ident.name, args.join(" "))
+            }
+            PatternKind::Value(value) => value.kind.literal().unwrap(),
+            PatternKind::Parenthesized { inner, .. } => grouped(inner),
+            PatternKind::Tuple { items, .. } => {
+                let items = items.iter().map(grouped).collect::<Vec<_>>();
+                format!("<{}>", items.join(", "))
+            }
+        }
+    }
+
+    #[test]
+    fn can_parse_patterns() {
+        let cases = [
+            ("x", "x"),
+            ("True", "True"),
+            ("Cons x (Cons _ rest)", "(Cons x (Cons _ rest))"),
+            ("(1, False)", "<1, False>"),
+            ("( Rect w h , _ , )", "<(Rect w h), _>"),
+            ("Pair (a, b) 3", "(Pair <a, b> 3)"),
+        ];
+        for (source, expected) in cases {
+            let (state, pattern) = pattern.process(source.into()).unwrap();
+            assert!(state.is_ok());
+            assert_eq!(state.as_input().as_inner(), "", "in {}", source);
+            assert_eq!(grouped(&pattern), expected);
+            assert_eq!(pattern.location, (0..source.len()).into());
+        }
+    }
+
+    #[test]
+    fn can_parse_match() {
+        let source = "match f x {\n    0 => a, (1, y) => b\n    _ => { c }\n}";
+        let (state, parsed) = match_expr.process(source.into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(parsed.location, (0..source.len()).into());
+        assert_eq!(parsed.scrutinee.location, (6..9).into());
+
+        let patterns = parsed
+            .arms
+            .iter()
+            .map(|arm| grouped(&arm.pattern))
+            .collect::<Vec<_>>();
+        assert_eq!(patterns, ["0", "<1, y>", "_"]);
+        assert_eq!(parsed.arms[0].comma, Some((22..23).into()));
+        assert_eq!(parsed.arms[1].comma, None);
+        assert_eq!(parsed.arms[0].location, (16..22).into());
+        assert!(matches!(
+            parsed.arms[0].pattern.kind,
+            PatternKind::Value(ref value) if value.kind == ValueKind::Integer(0)
+        ));
+    }
+
+    #[test]
+    fn cant_parse_match_as_a_name() {
+        assert!(match_expr.process("matches".into()).is_err());
+    }
+}
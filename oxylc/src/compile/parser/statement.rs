@@ -1,53 +1,214 @@
 use super::{
+    attribute::attribute,
+    comments::doc_comment,
     errors::ParserError,
     expression::expression,
-    ident::ident,
+    ident::{ident, keyword},
+    type_expr::{constructor, signature, type_params},
+    use_tree::use_tree,
     utils::{line_ending, wnnw, ww},
 };
-use crate::repr::fst::statement::{Statement, StatementKind};
+use crate::repr::fst::{
+    attribute::Attribute,
+    doc_comment::DocComment,
+    statement::{Statement, StatementKind, Visibility},
+};
 use errgonomic::{
-    combinators::{any, commit, is},
+    combinators::{any, commit, is, many, maybe},
     parser::{errors::Result, state::State, Parser},
 };
 
 /// Parses a `Statement` object.
 /// ```bnf
-/// <statement> ::= <let_stmt>
+/// <statement> ::= ( <doc_comment> | <attribute> )* "pub"?
+///                 ( <let_stmt> | <mod_stmt> | <use_stmt> | <impl_stmt> | <type_stmt> )
 /// ```
 pub fn statement(state: State<&str, ParserError>) -> Result<&str, Statement, ParserError> {
     // NOTE: Don't do `ww(statement)` in the `any`, as we simply recurse forever if we never
     // encounter a statement. Therefore, `ww` every individual kind of statement.
-    any((ww(let_stmt),)).process(state)
+    ww(decorated(visible(any((
+        let_stmt, mod_stmt, use_stmt, impl_stmt, type_stmt,
+    )))))
+    .process(state)
+}
+
+/// Parses the visibility of a statement, and attaches it to the statement.
+/// ```bnf
+/// <visible> ::= "pub"? your_statement_here
+/// ```
+fn visible<'a, P: Parser<&'a str, Statement, ParserError>>(
+    p: P,
+) -> impl Parser<&'a str, Statement, ParserError> {
+    maybe(wnnw(keyword("pub"))).then(p).map(|(pub_kwd, stmt)| {
+        stmt.with_visibility(match pub_kwd {
+            Some(pub_kwd) => Visibility::Public {
+                location: pub_kwd.span(),
+            },
+            None => Visibility::Private,
+        })
+    })
+}
+
+/// Something that decorates a statement.
+enum Decoration {
+    Doc(DocComment),
+    Attribute(Attribute),
+}
+
+/// Parses the doc comments and attributes before a statement, and attaches them to it.
+/// ```bnf
+/// <decorated> ::= ( <doc_comment> | <attribute> )* your_statement_here
+/// ```
+fn decorated<'a, P: Parser<&'a str, Statement, ParserError>>(
+    p: P,
+) -> impl Parser<&'a str, Statement, ParserError> {
+    many(ww(any((
+        doc_comment.map(Decoration::Doc),
+        attribute.map(Decoration::Attribute),
+    ))))
+    .then(p)
+    .map(|(decorations, stmt)| {
+        let mut docs = vec![];
+        let mut attributes = vec![];
+
+        for decoration in decorations {
+            match decoration {
+                Decoration::Doc(doc) => docs.push(doc),
+                Decoration::Attribute(attribute) => attributes.push(attribute),
+            }
+        }
+
+        stmt.with_docs(docs).with_attributes(attributes)
+    })
 }
 
 /// A `let`-statement.
 /// ```bnf
-/// <let_stmt> ::= "let" <ident> "=" <expression> <line_ending>
+/// <let_stmt> ::= "let" <ident> <signature>? "=" <expression> <line_ending>
 /// ```
 fn let_stmt(state: State<&str, ParserError>) -> Result<&str, Statement, ParserError> {
-    is("let")
+    keyword("let")
         // NOTE: commit on the rest of the statement, as we know we must parse a `let` statement
         // now.
         .then(commit(
             wnnw(ident)
+                .then(maybe(signature))
+                .then(ww(is("=")))
+                .then(expression) // NOTE: alr. wrapped in whitespace
+                .then(line_ending),
+        ))
+        .map_with_state(
+            |state, (let_kwd, ((((ident, signature), _), expression), ending))| {
+                let location = let_kwd.span().union_between(ending.span());
+                (
+                    state,
+                    Statement::new(
+                        location,
+                        StatementKind::Let {
+                            is_mutable: false, // TODO: Mutability in the future?
+                            ident,
+                            signature,
+                            expression,
+                        },
+                    ),
+                )
+            },
+        )
+        .process(state)
+}
+
+/// A `mod`-statement.
+/// ```bnf
+/// <mod_stmt> ::= "mod" <ident> <line_ending>
+/// ```
+fn mod_stmt(state: State<&str, ParserError>) -> Result<&str, Statement, ParserError> {
+    keyword("mod")
+        .then(commit(wnnw(ident).then(line_ending)))
+        .map(|(mod_kwd, (ident, ending))| {
+            Statement::new(
+                mod_kwd.span().union_between(ending.span()),
+                StatementKind::Mod { ident },
+            )
+        })
+        .process(state)
+}
+
+/// A `use`-statement.
+/// ```bnf
+/// <use_stmt> ::= "use" <use_tree> <line_ending>
+/// ```
+fn use_stmt(state: State<&str, ParserError>) -> Result<&str, Statement, ParserError> {
+    keyword("use")
+        .then(commit(wnnw(use_tree).then(line_ending)))
+        .map(|(use_kwd, (tree, ending))| {
+            Statement::new(
+                use_kwd.span().union_between(ending.span()),
+                StatementKind::Use { tree },
+            )
+        })
+        .process(state)
+}
+
+/// An `impl`-statement.
+/// ```bnf
+/// <impl_stmt> ::= "impl" <ident> "for" <ident> "=" <expression> <line_ending>
+/// ```
+fn impl_stmt(state: State<&str, ParserError>) -> Result<&str, Statement, ParserError> {
+    keyword("impl")
+        .then(commit(
+            wnnw(ident)
+                .then(wnnw(keyword("for")))
+                .then(wnnw(ident))
                 .then(ww(is("=")))
                 .then(expression) // NOTE: alr. wrapped in whitespace
                 .then(line_ending),
         ))
-        .map_with_state(|state, (let_kwd, (((ident, _), expression), ending))| {
-            let location = let_kwd.span().union_between(ending.span());
-            (
-                state,
+        .map(
+            |(impl_kwd, (((((trait_ident, _), type_ident), _), expression), ending))| {
                 Statement::new(
-                    location,
-                    StatementKind::Let {
-                        is_mutable: false, // TODO: Mutability in the future?
-                        ident,
+                    impl_kwd.span().union_between(ending.span()),
+                    StatementKind::Impl {
+                        trait_ident,
+                        type_ident,
                         expression,
                     },
-                ),
-            )
-        })
+                )
+            },
+        )
+        .process(state)
+}
+
+/// A `type`-statement.
+/// ```bnf
+/// <type_stmt> ::= "type" <ident> <type_params>? "=" <constructor> ( "|" <constructor> )*
+///                 <line_ending>
+/// ```
+fn type_stmt(state: State<&str, ParserError>) -> Result<&str, Statement, ParserError> {
+    keyword("type")
+        .then(commit(
+            wnnw(ident)
+                .then(maybe(wnnw(type_params)))
+                .then(ww(is("=")))
+                .then(constructor)
+                // NOTE: The constructors can be on lines of their own, as long as every line
+                // starts with a `|`.
+                .then(many(ww(is("|")).then(constructor)))
+                .then(line_ending),
+        ))
+        .map(
+            |(type_kwd, (((((ident, params), _), first), rest), ending))| {
+                let mut constructors = vec![first];
+                constructors.extend(rest.into_iter().map(|(_, constructor)| constructor));
+                Statement::new(
+                    type_kwd.span().union_between(ending.span()),
+                    StatementKind::Type {
+                        ident,
+                        params,
+                        constructors,
+                    },
+                )
+            },
+        )
         .process(state)
 }
 
@@ -55,8 +216,11 @@ fn let_stmt(state: State<&str, ParserError>) -> Result<&str, Statement, ParserEr
 mod tests {
     use super::*;
     use crate::repr::fst::{
+        attribute::{AttributeArg, AttributeArgKind},
+        doc_comment::DocCommentKind,
         expression::{Expression, ExpressionKind},
         identifier::Identifier,
+        type_expr::TypeExprKind,
         value::{Value, ValueKind},
     };
 
@@ -68,6 +232,7 @@ mod tests {
             StatementKind::Let {
                 is_mutable: false,
                 ident: Identifier::new((4..7).into(), "abc".into(),),
+                signature: None,
                 expression: Expression::new(
                     (10..13).into(),
                     ExpressionKind::Value(Value::new((10..13).into(), ValueKind::Integer(123),)),
@@ -76,4 +241,195 @@ mod tests {
         );
         assert_eq!(state.as_input().as_inner(), "");
     }
+
+    #[test]
+    fn can_parse_attributed_statement() {
+        let (state, stmt) = statement
+            .process("#[inline]\n#[extern(\"c\")] let f = 1\n".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(stmt.location, (25..35).into());
+        assert_eq!(
+            stmt.attributes,
+            [
+                Attribute::new(
+                    (0..9).into(),
+                    Identifier::new((2..8).into(), "inline".into()),
+                    vec![],
+                ),
+                Attribute::new(
+                    (10..24).into(),
+                    Identifier::new((12..18).into(), "extern".into()),
+                    vec![AttributeArg::new(
+                        (19..22).into(),
+                        AttributeArgKind::String("c".into()),
+                    )],
+                )
+                .with_parens(Some(((18..19).into(), (22..23).into()))),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_parse_documented_statement() {
+        let (state, stmt) = statement
+            .process("// Not docs!\n/// Docs!\n#[inline]\n/** More docs! */\nlet f = 1\n".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            stmt.docs,
+            [
+                DocComment::new((13..22).into(), DocCommentKind::Line, " Docs!".into()),
+                DocComment::new(
+                    (33..50).into(),
+                    DocCommentKind::Block,
+                    " More docs! ".into()
+                ),
+            ]
+        );
+        assert_eq!(stmt.attributes.len(), 1);
+    }
+
+    #[test]
+    fn can_parse_mod_statement() {
+        let (state, stmt) = statement.process("pub mod foo\n".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            stmt,
+            Statement::new(
+                (4..12).into(),
+                StatementKind::Mod {
+                    ident: Identifier::new((8..11).into(), "foo".into()),
+                },
+            )
+            .with_visibility(Visibility::Public {
+                location: (0..3).into()
+            })
+        );
+    }
+
+    #[test]
+    fn can_parse_use_statement() {
+        let (state, stmt) = statement.process("use a::{b, c}".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(stmt.location, (0..13).into());
+        assert_eq!(stmt.visibility, Visibility::Private);
+        assert!(matches!(stmt.kind, StatementKind::Use { .. }));
+    }
+
+    #[test]
+    fn cant_use_keyword_as_name() {
+        for source in ["let for = 1\n", "let x = match\n", "mod use\n"] {
+            assert!(statement.process(source.into()).is_err(), "in {}", source);
+        }
+    }
+
+    #[test]
+    fn cant_parse_name_as_statement() {
+        for source in ["model\n", "user x\n", "used + 1\n", "letter\n", "publish\n"] {
+            let state = statement.process(source.into()).unwrap_err();
+            assert_eq!(state.as_input().as_inner(), source);
+        }
+    }
+
+    #[test]
+    fn cant_parse_name_as_impl_statement() {
+        for source in ["implies\n", "impl_count = 1\n"] {
+            let state = statement.process(source.into()).unwrap_err();
+            assert_eq!(state.as_input().as_inner(), source);
+        }
+
+        let (state, _) = statement
+            .process("let x = { impl_count }\n".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+
+        // NOTE: `Add fortune` doesn't have a `for`, so this is still an `impl` statement, just a
+        // broken one.
+        assert!(statement
+            .process("impl Add fortune Bool = f\n".into())
+            .is_err());
+    }
+
+    #[test]
+    fn can_parse_impl_statement() {
+        let (state, stmt) = statement
+            .process("impl Add for Bool = |a, b| a\n".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(stmt.location, (0..29).into());
+        let StatementKind::Impl {
+            trait_ident,
+            type_ident,
+            expression,
+        } = stmt.kind
+        else {
+            panic!("expected an `impl` statement, got {:#?}", stmt.kind);
+        };
+        assert_eq!(trait_ident, Identifier::new((5..8).into(), "Add".into()));
+        assert_eq!(type_ident, Identifier::new((13..17).into(), "Bool".into()));
+        assert_eq!(expression.location, (20..28).into());
+    }
+
+    #[test]
+    fn can_parse_let_with_signature() {
+        let (state, stmt) = statement
+            .process("let id[a]: a -> a = |x| x\n".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        let StatementKind::Let {
+            ident, signature, ..
+        } = stmt.kind
+        else {
+            panic!("expected a `let` statement, got {:#?}", stmt.kind);
+        };
+        assert_eq!(ident, Identifier::new((4..6).into(), "id".into()));
+        let signature = signature.unwrap();
+        assert_eq!(signature.location, (6..17).into());
+        assert_eq!(signature.params.unwrap().params.len(), 1);
+        assert!(matches!(signature.ty.kind, TypeExprKind::Function { .. }));
+    }
+
+    #[test]
+    fn cant_parse_name_as_type_statement() {
+        for source in ["types\n", "type_of x\n"] {
+            let state = statement.process(source.into()).unwrap_err();
+            assert_eq!(state.as_input().as_inner(), source);
+        }
+    }
+
+    #[test]
+    fn can_parse_type_statement() {
+        let (state, stmt) = statement
+            .process("type List[a] = Nil\n    | Cons a (List a)\n".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(stmt.location, (0..41).into());
+        let StatementKind::Type {
+            ident,
+            params,
+            constructors,
+        } = stmt.kind
+        else {
+            panic!("expected a `type` statement, got {:#?}", stmt.kind);
+        };
+        assert_eq!(ident, Identifier::new((5..9).into(), "List".into()));
+        assert_eq!(params.unwrap().location, (9..12).into());
+        assert_eq!(
+            constructors
+                .iter()
+                .map(|constructor| (constructor.ident.name.as_str(), constructor.fields.len()))
+                .collect::<Vec<_>>(),
+            [("Nil", 0), ("Cons", 2)]
+        );
+        assert_eq!(constructors[1].location, (25..40).into());
+    }
 }
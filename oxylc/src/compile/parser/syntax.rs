@@ -0,0 +1,358 @@
+//! Builds the green tree of a file (see `repr::syntax`) from its FST and its tokens. The FST says
+//! where every node starts and ends, and the tokens fill in everything between, so the tree has
+//! every character of the source. Trivia before a node goes in its parent, and trivia at the end
+//! of a node goes in the node.
+
+use crate::{
+    compile::lexer::{lex, token::Token},
+    repr::{
+        fst::{
+            attribute::{Attribute, AttributeArgKind},
+            block::Block,
+            expression::{Expression, ExpressionKind},
+            pattern::{Match, Pattern, PatternKind},
+            statement::{Statement, StatementKind, Visibility},
+            use_tree::{UseTree, UseTreeKind},
+            value::{Value, ValueKind},
+            File, FstNode,
+        },
+        syntax::{
+            green::{GreenBuilder, GreenNode},
+            SyntaxKind,
+        },
+    },
+};
+use errgonomic::parser::input::Span;
+
+/// Builds the green tree of a whole file.
+pub fn build(source: &str, file: &File) -> GreenNode {
+    let mut builder = Builder::new(source);
+    builder.green.start_node(SyntaxKind::File);
+    for statement in &file.statements {
+        builder.statement(statement);
+    }
+    builder.finish()
+}
+
+/// Builds the green tree of a single statement, from its source and its FST. As green trees have
+/// no absolute positions, the tree can be put anywhere in a larger one.
+pub fn build_statement(source: &str, statement: &Statement) -> GreenNode {
+    Builder::new(source)
+        .statement(statement)
+        .expect("the statement is the only node")
+}
+
+/// Gets where a statement starts, including its doc comments, attributes and visibility.
+pub fn statement_start(statement: &Statement) -> usize {
+    let docs = statement.docs.iter().map(|doc| doc.location.start());
+    let attributes = statement
+        .attributes
+        .iter()
+        .map(|attribute| attribute.location.start());
+    let visibility = match statement.visibility {
+        Visibility::Public { location } => Some(location.start()),
+        Visibility::Private => None,
+    };
+
+    docs.chain(attributes)
+        .chain(visibility)
+        .fold(statement.location.start(), usize::min)
+}
+
+struct Builder<'a> {
+    source: &'a str,
+
+    tokens: Vec<Token>,
+
+    /// The index of the next token to add.
+    next: usize,
+
+    green: GreenBuilder,
+}
+
+impl<'a> Builder<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            tokens: lex(source),
+            next: 0,
+            green: GreenBuilder::new(),
+        }
+    }
+
+    /// Adds every token left, and finishes the root.
+    fn finish(mut self) -> GreenNode {
+        self.tokens_until(usize::MAX);
+        self.green.finish_node().expect("the root is finished last")
+    }
+
+    /// Adds the tokens that end before `end`.
+    fn tokens_until(&mut self, end: usize) {
+        while let Some(token) = self.tokens.get(self.next) {
+            if token.location.end() > end {
+                break;
+            }
+            self.green.token(token.kind, token.text(self.source));
+            self.next += 1;
+        }
+    }
+
+    /// Adds a node at `location`, with the children added by `inner`. If it's the root, it's
+    /// returned.
+    fn node(
+        &mut self,
+        kind: SyntaxKind,
+        location: Span,
+        inner: impl FnOnce(&mut Self),
+    ) -> Option<GreenNode> {
+        self.tokens_until(location.start());
+        self.green.start_node(kind);
+        inner(self);
+        self.tokens_until(location.end());
+        self.green.finish_node()
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Option<GreenNode> {
+        let kind = match statement.kind {
+            StatementKind::Let { .. } => SyntaxKind::LetStatement,
+            StatementKind::Mod { .. } => SyntaxKind::ModStatement,
+            StatementKind::Use { .. } => SyntaxKind::UseStatement,
+            StatementKind::Impl { .. } => SyntaxKind::ImplStatement,
+            StatementKind::Type { .. } => SyntaxKind::TypeStatement,
+        };
+        let location = (statement_start(statement)..statement.location.end()).into();
+
+        self.node(kind, location, |this| {
+            for attribute in &statement.attributes {
+                this.attribute(attribute);
+            }
+            match &statement.kind {
+                StatementKind::Let { expression, .. } | StatementKind::Impl { expression, .. } => {
+                    this.expression(expression)
+                }
+                StatementKind::Mod { .. } | StatementKind::Type { .. } => {}
+                StatementKind::Use { tree } => this.use_tree(tree),
+            }
+        })
+    }
+
+    fn attribute(&mut self, attribute: &Attribute) {
+        self.node(SyntaxKind::Attribute, attribute.location, |this| {
+            for arg in &attribute.args {
+                this.node(SyntaxKind::AttributeArg, arg.location, |this| {
+                    if let AttributeArgKind::Value(value) = &arg.kind {
+                        this.value(value);
+                    }
+                });
+            }
+        });
+    }
+
+    fn use_tree(&mut self, tree: &UseTree) {
+        self.node(SyntaxKind::UseTree, tree.location, |this| {
+            if let UseTreeKind::Group(trees) = &tree.kind {
+                for tree in trees {
+                    this.use_tree(tree);
+                }
+            }
+        });
+    }
+
+    fn expression(&mut self, expression: &Expression) {
+        // NOTE: Values and blocks are expressions themselves, rather than being wrapped in one.
+        let kind = match &expression.kind {
+            ExpressionKind::Value(value) => return self.value(value),
+            ExpressionKind::Block(block) => return self.block(block),
+            ExpressionKind::Match(m) => return self.match_expr(m),
+            ExpressionKind::Parenthesized { .. } => SyntaxKind::Parenthesized,
+            ExpressionKind::Tuple { .. } => SyntaxKind::Tuple,
+            ExpressionKind::Infix { .. } => SyntaxKind::Infix,
+            ExpressionKind::Prefix { .. } => SyntaxKind::Prefix,
+            ExpressionKind::Postfix { .. } => SyntaxKind::Postfix,
+            ExpressionKind::Application { .. } => SyntaxKind::Application,
+        };
+
+        self.node(kind, expression.location, |this| match &expression.kind {
+            ExpressionKind::Parenthesized { inner, .. } => this.expression(inner),
+            ExpressionKind::Tuple { items, .. } => {
+                for item in items {
+                    this.expression(item);
+                }
+            }
+            ExpressionKind::Infix { lhs, rhs, .. } => {
+                this.expression(lhs);
+                this.expression(rhs);
+            }
+            ExpressionKind::Prefix { rhs, .. } => this.expression(rhs),
+            ExpressionKind::Postfix { lhs, .. } => this.expression(lhs),
+            ExpressionKind::Application { function, arg } => {
+                this.expression(function);
+                this.expression(arg);
+            }
+            ExpressionKind::Value(_) | ExpressionKind::Block(_) | ExpressionKind::Match(_) => {
+                unreachable!()
+            }
+        });
+    }
+
+    fn value(&mut self, value: &Value) {
+        self.node(SyntaxKind::Value, *value.location(), |this| {
+            if let ValueKind::Function(function) = &value.kind {
+                this.node(SyntaxKind::Function, function.location, |this| {
+                    this.expression(&function.expression)
+                });
+            }
+        });
+    }
+
+    fn match_expr(&mut self, m: &Match) {
+        self.node(SyntaxKind::Match, m.location, |this| {
+            this.expression(&m.scrutinee);
+            for arm in &m.arms {
+                this.node(SyntaxKind::MatchArm, arm.location, |this| {
+                    this.pattern(&arm.pattern);
+                    this.expression(&arm.expression);
+                });
+            }
+        });
+    }
+
+    /// Adds a pattern. The literals in it are only tokens, rather than `Value`s.
+    fn pattern(&mut self, pattern: &Pattern) {
+        self.node(
+            SyntaxKind::Pattern,
+            pattern.location,
+            |this| match &pattern.kind {
+                PatternKind::Named { args: items, .. } | PatternKind::Tuple { items, .. } => {
+                    for item in items {
+                        this.pattern(item);
+                    }
+                }
+                PatternKind::Parenthesized { inner, .. } => this.pattern(inner),
+                PatternKind::Value(_) => {}
+            },
+        );
+    }
+
+    fn block(&mut self, block: &Block) {
+        self.node(SyntaxKind::Block, block.location, |this| {
+            for statement in &block.statements {
+                this.statement(statement);
+            }
+            this.expression(&block.expression);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compile::parser::parse,
+        repr::syntax::{
+            nodes::{ExpressionKind, StatementKind, TypedNode, ValueKind},
+            red::SyntaxNode,
+        },
+    };
+    use std::{fs, sync::Arc};
+
+    fn tree(source: &str) -> SyntaxNode {
+        SyntaxNode::new_root(Arc::new(build(source, &parse(source).unwrap())))
+    }
+
+    #[test]
+    fn can_build_lossless_tree() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../oxylc-fuzzer/in");
+
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            let root = tree(&source);
+            assert_eq!(root.text(), source, "in {}", path.display());
+
+            // NOTE: Every node has to be where its text is.
+            for node in root.descendants() {
+                let location = node.location();
+                assert_eq!(node.text(), source[location.start()..location.end()]);
+            }
+        }
+    }
+
+    #[test]
+    fn can_use_typed_nodes() {
+        let source = "/// Docs.\n#[a(1)]\npub let f = |x| { x + 1 }\nuse a::{b as c}\n";
+        let root = tree(source);
+        let file = crate::repr::syntax::nodes::File::cast(root).unwrap();
+        let statements = file.statements().collect::<Vec<_>>();
+        assert_eq!(statements.len(), 2);
+
+        let f = &statements[0];
+        assert!(f.is_public());
+        assert_eq!(
+            f.docs()
+                .map(|doc| doc.text().to_string())
+                .collect::<Vec<_>>(),
+            ["/// Docs."]
+        );
+        assert_eq!(f.attributes().next().unwrap().name().unwrap().text(), "a");
+        assert_eq!(f.location(), (0..44).into());
+
+        let StatementKind::Let {
+            ident: Some(ident),
+            expression: Some(expression),
+        } = f.kind()
+        else {
+            panic!("expected a let");
+        };
+        assert_eq!(ident.text(), "f");
+        let ExpressionKind::Value(value) = expression.kind() else {
+            panic!("expected a value");
+        };
+        let Some(ValueKind::Function(function)) = value.kind() else {
+            panic!("expected a function");
+        };
+        assert_eq!(
+            function
+                .args()
+                .map(|arg| arg.text().to_string())
+                .collect::<Vec<_>>(),
+            ["x"]
+        );
+        let ExpressionKind::Block(block) = function.expression().unwrap().kind() else {
+            panic!("expected a block");
+        };
+        let ExpressionKind::Infix { operator, lhs, .. } = block.expression().unwrap().kind() else {
+            panic!("expected an infix expression");
+        };
+        assert_eq!(operator.unwrap().text(), "+");
+        assert_eq!(lhs.unwrap().location(), (36..37).into());
+
+        let StatementKind::Use { tree: Some(tree) } = statements[1].kind() else {
+            panic!("expected a use");
+        };
+        assert_eq!(tree.path()[0].text(), "a");
+        let group = tree.group().unwrap();
+        assert_eq!(group[0].alias().unwrap().text(), "c");
+    }
+
+    #[test]
+    fn can_share_unchanged_nodes() {
+        let root = tree("let x = 1\nlet y = 2\n");
+        let x = root.children().next().unwrap();
+        let y = root.children().nth(1).unwrap();
+        assert_eq!(y.location(), (10..20).into());
+        assert_eq!(y.parent(), Some(root.clone()));
+
+        // NOTE: Making `x` longer moves `y`, but `y`'s green node is shared.
+        let source = "let xyz = 1\n";
+        let statement = &parse(source).unwrap().statements[0];
+        let edited = SyntaxNode::new_root(x.replace_with(build_statement(source, statement)));
+        assert_eq!(edited.text(), "let xyz = 1\nlet y = 2\n");
+
+        let moved = edited.children().nth(1).unwrap();
+        assert!(Arc::ptr_eq(moved.green(), y.green()));
+        assert_eq!(moved.location(), (12..22).into());
+        assert_eq!(edited.token_at_offset(16).unwrap().text(), "y");
+        assert_eq!(edited.covering_node(20..21).kind(), SyntaxKind::Value);
+    }
+}
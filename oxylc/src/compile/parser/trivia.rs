@@ -0,0 +1,77 @@
+//! Finds the trivia (whitespace and comments) in a file. The parser skips over trivia, so instead
+//! of keeping track of it while parsing, we find it afterwards from the tokens of the lexer.
+
+use crate::{
+    compile::lexer::token::{Token, TokenKind},
+    repr::fst::trivia::{Trivia, TriviaKind},
+};
+
+/// Collects the trivia in `source`, given the tokens that were lexed from it. Whitespace and
+/// newlines next to each other are one piece of trivia.
+pub fn collect(source: &str, tokens: &[Token]) -> Vec<Trivia> {
+    let mut trivia: Vec<Trivia> = vec![];
+
+    for token in tokens {
+        let kind = match token.kind {
+            TokenKind::Whitespace | TokenKind::Newline => TriviaKind::Whitespace,
+            TokenKind::Comment if token.text(source).starts_with("//") => TriviaKind::LineComment,
+            TokenKind::Comment => TriviaKind::BlockComment,
+            _ => continue,
+        };
+
+        match trivia.last_mut() {
+            Some(last)
+                if kind == TriviaKind::Whitespace
+                    && last.kind == TriviaKind::Whitespace
+                    && last.location.end() == token.location.start() =>
+            {
+                last.location = last.location.union_between(token.location);
+                last.text.push_str(token.text(source));
+            }
+            _ => trivia.push(Trivia::new(
+                token.location,
+                kind,
+                token.text(source).to_string(),
+            )),
+        }
+    }
+
+    trivia
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::lexer::lex;
+
+    #[test]
+    fn can_collect_trivia() {
+        let source = "let  x = /* a */1 // b\r\n";
+        assert_eq!(
+            collect(source, &lex(source)),
+            [
+                Trivia::new((3..5).into(), TriviaKind::Whitespace, "  ".into()),
+                Trivia::new((6..7).into(), TriviaKind::Whitespace, " ".into()),
+                Trivia::new((8..9).into(), TriviaKind::Whitespace, " ".into()),
+                Trivia::new((9..16).into(), TriviaKind::BlockComment, "/* a */".into()),
+                Trivia::new((17..18).into(), TriviaKind::Whitespace, " ".into()),
+                Trivia::new((18..22).into(), TriviaKind::LineComment, "// b".into()),
+                Trivia::new((22..24).into(), TriviaKind::Whitespace, "\r\n".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn doc_comments_arent_trivia() {
+        let source = "// x\n/// a\nmod b";
+        assert_eq!(
+            collect(source, &lex(source)),
+            [
+                Trivia::new((0..4).into(), TriviaKind::LineComment, "// x".into()),
+                Trivia::new((4..5).into(), TriviaKind::Whitespace, "\n".into()),
+                Trivia::new((10..11).into(), TriviaKind::Whitespace, "\n".into()),
+                Trivia::new((14..15).into(), TriviaKind::Whitespace, " ".into()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use super::{
+    errors::ParserError,
+    ident::ident,
+    utils::{comma_separated, parenthesized, tuple, wnnw, ww},
+};
+use crate::repr::fst::{
+    identifier::Identifier,
+    type_expr::{Constructor, Signature, TypeExpr, TypeExprKind, TypeParam, TypeParams},
+};
+use errgonomic::{
+    combinators::{any, commit, is, many, maybe},
+    parser::{errors::Result, state::State, Parser},
+};
+
+/// Parses a type.
+/// ```bnf
+/// <type> ::= <type_app> ( "->" <type> )?
+/// ```
+/// NOTE: `->` is right-associative, so `a -> b -> c` is `a -> (b -> c)`.
+pub fn type_expr(state: State<&str, ParserError>) -> Result<&str, TypeExpr, ParserError> {
+    type_app
+        .then(maybe(wnnw(is("->")).then(commit(type_expr))))
+        .map(|(param, function)| match function {
+            Some((arrow, result)) => TypeExpr::new(
+                param.location.union_between(result.location),
+                TypeExprKind::Function {
+                    arrow_location: arrow.span(),
+                    param: Box::new(param),
+                    result: Box::new(result),
+                },
+            ),
+            None => param,
+        })
+        .process(state)
+}
+
+/// Parses a type that can be on either side of a `->`, which is a name applied to its arguments.
+/// ```bnf
+/// <type_app> ::= ( <ident> <type_atom>* ) | <type_atom>
+/// ```
+fn type_app(state: State<&str, ParserError>) -> Result<&str, TypeExpr, ParserError> {
+    any((
+        wnnw(ident)
+            .then(many(type_atom))
+            .map(|(ident, args)| named(ident, args)),
+        type_atom,
+    ))
+    .process(state)
+}
+
+/// Parses a type that can be an argument of another one without parentheses around it.
+/// ```bnf
+/// <type_atom> ::= <ident> | ( "(" ")" ) | <tuple> | ( "(" <type> ")" )
+/// ```
+fn type_atom(state: State<&str, ParserError>) -> Result<&str, TypeExpr, ParserError> {
+    // NOTE: `()` and tuples are tried before a parenthesized type, as that commits after the `(`.
+    any((
+        wnnw(ident).map(|ident| named(ident, vec![])),
+        wnnw(is("(").then(ww(is(")")))).map(|(lparen, rparen)| {
+            TypeExpr::new(
+                lparen.span().union_between(rparen.span()),
+                TypeExprKind::Unit,
+            )
+        }),
+        wnnw(tuple(type_expr)).map(|(lparen, items, trailing_comma, rparen)| {
+            TypeExpr::new(
+                lparen.span().union_between(rparen.span()),
+                TypeExprKind::Tuple {
+                    lparen_location: lparen.span(),
+                    rparen_location: rparen.span(),
+                    items,
+                    trailing_comma,
+                },
+            )
+        }),
+        wnnw(parenthesized(ww(type_expr))).map(|(lparen, inner, rparen)| {
+            TypeExpr::new(
+                lparen.span().union_between(rparen.span()),
+                TypeExprKind::Parenthesized {
+                    lparen_location: lparen.span(),
+                    rparen_location: rparen.span(),
+                    inner: Box::new(inner),
+                },
+            )
+        }),
+    ))
+    .process(state)
+}
+
+/// Makes a type by its name, applied to `args`.
+fn named(ident: Identifier, args: Vec<TypeExpr>) -> TypeExpr {
+    let location = match args.last() {
+        Some(last) => ident.location.union_between(last.location),
+        None => ident.location,
+    };
+    TypeExpr::new(location, TypeExprKind::Named { ident, args })
+}
+
+/// Parses the type parameters of a `type`-statement, which can't have bounds.
+/// ```bnf
+/// <type_params> ::= "[" ( <ident> "," )* <ident>? ","? "]"
+/// ```
+pub fn type_params(state: State<&str, ParserError>) -> Result<&str, TypeParams, ParserError> {
+    params(ident.map(|ident| TypeParam::new(ident.location, ident))).process(state)
+}
+
+/// Parses a `let`'s signature, which is its type parameters (with their bounds) and its type.
+/// ```bnf
+/// <signature> ::= <bounded_params>? ":" <type>
+/// <bounded_params> ::= "[" ( <bounded_param> "," )* <bounded_param>? ","? "]"
+/// ```
+pub fn signature(state: State<&str, ParserError>) -> Result<&str, Signature, ParserError> {
+    maybe(wnnw(params(bounded_param)))
+        .then(wnnw(is(":")))
+        .then(type_expr)
+        .map(|((params, colon), ty)| {
+            let start = match &params {
+                Some(params) => params.location,
+                None => colon.span(),
+            };
+            Signature::new(start.union_between(ty.location), params, ty)
+        })
+        .process(state)
+}
+
+/// Parses a type parameter of a `let`, along with the traits it has to implement.
+/// ```bnf
+/// <bounded_param> ::= <ident> ( ":" ( <ident> "+" )* <ident> )?
+/// ```
+fn bounded_param(state: State<&str, ParserError>) -> Result<&str, TypeParam, ParserError> {
+    // NOTE: There has to be a bound after the `:`, so that the `:` is never lost.
+    ident
+        .then(maybe(ww(is(":")).then(commit(
+            ww(ident).then(many(ww(is("+")).then(ww(ident)))),
+        ))))
+        .map(|(ident, bounds)| {
+            let bounds = match bounds {
+                Some((_, (first, rest))) => std::iter::once(first)
+                    .chain(rest.into_iter().map(|(_, bound)| bound))
+                    .collect(),
+                None => vec![],
+            };
+            let location = match bounds.last() {
+                Some(last) => ident.location.union_between(last.location),
+                None => ident.location,
+            };
+            TypeParam::new(location, ident).with_bounds(bounds)
+        })
+        .process(state)
+}
+
+/// Parses type parameters in square brackets, with `param` parsing each of them.
+fn params<'a, P: Parser<&'a str, TypeParam, ParserError>>(
+    param: P,
+) -> impl Parser<&'a str, TypeParams, ParserError> {
+    is("[")
+        .then(commit(comma_separated(ww(param)).then(is("]"))))
+        .map(|(start, ((params, trailing_comma), end))| {
+            TypeParams::new(start.span().union_between(end.span()), params)
+                .with_trailing_comma(trailing_comma)
+        })
+}
+
+/// Parses a constructor of a type.
+/// ```bnf
+/// <constructor> ::= <ident> <type_atom>*
+/// ```
+pub fn constructor(state: State<&str, ParserError>) -> Result<&str, Constructor, ParserError> {
+    wnnw(ident)
+        .then(many(type_atom))
+        .map(|(ident, fields)| {
+            let location = match fields.last() {
+                Some(last) => ident.location.union_between(last.location),
+                None => ident.location,
+            };
+            Constructor::new(location, ident, fields)
+        })
+        .process(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a type back out, with parentheses around every application and function, so that
+    /// the tests can check how it was grouped.
+    fn grouped(ty: &TypeExpr) -> String {
+        match &ty.kind {
+            TypeExprKind::Named { ident, args } if args.is_empty() => ident.name.to_string(),
+            TypeExprKind::Named { ident, args } => {
+                let args = args.iter().map(grouped).collect::<Vec<_>>();
+                format!("({} {})", ident.name, args.join(" "))
+            }
+            TypeExprKind::Unit => "()".into(),
+            TypeExprKind::Parenthesized { inner, .. } => grouped(inner),
+            TypeExprKind::Tuple { items, .. } => {
+                let items = items.iter().map(grouped).collect::<Vec<_>>();
+                format!("<{}>", items.join(", "))
+            }
+            TypeExprKind::Function { param, result, .. } => {
+                format!("({} -> {})", grouped(param), grouped(result))
+            }
+        }
+    }
+
+    #[test]
+    fn can_parse_types() {
+        let cases = [
+            ("I64", "I64"),
+            ("List a", "(List a)"),
+            ("Map k (List v)", "(Map k (List v))"),
+            ("a -> b -> c", "(a -> (b -> c))"),
+            (
+                "(a -> b) -> List a -> List b",
+                "((a -> b) -> ((List a) -> (List b)))",
+            ),
+            ("( ) -> ()", "(() -> ())"),
+            (
+                "(I64, List a,) -> (a, b -> c)",
+                "(<I64, (List a)> -> <a, (b -> c)>)",
+            ),
+        ];
+        for (source, expected) in cases {
+            let (state, ty) = type_expr.process(source.into()).unwrap();
+            assert!(state.is_ok());
+            assert_eq!(state.as_input().as_inner(), "", "in {}", source);
+            assert_eq!(grouped(&ty), expected);
+            assert_eq!(ty.location, (0..source.len()).into());
+        }
+    }
+
+    #[test]
+    fn can_parse_signatures() {
+        let (state, signature) = signature
+            .process("[a: Add + Eq, b,]: a -> b".into())
+            .unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(signature.location, (0..25).into());
+
+        let params = signature.params.unwrap();
+        assert_eq!(params.location, (0..17).into());
+        assert_eq!(params.trailing_comma, Some((15..16).into()));
+        assert_eq!(
+            params.params,
+            [
+                TypeParam::new((1..12).into(), Identifier::new((1..2).into(), "a".into()))
+                    .with_bounds(vec![
+                        Identifier::new((4..7).into(), "Add".into()),
+                        Identifier::new((10..12).into(), "Eq".into()),
+                    ]),
+                TypeParam::new(
+                    (14..15).into(),
+                    Identifier::new((14..15).into(), "b".into())
+                ),
+            ]
+        );
+        assert_eq!(grouped(&signature.ty), "(a -> b)");
+    }
+}
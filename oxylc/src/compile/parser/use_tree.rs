@@ -0,0 +1,135 @@
+use super::{
+    errors::ParserError,
+    ident::{ident, keyword},
+    utils::{comma_separated, wnnw, ww},
+};
+use crate::repr::fst::{
+    use_tree::{UseTree, UseTreeKind},
+    FstNode,
+};
+use errgonomic::{
+    combinators::{any, commit, is, many, maybe},
+    parser::{errors::Result, state::State, Parser},
+};
+
+/// Parses the tree of things imported by a `use`-statement.
+/// ```bnf
+/// <use_tree> ::= ( <ident> "::" )* ( <use_group> | <ident> ( "as" <ident> )? )
+/// ```
+pub fn use_tree(state: State<&str, ParserError>) -> Result<&str, UseTree, ParserError> {
+    many(wnnw(ident).then(is("::")))
+        .then(any((use_group, use_simple)))
+        .map(|(prefix, tree)| {
+            let mut path = prefix
+                .into_iter()
+                .map(|(ident, _)| ident)
+                .collect::<Vec<_>>();
+            let location = match path.first() {
+                Some(first) => first.location().union_between(tree.location),
+                None => tree.location,
+            };
+
+            path.extend(tree.path);
+            UseTree::new(location, path, tree.kind).with_trailing_comma(tree.trailing_comma)
+        })
+        .process(state)
+}
+
+/// Parses the end of a simple import.
+/// ```bnf
+/// <use_simple> ::= <ident> ( "as" <ident> )?
+/// ```
+fn use_simple(state: State<&str, ParserError>) -> Result<&str, UseTree, ParserError> {
+    wnnw(ident)
+        .then(maybe(keyword("as").then(commit(wnnw(ident)))))
+        .map(|(name, alias)| {
+            let location = match &alias {
+                Some((_, alias)) => name.location().union_between(*alias.location()),
+                None => *name.location(),
+            };
+            UseTree::new(
+                location,
+                vec![name],
+                UseTreeKind::Simple {
+                    alias: alias.map(|(_, alias)| alias),
+                },
+            )
+        })
+        .process(state)
+}
+
+/// Parses a group of imports.
+/// ```bnf
+/// <use_group> ::= "{" ( <use_tree> "," )* <use_tree>? ","? "}"
+/// ```
+fn use_group(state: State<&str, ParserError>) -> Result<&str, UseTree, ParserError> {
+    wnnw(is("{"))
+        .then(commit(comma_separated(ww(use_tree)).then(is("}"))))
+        .map(|(start, ((trees, trailing_comma), end))| {
+            UseTree::new(
+                start.span().union_between(end.span()),
+                vec![],
+                UseTreeKind::Group(trees),
+            )
+            .with_trailing_comma(trailing_comma)
+        })
+        .process(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repr::fst::identifier::Identifier;
+
+    #[test]
+    fn can_parse_simple_use_tree() {
+        let (state, parsed) = use_tree.process("a::b as c".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            parsed,
+            UseTree::new(
+                (0..9).into(),
+                vec![
+                    Identifier::new((0..1).into(), "a".into()),
+                    Identifier::new((3..4).into(), "b".into()),
+                ],
+                UseTreeKind::Simple {
+                    alias: Some(Identifier::new((8..9).into(), "c".into())),
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn can_parse_grouped_use_tree() {
+        let (state, parsed) = use_tree.process("a::b::{c, d as e,}".into()).unwrap();
+        assert!(state.is_ok());
+        assert_eq!(state.as_input().as_inner(), "");
+        assert_eq!(
+            parsed,
+            UseTree::new(
+                (0..18).into(),
+                vec![
+                    Identifier::new((0..1).into(), "a".into()),
+                    Identifier::new((3..4).into(), "b".into()),
+                ],
+                UseTreeKind::Group(vec![
+                    UseTree::new(
+                        (7..8).into(),
+                        vec![Identifier::new((7..8).into(), "c".into())],
+                        UseTreeKind::Simple { alias: None },
+                    ),
+                    UseTree::new(
+                        (10..16).into(),
+                        vec![Identifier::new((10..11).into(), "d".into())],
+                        UseTreeKind::Simple {
+                            alias: Some(Identifier::new((15..16).into(), "e".into())),
+                        },
+                    ),
+                ]),
+            )
+            .with_trailing_comma(Some((16..17).into()))
+        );
+    }
+}
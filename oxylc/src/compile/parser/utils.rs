@@ -1,9 +1,15 @@
 use super::{comments::comment, errors::ParserError};
 use errgonomic::{
     combinators::{
-        any, between, commit, consumed, eoi, is, many, newlines, whitespace, whitespace_not_newline,
+        any, between, commit, consumed, eoi, is, many, maybe, newlines, separated, whitespace,
+        whitespace_not_newline,
+    },
+    parser::{
+        errors::Result,
+        input::{Input, Span},
+        state::State,
+        Parser,
     },
-    parser::{errors::Result, input::Input, state::State, Parser},
 };
 
 /// A line ending
@@ -27,6 +33,48 @@ pub fn parenthesized<'a, O, P: Parser<&'a str, O, ParserError>>(
         .map(|(p1, (o, p2))| (p1, o, p2))
 }
 
+/// Things separated by commas, with an optional trailing comma. Returns the location of the
+/// trailing comma along with the things, as it's needed to print the source back out.
+/// ```bnf
+/// <comma_separated> ::= ( your_thing_here "," )* your_thing_here? ","?
+/// ```
+/// NOTE: Wrap `p` in whitespace if there can be whitespace before the commas.
+pub fn comma_separated<'a, O, P: Parser<&'a str, O, ParserError>>(
+    p: P,
+) -> impl Parser<&'a str, (Vec<O>, Option<Span>), ParserError> {
+    separated(p, is(","), false)
+        .then(maybe(ww(is(","))))
+        .map(|(items, comma)| (items, comma.map(|comma| comma.span())))
+}
+
+/// Two or more things in parentheses, separated by commas, with an optional trailing comma, like
+/// `(a, b)`. Returns the parens and the location of the trailing comma along with the things.
+/// ```bnf
+/// <tuple> ::= "(" your_thing_here ( "," your_thing_here )+ ","? ")"
+/// ```
+/// NOTE: `p` is wrapped in whitespace. This only commits after the first comma, so that `(a)` can
+/// still be parsed as something in parentheses.
+pub fn tuple<'a, O, P: Parser<&'a str, O, ParserError> + Copy>(
+    p: P,
+) -> impl Parser<&'a str, (Input<&'a str>, Vec<O>, Option<Span>, Input<&'a str>), ParserError> {
+    is("(")
+        .then(ww(p))
+        .then(is(","))
+        .then(commit(
+            ww(p)
+                .then(many(is(",").then(ww(p))))
+                .then(maybe(ww(is(","))))
+                .then(is(")")),
+        ))
+        .map(
+            |(((lparen, first), _), (((second, rest), comma), rparen))| {
+                let mut items = vec![first, second];
+                items.extend(rest.into_iter().map(|(_, item)| item));
+                (lparen, items, comma.map(|comma| comma.span()), rparen)
+            },
+        )
+}
+
 /// Shorthand for our modified `whitespace_wrapped`, but includes comments
 pub fn ww<'a, O, P: Parser<&'a str, O, ParserError>>(p: P) -> impl Parser<&'a str, O, ParserError> {
     between(
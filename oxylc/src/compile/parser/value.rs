@@ -1,4 +1,8 @@
-use super::{errors::ParserError, functions::function, ident::ident};
+use super::{
+    errors::ParserError,
+    functions::function,
+    ident::{ident, keyword},
+};
 use crate::repr::fst::{
     value::{Value, ValueKind},
     FstNode,
@@ -24,7 +28,16 @@ pub fn value(state: State<&str, ParserError>) -> Result<&str, Value, ParserError
     .process(state)
 }
 
-/// Parses a numeric thing.
+/// Parses a literal that can be matched on, which is an integer or a boolean.
+/// ```bnf
+/// <literal> ::= <numeric> | <boolean>
+/// ```
+pub fn literal(state: State<&str, ParserError>) -> Result<&str, Value, ParserError> {
+    any((numeric, boolean)).process(state)
+}
+
+/// Parses a numeric thing. It can be as large as a `U64`, as whether it fits into its type is
+/// only checked once that's inferred.
 /// ```bnf
 /// <numeric> ::= [0-9]+
 /// ```
@@ -34,9 +47,9 @@ fn numeric(state: State<&str, ParserError>) -> Result<&str, Value, ParserError>
             let location = parsed.span();
             let number = parsed
                 .as_inner()
-                .parse::<i64>()
+                .parse::<u64>()
                 .map_err(ParserError::ParseInt)?;
-            Ok(Value::new(location, ValueKind::Integer(number)))
+            Ok(Value::new(location, ValueKind::Integer(number)).with_raw(parsed.as_inner()))
         })
         .process(state)
 }
@@ -59,7 +72,7 @@ fn floating(state: State<&str, ParserError>) -> Result<&str, Value, ParserError>
                 .as_inner()
                 .parse::<f64>()
                 .map_err(ParserError::ParseFloat)?;
-            Ok(Value::new(location, ValueKind::Floating(number)))
+            Ok(Value::new(location, ValueKind::Floating(number)).with_raw(full.as_inner()))
         })
         .process(state)
 }
@@ -69,7 +82,7 @@ fn floating(state: State<&str, ParserError>) -> Result<&str, Value, ParserError>
 /// <boolean> ::= ( "True" | "False" )
 /// ```
 fn boolean(state: State<&str, ParserError>) -> Result<&str, Value, ParserError> {
-    any((is("True"), is("False")))
+    any((keyword("True"), keyword("False")))
         .map(|parsed| {
             Value::new(
                 parsed.span(),
@@ -122,15 +135,16 @@ mod tests {
         assert!(state.is_ok());
         assert!(matches!(parsed_value.kind, ValueKind::Integer(789)));
 
-        // Test with negative number (assuming decimal parser accepts them)
+        // Test with negative number, which is `42` negated instead
         let input = "-42";
         let state = State::new(input);
         let result = numeric(state);
-        // This check depends on whether decimal parser accepts negative numbers
-        if result.is_ok() {
-            let (_, parsed_value) = result.unwrap();
-            assert!(matches!(parsed_value.kind, ValueKind::Integer(-42)));
-        }
+        assert!(result.is_err());
+
+        // Test with the largest number, which only fits into a `U64`
+        let input = "18446744073709551615";
+        let (_, parsed_value) = numeric(State::new(input)).unwrap();
+        assert_eq!(parsed_value.kind, ValueKind::Integer(u64::MAX));
 
         // Test with non-numeric input
         let input = "abc";
@@ -0,0 +1,106 @@
+//! The prelude, which has what every file can use without defining or importing it:
+//! - The conversions between numbers: numbers are never converted to another type on their own,
+//!   so `to_f64 x` has to be written to use an integer `x` as a float.
+//! - The built-in types, which `impl`-statements can name.
+//! - The methods of the traits, which operators call, and the traits the built-in types implement.
+
+use crate::repr::ast::types::{FloatType, IntType, Scheme, Trait, Type, TypeVar};
+
+/// The conversion functions, and the type each one converts to.
+const CONVERSIONS: [(&str, Type); 10] = [
+    ("to_i8", Type::Int(IntType::I8)),
+    ("to_i16", Type::Int(IntType::I16)),
+    ("to_i32", Type::Int(IntType::I32)),
+    ("to_i64", Type::Int(IntType::I64)),
+    ("to_u8", Type::Int(IntType::U8)),
+    ("to_u16", Type::Int(IntType::U16)),
+    ("to_u32", Type::Int(IntType::U32)),
+    ("to_u64", Type::Int(IntType::U64)),
+    ("to_f32", Type::Float(FloatType::F32)),
+    ("to_f64", Type::Float(FloatType::F64)),
+];
+
+/// Iterates over the names in the prelude.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    CONVERSIONS.iter().map(|(name, _)| *name)
+}
+
+/// Gets the type of a name in the prelude. Every conversion takes any number, so `to_f64` is
+/// `Num a => a -> F64`.
+pub fn type_of(name: &str) -> Option<Scheme> {
+    let (_, to) = CONVERSIONS.iter().find(|(builtin, _)| *builtin == name)?;
+    let from = TypeVar(0);
+    Some(Scheme {
+        vars: vec![from],
+        constraints: vec![(Trait::Num, from)],
+        ty: Type::function(Type::Var(from), to.clone()),
+    })
+}
+
+/// Gets the built-in type with the given name, like `I64` or `Bool`.
+pub fn type_named(name: &str) -> Option<Type> {
+    let ints = IntType::ALL.into_iter().map(Type::Int);
+    let floats = FloatType::ALL.into_iter().map(Type::Float);
+    ints.chain(floats)
+        .chain([Type::Bool])
+        .find(|ty| ty.to_string() == name)
+}
+
+/// Gets the name and the type of the method of a trait, if it has one. The type variable in it
+/// (`TypeVar(0)`) is the type implementing the trait, so `add` is `Add a => a -> a -> a`, and
+/// `Ord`'s `lt` is `Ord a => a -> a -> Bool`.
+pub fn method(t: Trait) -> Option<(&'static str, Scheme)> {
+    let this = TypeVar(0);
+    let a = || Type::Var(this);
+    let binary = |result| Type::function(a(), Type::function(a(), result));
+    let (name, ty) = match t {
+        Trait::Num => return None,
+        Trait::Add => ("add", binary(a())),
+        Trait::Sub => ("sub", binary(a())),
+        Trait::Mul => ("mul", binary(a())),
+        Trait::Div => ("div", binary(a())),
+        Trait::Neg => ("neg", Type::function(a(), a())),
+        Trait::Eq => ("eq", binary(Type::Bool)),
+        Trait::Ord => ("lt", binary(Type::Bool)),
+    };
+
+    Some((
+        name,
+        Scheme {
+            vars: vec![this],
+            constraints: vec![(t, this)],
+            ty,
+        },
+    ))
+}
+
+/// Gets the traits the built-in types implement. Every number can do arithmetic and be compared,
+/// but only signed ones can be negated. `Bool` can only be compared.
+pub fn instances() -> Vec<(Trait, Type)> {
+    let mut instances = vec![];
+    let ints = IntType::ALL
+        .into_iter()
+        .map(|int| (Type::Int(int), int.is_signed()));
+    let floats = FloatType::ALL
+        .into_iter()
+        .map(|float| (Type::Float(float), true));
+    for (ty, is_signed) in ints.chain(floats) {
+        let traits = [
+            Trait::Num,
+            Trait::Add,
+            Trait::Sub,
+            Trait::Mul,
+            Trait::Div,
+            Trait::Eq,
+            Trait::Ord,
+        ];
+        instances.extend(traits.into_iter().map(|t| (t, ty.clone())));
+        if is_signed {
+            instances.push((Trait::Neg, ty));
+        }
+    }
+
+    instances.push((Trait::Eq, Type::Bool));
+    instances.push((Trait::Ord, Type::Bool));
+    instances
+}
@@ -0,0 +1,93 @@
+//! Prints the FST back out as source code. As the FST keeps every token and all of the trivia
+//! (whitespace and comments) between them, printing a parsed file gives back exactly the source it
+//! was parsed from:
+//! ```
+//! use oxylc::compile::{parser::parse, printer::print};
+//!
+//! let source = "let x = 1 + /* two */ 2 // three\n";
+//! assert_eq!(print(&parse(source).unwrap()), source);
+//! ```
+//! This is what refactoring tools build on: change the FST, print it, and everything that wasn't
+//! changed stays the same.
+
+pub mod tokens;
+
+use crate::repr::fst::{File, FstNode};
+
+/// Prints a file, with its trivia. Trivia goes wherever its location says it was in the source,
+/// so a file without any trivia prints every token right next to each other.
+pub fn print(file: &File) -> String {
+    let mut out = String::new();
+    let mut trivia = file.trivia.iter().peekable();
+
+    for token in tokens::tokens(file) {
+        while let Some(next) = trivia.next_if(|next| next.location().start() <= out.len()) {
+            out.push_str(&next.text);
+        }
+        out.push_str(&token);
+    }
+
+    for next in trivia {
+        out.push_str(&next.text);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::parser::parse;
+    use std::{fs, path::Path};
+
+    /// Checks that `source` prints back out the same, if it parses.
+    fn assert_round_trips(source: &str, from: &str) {
+        if let Ok(file) = parse(source) {
+            assert_eq!(print(&file), source, "in {from}");
+        }
+    }
+
+    #[test]
+    fn can_print_every_kind_of_node() {
+        let source = r#"/// A doc comment.
+#[attribute("string", 1.50,)]
+/** A block doc comment. */
+pub let f = |x, y,| { let z = x * (y |> g) /* a comment */
+    z.a::b / -0010 }
+// A comment.
+mod a
+use a::{b as c, d::{e},}
+use /* a */ f
+"#;
+        assert!(parse(source).is_ok());
+        assert_round_trips(source, "the example");
+    }
+
+    #[test]
+    fn can_print_without_trivia() {
+        let file = parse("let x = (1)\n").unwrap().with_trivia(vec![]);
+        assert_eq!(print(&file), "letx=(1)");
+    }
+
+    /// The property test that the FST is lossless: `print(parse(s)) == s` for every file in the
+    /// fuzzer's corpus, and for every prefix of them that still parses.
+    #[test]
+    fn can_round_trip_fuzzer_corpus() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../oxylc-fuzzer/in");
+        let mut count = 0;
+
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            let from = path.display().to_string();
+
+            parse(&source).unwrap_or_else(|err| panic!("couldn't parse {from}: {err:?}"));
+            for (end, _) in source.char_indices().chain([(source.len(), ' ')]) {
+                assert_round_trips(&source[..end], &from);
+            }
+            count += 1;
+        }
+
+        assert!(count > 0, "there are no files in the fuzzer's corpus");
+    }
+}
@@ -0,0 +1,401 @@
+//! Turns the FST back into the tokens it was parsed from.
+
+use crate::repr::fst::{
+    attribute::{Attribute, AttributeArgKind},
+    block::Block,
+    doc_comment::{DocComment, DocCommentKind},
+    expression::{Expression, ExpressionKind, Operator, OperatorKind},
+    function::Function,
+    identifier::Identifier,
+    pattern::{Match, Pattern, PatternKind},
+    statement::{Statement, StatementKind},
+    type_expr::{TypeExpr, TypeExprKind, TypeParams},
+    use_tree::{UseTree, UseTreeKind},
+    value::{Value, ValueKind},
+    visitor::FstVisitor,
+    File, FstNode,
+};
+
+/// Gets the text of every token in a file, in order. Doc comments count as tokens, but trivia
+/// doesn't.
+pub fn tokens(file: &File) -> Vec<String> {
+    let mut tokenizer = Tokenizer { tokens: vec![] };
+    tokenizer.visit_file(file);
+    tokenizer.tokens
+}
+
+/// Gets how an operator is written. Application has no text, as it's just two expressions next to
+/// each other.
+pub fn operator_text(operator: &Operator) -> &'static str {
+    match operator.kind() {
+        OperatorKind::Plus => "+",
+        OperatorKind::Dash => "-",
+        OperatorKind::Star => "*",
+        OperatorKind::FSlash => "/",
+        OperatorKind::Triangle => "|>",
+        OperatorKind::DoubleEquals => "==",
+        OperatorKind::BangEquals => "!=",
+        OperatorKind::LeftAngle => "<",
+        OperatorKind::LeftAngleEquals => "<=",
+        OperatorKind::RightAngle => ">",
+        OperatorKind::RightAngleEquals => ">=",
+        OperatorKind::Dot => ".",
+        OperatorKind::DoubleColon => "::",
+        OperatorKind::Application => "",
+    }
+}
+
+/// Collects the tokens of every node it visits.
+struct Tokenizer {
+    tokens: Vec<String>,
+}
+
+impl Tokenizer {
+    fn push(&mut self, token: impl Into<String>) {
+        let token = token.into();
+        if !token.is_empty() {
+            self.tokens.push(token);
+        }
+    }
+
+    /// Pushes the tokens of a tuple, with `item` pushing the tokens of each of its items.
+    fn tuple<T>(&mut self, items: &[T], trailing_comma: bool, mut item: impl FnMut(&mut Self, &T)) {
+        self.push("(");
+        for (i, it) in items.iter().enumerate() {
+            if i != 0 {
+                self.push(",");
+            }
+            item(self, it);
+        }
+        if trailing_comma {
+            self.push(",");
+        }
+        self.push(")");
+    }
+}
+
+impl FstVisitor<()> for Tokenizer {
+    fn visit_file(&mut self, file: &File) {
+        for statement in &file.statements {
+            self.visit_statement(statement);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        // NOTE: Doc comments and attributes can be mixed, so they have to be put back in order.
+        let mut decorations = statement
+            .docs
+            .iter()
+            .map(|doc| (doc.location().start(), Ok(doc)))
+            .chain(
+                statement
+                    .attributes
+                    .iter()
+                    .map(|attribute| (attribute.location().start(), Err(attribute))),
+            )
+            .collect::<Vec<_>>();
+        decorations.sort_by_key(|(start, _)| *start);
+
+        for (_, decoration) in decorations {
+            match decoration {
+                Ok(doc) => self.visit_doc_comment(doc),
+                Err(attribute) => self.visit_attribute(attribute),
+            }
+        }
+
+        if statement.visibility.is_public() {
+            self.push("pub");
+        }
+
+        match &statement.kind {
+            StatementKind::Let {
+                is_mutable,
+                ident,
+                signature,
+                expression,
+            } => {
+                self.push("let");
+                if *is_mutable {
+                    self.push("mut");
+                }
+                self.visit_ident(ident);
+                if let Some(signature) = signature {
+                    if let Some(params) = &signature.params {
+                        self.visit_type_params(params);
+                    }
+                    self.push(":");
+                    self.visit_type_expr(&signature.ty);
+                }
+                self.push("=");
+                self.visit_expression(expression);
+            }
+            StatementKind::Mod { ident } => {
+                self.push("mod");
+                self.visit_ident(ident);
+            }
+            StatementKind::Use { tree } => {
+                self.push("use");
+                self.visit_use_tree(tree);
+            }
+            StatementKind::Impl {
+                trait_ident,
+                type_ident,
+                expression,
+            } => {
+                self.push("impl");
+                self.visit_ident(trait_ident);
+                self.push("for");
+                self.visit_ident(type_ident);
+                self.push("=");
+                self.visit_expression(expression);
+            }
+            StatementKind::Type {
+                ident,
+                params,
+                constructors,
+            } => {
+                self.push("type");
+                self.visit_ident(ident);
+                if let Some(params) = params {
+                    self.visit_type_params(params);
+                }
+                self.push("=");
+                for (i, constructor) in constructors.iter().enumerate() {
+                    if i != 0 {
+                        self.push("|");
+                    }
+                    self.visit_ident(&constructor.ident);
+                    for field in &constructor.fields {
+                        self.visit_type_expr(field);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match &expression.kind {
+            ExpressionKind::Parenthesized { inner, .. } => {
+                self.push("(");
+                self.visit_expression(inner);
+                self.push(")");
+            }
+            ExpressionKind::Tuple {
+                items,
+                trailing_comma,
+                ..
+            } => self.tuple(items, trailing_comma.is_some(), Self::visit_expression),
+            ExpressionKind::Value(value) => self.visit_value(value),
+            ExpressionKind::Block(block) => self.visit_block(block),
+            ExpressionKind::Match(m) => self.visit_match(m),
+            ExpressionKind::Infix { operator, lhs, rhs } => {
+                self.visit_expression(lhs);
+                self.push(operator_text(operator));
+                self.visit_expression(rhs);
+            }
+            ExpressionKind::Prefix { operator, rhs } => {
+                self.push(operator_text(operator));
+                self.visit_expression(rhs);
+            }
+            ExpressionKind::Postfix { operator, lhs } => {
+                self.visit_expression(lhs);
+                self.push(operator_text(operator));
+            }
+            ExpressionKind::Application { function, arg } => {
+                self.visit_expression(function);
+                self.visit_expression(arg);
+            }
+        }
+    }
+
+    fn visit_type_expr(&mut self, ty: &TypeExpr) {
+        match &ty.kind {
+            TypeExprKind::Named { ident, args } => {
+                self.visit_ident(ident);
+                for arg in args {
+                    self.visit_type_expr(arg);
+                }
+            }
+            TypeExprKind::Unit => {
+                self.push("(");
+                self.push(")");
+            }
+            TypeExprKind::Parenthesized { inner, .. } => {
+                self.push("(");
+                self.visit_type_expr(inner);
+                self.push(")");
+            }
+            TypeExprKind::Tuple {
+                items,
+                trailing_comma,
+                ..
+            } => self.tuple(items, trailing_comma.is_some(), Self::visit_type_expr),
+            TypeExprKind::Function { param, result, .. } => {
+                self.visit_type_expr(param);
+                self.push("->");
+                self.visit_type_expr(result);
+            }
+        }
+    }
+
+    fn visit_match(&mut self, m: &Match) {
+        self.push("match");
+        self.visit_expression(&m.scrutinee);
+        self.push("{");
+        for arm in &m.arms {
+            self.visit_pattern(&arm.pattern);
+            self.push("=>");
+            self.visit_expression(&arm.expression);
+            if arm.comma.is_some() {
+                self.push(",");
+            }
+        }
+        self.push("}");
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Named { ident, args } => {
+                self.visit_ident(ident);
+                for arg in args {
+                    self.visit_pattern(arg);
+                }
+            }
+            PatternKind::Value(value) => self.visit_value(value),
+            PatternKind::Parenthesized { inner, .. } => {
+                self.push("(");
+                self.visit_pattern(inner);
+                self.push(")");
+            }
+            PatternKind::Tuple {
+                items,
+                trailing_comma,
+                ..
+            } => self.tuple(items, trailing_comma.is_some(), Self::visit_pattern),
+        }
+    }
+
+    fn visit_type_params(&mut self, params: &TypeParams) {
+        self.push("[");
+        for (i, param) in params.params.iter().enumerate() {
+            if i != 0 {
+                self.push(",");
+            }
+            self.visit_ident(&param.ident);
+            for (i, bound) in param.bounds.iter().enumerate() {
+                self.push(if i == 0 { ":" } else { "+" });
+                self.visit_ident(bound);
+            }
+        }
+        if params.trailing_comma.is_some() {
+            self.push(",");
+        }
+        self.push("]");
+    }
+
+    fn visit_value(&mut self, value: &Value) {
+        match &value.kind {
+            ValueKind::Identifier(ident) => self.visit_ident(ident),
+            ValueKind::Function(function) => self.visit_function(function),
+            kind => match &value.raw {
+                Some(raw) => self.push(raw.as_str()),
+                None => self.push(kind.literal().unwrap_or_default()),
+            },
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &Identifier) {
+        self.push(ident.name.as_str());
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        self.push("|");
+        for (i, arg) in function.args.iter().enumerate() {
+            if i != 0 {
+                self.push(",");
+            }
+            self.visit_ident(arg);
+        }
+        if function.trailing_comma.is_some() {
+            self.push(",");
+        }
+        self.push("|");
+        self.visit_expression(&function.expression);
+    }
+
+    fn visit_block(&mut self, block: &Block) {
+        self.push("{");
+        for statement in &block.statements {
+            self.visit_statement(statement);
+        }
+        self.visit_expression(&block.expression);
+        self.push("}");
+    }
+
+    fn visit_attribute(&mut self, attribute: &Attribute) {
+        self.push("#[");
+        self.visit_ident(&attribute.name);
+
+        if attribute.parens.is_some() {
+            self.push("(");
+            for (i, arg) in attribute.args.iter().enumerate() {
+                if i != 0 {
+                    self.push(",");
+                }
+                match &arg.kind {
+                    AttributeArgKind::String(string) => self.push(format!("\"{}\"", string)),
+                    AttributeArgKind::Value(value) => self.visit_value(value),
+                }
+            }
+            if attribute.trailing_comma.is_some() {
+                self.push(",");
+            }
+            self.push(")");
+        }
+
+        self.push("]");
+    }
+
+    fn visit_doc_comment(&mut self, doc_comment: &DocComment) {
+        match doc_comment.kind {
+            DocCommentKind::Line => self.push(format!("///{}", doc_comment.content)),
+            DocCommentKind::Block => self.push(format!("/**{}*/", doc_comment.content)),
+        }
+    }
+
+    fn visit_use_tree(&mut self, tree: &UseTree) {
+        match &tree.kind {
+            UseTreeKind::Simple { alias } => {
+                for (i, ident) in tree.path.iter().enumerate() {
+                    if i != 0 {
+                        self.push("::");
+                    }
+                    self.visit_ident(ident);
+                }
+
+                if let Some(alias) = alias {
+                    self.push("as");
+                    self.visit_ident(alias);
+                }
+            }
+            UseTreeKind::Group(trees) => {
+                for ident in &tree.path {
+                    self.visit_ident(ident);
+                    self.push("::");
+                }
+
+                self.push("{");
+                for (i, tree) in trees.iter().enumerate() {
+                    if i != 0 {
+                        self.push(",");
+                    }
+                    self.visit_use_tree(tree);
+                }
+                if tree.trailing_comma.is_some() {
+                    self.push(",");
+                }
+                self.push("}");
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProjectError {
+    #[error("Couldn't find an `Oxyl.toml` in `{}` or any of its parents", dir.display())]
+    NotFound { dir: PathBuf },
+
+    #[error("Couldn't read `{}`: {message}", path.display())]
+    Io { path: PathBuf, message: String },
+
+    #[error("Invalid manifest `{}`: {message}", path.display())]
+    Manifest { path: PathBuf, message: String },
+
+    #[error("Invalid manifest `{}`: `{name}` isn't a valid package name", path.display())]
+    InvalidName { path: PathBuf, name: String },
+
+    #[error("Invalid manifest `{}`: `{version}` isn't a valid version, expected something like `1.2.3`", path.display())]
+    InvalidVersion { path: PathBuf, version: String },
+
+    #[error("Couldn't find the entry point of the package in `{}`, expected one of {}", path.display(), candidates.iter().map(|c| format!("`{}`", c.display())).collect::<Vec<_>>().join(", "))]
+    MissingEntry {
+        path: PathBuf,
+        candidates: Vec<PathBuf>,
+    },
+
+    #[error("The package `{name}` in `{}` depends on itself", path.display())]
+    CyclicDependency { name: String, path: PathBuf },
+}
@@ -0,0 +1,307 @@
+//! The manifest of a package, `Oxyl.toml`, which looks like this:
+//! ```toml
+//! [package]
+//! name = "hello"
+//! version = "0.1.0"
+//! entry = "src/main.oxyl"     # Optional, see `Package::entry`.
+//! source-dirs = ["src"]       # Optional, defaults to `["src"]`.
+//!
+//! [dependencies]
+//! collections = { path = "../collections" }
+//! ```
+
+use super::errors::ProjectError;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use toml::{Table, Value};
+
+/// The name of the manifest file of every package.
+pub const MANIFEST_NAME: &str = "Oxyl.toml";
+
+/// A parsed `Oxyl.toml`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    /// Information about the package itself.
+    pub package: Package,
+
+    /// The packages this package depends on, by the name they're used with.
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+/// The `[package]` section of a manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Package {
+    /// The name of the package, which must be a valid identifier.
+    pub name: String,
+
+    /// The version of the package, like `1.2.3`.
+    pub version: String,
+
+    /// The root file of the package, relative to the manifest. If this isn't given, it's
+    /// `main.oxyl` or `lib.oxyl` in the first source directory, whichever exists.
+    pub entry: Option<PathBuf>,
+
+    /// The directories the source files of the package are in, relative to the manifest. This is
+    /// `source-dirs` in the manifest, and defaults to `["src"]`.
+    pub source_dirs: Vec<PathBuf>,
+}
+
+/// A package that another package depends on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dependency {
+    /// The directory of the package (which has its own `Oxyl.toml`), relative to the manifest.
+    pub path: PathBuf,
+}
+
+impl Manifest {
+    /// Parses and validates a manifest. `path` is only used for errors.
+    pub fn parse(path: &Path, source: &str) -> Result<Self, ProjectError> {
+        let manifest = source
+            .parse::<Table>()
+            .map_err(|err| err.message().to_string())
+            .and_then(Self::from_table)
+            .map_err(|message| ProjectError::Manifest {
+                path: path.to_path_buf(),
+                message,
+            })?;
+
+        let names = std::iter::once(&manifest.package.name).chain(manifest.dependencies.keys());
+        for name in names {
+            if !is_identifier(name) {
+                return Err(ProjectError::InvalidName {
+                    path: path.to_path_buf(),
+                    name: name.clone(),
+                });
+            }
+        }
+
+        if !is_version(&manifest.package.version) {
+            return Err(ProjectError::InvalidVersion {
+                path: path.to_path_buf(),
+                version: manifest.package.version.clone(),
+            });
+        }
+
+        if manifest.package.source_dirs.is_empty() && manifest.package.entry.is_none() {
+            return Err(ProjectError::Manifest {
+                path: path.to_path_buf(),
+                message: "`source-dirs` can't be empty without an `entry`".into(),
+            });
+        }
+
+        Ok(manifest)
+    }
+
+    /// Gets a manifest out of its TOML, without validating it.
+    fn from_table(table: Table) -> Result<Self, String> {
+        let mut fields = Fields::new("", table);
+        let package = match fields.take("package") {
+            Some(value) => Package::from_value(value)?,
+            None => return Err("missing field `package`".into()),
+        };
+
+        let mut dependencies = BTreeMap::new();
+        if let Some(value) = fields.take("dependencies") {
+            for (name, value) in Fields::table("dependencies", value)? {
+                let dependency = Dependency::from_value(&name, value)?;
+                dependencies.insert(name, dependency);
+            }
+        }
+
+        fields.finish()?;
+        Ok(Self {
+            package,
+            dependencies,
+        })
+    }
+}
+
+impl Package {
+    fn from_value(value: Value) -> Result<Self, String> {
+        let mut fields = Fields::new("package", Fields::table("package", value)?);
+        let name = fields.required_string("name")?;
+        let version = fields.required_string("version")?;
+        let entry = fields.string("entry")?.map(PathBuf::from);
+        let source_dirs = match fields.take("source-dirs") {
+            Some(Value::Array(dirs)) => dirs
+                .into_iter()
+                .map(|dir| match dir {
+                    Value::String(dir) => Ok(PathBuf::from(dir)),
+                    _ => Err("`package.source-dirs` must be an array of strings".to_string()),
+                })
+                .collect::<Result<_, _>>()?,
+            Some(_) => return Err("`package.source-dirs` must be an array of strings".into()),
+            None => vec!["src".into()],
+        };
+
+        fields.finish()?;
+        Ok(Self {
+            name,
+            version,
+            entry,
+            source_dirs,
+        })
+    }
+}
+
+impl Dependency {
+    fn from_value(name: &str, value: Value) -> Result<Self, String> {
+        let section = format!("dependencies.{}", name);
+        let mut fields = Fields::new(&section, Fields::table(&section, value)?);
+        let path = fields.required_string("path")?.into();
+
+        fields.finish()?;
+        Ok(Self { path })
+    }
+}
+
+/// The fields of a table in the manifest, which are taken out one at a time. Any fields that are
+/// left once they've all been taken aren't known, which is an error.
+struct Fields {
+    /// Where the table is, like `package`, or nothing for the whole manifest.
+    section: String,
+    table: Table,
+}
+
+impl Fields {
+    fn new(section: &str, table: Table) -> Self {
+        Self {
+            section: section.to_string(),
+            table,
+        }
+    }
+
+    /// Gets the table out of a value, which is at `section`.
+    fn table(section: &str, value: Value) -> Result<Table, String> {
+        match value {
+            Value::Table(table) => Ok(table),
+            _ => Err(format!("`{}` must be a table", section)),
+        }
+    }
+
+    /// The full name of a field, like `package.name`.
+    fn name(&self, key: &str) -> String {
+        match self.section.as_str() {
+            "" => key.to_string(),
+            section => format!("{}.{}", section, key),
+        }
+    }
+
+    fn take(&mut self, key: &str) -> Option<Value> {
+        self.table.remove(key)
+    }
+
+    fn string(&mut self, key: &str) -> Result<Option<String>, String> {
+        match self.take(key) {
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(_) => Err(format!("`{}` must be a string", self.name(key))),
+            None => Ok(None),
+        }
+    }
+
+    fn required_string(&mut self, key: &str) -> Result<String, String> {
+        self.string(key)?
+            .ok_or_else(|| format!("missing field `{}`", self.name(key)))
+    }
+
+    /// Checks that every field has been taken.
+    fn finish(self) -> Result<(), String> {
+        match self.table.keys().next() {
+            Some(key) => Err(format!("unknown field `{}`", self.name(key))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks if a name can be used as an identifier, and so as the name of a module.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !matches!(name, "crate" | "self" | "super" | "_")
+}
+
+/// Checks if a version looks like `MAJOR.MINOR.PATCH`.
+fn is_version(version: &str) -> bool {
+    let parts = version.split('.').collect::<Vec<_>>();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_manifest() {
+        let manifest = Manifest::parse(
+            Path::new("Oxyl.toml"),
+            "[package]\nname = \"hello\"\nversion = \"0.1.0\"\n\n[dependencies]\nlib = { path = \"../lib\" }\n",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest,
+            Manifest {
+                package: Package {
+                    name: "hello".into(),
+                    version: "0.1.0".into(),
+                    entry: None,
+                    source_dirs: vec!["src".into()],
+                },
+                dependencies: BTreeMap::from([(
+                    "lib".into(),
+                    Dependency {
+                        path: "../lib".into()
+                    }
+                )]),
+            }
+        );
+    }
+
+    #[test]
+    fn cant_parse_invalid_manifest() {
+        let parse = |source: &str| Manifest::parse(Path::new("Oxyl.toml"), source);
+
+        assert!(matches!(
+            parse("[package]\nname = \"hello\"\n"),
+            Err(ProjectError::Manifest { .. })
+        ));
+        assert!(matches!(
+            parse("[package]\nname = \"hello-world\"\nversion = \"0.1.0\"\n"),
+            Err(ProjectError::InvalidName { .. })
+        ));
+        assert!(matches!(
+            parse("[package]\nname = \"hello\"\nversion = \"1.0\"\n"),
+            Err(ProjectError::InvalidVersion { .. })
+        ));
+        assert!(matches!(
+            parse("[package]\nname = \"hello\"\nversion = \"0.1.0\"\nsource-dirs = []\n"),
+            Err(ProjectError::Manifest { .. })
+        ));
+
+        let message = |source: &str| match parse(source) {
+            Err(ProjectError::Manifest { message, .. }) => message,
+            result => panic!("expected an invalid manifest, got {:?}", result),
+        };
+        assert_eq!(
+            message("[package]\nname = \"hello\"\n"),
+            "missing field `package.version`"
+        );
+        assert_eq!(
+            message("[package]\nname = \"a\"\nversion = \"0.1.0\"\nauthor = \"b\"\n"),
+            "unknown field `package.author`"
+        );
+        assert_eq!(
+            message("[package]\nname = 1\nversion = \"0.1.0\"\n"),
+            "`package.name` must be a string"
+        );
+        assert_eq!(
+            message("[package]\nname = \"a\"\nversion = \"0.1.0\"\n[dependencies]\nb = \"1\"\n"),
+            "`dependencies.b` must be a table"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! Packages, which are described by an `Oxyl.toml` manifest. A package has a root file (its entry
+//! point) that the module tree is loaded from, and can depend on other packages by their path.
+//! The root module of every dependency can be used from the root of the package by its name, so
+//! with `collections = { path = "../collections" }`, you can write `use collections::List`.
+
+pub mod errors;
+pub mod manifest;
+
+use super::module::{
+    errors::ModuleError,
+    loader::{FsLoader, SourceLoader},
+    Extern, ModuleTree,
+};
+use errors::ProjectError;
+use manifest::{Manifest, MANIFEST_NAME};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+/// A package, along with all the packages it depends on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Project {
+    /// The directory the manifest is in.
+    pub dir: PathBuf,
+
+    /// The manifest of the package.
+    pub manifest: Manifest,
+
+    /// The path to the root file of the package.
+    pub entry: PathBuf,
+
+    /// The packages this package depends on, by the name they're used with.
+    pub dependencies: Vec<(String, Project)>,
+}
+
+impl Project {
+    /// Finds the directory of the package that `start` is in, by looking for an `Oxyl.toml` in
+    /// `start` and then in each of its parents.
+    pub fn find(start: &Path) -> Result<PathBuf, ProjectError> {
+        start
+            .ancestors()
+            .find(|dir| dir.join(MANIFEST_NAME).is_file())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| ProjectError::NotFound {
+                dir: start.to_path_buf(),
+            })
+    }
+
+    /// Loads the package in `dir` from the disk.
+    pub fn open(dir: &Path) -> Result<Self, ProjectError> {
+        Self::load(dir, &mut FsLoader)
+    }
+
+    /// Loads the package in `dir`, and all of the packages it depends on. A package that several
+    /// packages depend on is only loaded once.
+    pub fn load(dir: &Path, loader: &mut impl SourceLoader) -> Result<Self, ProjectError> {
+        Self::load_package(&normalize(dir), loader, &mut vec![], &mut HashMap::new())
+    }
+
+    /// Loads the whole module tree of the package, including all of its dependencies.
+    pub fn modules(&self, loader: &mut impl SourceLoader) -> (ModuleTree, Vec<ModuleError>) {
+        ModuleTree::load_with_externs(&self.entry, &self.externs(), loader)
+    }
+
+    /// Finds the source files in the source directories of the package which aren't part of its
+    /// module tree, as they're probably missing a `mod`. This always reads from the disk.
+    pub fn unused_files(&self, modules: &ModuleTree) -> Vec<PathBuf> {
+        let used = modules
+            .modules()
+            .filter_map(|(_, module)| fs::canonicalize(&module.path).ok())
+            .collect::<HashSet<_>>();
+        let mut files = vec![];
+
+        for dir in &self.manifest.package.source_dirs {
+            // NOTE: Source directories that don't exist have nothing in them to be unused.
+            let _ = source_files(&self.dir.join(dir), &mut files);
+        }
+
+        files.retain(|file| {
+            fs::canonicalize(file)
+                .map(|file| !used.contains(&file))
+                .unwrap_or(false)
+        });
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Gets the dependencies of the package as `Extern`s for the module tree.
+    pub fn externs(&self) -> Vec<Extern> {
+        self.dependencies
+            .iter()
+            .map(|(name, project)| Extern {
+                name: name.clone(),
+                root: project.entry.clone(),
+                externs: project.externs(),
+            })
+            .collect()
+    }
+
+    /// Loads a package, where `stack` is the packages that (transitively) depend on it, and
+    /// `loaded` is the packages that have already been loaded, by the canonical paths of their
+    /// manifests.
+    fn load_package(
+        dir: &Path,
+        loader: &mut impl SourceLoader,
+        stack: &mut Vec<PathBuf>,
+        loaded: &mut HashMap<PathBuf, Project>,
+    ) -> Result<Self, ProjectError> {
+        let path = dir.join(MANIFEST_NAME);
+        let (canonical, source) = loader.load(&path).map_err(|err| ProjectError::Io {
+            path: path.clone(),
+            message: err.to_string(),
+        })?;
+        if let Some(project) = loaded.get(&canonical) {
+            return Ok(project.clone());
+        }
+        let manifest = Manifest::parse(&path, &source)?;
+
+        if stack.contains(&canonical) {
+            return Err(ProjectError::CyclicDependency {
+                name: manifest.package.name,
+                path,
+            });
+        }
+
+        let entry = find_entry(dir, &manifest, loader)?;
+
+        stack.push(canonical.clone());
+        let dependencies = manifest
+            .dependencies
+            .iter()
+            .map(|(name, dependency)| {
+                let dir = normalize(&dir.join(&dependency.path));
+                Ok((
+                    name.clone(),
+                    Self::load_package(&dir, loader, stack, loaded)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>();
+        stack.pop();
+
+        let project = Self {
+            dir: dir.to_path_buf(),
+            manifest,
+            entry,
+            dependencies: dependencies?,
+        };
+        loaded.insert(canonical, project.clone());
+        Ok(project)
+    }
+}
+
+/// Finds the root file of a package.
+fn find_entry(
+    dir: &Path,
+    manifest: &Manifest,
+    loader: &mut impl SourceLoader,
+) -> Result<PathBuf, ProjectError> {
+    let candidates = match &manifest.package.entry {
+        Some(entry) => vec![normalize(&dir.join(entry))],
+        None => {
+            // NOTE: The manifest makes sure there's a source directory if there's no entry.
+            let source_dir = dir.join(&manifest.package.source_dirs[0]);
+            vec![
+                normalize(&source_dir.join("main.oxyl")),
+                normalize(&source_dir.join("lib.oxyl")),
+            ]
+        }
+    };
+
+    for candidate in &candidates {
+        match loader.load(candidate) {
+            Ok(_) => return Ok(candidate.clone()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(ProjectError::Io {
+                    path: candidate.clone(),
+                    message: err.to_string(),
+                });
+            }
+        }
+    }
+
+    Err(ProjectError::MissingEntry {
+        path: dir.join(MANIFEST_NAME),
+        candidates,
+    })
+}
+
+/// Finds all the source files in a directory, recursively.
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            source_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "oxyl") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the `.` and `..` in a path without touching the disk, so that the paths of packages
+/// are the same no matter which package they're reached from.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::module::{loader::files, Binding};
+
+    #[test]
+    fn can_load_project_with_dependencies() {
+        let mut loader = files(&[
+            (
+                "app/Oxyl.toml",
+                "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nlib = { path = \"../lib\" }\n",
+            ),
+            ("app/src/main.oxyl", "mod a\nuse lib::x\n"),
+            ("app/src/a.oxyl", "let y = 1\n"),
+            (
+                "lib/Oxyl.toml",
+                "[package]\nname = \"lib\"\nversion = \"1.0.0\"\nentry = \"lib.oxyl\"\n",
+            ),
+            ("lib/lib.oxyl", "pub let x = 1\n"),
+        ]);
+        let project = Project::load(Path::new("app"), &mut loader).unwrap();
+        assert_eq!(project.entry, PathBuf::from("app/src/main.oxyl"));
+        assert_eq!(
+            project.externs(),
+            [Extern {
+                name: "lib".into(),
+                root: "lib/lib.oxyl".into(),
+                externs: vec![],
+            }]
+        );
+
+        let (tree, errors) = project.modules(&mut loader);
+        assert_eq!(errors, []);
+
+        let paths = tree
+            .modules()
+            .map(|(id, _)| tree.path_of(id))
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["crate", "crate::a", "lib"]);
+        assert_eq!(
+            tree.lookup(tree.root(), "x").map(|e| e.binding.clone()),
+            Some(Binding::Item {
+                module: tree.get(tree.root()).externs["lib"],
+                name: "x".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn can_share_dependencies() {
+        let mut loader = files(&[
+            (
+                "app/Oxyl.toml",
+                "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\na = { path = \"../a\" }\nb = { path = \"../b\" }\n",
+            ),
+            ("app/src/main.oxyl", "use a::x\nuse b::y\n"),
+            (
+                "a/Oxyl.toml",
+                "[package]\nname = \"a\"\nversion = \"0.1.0\"\n\n[dependencies]\nc = { path = \"../c\" }\n",
+            ),
+            ("a/src/lib.oxyl", "pub use c::z as x\n"),
+            (
+                "b/Oxyl.toml",
+                "[package]\nname = \"b\"\nversion = \"0.1.0\"\n\n[dependencies]\nshared = { path = \"../a/../c\" }\n",
+            ),
+            ("b/src/lib.oxyl", "pub use shared::z as y\n"),
+            (
+                "c/Oxyl.toml",
+                "[package]\nname = \"c\"\nversion = \"0.1.0\"\n",
+            ),
+            ("c/src/lib.oxyl", "pub let z = 1\n"),
+        ]);
+        let project = Project::load(Path::new("app"), &mut loader).unwrap();
+        assert_eq!(
+            project.dependencies[0].1.dependencies[0].1,
+            project.dependencies[1].1.dependencies[0].1
+        );
+
+        let (tree, errors) = project.modules(&mut loader);
+        assert_eq!(errors, []);
+
+        let paths = tree
+            .modules()
+            .map(|(id, _)| tree.path_of(id))
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["crate", "a", "c", "b"]);
+        assert_eq!(
+            tree.lookup(tree.root(), "x").map(|e| e.binding.clone()),
+            tree.lookup(tree.root(), "y").map(|e| e.binding.clone()),
+        );
+    }
+
+    #[test]
+    fn can_report_missing_entry() {
+        let mut loader = files(&[(
+            "Oxyl.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        )]);
+        assert_eq!(
+            Project::load(Path::new(""), &mut loader),
+            Err(ProjectError::MissingEntry {
+                path: "Oxyl.toml".into(),
+                candidates: vec!["src/main.oxyl".into(), "src/lib.oxyl".into()],
+            })
+        );
+    }
+
+    #[test]
+    fn can_report_cyclic_dependency() {
+        let mut loader = files(&[
+            (
+                "a/Oxyl.toml",
+                "[package]\nname = \"a\"\nversion = \"0.1.0\"\n\n[dependencies]\nb = { path = \"../b\" }\n",
+            ),
+            ("a/src/lib.oxyl", ""),
+            (
+                "b/Oxyl.toml",
+                "[package]\nname = \"b\"\nversion = \"0.1.0\"\n\n[dependencies]\na = { path = \"../a\" }\n",
+            ),
+            ("b/src/lib.oxyl", ""),
+        ]);
+        assert_eq!(
+            Project::load(Path::new("a"), &mut loader),
+            Err(ProjectError::CyclicDependency {
+                name: "a".into(),
+                path: "a/Oxyl.toml".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn can_find_unused_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, source: &str| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        };
+        write(
+            "Oxyl.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        );
+        write("src/main.oxyl", "mod a\n");
+        write("src/a.oxyl", "");
+        write("src/b.oxyl", "");
+        write("src/c/d.oxyl", "");
+        write("src/notes.txt", "");
+
+        let err = Project::open(&dir.path().join("src").join("c")).unwrap_err();
+        assert!(matches!(err, ProjectError::Io { .. }));
+
+        let project = Project::open(&Project::find(&dir.path().join("src")).unwrap()).unwrap();
+        let (tree, errors) = project.modules(&mut FsLoader);
+        assert_eq!(errors, []);
+        assert_eq!(
+            project.unused_files(&tree),
+            [
+                dir.path().join("src").join("b.oxyl"),
+                dir.path().join("src").join("c").join("d.oxyl"),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_normalize_paths() {
+        assert_eq!(normalize(Path::new("a/./b/../../c")), PathBuf::from("c"));
+        assert_eq!(normalize(Path::new("a/../../b")), PathBuf::from("../b"));
+        assert_eq!(normalize(Path::new("/../a")), PathBuf::from("/a"));
+    }
+}
@@ -0,0 +1,26 @@
+use errgonomic::parser::input::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ResolveError {
+    #[error("Cannot find `{name}` in this scope")]
+    Unresolved {
+        name: String,
+        location: Span,
+
+        /// A name in scope that's spelled like `name`, if there is one.
+        suggestion: Option<String>,
+    },
+
+    #[error("`{name}` is bound more than once in the same pattern")]
+    DuplicateBinding { name: String, location: Span },
+}
+
+impl ResolveError {
+    /// Gets the location in the file where the error happened.
+    pub fn location(&self) -> Span {
+        match self {
+            ResolveError::Unresolved { location, .. }
+            | ResolveError::DuplicateBinding { location, .. } => *location,
+        }
+    }
+}
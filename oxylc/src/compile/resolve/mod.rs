@@ -0,0 +1,533 @@
+//! Name resolution, which finds the definition of every name used in the AST of a file.
+//!
+//! Names are looked up from the innermost scope outwards:
+//! - The prelude's scope has the names every file can use (`compile::prelude`). It's outside of
+//!   the file's scope, so the file can shadow them.
+//! - The file's scope has everything defined by its top-level statements, which can be used
+//!   anywhere in the file, even before they're defined (so top-level functions can be recursive).
+//! - A function's scope has its parameter.
+//! - The scope of an arm of a `match` has the names its pattern binds, which can each only be bound
+//!   once. The constructors in the pattern are looked up like any other name.
+//! - A block's scope is empty, but every `let` in it starts a new scope inside of the last one, so
+//!   a `let` can only be used after it, and can shadow an earlier one without losing it.
+//!
+//! Only the first segment of a path like `a::b` is resolved here, as the rest are looked up
+//! through the module tree. Paths starting with `crate`, `self` or `super` are left alone.
+
+pub mod errors;
+
+use super::prelude;
+use crate::repr::ast::{
+    arena::Arena,
+    expr::{ExprId, ExprKind},
+    ident::Ident,
+    pattern::{Pattern, PatternKind},
+    scope::{Def, DefId, DefKind, Scope, ScopeId},
+    stmt::{StmtId, StmtKind},
+    File, Name,
+};
+use errgonomic::parser::input::Span;
+use errors::ResolveError;
+use std::collections::BTreeMap;
+
+/// The names that start a path through the module tree instead of being looked up in scope.
+const PATH_ROOTS: [&str; 3] = ["crate", "self", "super"];
+
+/// What every name in a file refers to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+    /// Every scope in the file.
+    pub scopes: Arena<Scope>,
+
+    /// Every definition in the file, in the order they were found.
+    pub defs: Arena<Def>,
+
+    /// The scope of the file itself.
+    pub root: ScopeId,
+
+    /// The scope of the prelude, which the file's scope is in.
+    pub prelude: ScopeId,
+
+    /// The definition every `ExprKind::Var` refers to, and the definition the first segment of
+    /// every `ExprKind::Path` refers to. Names that couldn't be resolved aren't in here.
+    uses: BTreeMap<ExprId, DefId>,
+}
+
+impl Resolution {
+    fn new() -> Self {
+        let mut scopes = Arena::new();
+        let mut defs = Arena::new();
+
+        let prelude = scopes.alloc(Scope::new(None));
+        for name in prelude::names() {
+            let ident = Ident::new(name);
+            let def = defs.alloc(Def {
+                name: Name::new((0..0).into(), ident),
+                kind: DefKind::Builtin,
+                scope: prelude,
+            });
+            scopes[prelude].define(ident, def);
+        }
+        let root = scopes.alloc(Scope::new(Some(prelude)));
+
+        Self {
+            scopes,
+            defs,
+            root,
+            prelude,
+            uses: BTreeMap::new(),
+        }
+    }
+
+    /// Gets the definition that the name used by `expr` refers to.
+    pub fn def_of(&self, expr: ExprId) -> Option<DefId> {
+        self.uses.get(&expr).copied()
+    }
+
+    /// Iterates over every resolved use of a name, along with what it refers to.
+    pub fn uses(&self) -> impl Iterator<Item = (ExprId, DefId)> + '_ {
+        self.uses.iter().map(|(&expr, &def)| (expr, def))
+    }
+
+    /// Iterates over the expressions that use `def`.
+    pub fn uses_of(&self, def: DefId) -> impl Iterator<Item = ExprId> + '_ {
+        self.uses()
+            .filter(move |(_, to)| *to == def)
+            .map(|(expr, _)| expr)
+    }
+
+    /// Looks up `ident` from inside `scope`.
+    pub fn lookup(&self, scope: ScopeId, ident: Ident) -> Option<DefId> {
+        let mut scope = Some(scope);
+        while let Some(id) = scope {
+            if let Some(def) = self.scopes[id].get(ident) {
+                return Some(def);
+            }
+            scope = self.scopes[id].parent();
+        }
+        None
+    }
+
+    /// Gets the definition of the name at `offset`, whether it's where the name is defined or
+    /// where it's used. This is what go to definition needs. A name ending right at `offset`
+    /// counts, so that a cursor right after a name finds it.
+    pub fn definition_at(&self, file: &File, offset: usize) -> Option<DefId> {
+        let contains = |location: Span| location.start() <= offset && offset <= location.end();
+
+        let defined = self
+            .defs
+            .iter()
+            .filter(|(_, def)| !matches!(def.kind, DefKind::Extern | DefKind::Builtin))
+            .find(|(_, def)| contains(def.name.location))
+            .map(|(id, _)| id);
+
+        defined.or_else(|| {
+            self.uses()
+                .find(|(expr, _)| used_name(file, *expr).is_some_and(|n| contains(n.location)))
+                .map(|(_, def)| def)
+        })
+    }
+}
+
+/// Gets the name an expression uses, if it uses one: the name of a `Var`, or the first segment of
+/// a `Path`.
+pub fn used_name(file: &File, expr: ExprId) -> Option<Name> {
+    match &file[expr].kind {
+        ExprKind::Var(name) => Some(*name),
+        ExprKind::Path(segments) => segments.first().copied(),
+        _ => None,
+    }
+}
+
+/// Resolves every name in a file. Names that can't be resolved are left out of the `Resolution`,
+/// and the errors for them are returned along with it.
+pub fn resolve(file: &File) -> (Resolution, Vec<ResolveError>) {
+    resolve_with_externs(file, &[])
+}
+
+/// Like `resolve`, but the file can also use the packages in `externs` by their names, like a
+/// root module can.
+pub fn resolve_with_externs(file: &File, externs: &[Ident]) -> (Resolution, Vec<ResolveError>) {
+    let resolution = Resolution::new();
+    let mut resolver = Resolver {
+        file,
+        scope: resolution.root,
+        resolution,
+        errors: vec![],
+    };
+
+    for &ident in externs {
+        resolver.define(Name::new((0..0).into(), ident), DefKind::Extern);
+    }
+
+    // NOTE: Top-level statements can be used anywhere in the file, even before they're defined, so
+    // they're all defined first.
+    for &stmt in &file.statements {
+        resolver.define_statement(stmt);
+    }
+    for &stmt in &file.statements {
+        resolver.statement(stmt);
+    }
+
+    (resolver.resolution, resolver.errors)
+}
+
+/// Walks the AST, keeping track of the scope it's in.
+struct Resolver<'a> {
+    file: &'a File,
+    resolution: Resolution,
+
+    /// The innermost scope.
+    scope: ScopeId,
+
+    errors: Vec<ResolveError>,
+}
+
+impl Resolver<'_> {
+    /// Starts a new scope inside of the current one.
+    fn enter(&mut self) {
+        let scope = Scope::new(Some(self.scope));
+        self.scope = self.resolution.scopes.alloc(scope);
+    }
+
+    fn define(&mut self, name: Name, kind: DefKind) {
+        let def = self.resolution.defs.alloc(Def {
+            name,
+            kind,
+            scope: self.scope,
+        });
+        // NOTE: Names defined twice at the top level are reported by the module system, so the
+        // first one is kept without another error.
+        self.resolution.scopes[self.scope].define(name.ident, def);
+    }
+
+    /// Defines the names a statement introduces.
+    fn define_statement(&mut self, stmt: StmtId) {
+        match &self.file[stmt].kind {
+            StmtKind::Let { name, .. } => self.define(*name, DefKind::Let(stmt)),
+            StmtKind::Mod { name } => self.define(*name, DefKind::Mod(stmt)),
+            StmtKind::Use { imports } => {
+                for import in imports {
+                    self.define(import.name, DefKind::Import(stmt));
+                }
+            }
+            StmtKind::Type { constructors, .. } => {
+                for (i, constructor) in constructors.iter().enumerate() {
+                    self.define(constructor.name, DefKind::Constructor(stmt, i));
+                }
+            }
+            // NOTE: The trait and the type of an implementation are looked up by type inference.
+            StmtKind::Impl { .. } => {}
+        }
+    }
+
+    fn statement(&mut self, stmt: StmtId) {
+        if let StmtKind::Let { value, .. } | StmtKind::Impl { value, .. } = &self.file[stmt].kind {
+            self.expression(*value);
+        }
+    }
+
+    fn expression(&mut self, expr: ExprId) {
+        match &self.file[expr].kind {
+            ExprKind::Literal(_) | ExprKind::Error => {}
+            ExprKind::Var(name) => self.use_name(expr, *name),
+            ExprKind::Path(segments) => {
+                let head = segments[0];
+                if !PATH_ROOTS.contains(&head.ident.as_str()) {
+                    self.use_name(expr, head);
+                }
+            }
+            ExprKind::Lambda { param, body } => {
+                let outer = self.scope;
+                self.enter();
+                if let Some(param) = param {
+                    self.define(*param, DefKind::Param(expr));
+                }
+                self.expression(*body);
+                self.scope = outer;
+            }
+            ExprKind::Apply { function, arg } => {
+                self.expression(*function);
+                self.expression(*arg);
+            }
+            ExprKind::Binary { lhs, rhs, .. } => {
+                self.expression(*lhs);
+                self.expression(*rhs);
+            }
+            ExprKind::Unary { operand, .. } => self.expression(*operand),
+            // NOTE: The field is looked up on the value, not in scope.
+            ExprKind::Field { expr, .. } => self.expression(*expr),
+            ExprKind::Block { statements, result } => {
+                let outer = self.scope;
+                self.enter();
+                for &stmt in statements {
+                    // NOTE: The value of a `let` can't use the name it defines.
+                    self.statement(stmt);
+                    self.enter();
+                    self.define_statement(stmt);
+                }
+                self.expression(*result);
+                self.scope = outer;
+            }
+            ExprKind::Tuple(items) => {
+                for &item in items {
+                    self.expression(item);
+                }
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expression(*scrutinee);
+                for (i, arm) in arms.iter().enumerate() {
+                    let outer = self.scope;
+                    self.enter();
+                    self.pattern(&arm.pattern, expr, i);
+                    self.expression(arm.body);
+                    self.scope = outer;
+                }
+            }
+        }
+    }
+
+    /// Resolves the constructors in the pattern of the `i`th arm of `m`, and defines the names it
+    /// binds in the current scope.
+    fn pattern(&mut self, pattern: &Pattern, m: ExprId, i: usize) {
+        match &pattern.kind {
+            PatternKind::Wildcard | PatternKind::Literal(_) => {}
+            PatternKind::Binding(name) => {
+                match self.resolution.scopes[self.scope].get(name.ident) {
+                    Some(_) => self.errors.push(ResolveError::DuplicateBinding {
+                        name: name.ident.to_string(),
+                        location: name.location,
+                    }),
+                    None => self.define(*name, DefKind::Binding(m, i)),
+                }
+            }
+            PatternKind::Tuple(items) => {
+                for item in items {
+                    self.pattern(item, m, i);
+                }
+            }
+            PatternKind::Constructor { constructor, args } => {
+                self.expression(*constructor);
+                for arg in args {
+                    self.pattern(arg, m, i);
+                }
+            }
+        }
+    }
+
+    /// Resolves a use of `name` by `expr`.
+    fn use_name(&mut self, expr: ExprId, name: Name) {
+        match self.resolution.lookup(self.scope, name.ident) {
+            Some(def) => {
+                self.resolution.uses.insert(expr, def);
+            }
+            None => self.errors.push(ResolveError::Unresolved {
+                name: name.ident.to_string(),
+                location: name.location,
+                suggestion: self.suggest(name.ident),
+            }),
+        }
+    }
+
+    /// Finds the name in scope that's spelled the most like `ident`, if any are close enough to
+    /// probably be a typo of it. Inner scopes win ties.
+    fn suggest(&self, ident: Ident) -> Option<String> {
+        let name = ident.as_str();
+        // NOTE: Like `rustc`, allow about one typo for every 3 characters.
+        let max_distance = (name.chars().count() / 3).max(1);
+
+        let mut best: Option<(usize, &str)> = None;
+        let mut scope = Some(self.scope);
+        while let Some(id) = scope {
+            let scope_ = &self.resolution.scopes[id];
+            let mut candidates = scope_.names().map(Ident::as_str).collect::<Vec<_>>();
+            // NOTE: Scopes are hash maps, so sort to always suggest the same name.
+            candidates.sort_unstable();
+
+            for candidate in candidates {
+                let distance = edit_distance(name, candidate);
+                if distance <= max_distance && best.is_none_or(|(best, _)| distance < best) {
+                    best = Some((distance, candidate));
+                }
+            }
+            scope = scope_.parent();
+        }
+
+        best.map(|(_, candidate)| candidate.to_string())
+    }
+}
+
+/// The edit distance between 2 strings, which is the number of characters that need to be
+/// inserted, removed, changed or swapped with their neighbour to turn one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+
+    // NOTE: `distances[i][j]` is the distance between the first `i` characters of `a` and the
+    // first `j` characters of `b`.
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let changed = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = changed
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{lower::lower, parser::parse};
+
+    /// Parses, lowers and resolves `source`.
+    fn resolved(source: &str) -> (File, Resolution, Vec<ResolveError>) {
+        let (file, errors) = lower(&parse(source).unwrap());
+        assert_eq!(errors, []);
+        let (resolution, errors) = resolve(&file);
+        (file, resolution, errors)
+    }
+
+    #[test]
+    fn can_resolve_names() {
+        let source = "let f = |x| { let y = x\ny + g }\nlet g = f\n";
+        let (file, resolution, errors) = resolved(source);
+        assert_eq!(errors, []);
+        let name = |def: Option<DefId>| def.map(|def| resolution.defs[def].name.ident.as_str());
+
+        // NOTE: `x` in the block is the argument, `g` is defined after `f` but still in scope.
+        assert_eq!(name(resolution.definition_at(&file, 22)), Some("x"));
+        assert!(matches!(
+            resolution.defs[resolution.definition_at(&file, 22).unwrap()].kind,
+            DefKind::Param(_)
+        ));
+        assert_eq!(name(resolution.definition_at(&file, 28)), Some("g"));
+        assert_eq!(name(resolution.definition_at(&file, 24)), Some("y"));
+
+        let f = resolution.definition_at(&file, 4).unwrap();
+        let uses = resolution
+            .uses_of(f)
+            .map(|expr| file[expr].location)
+            .collect::<Vec<_>>();
+        assert_eq!(uses, [(40..41).into()]);
+    }
+
+    #[test]
+    fn can_shadow_names() {
+        let (file, resolution, errors) =
+            resolved("let x = 1\nlet y = |x| {\n    let x = x + 1\n    x\n}\n");
+        assert_eq!(errors, []);
+        let kind = |offset| resolution.defs[resolution.definition_at(&file, offset).unwrap()].kind;
+
+        // NOTE: The value of the inner `let x` uses the parameter, and the block's result uses the
+        // inner `let x`.
+        assert!(matches!(kind(36), DefKind::Param(_)));
+        assert!(matches!(kind(46), DefKind::Let(_)));
+        assert_ne!(
+            resolution.definition_at(&file, 46),
+            resolution.definition_at(&file, 4)
+        );
+    }
+
+    #[test]
+    fn can_resolve_paths_and_imports() {
+        let (file, resolution, errors) =
+            resolved("mod a\nuse a::b as c\nlet x = a::d + c + crate::e + super::f\n");
+        assert_eq!(errors, []);
+        assert!(matches!(
+            resolution.defs[resolution.definition_at(&file, 29).unwrap()].kind,
+            DefKind::Mod(_)
+        ));
+        assert!(matches!(
+            resolution.defs[resolution.definition_at(&file, 36).unwrap()].kind,
+            DefKind::Import(_)
+        ));
+        assert_eq!(resolution.definition_at(&file, 42), None);
+    }
+
+    #[test]
+    fn can_resolve_externs() {
+        let file = lower(&parse("let x = std::y\n").unwrap()).0;
+        let (_, errors) = resolve(&file);
+        assert_eq!(errors.len(), 1);
+        let (resolution, errors) = resolve_with_externs(&file, &[Ident::new("std")]);
+        assert_eq!(errors, []);
+        assert_eq!(resolution.uses().count(), 1);
+    }
+
+    #[test]
+    fn cant_resolve_unknown_names() {
+        let (_, resolution, errors) =
+            resolved("let count = 1\nlet x = { let y = y\n1 } + cuont + zzz\n");
+        assert_eq!(
+            errors,
+            [
+                ResolveError::Unresolved {
+                    name: "y".into(),
+                    location: (32..33).into(),
+                    suggestion: Some("x".into()),
+                },
+                ResolveError::Unresolved {
+                    name: "cuont".into(),
+                    location: (40..45).into(),
+                    suggestion: Some("count".into()),
+                },
+                ResolveError::Unresolved {
+                    name: "zzz".into(),
+                    location: (48..51).into(),
+                    suggestion: None,
+                },
+            ]
+        );
+        assert_eq!(resolution.uses().count(), 0);
+    }
+
+    #[test]
+    fn can_resolve_patterns() {
+        let (file, resolution, errors) = resolved(
+            "type T = A I64 | B\nlet f = |x| match x { A y => y + x, B => 0 }\nlet y = 1\n",
+        );
+        assert_eq!(errors, []);
+        let kind = |offset| resolution.defs[resolution.definition_at(&file, offset).unwrap()].kind;
+
+        // NOTE: The `y` in the arm is the one it binds, not the `let` after it.
+        assert!(matches!(kind(41), DefKind::Constructor(_, 0)));
+        assert!(matches!(kind(48), DefKind::Binding(_, 0)));
+        assert!(matches!(kind(52), DefKind::Param(_)));
+        assert!(matches!(kind(55), DefKind::Constructor(_, 1)));
+    }
+
+    #[test]
+    fn cant_bind_names_twice() {
+        let (_, _, errors) = resolved("let f = |p| match p { (x, x) => x }\n");
+        assert_eq!(
+            errors,
+            [ResolveError::DuplicateBinding {
+                name: "x".into(),
+                location: (26..27).into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn can_measure_edit_distance() {
+        assert_eq!(edit_distance("count", "count"), 0);
+        assert_eq!(edit_distance("cuont", "count"), 1);
+        assert_eq!(edit_distance("cont", "count"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+}
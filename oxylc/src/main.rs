@@ -0,0 +1,357 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use oxylc::compile::{
+    diagnostic::{self, Diagnostic, Severity},
+    dump, exhaustive,
+    formatter::{self, FormatConfig},
+    infer::infer,
+    lexer,
+    lower::lower,
+    module::{loader::FsLoader, ModuleTree},
+    parser,
+    project::Project,
+    resolve::{resolve, resolve_with_externs},
+};
+use oxylc::repr::ast::ident::Ident;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+/// The exit code when the program being compiled has errors.
+const EXIT_ERRORS: u8 = 1;
+
+/// The exit code when a command can't be used, like when it isn't supported yet. This is the same
+/// code `clap` uses for invalid arguments.
+const EXIT_USAGE: u8 = 2;
+
+/// The compiler for the Oxyl programming language.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+#[command(after_help = "Exits with 0 on success, 1 if there were errors, and 2 on invalid usage.")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks a file, or a package, for errors.
+    ///
+    /// Every module is type checked on its own, so what a module uses from another one (with
+    /// `use`, or a path like `a::b`) isn't type checked yet, and can be used as any type.
+    Check {
+        /// A root file, or a directory in a package. Defaults to the package of the current
+        /// directory.
+        path: Option<PathBuf>,
+    },
+
+    /// Builds a package. There is no backend yet, so this only checks the whole package.
+    ///
+    /// Like `check`, this doesn't type check what a module uses from another one yet.
+    Build {
+        /// A directory in the package. Defaults to the current directory.
+        path: Option<PathBuf>,
+    },
+
+    /// Builds and runs a package.
+    Run {
+        /// A directory in the package. Defaults to the current directory.
+        path: Option<PathBuf>,
+    },
+
+    /// Formats files.
+    Fmt {
+        /// The files to format.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Only check whether the files are formatted, without changing them.
+        #[arg(long)]
+        check: bool,
+
+        /// The number of columns code should fit in.
+        #[arg(long, default_value_t = FormatConfig::default().width)]
+        width: usize,
+    },
+
+    /// Prints an intermediate representation of a file, for debugging.
+    Dump {
+        /// The file to dump.
+        file: PathBuf,
+
+        /// The stage of the compiler to print the output of.
+        #[arg(long, value_enum, default_value_t = Emit::Fst)]
+        emit: Emit,
+
+        /// Print the dump as JSON instead of as text.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// The stages of the compiler that can be dumped.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Emit {
+    /// The tokens of the file.
+    Tokens,
+
+    /// The full syntax tree, straight from the parser.
+    Fst,
+
+    /// The abstract syntax tree.
+    Ast,
+
+    /// The types of every `let` and function parameter.
+    Types,
+
+    /// The intermediate representation used by the backend.
+    Ir,
+}
+
+/// A loaded program, which is either a single file or a whole package.
+struct Program {
+    modules: ModuleTree,
+    diagnostics: Vec<Diagnostic>,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let result = match args.command {
+        Command::Check { path } => check(path.as_deref(), false),
+        Command::Build { path } => check(path.as_deref(), true),
+        Command::Run { path } => check(path.as_deref(), true).and_then(|_| {
+            Err(unsupported(
+                "Running programs isn't supported yet, as there is no backend",
+            ))
+        }),
+        Command::Fmt {
+            files,
+            check,
+            width,
+        } => fmt(&files, check, &FormatConfig::default().with_width(width)),
+        Command::Dump { file, emit, json } => dump(&file, emit, json),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(code) => ExitCode::from(code),
+    }
+}
+
+/// Checks a program for errors, reporting them. If `package` is set, `path` must be in a package.
+fn check(path: Option<&Path>, package: bool) -> Result<(), u8> {
+    let mut program = load(path, package).map_err(|diagnostic| {
+        emit(&diagnostic, None);
+        EXIT_ERRORS
+    })?;
+    program.diagnostics.extend(analyze(&program.modules));
+
+    for diagnostic in &program.diagnostics {
+        emit(diagnostic, Some(&program.modules));
+    }
+
+    let errors = program
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+
+    match errors {
+        0 => Ok(()),
+        1 => {
+            eprintln!("error: aborting due to 1 previous error");
+            Err(EXIT_ERRORS)
+        }
+        n => {
+            eprintln!("error: aborting due to {} previous errors", n);
+            Err(EXIT_ERRORS)
+        }
+    }
+}
+
+/// Formats files, or only checks that they're formatted if `check` is set.
+fn fmt(files: &[PathBuf], check: bool, config: &FormatConfig) -> Result<(), u8> {
+    let mut failed = false;
+
+    for file in files {
+        match formatter::format_path(file, config, check) {
+            Ok(true) if check => {
+                emit(&diagnostic::not_formatted(file), None);
+                failed = true;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                emit(&Diagnostic::from(&err).with_file(file), None);
+                failed = true;
+            }
+        }
+    }
+
+    match failed {
+        true => Err(EXIT_ERRORS),
+        false => Ok(()),
+    }
+}
+
+/// Prints an intermediate representation of a single file.
+fn dump(file: &Path, stage: Emit, json: bool) -> Result<(), u8> {
+    let source = fs::read_to_string(file).map_err(|err| {
+        let diagnostic = Diagnostic::error(format!("Couldn't read `{}`: {}", file.display(), err));
+        emit(&diagnostic, None);
+        EXIT_ERRORS
+    })?;
+    // NOTE: The lexer never fails, so tokens can be dumped even if the file doesn't parse.
+    let parse = || {
+        parser::parse(&source).map_err(|err| {
+            let diagnostic =
+                Diagnostic::error(format!("Couldn't parse `{}`: {:?}", file.display(), err));
+            emit(&diagnostic, None);
+            EXIT_ERRORS
+        })
+    };
+
+    let mut failed = false;
+    let node = match stage {
+        Emit::Tokens => dump::tokens::dump(&source, &lexer::lex(&source)),
+        Emit::Fst => dump::fst::dump(&parse()?),
+        Emit::Ast => {
+            let (ast, errors) = lower(&parse()?);
+            for err in &errors {
+                emit(&Diagnostic::from(err).with_file(file), None);
+            }
+            failed = !errors.is_empty();
+            dump::ast::dump(&ast)
+        }
+        Emit::Types => {
+            let (ast, errors) = lower(&parse()?);
+            let (mut resolution, unresolved) = resolve(&ast);
+            let (types, type_errors) = infer(&ast, &mut resolution);
+            let pattern_errors = exhaustive::check(&ast, &resolution);
+
+            let errors = errors.iter().map(Diagnostic::from);
+            let unresolved = unresolved.iter().map(Diagnostic::from);
+            let type_errors = type_errors.iter().map(Diagnostic::from);
+            let pattern_errors = pattern_errors.iter().map(Diagnostic::from);
+            for diagnostic in errors
+                .chain(unresolved)
+                .chain(type_errors)
+                .chain(pattern_errors)
+            {
+                failed |= diagnostic.severity == Severity::Error;
+                emit(&diagnostic.with_file(file), None);
+            }
+            dump::types::dump(&ast, &resolution, &types)
+        }
+        Emit::Ir => return Err(unsupported("There is no IR yet")),
+    };
+
+    if json {
+        println!("{}", node.to_json());
+    } else {
+        print!("{}", node.to_text());
+    }
+
+    match failed {
+        true => Err(EXIT_ERRORS),
+        false => Ok(()),
+    }
+}
+
+/// Loads a program. A path to a file is loaded on its own, while anything else is loaded as the
+/// package it's in.
+fn load(path: Option<&Path>, package: bool) -> Result<Program, Diagnostic> {
+    if let Some(file) = path.filter(|path| !package && path.is_file()) {
+        let (modules, errors) = ModuleTree::load(file, &mut FsLoader);
+        return Ok(Program {
+            modules,
+            diagnostics: errors.iter().map(Diagnostic::from).collect(),
+        });
+    }
+
+    let start = match path {
+        Some(path) => path.to_path_buf(),
+        None => env::current_dir().map_err(|err| {
+            Diagnostic::error(format!("Couldn't get the current directory: {}", err))
+        })?,
+    };
+    let project = Project::find(&start)
+        .and_then(|dir| Project::open(&dir))
+        .map_err(|err| Diagnostic::from(&err))?;
+
+    let (modules, errors) = project.modules(&mut FsLoader);
+    let mut diagnostics = errors.iter().map(Diagnostic::from).collect::<Vec<_>>();
+    diagnostics.extend(
+        project
+            .unused_files(&modules)
+            .iter()
+            .map(|file| diagnostic::unused_file(&relative(file))),
+    );
+
+    Ok(Program {
+        modules,
+        diagnostics,
+    })
+}
+
+/// Runs the passes after parsing on every module in the tree, and gets the errors they find.
+/// NOTE: Every module is inferred on its own, so the names it uses from other modules have unknown
+/// types, which are never checked against what those names are defined as.
+fn analyze(modules: &ModuleTree) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    for (_, module) in modules.modules() {
+        let (ast, errors) = lower(&module.file);
+        let externs = module
+            .externs
+            .keys()
+            .map(|name| Ident::new(name))
+            .collect::<Vec<_>>();
+        let (mut resolution, unresolved) = resolve_with_externs(&ast, &externs);
+        let (_, type_errors) = infer(&ast, &mut resolution);
+        let pattern_errors = exhaustive::check(&ast, &resolution);
+
+        let errors = errors.iter().map(Diagnostic::from);
+        let unresolved = unresolved.iter().map(Diagnostic::from);
+        let type_errors = type_errors.iter().map(Diagnostic::from);
+        let pattern_errors = pattern_errors.iter().map(Diagnostic::from);
+        diagnostics.extend(
+            errors
+                .chain(unresolved)
+                .chain(type_errors)
+                .chain(pattern_errors)
+                .map(|d| d.with_file(&module.path)),
+        );
+    }
+    diagnostics
+}
+
+/// Prints a diagnostic, with the source it points at from `modules`.
+fn emit(diagnostic: &Diagnostic, modules: Option<&ModuleTree>) {
+    let source = diagnostic.file.as_ref().and_then(|file| {
+        modules?
+            .modules()
+            .find(|(_, module)| &module.path == file)
+            .map(|(_, module)| module.source.as_str())
+    });
+    let mut diagnostic = diagnostic.clone();
+    diagnostic.file = diagnostic.file.as_deref().map(relative);
+
+    eprintln!("{}", diagnostic.render(source));
+}
+
+/// Creates the error for a command that can't be used yet.
+fn unsupported(message: &str) -> u8 {
+    emit(&Diagnostic::error(message), None);
+    EXIT_USAGE
+}
+
+/// Makes a path relative to the current directory, if it's inside of it, so it's shorter to show.
+fn relative(path: &Path) -> PathBuf {
+    env::current_dir()
+        .and_then(fs::canonicalize)
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| path.to_path_buf())
+}
@@ -0,0 +1,170 @@
+//! The arenas the nodes of the AST live in, and the `Idx`s that point into them.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+/// A typed index into an `Arena`. It's a `u32` under the hood, so trees that point at their
+/// children with these are a lot smaller (and a lot cheaper to build and copy around) than ones
+/// made out of `Box`es.
+pub struct Idx<T> {
+    raw: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Idx<T> {
+    /// The position of the thing this points to in its arena.
+    pub fn index(self) -> usize {
+        self.raw as usize
+    }
+}
+
+// NOTE: These are written by hand, as deriving them would require `T` to implement them too, even
+// though we never store a `T`.
+impl<T> Clone for Idx<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Idx<T> {}
+
+impl<T> PartialEq for Idx<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl<T> Eq for Idx<T> {}
+
+impl<T> PartialOrd for Idx<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Idx<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.raw.cmp(&other.raw)
+    }
+}
+
+impl<T> Hash for Idx<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.raw.hash(state)
+    }
+}
+
+impl<T> fmt::Debug for Idx<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Idx({})", self.raw)
+    }
+}
+
+/// A flat store of `T`s, which hands out an `Idx` for every one put into it. Things are never
+/// removed, so an `Idx` stays valid for as long as the arena it came from is around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Arena<T> {
+    items: Vec<T>,
+}
+
+impl<T> Arena<T> {
+    /// Creates an empty arena.
+    pub fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    /// Creates an empty arena with room for `capacity` things.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Puts `item` into the arena, and returns where it went.
+    pub fn alloc(&mut self, item: T) -> Idx<T> {
+        let raw =
+            u32::try_from(self.items.len()).expect("an arena can hold at most `u32::MAX` things");
+        self.items.push(item);
+        Idx {
+            raw,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of things in the arena.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Whether the arena is empty.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Gets the thing at `idx`, if it's in this arena.
+    pub fn get(&self, idx: Idx<T>) -> Option<&T> {
+        self.items.get(idx.index())
+    }
+
+    /// Iterates over everything in the arena, in the order it was put in.
+    pub fn iter(&self) -> impl Iterator<Item = (Idx<T>, &T)> {
+        self.items.iter().enumerate().map(|(raw, item)| {
+            (
+                Idx {
+                    raw: raw as u32,
+                    _marker: PhantomData,
+                },
+                item,
+            )
+        })
+    }
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<Idx<T>> for Arena<T> {
+    type Output = T;
+
+    fn index(&self, idx: Idx<T>) -> &T {
+        &self.items[idx.index()]
+    }
+}
+
+impl<T> IndexMut<Idx<T>> for Arena<T> {
+    fn index_mut(&mut self, idx: Idx<T>) -> &mut T {
+        &mut self.items[idx.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_alloc_and_index() {
+        let mut arena = Arena::new();
+        let a = arena.alloc("a");
+        let b = arena.alloc("b");
+        assert_ne!(a, b);
+        assert_eq!(arena.len(), 2);
+        assert_eq!(arena[a], "a");
+        assert_eq!(arena[b], "b");
+
+        arena[a] = "c";
+        assert_eq!(arena.get(a), Some(&"c"));
+        assert_eq!(
+            arena.iter().map(|(_, item)| *item).collect::<Vec<_>>(),
+            vec!["c", "b"]
+        );
+        assert_eq!(Arena::new().get(b), None::<&&str>);
+    }
+}
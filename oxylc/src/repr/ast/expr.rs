@@ -0,0 +1,187 @@
+use super::{arena::Idx, pattern::Arm, stmt::StmtId, types::Trait, Name};
+use errgonomic::parser::input::Span;
+
+/// Where an `Expr` is in its file.
+pub type ExprId = Idx<Expr>;
+
+/// An expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expr {
+    /// The kind of expression it is.
+    pub kind: ExprKind,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+}
+
+impl Expr {
+    /// Creates a new `Expr`.
+    pub fn new(location: Span, kind: ExprKind) -> Self {
+        Self { kind, location }
+    }
+}
+
+/// The kinds of expressions we can have.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprKind {
+    /// A literal, like `1`, `2.5` or `True`.
+    Literal(Literal),
+
+    /// A use of a name, like `x`.
+    Var(Name),
+
+    /// A path through namespaces, like `a::b::c`. There are always at least 2 segments.
+    Path(Vec<Name>),
+
+    /// A function. Every function takes exactly 1 argument, so `|a, b| e` becomes `|a| |b| e`.
+    /// A function written without arguments (`|| e`) has no `param`, and takes nothing at all.
+    Lambda { param: Option<Name>, body: ExprId },
+
+    /// An application of a function to its argument, from `f x` or `x |> f`.
+    Apply { function: ExprId, arg: ExprId },
+
+    /// An arithmetic operation or a comparison, like `a + b` or `a < b`. It's a call of the method
+    /// of the operator's trait (see `BinaryOp::method_trait`).
+    Binary {
+        op: BinaryOp,
+
+        /// The location of the operator.
+        op_location: Span,
+
+        lhs: ExprId,
+        rhs: ExprId,
+    },
+
+    /// A unary operation, like `-a`. Like `Binary`, it's a call of the method of the operator's
+    /// trait.
+    Unary {
+        op: UnaryOp,
+
+        /// The location of the operator.
+        op_location: Span,
+
+        operand: ExprId,
+    },
+
+    /// A field access, like `a.b`.
+    Field { expr: ExprId, field: Name },
+
+    /// A block, with its statements and its final expression.
+    Block {
+        statements: Vec<StmtId>,
+        result: ExprId,
+    },
+
+    /// A tuple of 2 or more values, like `(a, b)`.
+    Tuple(Vec<ExprId>),
+
+    /// A `match`, which evaluates to the body of the first arm whose pattern matches `scrutinee`.
+    Match { scrutinee: ExprId, arms: Vec<Arm> },
+
+    /// Something that couldn't be lowered. The error for it has already been reported.
+    Error,
+}
+
+/// A literal value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Literal {
+    /// An integer, which can be any integer type (see `compile::infer`).
+    Integer(u64),
+
+    /// A floating-point number, which can be any floating-point type (see `compile::infer`).
+    Floating(f64),
+
+    /// A boolean.
+    Boolean(bool),
+}
+
+// NOTE: Have to do this b/c of `f64` not implementing `Eq`.
+impl Eq for Literal {}
+
+/// The operators that take 2 operands.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    /// `+`
+    Add,
+
+    /// `-`
+    Sub,
+
+    /// `*`
+    Mul,
+
+    /// `/`
+    Div,
+
+    /// `==`
+    Eq,
+
+    /// `!=`
+    Ne,
+
+    /// `<`
+    Lt,
+
+    /// `<=`
+    Le,
+
+    /// `>`
+    Gt,
+
+    /// `>=`
+    Ge,
+}
+
+impl BinaryOp {
+    /// Gets how the operator is written.
+    pub fn text(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+        }
+    }
+
+    /// Gets the trait whose method the operator calls. Comparisons all call the same method, like
+    /// `a > b` being `b < a`.
+    pub fn method_trait(self) -> Trait {
+        match self {
+            BinaryOp::Add => Trait::Add,
+            BinaryOp::Sub => Trait::Sub,
+            BinaryOp::Mul => Trait::Mul,
+            BinaryOp::Div => Trait::Div,
+            BinaryOp::Eq | BinaryOp::Ne => Trait::Eq,
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => Trait::Ord,
+        }
+    }
+}
+
+/// The operators that take 1 operand.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    /// `-`
+    Neg,
+}
+
+impl UnaryOp {
+    /// Gets how the operator is written.
+    pub fn text(self) -> &'static str {
+        match self {
+            UnaryOp::Neg => "-",
+        }
+    }
+
+    /// Gets the trait whose method the operator calls.
+    pub fn method_trait(self) -> Trait {
+        match self {
+            UnaryOp::Neg => Trait::Neg,
+        }
+    }
+}
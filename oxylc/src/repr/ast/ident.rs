@@ -1,7 +1,115 @@
+use super::interner;
+use std::fmt;
+
 /// An identifier. This is used for variable names, function names, etc. Note that instead of using
 /// the actual "name", we use a number to represent the identifier. This is because we want to make
-/// everything as `Copy`-able as possible.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// everything as `Copy`-able as possible. The numbers come from the global interner, so two
+/// `Ident`s are equal exactly when their names are.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Ident {
     inner: u64,
 }
+
+impl Ident {
+    /// Gets the `Ident` for `name`, interning it if it's new.
+    pub fn new(name: &str) -> Self {
+        interner::intern(name)
+    }
+
+    /// Creates an `Ident` from its number. Only the interner should hand these out.
+    pub(super) const fn from_index(inner: u64) -> Self {
+        Self { inner }
+    }
+
+    /// The number of this `Ident`.
+    pub fn index(self) -> u64 {
+        self.inner
+    }
+
+    /// Gets the name of this `Ident`.
+    pub fn as_str(self) -> &'static str {
+        interner::resolve(self)
+    }
+
+    /// Whether this is a keyword, like `let` or `True`.
+    pub fn is_keyword(self) -> bool {
+        // NOTE: The keywords are interned first, so they're the smallest numbers.
+        self.inner < kw::COUNT
+    }
+}
+
+impl From<&str> for Ident {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+/// An `Ident` is written as its name, as its number depends on the order names were interned in.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Ident {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Ident {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(Self::new(&name))
+    }
+}
+
+impl fmt::Debug for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ident({}: {:?})", self.inner, self.as_str())
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The `Ident`s of the keywords, which are always interned. These are in the same order as
+/// `compile::lexer::KEYWORDS`.
+pub mod kw {
+    use super::Ident;
+
+    pub const LET: Ident = Ident::from_index(0);
+    pub const MOD: Ident = Ident::from_index(1);
+    pub const PUB: Ident = Ident::from_index(2);
+    pub const USE: Ident = Ident::from_index(3);
+    pub const AS: Ident = Ident::from_index(4);
+    pub const TRUE: Ident = Ident::from_index(5);
+    pub const FALSE: Ident = Ident::from_index(6);
+    pub const IMPL: Ident = Ident::from_index(7);
+    pub const FOR: Ident = Ident::from_index(8);
+    pub const TYPE: Ident = Ident::from_index(9);
+    pub const MATCH: Ident = Ident::from_index(10);
+
+    /// The number of keywords.
+    pub(super) const COUNT: u64 = 11;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::lexer::KEYWORDS;
+
+    #[test]
+    fn can_get_names_back() {
+        let x = Ident::new("x");
+        assert_eq!(x, Ident::new("x"));
+        assert_ne!(x, Ident::new("y"));
+        assert_eq!(x.as_str(), "x");
+        assert_eq!(x.to_string(), "x");
+        assert!(!x.is_keyword());
+
+        assert_eq!(Ident::new("let"), kw::LET);
+        assert_eq!(kw::USE.as_str(), "use");
+        assert!(kw::TRUE.is_keyword());
+        assert_eq!(kw::COUNT as usize, KEYWORDS.len());
+    }
+}
@@ -0,0 +1,161 @@
+//! The global string interner behind `Ident`. Every name is stored once, for the whole life of the
+//! program, and every `Ident` with the same number has the same name, no matter which thread or
+//! which file it came from.
+
+use super::ident::Ident;
+use crate::compile::lexer::KEYWORDS;
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
+
+/// The interner everything shares.
+static INTERNER: LazyLock<RwLock<Interner>> = LazyLock::new(|| RwLock::new(Interner::new()));
+
+/// Gets the `Ident` for `name`, interning it if it's new.
+pub fn intern(name: &str) -> Ident {
+    // NOTE: Most names have been seen before, so try with only a read lock first. The lock can
+    // only be poisoned by a panic in here, which leaves the interner as it was, so we go on.
+    if let Some(ident) = INTERNER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+    {
+        return ident;
+    }
+
+    INTERNER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .intern(name)
+}
+
+/// Gets the `Ident` for `name`, if it's been interned already.
+pub fn get(name: &str) -> Option<Ident> {
+    INTERNER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+}
+
+/// Gets the name of `ident`.
+pub fn resolve(ident: Ident) -> &'static str {
+    INTERNER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .resolve(ident)
+}
+
+/// A map from names to `Ident`s and back. The keywords are always interned first, in the order of
+/// `KEYWORDS`, so they match the constants in `ident::kw`.
+#[derive(Debug)]
+pub struct Interner {
+    /// The name of every `Ident`, indexed by its number.
+    names: Vec<&'static str>,
+
+    /// The `Ident` of every name.
+    idents: HashMap<&'static str, Ident>,
+}
+
+impl Interner {
+    /// Creates a new `Interner` with only the keywords in it.
+    pub fn new() -> Self {
+        let mut interner = Self {
+            names: vec![],
+            idents: HashMap::new(),
+        };
+        for keyword in KEYWORDS {
+            interner.intern(keyword);
+        }
+        interner
+    }
+
+    /// Gets the `Ident` for `name`, interning it if it's new.
+    pub fn intern(&mut self, name: &str) -> Ident {
+        if let Some(ident) = self.get(name) {
+            return ident;
+        }
+
+        // NOTE: Names are never taken out of the interner, so they live as long as the program.
+        // Leaking them lets us hand out `&'static str`s without holding the lock.
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        let ident = Ident::from_index(self.names.len() as u64);
+        self.names.push(name);
+        self.idents.insert(name, ident);
+        ident
+    }
+
+    /// Gets the `Ident` for `name`, if it's been interned already.
+    pub fn get(&self, name: &str) -> Option<Ident> {
+        self.idents.get(name).copied()
+    }
+
+    /// Gets the name of `ident`.
+    /// NOTE: Panics if `ident` came from a different `Interner`, which can't happen with the
+    /// global one.
+    pub fn resolve(&self, ident: Ident) -> &'static str {
+        self.names[ident.index() as usize]
+    }
+
+    /// The number of names in the interner.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the interner is empty. It never is, as the keywords are always in it.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl Default for Interner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repr::ast::ident::kw;
+    use std::thread;
+
+    #[test]
+    fn can_intern_and_resolve() {
+        let mut interner = Interner::new();
+        let x = interner.intern("x");
+        let y = interner.intern("y");
+        assert_ne!(x, y);
+        assert_eq!(interner.intern("x"), x);
+        assert_eq!(interner.get("y"), Some(y));
+        assert_eq!(interner.get("z"), None);
+        assert_eq!(interner.resolve(x), "x");
+        assert_eq!(interner.len(), KEYWORDS.len() + 2);
+
+        for (i, keyword) in KEYWORDS.iter().enumerate() {
+            assert_eq!(interner.get(keyword), Some(Ident::from_index(i as u64)));
+        }
+        assert_eq!(interner.resolve(kw::LET), "let");
+        assert_eq!(interner.resolve(kw::FALSE), "False");
+    }
+
+    #[test]
+    fn can_intern_from_many_threads() {
+        let names = ["a_name", "another_name", "yet_another_name"];
+        let idents = thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|_| scope.spawn(|| names.map(intern)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        assert!(idents.iter().all(|these| *these == idents[0]));
+        for (name, ident) in names.iter().zip(idents[0]) {
+            assert_eq!(resolve(ident), *name);
+            assert_eq!(get(name), Some(ident));
+        }
+    }
+}
@@ -1,14 +1,87 @@
-pub mod ident;
+//! The `ast` module is responsible for representing the AST (Abstract Syntax Tree) of the Oxyl
+//! language. Unlike the FST, it only has what's needed to understand the program: there are no
+//! comments or parentheses, and the sugar (like multi-argument functions and `|>`) is gone. It's
+//! made by lowering the FST with `compile::lower`.
+//!
+//! The nodes live in arenas in the `File` and point to each other with `Idx`s, and names are
+//! interned `Ident`s, so the tree is cheap to build and to walk. Every node keeps the location of
+//! the FST node it came from, so errors found in later passes can point at the source.
 
-use std::collections::HashMap;
+pub mod arena;
+pub mod expr;
+pub mod ident;
+pub mod interner;
+pub mod pattern;
+pub mod scope;
+pub mod stmt;
+pub mod type_expr;
+pub mod types;
 
+use arena::Arena;
+use errgonomic::parser::input::Span;
+use expr::{Expr, ExprId};
 use ident::Ident;
+use std::ops::Index;
+use stmt::{Stmt, StmtId};
+
+/// The AST of a whole file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct File {
+    /// The statements at the top of the file, in order.
+    pub statements: Vec<StmtId>,
+
+    /// Every expression in the file.
+    pub exprs: Arena<Expr>,
+
+    /// Every statement in the file, including the ones in blocks.
+    pub stmts: Arena<Stmt>,
+
+    /// The location of the file.
+    pub location: Span,
+}
+
+impl File {
+    /// Creates a new, empty `File`.
+    pub fn new(location: Span) -> Self {
+        Self {
+            statements: vec![],
+            exprs: Arena::new(),
+            stmts: Arena::new(),
+            location,
+        }
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Scope {
-    /// The map from symbol to whatever.
-    sym_map: HashMap<Ident, ()>,
+impl Index<ExprId> for File {
+    type Output = Expr;
+
+    fn index(&self, id: ExprId) -> &Expr {
+        &self.exprs[id]
+    }
+}
+
+impl Index<StmtId> for File {
+    type Output = Stmt;
+
+    fn index(&self, id: StmtId) -> &Stmt {
+        &self.stmts[id]
+    }
+}
+
+/// A name in the source, along with where it is. This is used wherever a name is written down,
+/// but isn't an expression on its own, like the name of a `let` or of a field.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Name {
+    /// The name.
+    pub ident: Ident,
+
+    /// The location of the name.
+    pub location: Span,
+}
 
-    /// The map from symbol to type.
-    type_map: HashMap<Ident, ()>,
+impl Name {
+    /// Creates a new `Name`.
+    pub fn new(location: Span, ident: Ident) -> Self {
+        Self { ident, location }
+    }
 }
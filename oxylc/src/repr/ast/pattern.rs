@@ -0,0 +1,77 @@
+//! Patterns, which the arms of a `match` test their value against.
+
+use super::{expr::ExprId, Name};
+use errgonomic::parser::input::Span;
+
+/// A pattern. Unlike the FST, there are no parentheses, and it's known whether a name is a
+/// constructor or binds the value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    /// The kind of pattern it is.
+    pub kind: PatternKind,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+}
+
+impl Pattern {
+    /// Creates a new `Pattern`.
+    pub fn new(location: Span, kind: PatternKind) -> Self {
+        Self { kind, location }
+    }
+
+    /// Gets every name the pattern binds, in the order they're written.
+    pub fn bindings(&self) -> Vec<Name> {
+        let mut bindings = vec![];
+        self.collect_bindings(&mut bindings);
+        bindings
+    }
+
+    fn collect_bindings(&self, out: &mut Vec<Name>) {
+        match &self.kind {
+            PatternKind::Binding(name) => out.push(*name),
+            PatternKind::Tuple(items) | PatternKind::Constructor { args: items, .. } => {
+                for item in items {
+                    item.collect_bindings(out);
+                }
+            }
+            PatternKind::Wildcard | PatternKind::Literal(_) => {}
+        }
+    }
+}
+
+/// The kinds of patterns we can have.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatternKind {
+    /// `_`, which matches anything.
+    Wildcard,
+
+    /// A name starting with a lowercase letter, which matches anything and binds it.
+    Binding(Name),
+
+    /// A literal, like `1` or `True`. It points to an `ExprKind::Literal`, so it gets its type
+    /// like any other literal.
+    Literal(ExprId),
+
+    /// A tuple of 2 or more patterns, like `(x, True)`.
+    Tuple(Vec<Pattern>),
+
+    /// A constructor of a type, applied to a pattern for each of its fields, like `Cons x rest`.
+    /// The constructor points to an `ExprKind::Var`, so it's resolved like any other use of it.
+    Constructor {
+        constructor: ExprId,
+        args: Vec<Pattern>,
+    },
+}
+
+/// An arm of a `match`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Arm {
+    pub pattern: Pattern,
+
+    /// What the `match` evaluates to if the pattern matches.
+    pub body: ExprId,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+}
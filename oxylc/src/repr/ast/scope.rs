@@ -0,0 +1,118 @@
+//! Scopes and the definitions in them, which are made by name resolution (`compile::resolve`).
+//! Type inference (`compile::infer`) then fills in the types of the names in each scope.
+
+use super::{arena::Idx, expr::ExprId, ident::Ident, stmt::StmtId, types::Scheme, Name};
+use std::collections::HashMap;
+
+/// Where a `Scope` is in its resolution.
+pub type ScopeId = Idx<Scope>;
+
+/// Where a `Def` is in its resolution.
+pub type DefId = Idx<Def>;
+
+/// A scope, which is a set of names that can be used inside of it, along with everything in the
+/// scopes it's in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    /// The scope this one is in, if it isn't the scope of the file.
+    parent: Option<ScopeId>,
+
+    /// The map from symbol to its definition.
+    sym_map: HashMap<Ident, DefId>,
+
+    /// The map from symbol to its type, once type inference has found it.
+    type_map: HashMap<Ident, Scheme>,
+}
+
+impl Scope {
+    /// Creates a new, empty `Scope` inside of `parent`.
+    pub fn new(parent: Option<ScopeId>) -> Self {
+        Self {
+            parent,
+            sym_map: HashMap::new(),
+            type_map: HashMap::new(),
+        }
+    }
+
+    /// Gets the scope this one is in.
+    pub fn parent(&self) -> Option<ScopeId> {
+        self.parent
+    }
+
+    /// Gets the definition of `ident` in this scope, not looking at the scopes it's in.
+    pub fn get(&self, ident: Ident) -> Option<DefId> {
+        self.sym_map.get(&ident).copied()
+    }
+
+    /// Defines `ident` in this scope. If it's already defined here, the first definition is kept
+    /// and `false` is returned.
+    pub fn define(&mut self, ident: Ident, def: DefId) -> bool {
+        match self.sym_map.contains_key(&ident) {
+            true => false,
+            false => {
+                self.sym_map.insert(ident, def);
+                true
+            }
+        }
+    }
+
+    /// Gets the type of `ident` in this scope, not looking at the scopes it's in.
+    pub fn type_of(&self, ident: Ident) -> Option<&Scheme> {
+        self.type_map.get(&ident)
+    }
+
+    /// Sets the type of `ident` in this scope.
+    pub fn set_type(&mut self, ident: Ident, scheme: Scheme) {
+        self.type_map.insert(ident, scheme);
+    }
+
+    /// Iterates over the names defined in this scope.
+    pub fn names(&self) -> impl Iterator<Item = Ident> + '_ {
+        self.sym_map.keys().copied()
+    }
+}
+
+/// A definition of a name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Def {
+    /// The name being defined, where it's defined.
+    pub name: Name,
+
+    /// What defined it.
+    pub kind: DefKind,
+
+    /// The scope it's defined in.
+    pub scope: ScopeId,
+}
+
+/// The things that can define a name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DefKind {
+    /// A `let`-statement.
+    Let(StmtId),
+
+    /// The parameter of a function, which is the `ExprKind::Lambda` it's for.
+    Param(ExprId),
+
+    /// A name bound by the pattern of an arm, which is the `ExprKind::Match` along with the index
+    /// of the arm in it.
+    Binding(ExprId, usize),
+
+    /// A `mod`-statement.
+    Mod(StmtId),
+
+    /// An import in a `use`-statement.
+    Import(StmtId),
+
+    /// A constructor of a type declared by a `type`-statement, which is the statement along with
+    /// the index of the constructor in it.
+    Constructor(StmtId, usize),
+
+    /// A package the file's package depends on. These aren't written anywhere in the file, so
+    /// their names have empty locations.
+    Extern,
+
+    /// A name from the prelude (`compile::prelude`). These aren't written anywhere either, so their
+    /// names have empty locations too.
+    Builtin,
+}
@@ -0,0 +1,80 @@
+use super::{
+    arena::Idx,
+    expr::ExprId,
+    type_expr::{Constructor, Signature},
+    Name,
+};
+use errgonomic::parser::input::Span;
+
+/// Where a `Stmt` is in its file.
+pub type StmtId = Idx<Stmt>;
+
+/// A statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stmt {
+    /// The kind of statement it is.
+    pub kind: StmtKind,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+
+    /// Whether the statement is marked `pub`.
+    pub is_public: bool,
+}
+
+impl Stmt {
+    /// Creates a new `Stmt`.
+    pub fn new(location: Span, kind: StmtKind, is_public: bool) -> Self {
+        Self {
+            kind,
+            location,
+            is_public,
+        }
+    }
+}
+
+/// The kinds of statements we can have.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StmtKind {
+    /// A `let`-statement, binding `name` to `value`, which has the type in `signature` if there
+    /// is one.
+    Let {
+        name: Name,
+        is_mutable: bool,
+        signature: Option<Signature>,
+        value: ExprId,
+    },
+
+    /// A `mod`-statement, declaring a child module.
+    Mod { name: Name },
+
+    /// A `use`-statement. The tree of the FST is flattened, so `use a::{b, c as d}` has the
+    /// imports `a::b` (as `b`) and `a::c` (as `d`).
+    Use { imports: Vec<Import> },
+
+    /// An `impl`-statement, implementing the trait `trait_name` for the type `type_name` with
+    /// `value` as the trait's method.
+    Impl {
+        trait_name: Name,
+        type_name: Name,
+        value: ExprId,
+    },
+
+    /// A `type`-statement, declaring the type `name` (with the type parameters `params`) and its
+    /// constructors.
+    Type {
+        name: Name,
+        params: Vec<Name>,
+        constructors: Vec<Constructor>,
+    },
+}
+
+/// A single thing imported by a `use`-statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Import {
+    /// The full path to the thing being imported.
+    pub path: Vec<Name>,
+
+    /// The name it's imported as. This is either its alias, or the last segment of the path.
+    pub name: Name,
+}
@@ -0,0 +1,102 @@
+//! Types as they're written in the source, which are turned into `Type`s by type inference.
+
+use super::Name;
+use errgonomic::parser::input::Span;
+use std::fmt;
+
+/// A type, as it's written in the source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeExpr {
+    /// The kind of type it is.
+    pub kind: TypeExprKind,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+}
+
+impl TypeExpr {
+    /// Creates a new `TypeExpr`.
+    pub fn new(location: Span, kind: TypeExprKind) -> Self {
+        Self { kind, location }
+    }
+}
+
+/// The kinds of types we can write. Unlike the FST, there are no parentheses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeExprKind {
+    /// A type by its name, applied to `args`, like `I64` or `List a`. The name is either a type
+    /// parameter, a built-in type or a type declared by a `type`-statement.
+    Named { name: Name, args: Vec<TypeExpr> },
+
+    /// `()`, the type of nothing at all.
+    Unit,
+
+    /// A tuple of 2 or more types, like `(I64, Bool)`.
+    Tuple(Vec<TypeExpr>),
+
+    /// A function from its parameter to its result.
+    Function(Box<TypeExpr>, Box<TypeExpr>),
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TypeExprKind::Named { name, args } => {
+                write!(f, "{}", name.ident)?;
+                for arg in args {
+                    match &arg.kind {
+                        TypeExprKind::Named { args, .. } if args.is_empty() => {
+                            write!(f, " {}", arg)?
+                        }
+                        TypeExprKind::Unit | TypeExprKind::Tuple(_) => write!(f, " {}", arg)?,
+                        _ => write!(f, " ({})", arg)?,
+                    }
+                }
+                Ok(())
+            }
+            TypeExprKind::Unit => write!(f, "()"),
+            TypeExprKind::Tuple(items) => {
+                let items = items.iter().map(ToString::to_string).collect::<Vec<_>>();
+                write!(f, "({})", items.join(", "))
+            }
+            TypeExprKind::Function(param, result) => match param.kind {
+                TypeExprKind::Function(..) => write!(f, "({}) -> {}", param, result),
+                _ => write!(f, "{} -> {}", param, result),
+            },
+        }
+    }
+}
+
+/// A type parameter of a `let`, along with the traits it has to implement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeParam {
+    pub name: Name,
+
+    /// The traits the parameter has to implement, in order.
+    pub bounds: Vec<Name>,
+}
+
+/// The type written for a `let`, like `[a]: a -> a`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The type parameters, which are the only type variables `ty` can use.
+    pub params: Vec<TypeParam>,
+
+    pub ty: TypeExpr,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+}
+
+/// One of the ways to make a value of a type declared by a `type`-statement, like
+/// `Cons a (List a)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Constructor {
+    pub name: Name,
+
+    /// The types of the values the constructor takes, in order.
+    pub fields: Vec<TypeExpr>,
+
+    /// The location of the FST node this came from.
+    pub location: Span,
+}
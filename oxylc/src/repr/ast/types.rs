@@ -0,0 +1,517 @@
+//! The types of values, which are found by type inference (`compile::infer`).
+
+use super::ident::Ident;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// A type variable, which stands for a type that isn't known (yet). The number is only unique
+/// within the inference that made it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TypeVar(pub u32);
+
+/// A type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Type {
+    /// An integer, like `1`.
+    Int(IntType),
+
+    /// A floating-point number, like `2.5`.
+    Float(FloatType),
+
+    /// A boolean, `True` or `False`.
+    Bool,
+
+    /// Nothing at all. This is what a function without arguments (`|| e`) takes.
+    Unit,
+
+    /// A type that isn't known.
+    Var(TypeVar),
+
+    /// A function from its parameter to its result, like `I64 -> Bool`.
+    Function(Box<Type>, Box<Type>),
+
+    /// A type declared by a `type`-statement, applied to its type arguments, like `List I64`.
+    Named(Ident, Vec<Type>),
+
+    /// A tuple of 2 or more types, like `(I64, Bool)`.
+    Tuple(Vec<Type>),
+
+    /// A type parameter of a `let` with a signature, while its value is being checked. It stands
+    /// for any type the `let` could be used as, so it's only ever the same as itself.
+    Rigid(RigidVar),
+}
+
+/// A type parameter written in a signature, like the `a` in `[a]: a -> a`. The number tells apart
+/// parameters with the same name, and is only unique within the inference that made it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RigidVar {
+    pub ident: Ident,
+    pub id: u32,
+}
+
+/// The sizes of integers, which are signed (`I..`) or unsigned (`U..`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum IntType {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl IntType {
+    /// Every integer type, smallest first, signed before unsigned.
+    pub const ALL: [IntType; 8] = [
+        IntType::I8,
+        IntType::I16,
+        IntType::I32,
+        IntType::I64,
+        IntType::U8,
+        IntType::U16,
+        IntType::U32,
+        IntType::U64,
+    ];
+
+    /// Gets the name of the type, like `I32`.
+    pub fn name(self) -> &'static str {
+        match self {
+            IntType::I8 => "I8",
+            IntType::I16 => "I16",
+            IntType::I32 => "I32",
+            IntType::I64 => "I64",
+            IntType::U8 => "U8",
+            IntType::U16 => "U16",
+            IntType::U32 => "U32",
+            IntType::U64 => "U64",
+        }
+    }
+
+    /// Whether the type can hold negative numbers.
+    pub fn is_signed(self) -> bool {
+        matches!(
+            self,
+            IntType::I8 | IntType::I16 | IntType::I32 | IntType::I64
+        )
+    }
+
+    /// The smallest number the type can hold.
+    pub fn min(self) -> i128 {
+        match self {
+            IntType::I8 => i8::MIN.into(),
+            IntType::I16 => i16::MIN.into(),
+            IntType::I32 => i32::MIN.into(),
+            IntType::I64 => i64::MIN.into(),
+            IntType::U8 | IntType::U16 | IntType::U32 | IntType::U64 => 0,
+        }
+    }
+
+    /// The largest number the type can hold.
+    pub fn max(self) -> i128 {
+        match self {
+            IntType::I8 => i8::MAX.into(),
+            IntType::I16 => i16::MAX.into(),
+            IntType::I32 => i32::MAX.into(),
+            IntType::I64 => i64::MAX.into(),
+            IntType::U8 => u8::MAX.into(),
+            IntType::U16 => u16::MAX.into(),
+            IntType::U32 => u32::MAX.into(),
+            IntType::U64 => u64::MAX.into(),
+        }
+    }
+}
+
+/// The sizes of floating-point numbers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FloatType {
+    F32,
+    F64,
+}
+
+impl FloatType {
+    /// Every floating-point type, smallest first.
+    pub const ALL: [FloatType; 2] = [FloatType::F32, FloatType::F64];
+
+    /// Gets the name of the type, like `F32`.
+    pub fn name(self) -> &'static str {
+        match self {
+            FloatType::F32 => "F32",
+            FloatType::F64 => "F64",
+        }
+    }
+
+    /// The largest finite number the type can hold. The smallest is the same, but negative.
+    pub fn max(self) -> f64 {
+        match self {
+            FloatType::F32 => f32::MAX.into(),
+            FloatType::F64 => f64::MAX,
+        }
+    }
+}
+
+/// The kinds of numbers the type of a literal can be.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Class {
+    /// Any integer, which is what an integer literal like `1` can be.
+    Integral,
+
+    /// Any floating-point number, which is what a literal like `2.5` can be.
+    Fractional,
+}
+
+impl Class {
+    /// Whether `ty` is in this class.
+    pub fn contains(self, ty: &Type) -> bool {
+        match self {
+            Class::Integral => matches!(ty, Type::Int(_)),
+            Class::Fractional => matches!(ty, Type::Float(_)),
+        }
+    }
+
+    /// The type a type variable in this class becomes if nothing else decides what it is.
+    pub fn default_type(self) -> Type {
+        match self {
+            Class::Integral => Type::Int(IntType::I64),
+            Class::Fractional => Type::Float(FloatType::F64),
+        }
+    }
+
+    /// How a type variable in this class is written in errors, like `{integer}`.
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            Class::Integral => "{integer}",
+            Class::Fractional => "{float}",
+        }
+    }
+}
+
+/// The traits, which are the things a type can do. A type variable can be limited to the types
+/// that implement a trait, like `Add a => a -> a -> a`. Every trait but `Num` has a method, which
+/// its operators call (see `compile::prelude::method`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Trait {
+    /// Numbers, which can be converted to each other with `to_i8`, `to_f64` and so on.
+    Num,
+
+    /// `+`
+    Add,
+
+    /// `-`
+    Sub,
+
+    /// `*`
+    Mul,
+
+    /// `/`
+    Div,
+
+    /// `-`, on its own.
+    Neg,
+
+    /// `==` and `!=`
+    Eq,
+
+    /// `<`, `<=`, `>` and `>=`
+    Ord,
+}
+
+impl Trait {
+    /// Every trait.
+    pub const ALL: [Trait; 8] = [
+        Trait::Num,
+        Trait::Add,
+        Trait::Sub,
+        Trait::Mul,
+        Trait::Div,
+        Trait::Neg,
+        Trait::Eq,
+        Trait::Ord,
+    ];
+
+    /// Gets the name of the trait, like `Add`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Trait::Num => "Num",
+            Trait::Add => "Add",
+            Trait::Sub => "Sub",
+            Trait::Mul => "Mul",
+            Trait::Div => "Div",
+            Trait::Neg => "Neg",
+            Trait::Eq => "Eq",
+            Trait::Ord => "Ord",
+        }
+    }
+
+    /// Gets the trait with the given name, if there is one.
+    pub fn named(name: &str) -> Option<Trait> {
+        Trait::ALL.into_iter().find(|t| t.name() == name)
+    }
+}
+
+impl Type {
+    /// Creates a new function type.
+    pub fn function(param: Type, result: Type) -> Self {
+        Type::Function(Box::new(param), Box::new(result))
+    }
+
+    /// Gets the type variables in this type, in the order they're first written.
+    pub fn vars(&self) -> Vec<TypeVar> {
+        let mut vars = vec![];
+        self.collect_vars(&mut vars);
+        vars
+    }
+
+    /// Gets the names of the rigid types in this type.
+    fn rigid_names(&self, names: &mut HashSet<String>) {
+        match self {
+            Type::Rigid(rigid) => {
+                names.insert(rigid.ident.to_string());
+            }
+            Type::Function(param, result) => {
+                param.rigid_names(names);
+                result.rigid_names(names);
+            }
+            Type::Named(_, args) | Type::Tuple(args) => {
+                for arg in args {
+                    arg.rigid_names(names);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_vars(&self, vars: &mut Vec<TypeVar>) {
+        match self {
+            Type::Var(var) if !vars.contains(var) => vars.push(*var),
+            Type::Function(param, result) => {
+                param.collect_vars(vars);
+                result.collect_vars(vars);
+            }
+            Type::Named(_, args) | Type::Tuple(args) => {
+                for arg in args {
+                    arg.collect_vars(vars);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Writes this type, naming its type variables with `names`.
+    fn write(&self, f: &mut impl fmt::Write, names: &mut VarNames) -> fmt::Result {
+        match self {
+            Type::Int(int) => f.write_str(int.name()),
+            Type::Float(float) => f.write_str(float.name()),
+            Type::Bool => f.write_str("Bool"),
+            Type::Unit => f.write_str("()"),
+            Type::Var(var) => f.write_str(&names.name(*var)),
+            Type::Function(param, result) => {
+                // NOTE: `->` is right-associative, so only functions taking functions need
+                // parentheses.
+                match **param {
+                    Type::Function(..) => {
+                        f.write_str("(")?;
+                        param.write(f, names)?;
+                        f.write_str(")")?;
+                    }
+                    _ => param.write(f, names)?,
+                }
+                f.write_str(" -> ")?;
+                result.write(f, names)
+            }
+            Type::Named(ident, args) => {
+                f.write_str(ident.as_str())?;
+                for arg in args {
+                    f.write_str(" ")?;
+                    match arg {
+                        Type::Function(..) => {
+                            f.write_str("(")?;
+                            arg.write(f, names)?;
+                            f.write_str(")")?;
+                        }
+                        Type::Named(_, args) if !args.is_empty() => {
+                            f.write_str("(")?;
+                            arg.write(f, names)?;
+                            f.write_str(")")?;
+                        }
+                        _ => arg.write(f, names)?,
+                    }
+                }
+                Ok(())
+            }
+            Type::Tuple(items) => {
+                f.write_str("(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+                    item.write(f, names)?;
+                }
+                f.write_str(")")
+            }
+            Type::Rigid(rigid) => f.write_str(rigid.ident.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, &mut VarNames::default())
+    }
+}
+
+/// A type that can be used as many different types, by replacing its `vars` with any types. This
+/// is what a `let` is given, so that `let id = |x| x` can be used on anything.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Scheme {
+    /// The type variables that can be replaced.
+    pub vars: Vec<TypeVar>,
+
+    /// The traits the `vars` have to implement, like `Add a`, in the order they're written.
+    pub constraints: Vec<(Trait, TypeVar)>,
+
+    pub ty: Type,
+}
+
+impl Scheme {
+    /// Creates a `Scheme` that's always exactly `ty`.
+    pub fn mono(ty: Type) -> Self {
+        Self {
+            vars: vec![],
+            constraints: vec![],
+            ty,
+        }
+    }
+}
+
+impl fmt::Display for Scheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = VarNames::default();
+        // NOTE: Name the variables in the order they're written in the type, rather than in the
+        // order of the constraints.
+        for var in self.ty.vars() {
+            names.name(var);
+        }
+
+        if !self.constraints.is_empty() {
+            let constraints = self
+                .constraints
+                .iter()
+                .map(|(t, var)| format!("{} {}", t.name(), names.name(*var)))
+                .collect::<Vec<_>>();
+            match constraints.len() {
+                1 => write!(f, "{} => ", constraints[0])?,
+                _ => write!(f, "({}) => ", constraints.join(", "))?,
+            }
+        }
+        self.ty.write(f, &mut names)
+    }
+}
+
+/// Writes types so that they can be read together, like in an error message: the same type variable
+/// always gets the same name. Type variables in `classes` are written as the class they're in, like
+/// `{integer}`, as they're probably from a literal that the reader knows as a number.
+pub fn display_together(types: &[&Type], classes: &HashMap<TypeVar, Class>) -> Vec<String> {
+    let mut names = VarNames::default();
+    for ty in types {
+        ty.rigid_names(&mut names.taken);
+    }
+    for (var, class) in classes {
+        names.names.insert(*var, class.placeholder().to_string());
+    }
+
+    types
+        .iter()
+        .map(|ty| {
+            let mut shown = String::new();
+            let _ = ty.write(&mut shown, &mut names);
+            shown
+        })
+        .collect()
+}
+
+/// Names type variables `a`, `b`, ... `z`, `a1`, `b1`, ... in the order they're seen.
+#[derive(Debug, Default)]
+struct VarNames {
+    names: HashMap<TypeVar, String>,
+
+    /// The number of names given out. Placeholders for classes don't count.
+    next: usize,
+
+    /// The names of rigid types, which type variables can't be given, so that they aren't
+    /// mistaken for each other.
+    taken: HashSet<String>,
+}
+
+impl VarNames {
+    fn name(&mut self, var: TypeVar) -> String {
+        if let Some(name) = self.names.get(&var) {
+            return name.clone();
+        }
+
+        let name = loop {
+            let letter = (b'a' + (self.next % 26) as u8) as char;
+            let name = match self.next / 26 {
+                0 => letter.to_string(),
+                n => format!("{}{}", letter, n),
+            };
+            self.next += 1;
+            if !self.taken.contains(&name) {
+                break name;
+            }
+        };
+        self.names.insert(var, name.clone());
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_display_types() {
+        let (a, b) = (Type::Var(TypeVar(7)), Type::Var(TypeVar(3)));
+        let map = Type::function(
+            Type::function(a.clone(), b.clone()),
+            Type::function(a.clone(), b.clone()),
+        );
+        assert_eq!(map.to_string(), "(a -> b) -> a -> b");
+        assert_eq!(
+            Type::function(Type::Unit, Type::Int(IntType::U8)).to_string(),
+            "() -> U8"
+        );
+
+        let scheme = Scheme {
+            vars: vec![TypeVar(3), TypeVar(7)],
+            constraints: vec![(Trait::Add, TypeVar(3)), (Trait::Eq, TypeVar(7))],
+            ty: Type::function(b.clone(), Type::function(a.clone(), b.clone())),
+        };
+        assert_eq!(scheme.to_string(), "(Add a, Eq b) => a -> b -> a");
+
+        let list = |ty| Type::Named(Ident::new("List"), vec![ty]);
+        assert_eq!(
+            Type::function(
+                list(list(a.clone())),
+                list(Type::function(a.clone(), b.clone()))
+            )
+            .to_string(),
+            "List (List a) -> List (a -> b)"
+        );
+        assert_eq!(
+            list(Type::Tuple(vec![
+                Type::Bool,
+                Type::function(a.clone(), b.clone())
+            ]))
+            .to_string(),
+            "List (Bool, a -> b)"
+        );
+
+        let classes = HashMap::from([(TypeVar(7), Class::Integral)]);
+        let together = display_together(&[&Type::function(a.clone(), b.clone()), &b], &classes);
+        assert_eq!(together, ["{integer} -> a", "a"]);
+    }
+}
@@ -0,0 +1,98 @@
+use super::{identifier::Identifier, value::Value, FstNode};
+use errgonomic::parser::input::Span;
+
+/// An attribute attached to a statement, like `#[inline]` or `#[deprecated("use bar")]`. These
+/// hold metadata about whatever they are attached to, which later passes (tests, FFI, lints, etc.)
+/// can then read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute {
+    /// The location of the attribute, from the `#` to the `]`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The name of the attribute, e.g. the `inline` in `#[inline]`.
+    pub name: Identifier,
+
+    /// The arguments given to the attribute, e.g. the `"c"` in `#[extern("c")]`. This is empty if
+    /// the attribute has no parentheses.
+    pub args: Vec<AttributeArg>,
+
+    /// The locations of the parentheses around the arguments, if there are any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::repr::fst::json::opt_span_pair")
+    )]
+    pub parens: Option<(Span, Span)>,
+
+    /// The location of the comma after the last argument, if there is one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+    pub trailing_comma: Option<Span>,
+}
+
+impl Attribute {
+    /// Creates a new `Attribute` object.
+    pub fn new(location: Span, name: Identifier, args: Vec<AttributeArg>) -> Self {
+        Self {
+            location,
+            name,
+            args,
+            parens: None,
+            trailing_comma: None,
+        }
+    }
+
+    /// Sets the locations of the parentheses around the arguments.
+    pub fn with_parens(mut self, parens: Option<(Span, Span)>) -> Self {
+        self.parens = parens;
+        self
+    }
+
+    /// Sets the location of the comma after the last argument.
+    pub fn with_trailing_comma(mut self, trailing_comma: Option<Span>) -> Self {
+        self.trailing_comma = trailing_comma;
+        self
+    }
+}
+
+impl FstNode for Attribute {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// An argument to an attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttributeArg {
+    /// The location of the argument.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The kind of argument it is.
+    pub kind: AttributeArgKind,
+}
+
+impl AttributeArg {
+    /// Creates a new `AttributeArg` object.
+    pub fn new(location: Span, kind: AttributeArgKind) -> Self {
+        Self { location, kind }
+    }
+}
+
+impl FstNode for AttributeArg {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The kinds of arguments an attribute can take.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttributeArgKind {
+    /// A string, without the surrounding quotes.
+    String(String),
+
+    /// A value.
+    Value(Value),
+}
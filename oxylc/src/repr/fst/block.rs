@@ -3,8 +3,10 @@ use errgonomic::parser::input::Span;
 
 /// An expression.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Block {
     /// The location where the value was found.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
 
     /// The statements in the block to be executed.
@@ -0,0 +1,47 @@
+use super::FstNode;
+use errgonomic::parser::input::Span;
+
+/// A doc comment, like `/// Hello!` or `/** Hello! */`, which documents the statement that follows
+/// it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DocComment {
+    /// The location of the doc comment, including the `///` or `/** */`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The kind of doc comment it is.
+    pub kind: DocCommentKind,
+
+    /// The (Markdown) content of the doc comment, verbatim. This is everything after the `///` up
+    /// until the end of the line, or everything between the `/**` and the `*/`.
+    pub content: String,
+}
+
+impl DocComment {
+    /// Creates a new `DocComment` object.
+    pub fn new(location: Span, kind: DocCommentKind, content: String) -> Self {
+        Self {
+            location,
+            kind,
+            content,
+        }
+    }
+}
+
+impl FstNode for DocComment {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The kinds of doc comments we can have.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DocCommentKind {
+    /// A `///` doc comment.
+    Line,
+
+    /// A `/** */` doc comment.
+    Block,
+}
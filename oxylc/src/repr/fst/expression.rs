@@ -1,10 +1,12 @@
-use super::{block::Block, value::Value, FstNode};
+use super::{block::Block, pattern::Match, value::Value, FstNode};
 use errgonomic::parser::input::Span;
 
 /// An expression.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Expression {
     /// The location where the value was found.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
 
     /// The type of expression it is.
@@ -26,20 +28,39 @@ impl FstNode for Expression {
 
 /// The kinds of expressions we can have.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExpressionKind {
     /// A parenthesized expression
     Parenthesized {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
         lparen_location: Span,
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
         rparen_location: Span,
         inner: Box<Expression>,
     },
 
+    /// A tuple of 2 or more expressions, like `(a, b)`.
+    Tuple {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        lparen_location: Span,
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        rparen_location: Span,
+        items: Vec<Expression>,
+
+        /// The location of the comma after the last item, if there is one.
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+        trailing_comma: Option<Span>,
+    },
+
     /// A value
     Value(Value),
 
     /// A block
     Block(Block),
 
+    /// A `match` expression
+    Match(Match),
+
     /// An infix expression.
     Infix {
         operator: Operator,
@@ -73,7 +94,9 @@ pub enum ExpressionKind {
 
 /// The operator that we found.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Operator {
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     location: Span,
     kind: OperatorKind,
 }
@@ -98,6 +121,7 @@ impl FstNode for Operator {
 
 /// Operators we can have
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OperatorKind {
     /// The `+`
     Plus,
@@ -114,6 +138,24 @@ pub enum OperatorKind {
     /// The `|>`
     Triangle,
 
+    /// The `==`
+    DoubleEquals,
+
+    /// The `!=`
+    BangEquals,
+
+    /// The `<`
+    LeftAngle,
+
+    /// The `<=`
+    LeftAngleEquals,
+
+    /// The `>`
+    RightAngle,
+
+    /// The `>=`
+    RightAngleEquals,
+
     /// The `.`
     Dot,
 
@@ -2,8 +2,10 @@ use super::{expression::Expression, identifier::Identifier, FstNode};
 use errgonomic::parser::input::Span;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function {
     /// The location where the value was found.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
 
     /// The "arguments" to the function (even though they're not really arguments, and every
@@ -12,6 +14,10 @@ pub struct Function {
 
     /// The expression to evaluate within the function.
     pub expression: Box<Expression>,
+
+    /// The location of the comma after the last argument, if there is one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+    pub trailing_comma: Option<Span>,
 }
 
 impl Function {
@@ -20,8 +26,15 @@ impl Function {
             location,
             args,
             expression: Box::new(expression),
+            trailing_comma: None,
         }
     }
+
+    /// Sets the location of the comma after the last argument.
+    pub fn with_trailing_comma(mut self, trailing_comma: Option<Span>) -> Self {
+        self.trailing_comma = trailing_comma;
+        self
+    }
 }
 
 impl FstNode for Function {
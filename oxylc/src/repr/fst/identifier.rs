@@ -1,21 +1,30 @@
 use super::FstNode;
+use crate::repr::ast::ident::Ident;
 use errgonomic::parser::input::Span;
 
-/// An identifier.
+/// An identifier. Its name is interned while parsing, so that every use of a name shares one copy
+/// of it.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Identifier {
     /// The name
-    pub name: String,
+    pub name: Ident,
 
     /// The location of the identifier.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
 }
 
 impl Identifier {
     /// Creates a new `Identifier` object.
-    pub fn new(location: Span, name: String) -> Self {
+    pub fn new(location: Span, name: Ident) -> Self {
         Self { name, location }
     }
+
+    /// Gets the interned `Ident` for this identifier's name.
+    pub fn ident(&self) -> Ident {
+        self.name
+    }
 }
 
 impl FstNode for Identifier {
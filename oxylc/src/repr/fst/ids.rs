@@ -0,0 +1,500 @@
+//! Gives every node in an FST an ID, so that analyses can tell nodes apart even when they have the
+//! same span (like an `Expression` and the `Value` in it). The IDs live in a side table rather than
+//! in the nodes, and are given out in the order the nodes are visited, so building the table twice
+//! for the same tree gives the same IDs.
+
+use super::{
+    attribute::{Attribute, AttributeArgKind},
+    block::Block,
+    doc_comment::DocComment,
+    expression::{Expression, ExpressionKind},
+    function::Function,
+    identifier::Identifier,
+    pattern::{Match, Pattern, PatternKind},
+    statement::{Statement, StatementKind},
+    type_expr::{TypeExpr, TypeExprKind, TypeParams},
+    use_tree::{UseTree, UseTreeKind},
+    value::{Value, ValueKind},
+    File, FstNode,
+};
+use errgonomic::parser::input::Span;
+use std::collections::HashMap;
+
+/// The ID of a node in a `NodeMap`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(pub usize);
+
+/// The kinds of nodes that get IDs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    File,
+    Statement,
+    Attribute,
+    DocComment,
+    UseTree,
+    Expression,
+    Value,
+    Identifier,
+    Function,
+    Block,
+    TypeExpr,
+    Match,
+    Pattern,
+}
+
+/// A reference to any node that gets an ID.
+#[derive(Copy, Clone, Debug)]
+pub enum NodeRef<'a> {
+    File(&'a File),
+    Statement(&'a Statement),
+    Attribute(&'a Attribute),
+    DocComment(&'a DocComment),
+    UseTree(&'a UseTree),
+    Expression(&'a Expression),
+    Value(&'a Value),
+    Identifier(&'a Identifier),
+    Function(&'a Function),
+    Block(&'a Block),
+    TypeExpr(&'a TypeExpr),
+    Match(&'a Match),
+    Pattern(&'a Pattern),
+}
+
+impl NodeRef<'_> {
+    /// Gets the kind of node it is.
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            NodeRef::File(_) => NodeKind::File,
+            NodeRef::Statement(_) => NodeKind::Statement,
+            NodeRef::Attribute(_) => NodeKind::Attribute,
+            NodeRef::DocComment(_) => NodeKind::DocComment,
+            NodeRef::UseTree(_) => NodeKind::UseTree,
+            NodeRef::Expression(_) => NodeKind::Expression,
+            NodeRef::Value(_) => NodeKind::Value,
+            NodeRef::Identifier(_) => NodeKind::Identifier,
+            NodeRef::Function(_) => NodeKind::Function,
+            NodeRef::Block(_) => NodeKind::Block,
+            NodeRef::TypeExpr(_) => NodeKind::TypeExpr,
+            NodeRef::Match(_) => NodeKind::Match,
+            NodeRef::Pattern(_) => NodeKind::Pattern,
+        }
+    }
+
+    /// Gets the location of the node.
+    pub fn location(&self) -> Span {
+        *match self {
+            NodeRef::File(node) => node.location(),
+            NodeRef::Statement(node) => node.location(),
+            NodeRef::Attribute(node) => node.location(),
+            NodeRef::DocComment(node) => node.location(),
+            NodeRef::UseTree(node) => node.location(),
+            NodeRef::Expression(node) => node.location(),
+            NodeRef::Value(node) => node.location(),
+            NodeRef::Identifier(node) => node.location(),
+            NodeRef::Function(node) => node.location(),
+            NodeRef::Block(node) => node.location(),
+            NodeRef::TypeExpr(node) => node.location(),
+            NodeRef::Match(node) => node.location(),
+            NodeRef::Pattern(node) => node.location(),
+        }
+    }
+
+    /// Gets what identifies the node: its kind and its address. Nodes of different kinds can have
+    /// the same address (like an expression and the value at the start of it), but nodes of the
+    /// same kind can't.
+    fn key(&self) -> (NodeKind, *const ()) {
+        let address = match self {
+            NodeRef::File(node) => *node as *const File as *const (),
+            NodeRef::Statement(node) => *node as *const Statement as *const (),
+            NodeRef::Attribute(node) => *node as *const Attribute as *const (),
+            NodeRef::DocComment(node) => *node as *const DocComment as *const (),
+            NodeRef::UseTree(node) => *node as *const UseTree as *const (),
+            NodeRef::Expression(node) => *node as *const Expression as *const (),
+            NodeRef::Value(node) => *node as *const Value as *const (),
+            NodeRef::Identifier(node) => *node as *const Identifier as *const (),
+            NodeRef::Function(node) => *node as *const Function as *const (),
+            NodeRef::Block(node) => *node as *const Block as *const (),
+            NodeRef::TypeExpr(node) => *node as *const TypeExpr as *const (),
+            NodeRef::Match(node) => *node as *const Match as *const (),
+            NodeRef::Pattern(node) => *node as *const Pattern as *const (),
+        };
+        (self.kind(), address)
+    }
+}
+
+/// The IDs of every node in a file, along with the parent of each of them.
+#[derive(Clone, Debug)]
+pub struct NodeMap<'a> {
+    /// The nodes, along with their parents, indexed by their IDs. Nodes are in the order they're
+    /// visited, so parents always come before their children.
+    nodes: Vec<(NodeRef<'a>, Option<NodeId>)>,
+
+    ids: HashMap<(NodeKind, *const ()), NodeId>,
+}
+
+impl<'a> NodeMap<'a> {
+    /// Gives every node in `file` an ID.
+    pub fn new(file: &'a File) -> Self {
+        let mut builder = Builder {
+            map: NodeMap {
+                nodes: vec![],
+                ids: HashMap::new(),
+            },
+            parents: vec![],
+        };
+        builder.file(file);
+        builder.map
+    }
+
+    /// Gets the number of nodes in the map.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Checks if there are no nodes in the map, which never happens, as there's always a file.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Gets the ID of a node, if it's in the file the map was made for.
+    pub fn id(&self, node: NodeRef<'_>) -> Option<NodeId> {
+        self.ids.get(&node.key()).copied()
+    }
+
+    /// Gets the node with the given ID.
+    pub fn node(&self, id: NodeId) -> NodeRef<'a> {
+        self.nodes[id.0].0
+    }
+
+    /// Gets the parent of the node with the given ID, if it isn't the file.
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id.0].1
+    }
+
+    /// Gets the node with the given ID and all of its ancestors, innermost first.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(Some(id), |&id| self.parent(id))
+    }
+
+    /// Gets the innermost node at `offset`.
+    pub fn node_at(&self, offset: usize) -> Option<NodeId> {
+        self.innermost(|node| {
+            let location = node.location();
+            location.start() <= offset && offset < location.end()
+        })
+    }
+
+    /// Gets the innermost expression that contains all of `span`.
+    pub fn expression_containing(&self, span: Span) -> Option<NodeId> {
+        self.innermost(|node| {
+            let location = node.location();
+            node.kind() == NodeKind::Expression
+                && location.start() <= span.start()
+                && span.end() <= location.end()
+        })
+    }
+
+    /// Gets the innermost node that matches `predicate`. As parents come before their children,
+    /// and nodes at the same place are nested, this is the last one that matches.
+    fn innermost(&self, predicate: impl Fn(&NodeRef<'a>) -> bool) -> Option<NodeId> {
+        self.nodes
+            .iter()
+            .rposition(|(node, _)| predicate(node))
+            .map(NodeId)
+    }
+}
+
+/// Visits every node, and gives it the next ID.
+///
+/// NOTE: This doesn't use `FstVisitor`, as it only gives out references for as long as each call,
+/// and the map needs ones that live as long as the file.
+struct Builder<'a> {
+    map: NodeMap<'a>,
+
+    /// The nodes being visited, innermost last.
+    parents: Vec<NodeId>,
+}
+
+impl<'a> Builder<'a> {
+    /// Gives `node` an ID, and visits its children with `walk`.
+    fn node(&mut self, node: NodeRef<'a>, walk: impl FnOnce(&mut Self)) {
+        let id = NodeId(self.map.nodes.len());
+        self.map.nodes.push((node, self.parents.last().copied()));
+        self.map.ids.insert(node.key(), id);
+
+        self.parents.push(id);
+        walk(self);
+        self.parents.pop();
+    }
+
+    fn file(&mut self, file: &'a File) {
+        self.node(NodeRef::File(file), |this| {
+            for statement in &file.statements {
+                this.statement(statement);
+            }
+        })
+    }
+
+    fn statement(&mut self, statement: &'a Statement) {
+        self.node(NodeRef::Statement(statement), |this| {
+            for doc in &statement.docs {
+                this.node(NodeRef::DocComment(doc), |_| {});
+            }
+            for attribute in &statement.attributes {
+                this.attribute(attribute);
+            }
+
+            match &statement.kind {
+                StatementKind::Let {
+                    ident,
+                    signature,
+                    expression,
+                    ..
+                } => {
+                    this.ident(ident);
+                    if let Some(signature) = signature {
+                        if let Some(params) = &signature.params {
+                            this.type_params(params);
+                        }
+                        this.type_expr(&signature.ty);
+                    }
+                    this.expression(expression);
+                }
+                StatementKind::Mod { ident } => this.ident(ident),
+                StatementKind::Use { tree } => this.use_tree(tree),
+                StatementKind::Impl {
+                    trait_ident,
+                    type_ident,
+                    expression,
+                } => {
+                    this.ident(trait_ident);
+                    this.ident(type_ident);
+                    this.expression(expression);
+                }
+                StatementKind::Type {
+                    ident,
+                    params,
+                    constructors,
+                } => {
+                    this.ident(ident);
+                    if let Some(params) = params {
+                        this.type_params(params);
+                    }
+                    for constructor in constructors {
+                        this.ident(&constructor.ident);
+                        for field in &constructor.fields {
+                            this.type_expr(field);
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    fn attribute(&mut self, attribute: &'a Attribute) {
+        self.node(NodeRef::Attribute(attribute), |this| {
+            this.ident(&attribute.name);
+            for arg in &attribute.args {
+                if let AttributeArgKind::Value(value) = &arg.kind {
+                    this.value(value);
+                }
+            }
+        })
+    }
+
+    fn use_tree(&mut self, tree: &'a UseTree) {
+        self.node(NodeRef::UseTree(tree), |this| {
+            for ident in &tree.path {
+                this.ident(ident);
+            }
+            match &tree.kind {
+                UseTreeKind::Simple { alias } => {
+                    if let Some(alias) = alias {
+                        this.ident(alias);
+                    }
+                }
+                UseTreeKind::Group(trees) => {
+                    for tree in trees {
+                        this.use_tree(tree);
+                    }
+                }
+            }
+        })
+    }
+
+    fn expression(&mut self, expression: &'a Expression) {
+        self.node(NodeRef::Expression(expression), |this| {
+            match &expression.kind {
+                ExpressionKind::Parenthesized { inner, .. } => this.expression(inner),
+                ExpressionKind::Tuple { items, .. } => {
+                    for item in items {
+                        this.expression(item);
+                    }
+                }
+                ExpressionKind::Value(value) => this.value(value),
+                ExpressionKind::Block(block) => this.block(block),
+                ExpressionKind::Match(m) => this.match_expr(m),
+                ExpressionKind::Infix { lhs, rhs, .. }
+                | ExpressionKind::Application {
+                    function: lhs,
+                    arg: rhs,
+                } => {
+                    this.expression(lhs);
+                    this.expression(rhs);
+                }
+                ExpressionKind::Prefix { rhs: inner, .. }
+                | ExpressionKind::Postfix { lhs: inner, .. } => this.expression(inner),
+            }
+        })
+    }
+
+    fn value(&mut self, value: &'a Value) {
+        self.node(NodeRef::Value(value), |this| match &value.kind {
+            ValueKind::Identifier(ident) => this.ident(ident),
+            ValueKind::Function(function) => this.function(function),
+            ValueKind::Integer(_) | ValueKind::Floating(_) | ValueKind::Boolean(_) => {}
+        })
+    }
+
+    fn ident(&mut self, ident: &'a Identifier) {
+        self.node(NodeRef::Identifier(ident), |_| {})
+    }
+
+    fn function(&mut self, function: &'a Function) {
+        self.node(NodeRef::Function(function), |this| {
+            for arg in &function.args {
+                this.ident(arg);
+            }
+            this.expression(&function.expression);
+        })
+    }
+
+    fn block(&mut self, block: &'a Block) {
+        self.node(NodeRef::Block(block), |this| {
+            for statement in &block.statements {
+                this.statement(statement);
+            }
+            this.expression(&block.expression);
+        })
+    }
+
+    fn match_expr(&mut self, m: &'a Match) {
+        self.node(NodeRef::Match(m), |this| {
+            this.expression(&m.scrutinee);
+            for arm in &m.arms {
+                this.pattern(&arm.pattern);
+                this.expression(&arm.expression);
+            }
+        })
+    }
+
+    fn pattern(&mut self, pattern: &'a Pattern) {
+        self.node(NodeRef::Pattern(pattern), |this| match &pattern.kind {
+            PatternKind::Named { ident, args } => {
+                this.ident(ident);
+                for arg in args {
+                    this.pattern(arg);
+                }
+            }
+            PatternKind::Value(value) => this.value(value),
+            PatternKind::Parenthesized { inner, .. } => this.pattern(inner),
+            PatternKind::Tuple { items, .. } => {
+                for item in items {
+                    this.pattern(item);
+                }
+            }
+        })
+    }
+
+    fn type_params(&mut self, params: &'a TypeParams) {
+        for param in &params.params {
+            self.ident(&param.ident);
+            for bound in &param.bounds {
+                self.ident(bound);
+            }
+        }
+    }
+
+    fn type_expr(&mut self, ty: &'a TypeExpr) {
+        self.node(NodeRef::TypeExpr(ty), |this| match &ty.kind {
+            TypeExprKind::Named { ident, args } => {
+                this.ident(ident);
+                for arg in args {
+                    this.type_expr(arg);
+                }
+            }
+            TypeExprKind::Unit => {}
+            TypeExprKind::Parenthesized { inner, .. } => this.type_expr(inner),
+            TypeExprKind::Tuple { items, .. } => {
+                for item in items {
+                    this.type_expr(item);
+                }
+            }
+            TypeExprKind::Function { param, result, .. } => {
+                this.type_expr(param);
+                this.type_expr(result);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::parser::parse;
+
+    const SOURCE: &str = "/// Docs.\nlet f = |x| { x + 1 }\nlet y = abc\n";
+
+    #[test]
+    fn can_tell_nodes_apart() {
+        let file = parse(SOURCE).unwrap();
+        let map = NodeMap::new(&file);
+        assert_eq!(map.id(NodeRef::File(&file)), Some(NodeId(0)));
+
+        // NOTE: `abc` is an expression, a value and an identifier, all at the same place.
+        let StatementKind::Let { expression, .. } = &file.statements[1].kind else {
+            panic!("expected a let");
+        };
+        let ExpressionKind::Value(value) = &expression.kind else {
+            panic!("expected a value");
+        };
+        let expression = map.id(NodeRef::Expression(expression)).unwrap();
+        let value = map.id(NodeRef::Value(value)).unwrap();
+        assert_ne!(expression, value);
+        assert_eq!(map.node(expression).location(), map.node(value).location());
+        assert_eq!(map.parent(value), Some(expression));
+
+        let statement = map.id(NodeRef::Statement(&file.statements[1])).unwrap();
+        assert_eq!(
+            map.ancestors(value).collect::<Vec<_>>(),
+            [value, expression, statement, NodeId(0)]
+        );
+
+        // NOTE: IDs only depend on the shape of the tree.
+        let clone = file.clone();
+        assert_eq!(
+            NodeMap::new(&clone).id(NodeRef::Statement(&clone.statements[1])),
+            Some(statement)
+        );
+        assert_eq!(map.id(NodeRef::Statement(&clone.statements[1])), None);
+    }
+
+    #[test]
+    fn can_find_nodes_at_offsets() {
+        let file = parse(SOURCE).unwrap();
+        let map = NodeMap::new(&file);
+
+        let at = |offset: usize| map.node(map.node_at(offset).unwrap()).kind();
+        assert_eq!(at(0), NodeKind::DocComment);
+        assert_eq!(at(SOURCE.find("x +").unwrap()), NodeKind::Identifier);
+        assert_eq!(at(SOURCE.find("+").unwrap()), NodeKind::Expression);
+        assert_eq!(at(SOURCE.find("abc").unwrap()), NodeKind::Identifier);
+        assert_eq!(map.node_at(SOURCE.len()), None);
+
+        let plus = SOURCE.find("+").unwrap();
+        let infix = map.expression_containing((plus..plus + 3).into()).unwrap();
+        assert_eq!(map.node(infix).location(), (plus - 2..plus + 3).into());
+        let block = map.parent(infix).unwrap();
+        assert_eq!(map.node(block).kind(), NodeKind::Block);
+    }
+}
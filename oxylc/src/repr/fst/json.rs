@@ -0,0 +1,221 @@
+//! Serializes the FST to JSON and back, for tools outside of the compiler. This is only available
+//! with the `serde` feature.
+//!
+//! The JSON is an object with the version of the schema and the file itself:
+//! ```json
+//! { "version": 6, "file": { "statements": [ ... ], "location": [0, 10] } }
+//! ```
+//! Every struct is an object with the same fields as in Rust, and every enum is "externally
+//! tagged": a variant without fields is just its name (like `"Private"`), and any other variant is
+//! an object with the name as its only key (like `{ "Integer": 42 }` or `{ "Mod": { "ident": ... } }`).
+//! Every `Span` is an array of its start and end, as byte offsets into the source, and a missing
+//! `Option` is `null`.
+//!
+//! Any change to the FST that changes the JSON must also bump `SCHEMA_VERSION`, so that tools can
+//! tell which version they're reading.
+
+use super::File;
+use serde::{Deserialize, Serialize};
+
+/// The version of the JSON schema. Since version 2, it has changed for:
+/// - 3: `impl` statements, and the comparison operators.
+/// - 4: `type` statements, type parameters, and type signatures on `let` statements.
+/// - 5: `match` expressions, patterns, and tuples.
+/// - 6: integers are unsigned, so they can be as large as a `U64`, but can't be negative.
+pub const SCHEMA_VERSION: u32 = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JsonError {
+    #[error("Invalid FST JSON: {0}")]
+    Invalid(String),
+
+    #[error("Unsupported FST JSON version {found}, expected version {expected}")]
+    UnsupportedVersion { found: u64, expected: u32 },
+}
+
+/// The top-level JSON object.
+#[derive(Serialize)]
+struct Versioned<'a> {
+    version: u32,
+    file: &'a File,
+}
+
+/// Serializes a file to (compact) JSON.
+pub fn to_json(file: &File) -> String {
+    let versioned = Versioned {
+        version: SCHEMA_VERSION,
+        file,
+    };
+
+    // NOTE: Every key is a string, so this can't fail.
+    serde_json::to_string(&versioned).expect("the FST can always be serialized")
+}
+
+/// Deserializes a file from JSON, checking that it's the right version.
+pub fn from_json(json: &str) -> Result<File, JsonError> {
+    let mut value = serde_json::from_str::<serde_json::Value>(json)
+        .map_err(|err| JsonError::Invalid(err.to_string()))?;
+
+    match value.get("version").and_then(serde_json::Value::as_u64) {
+        Some(version) if version == SCHEMA_VERSION as u64 => {}
+        Some(version) => {
+            return Err(JsonError::UnsupportedVersion {
+                found: version,
+                expected: SCHEMA_VERSION,
+            })
+        }
+        None => return Err(JsonError::Invalid("missing the `version` field".into())),
+    }
+
+    let file = value
+        .get_mut("file")
+        .map(serde_json::Value::take)
+        .ok_or_else(|| JsonError::Invalid("missing the `file` field".into()))?;
+
+    File::deserialize(file).map_err(|err| JsonError::Invalid(err.to_string()))
+}
+
+/// Serializes a `Span` as `[start, end]`. Use with `#[serde(with = "...")]`.
+pub mod span {
+    use errgonomic::parser::input::Span;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(span: &Span, serializer: S) -> Result<S::Ok, S::Error> {
+        [span.start(), span.end()].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Span, D::Error> {
+        let [start, end] = <[usize; 2]>::deserialize(deserializer)?;
+        Ok((start..end).into())
+    }
+}
+
+/// Serializes an `Option<Span>` as `[start, end]` or `null`. Use with `#[serde(with = "...")]`.
+pub mod opt_span {
+    use errgonomic::parser::input::Span;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(span: &Option<Span>, serializer: S) -> Result<S::Ok, S::Error> {
+        span.map(|span| [span.start(), span.end()])
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Span>, D::Error> {
+        let span = Option::<[usize; 2]>::deserialize(deserializer)?;
+        Ok(span.map(|[start, end]| (start..end).into()))
+    }
+}
+
+/// Serializes an `Option<(Span, Span)>` as `[[start, end], [start, end]]` or `null`. Use with
+/// `#[serde(with = "...")]`.
+pub mod opt_span_pair {
+    use errgonomic::parser::input::Span;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        spans: &Option<(Span, Span)>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        spans
+            .map(|(a, b)| [[a.start(), a.end()], [b.start(), b.end()]])
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<(Span, Span)>, D::Error> {
+        let spans = Option::<[[usize; 2]; 2]>::deserialize(deserializer)?;
+        Ok(spans.map(|[[a1, a2], [b1, b2]]| ((a1..a2).into(), (b1..b2).into())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::parser::parse;
+    use std::{fs, path::Path};
+
+    /// Gets all the `oxyl` code blocks in the Markdown files in `dir`, along with where they are.
+    fn examples(dir: &Path, out: &mut Vec<(String, String)>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.is_dir() {
+                examples(&path, out);
+            } else if path.extension().is_some_and(|ext| ext == "md") {
+                let source = fs::read_to_string(&path).unwrap();
+                let mut lines = source.lines().enumerate();
+
+                while let Some((start, line)) = lines.next() {
+                    if line.trim() == "```oxyl" {
+                        let code = lines
+                            .by_ref()
+                            .map(|(_, line)| line)
+                            .take_while(|line| line.trim() != "```")
+                            .fold(String::new(), |code, line| code + line + "\n");
+                        out.push((format!("{}:{}", path.display(), start + 1), code));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn can_round_trip_spec_examples() {
+        let mut found = vec![];
+        examples(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("../spec/src"),
+            &mut found,
+        );
+        assert!(!found.is_empty(), "there are no examples in the spec");
+
+        for (location, code) in found {
+            let file = parse(&code)
+                .unwrap_or_else(|err| panic!("couldn't parse the example at {location}: {err:?}"));
+            let json = to_json(&file);
+            assert_eq!(from_json(&json), Ok(file), "at {location}");
+        }
+    }
+
+    #[test]
+    fn can_serialize_spans_and_operators() {
+        let file = parse("let x = 1 + 2\n").unwrap();
+        let json = serde_json::from_str::<serde_json::Value>(&to_json(&file)).unwrap();
+        let expression = &json["file"]["statements"][0]["kind"]["Let"]["expression"];
+
+        assert_eq!(json["version"], 6);
+        assert_eq!(expression["location"], serde_json::json!([8, 13]));
+        assert_eq!(
+            expression["kind"]["Infix"]["operator"],
+            serde_json::json!({ "location": [10, 11], "kind": "Plus" })
+        );
+    }
+
+    #[test]
+    fn can_serialize_unsigned_integers() {
+        let file = parse("let x = 18446744073709551615\n").unwrap();
+        let json = to_json(&file);
+        assert!(json.contains(r#"{"Integer":18446744073709551615}"#));
+        assert_eq!(from_json(&json), Ok(file));
+
+        let negative = json.replace("18446744073709551615", "-1");
+        assert!(matches!(from_json(&negative), Err(JsonError::Invalid(_))));
+    }
+
+    #[test]
+    fn cant_deserialize_other_versions() {
+        assert_eq!(
+            from_json(r#"{ "version": 5, "file": {} }"#),
+            Err(JsonError::UnsupportedVersion {
+                found: 5,
+                expected: SCHEMA_VERSION,
+            })
+        );
+        assert!(matches!(
+            from_json(r#"{ "file": {} }"#),
+            Err(JsonError::Invalid(_))
+        ));
+    }
+}
@@ -4,16 +4,26 @@
 //! FST once, and then run formatters/LSPs/the actual compiler on it, and therefore save time by
 //! only needing 1 parser.
 
+pub mod attribute;
 pub mod block;
+pub mod doc_comment;
 pub mod expression;
 pub mod function;
 pub mod identifier;
+pub mod ids;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod pattern;
 pub mod statement;
+pub mod trivia;
+pub mod type_expr;
+pub mod use_tree;
 pub mod value;
 pub mod visitor;
 
 use errgonomic::parser::input::Span;
 use statement::Statement;
+use trivia::Trivia;
 
 pub trait FstNode {
     /// Gets the location of the `FstNode`, as a `Span`.
@@ -21,12 +31,18 @@ pub trait FstNode {
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct File {
     /// The statements in the file.
     pub statements: Vec<Statement>,
 
     /// The location of the file.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
+
+    /// The whitespace and (non-doc) comments in the file, in order. Along with everything else in
+    /// the FST, this is enough to print out the exact source that was parsed.
+    pub trivia: Vec<Trivia>,
 }
 
 impl File {
@@ -35,8 +51,15 @@ impl File {
         Self {
             location,
             statements,
+            trivia: vec![],
         }
     }
+
+    /// Attaches the given trivia to the `File`.
+    pub fn with_trivia(mut self, trivia: Vec<Trivia>) -> Self {
+        self.trivia = trivia;
+        self
+    }
 }
 
 impl FstNode for File {
@@ -0,0 +1,141 @@
+use super::{expression::Expression, identifier::Identifier, value::Value, FstNode};
+use errgonomic::parser::input::Span;
+
+/// A `match` expression, like `match x { 0 => a, _ => b }`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Match {
+    /// The location of the whole `match`, from the keyword to the `}`.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The value being matched on.
+    pub scrutinee: Box<Expression>,
+
+    /// The arms, in the order they're tried in.
+    pub arms: Vec<Arm>,
+}
+
+impl Match {
+    /// Creates a new `Match` object.
+    pub fn new(location: Span, scrutinee: Expression, arms: Vec<Arm>) -> Self {
+        Self {
+            location,
+            scrutinee: Box::new(scrutinee),
+            arms,
+        }
+    }
+}
+
+impl FstNode for Match {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// An arm of a `match`, like `Cons x _ => x`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Arm {
+    /// The location of the arm, from its pattern to its expression (without the comma).
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    pub pattern: Pattern,
+
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub arrow_location: Span,
+
+    pub expression: Expression,
+
+    /// The location of the comma after the arm, if there is one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+    pub comma: Option<Span>,
+}
+
+impl Arm {
+    /// Creates a new `Arm` object, without a comma after it.
+    pub fn new(pattern: Pattern, arrow_location: Span, expression: Expression) -> Self {
+        Self {
+            location: pattern.location.union_between(expression.location),
+            pattern,
+            arrow_location,
+            expression,
+            comma: None,
+        }
+    }
+
+    /// Sets the location of the comma after the arm.
+    pub fn with_comma(mut self, comma: Option<Span>) -> Self {
+        self.comma = comma;
+        self
+    }
+}
+
+impl FstNode for Arm {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// A pattern, which a value can be matched against, like `Cons x (Cons _ _)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pattern {
+    /// The kind of pattern it is.
+    pub kind: PatternKind,
+
+    /// The location of the pattern.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+}
+
+impl Pattern {
+    /// Creates a new `Pattern` object.
+    pub fn new(location: Span, kind: PatternKind) -> Self {
+        Self { kind, location }
+    }
+}
+
+impl FstNode for Pattern {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The kinds of patterns we can write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PatternKind {
+    /// A name, along with the patterns it's applied to, like `x`, `_` or `Cons x rest`. Whether
+    /// it's a constructor or binds the value is only decided when it's lowered.
+    Named {
+        ident: Identifier,
+        args: Vec<Pattern>,
+    },
+
+    /// A literal, like `1` or `True`.
+    Value(Value),
+
+    /// A pattern in parentheses, like `(Cons x rest)`.
+    Parenthesized {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        lparen_location: Span,
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        rparen_location: Span,
+        inner: Box<Pattern>,
+    },
+
+    /// A tuple of 2 or more patterns, like `(x, True)`.
+    Tuple {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        lparen_location: Span,
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        rparen_location: Span,
+        items: Vec<Pattern>,
+
+        /// The location of the comma after the last item, if there is one.
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+        trailing_comma: Option<Span>,
+    },
+}
@@ -1,20 +1,64 @@
-use super::{expression::Expression, identifier::Identifier, FstNode};
+use super::{
+    attribute::Attribute,
+    doc_comment::DocComment,
+    expression::Expression,
+    identifier::Identifier,
+    type_expr::{Constructor, Signature, TypeParams},
+    use_tree::UseTree,
+    FstNode,
+};
 use errgonomic::parser::input::Span;
 
 /// An expression.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Statement {
     /// The type of statement it is.
     pub kind: StatementKind,
 
-    /// The location where the value was found.
+    /// The location where the value was found. Note that this does not include the doc comments,
+    /// attributes or visibility.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
+
+    /// The visibility of the statement.
+    pub visibility: Visibility,
+
+    /// The doc comments attached to the statement.
+    pub docs: Vec<DocComment>,
+
+    /// The attributes attached to the statement.
+    pub attributes: Vec<Attribute>,
 }
 
 impl Statement {
     /// Creates a new `Expression` object.
     pub fn new(location: Span, kind: StatementKind) -> Self {
-        Self { kind, location }
+        Self {
+            kind,
+            location,
+            visibility: Visibility::Private,
+            docs: vec![],
+            attributes: vec![],
+        }
+    }
+
+    /// Sets the visibility of the `Statement`.
+    pub fn with_visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Attaches the given doc comments to the `Statement`.
+    pub fn with_docs(mut self, docs: Vec<DocComment>) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    /// Attaches the given attributes to the `Statement`.
+    pub fn with_attributes(mut self, attributes: Vec<Attribute>) -> Self {
+        self.attributes = attributes;
+        self
     }
 }
 
@@ -26,11 +70,59 @@ impl FstNode for Statement {
 
 /// The kinds of statements we can have.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatementKind {
     /// A `let`-statement, representing assignment.
     Let {
         is_mutable: bool,
         ident: Identifier,
+
+        /// The type written for the `let`, if there is one, like `[a]: a -> a`.
+        signature: Option<Signature>,
+
+        expression: Expression,
+    },
+
+    /// A `mod`-statement, declaring a child module.
+    Mod { ident: Identifier },
+
+    /// A `use`-statement, importing things from other modules.
+    Use { tree: UseTree },
+
+    /// An `impl`-statement, implementing a trait for a type by giving the trait's method, like
+    /// `impl Add for Bool = |a, b| a`.
+    Impl {
+        trait_ident: Identifier,
+        type_ident: Identifier,
         expression: Expression,
     },
+
+    /// A `type`-statement, declaring a type and the constructors of its values, like
+    /// `type List[a] = Nil | Cons a (List a)`.
+    Type {
+        ident: Identifier,
+        params: Option<TypeParams>,
+        constructors: Vec<Constructor>,
+    },
+}
+
+/// Whether a statement can be seen from outside of its module.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Visibility {
+    /// Visible only inside the module (and its children).
+    Private,
+
+    /// Visible everywhere, marked with `pub`.
+    Public {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        location: Span,
+    },
+}
+
+impl Visibility {
+    /// Checks if this is `Visibility::Public`.
+    pub fn is_public(&self) -> bool {
+        matches!(self, Visibility::Public { .. })
+    }
 }
@@ -0,0 +1,49 @@
+use super::FstNode;
+use errgonomic::parser::input::Span;
+
+/// Something in the source which doesn't affect what the program means, like whitespace or a
+/// comment. Note that doc comments aren't trivia, as they're attached to statements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trivia {
+    /// The location of the trivia.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The kind of trivia it is.
+    pub kind: TriviaKind,
+
+    /// The text of the trivia, exactly as it is in the source.
+    pub text: String,
+}
+
+impl Trivia {
+    /// Creates a new `Trivia` object.
+    pub fn new(location: Span, kind: TriviaKind, text: String) -> Self {
+        Self {
+            location,
+            kind,
+            text,
+        }
+    }
+}
+
+impl FstNode for Trivia {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The kinds of trivia we can have.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriviaKind {
+    /// A run of whitespace, including newlines.
+    Whitespace,
+
+    /// A `//` comment, not including the line ending.
+    LineComment,
+
+    /// A `/* */` comment.
+    BlockComment,
+}
@@ -0,0 +1,208 @@
+use super::{identifier::Identifier, FstNode};
+use errgonomic::parser::input::Span;
+
+/// A type, as it's written in the source, like `List a -> I64`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeExpr {
+    /// The kind of type it is.
+    pub kind: TypeExprKind,
+
+    /// The location of the type.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+}
+
+impl TypeExpr {
+    /// Creates a new `TypeExpr` object.
+    pub fn new(location: Span, kind: TypeExprKind) -> Self {
+        Self { kind, location }
+    }
+}
+
+impl FstNode for TypeExpr {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The kinds of types we can write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TypeExprKind {
+    /// A type by its name, along with the types it's applied to, like `I64` or `List a`.
+    Named {
+        ident: Identifier,
+        args: Vec<TypeExpr>,
+    },
+
+    /// `()`, the type of nothing at all.
+    Unit,
+
+    /// A type in parentheses, like `(List a)`.
+    Parenthesized {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        lparen_location: Span,
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        rparen_location: Span,
+        inner: Box<TypeExpr>,
+    },
+
+    /// A tuple of 2 or more types, like `(I64, Bool)`.
+    Tuple {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        lparen_location: Span,
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        rparen_location: Span,
+        items: Vec<TypeExpr>,
+
+        /// The location of the comma after the last item, if there is one.
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+        trailing_comma: Option<Span>,
+    },
+
+    /// A function from `param` to `result`, like `a -> b`.
+    Function {
+        #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+        arrow_location: Span,
+        param: Box<TypeExpr>,
+        result: Box<TypeExpr>,
+    },
+}
+
+/// The type parameters of a `type` or a `let`, like `[a, b]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeParams {
+    /// The location of the parameters, including the square brackets.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    pub params: Vec<TypeParam>,
+
+    /// The location of the comma after the last parameter, if there is one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+    pub trailing_comma: Option<Span>,
+}
+
+impl TypeParams {
+    /// Creates a new `TypeParams` object.
+    pub fn new(location: Span, params: Vec<TypeParam>) -> Self {
+        Self {
+            location,
+            params,
+            trailing_comma: None,
+        }
+    }
+
+    /// Sets the location of the comma after the last parameter.
+    pub fn with_trailing_comma(mut self, trailing_comma: Option<Span>) -> Self {
+        self.trailing_comma = trailing_comma;
+        self
+    }
+}
+
+impl FstNode for TypeParams {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// A type parameter, along with the traits it has to implement, like `a: Add + Eq`. Only the
+/// parameters of a `let` can have bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeParam {
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    pub ident: Identifier,
+
+    /// The traits the parameter has to implement, in order.
+    pub bounds: Vec<Identifier>,
+}
+
+impl TypeParam {
+    /// Creates a new `TypeParam` object, without any bounds.
+    pub fn new(location: Span, ident: Identifier) -> Self {
+        Self {
+            location,
+            ident,
+            bounds: vec![],
+        }
+    }
+
+    /// Sets the traits the parameter has to implement.
+    pub fn with_bounds(mut self, bounds: Vec<Identifier>) -> Self {
+        self.bounds = bounds;
+        self
+    }
+}
+
+impl FstNode for TypeParam {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The type written for a `let`, like `[a]: a -> a`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Signature {
+    /// The location of the signature, from its type parameters (or its `:`) to the end of its
+    /// type.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The type parameters, which are the only type variables the type can use.
+    pub params: Option<TypeParams>,
+
+    pub ty: TypeExpr,
+}
+
+impl Signature {
+    /// Creates a new `Signature` object.
+    pub fn new(location: Span, params: Option<TypeParams>, ty: TypeExpr) -> Self {
+        Self {
+            location,
+            params,
+            ty,
+        }
+    }
+}
+
+impl FstNode for Signature {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// One of the ways to make a value of a type declared by a `type`-statement, like `Cons a (List a)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constructor {
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    pub ident: Identifier,
+
+    /// The types of the values the constructor takes, in order.
+    pub fields: Vec<TypeExpr>,
+}
+
+impl Constructor {
+    /// Creates a new `Constructor` object.
+    pub fn new(location: Span, ident: Identifier, fields: Vec<TypeExpr>) -> Self {
+        Self {
+            location,
+            ident,
+            fields,
+        }
+    }
+}
+
+impl FstNode for Constructor {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
@@ -0,0 +1,61 @@
+use super::{identifier::Identifier, FstNode};
+use errgonomic::parser::input::Span;
+
+/// The tree of things being imported by a `use`-statement. For example, `a::b::{c, d as e}` is a
+/// tree with the path `a::b`, which has 2 children: `c` and `d as e`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UseTree {
+    /// The location of the tree.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
+    pub location: Span,
+
+    /// The path leading up to the thing(s) being imported. For a simple import (like `a::b`), the
+    /// last segment is the name of the thing being imported.
+    pub path: Vec<Identifier>,
+
+    /// The kind of tree it is.
+    pub kind: UseTreeKind,
+
+    /// The location of the comma after the last tree in a group, if there is one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::opt_span"))]
+    pub trailing_comma: Option<Span>,
+}
+
+impl UseTree {
+    /// Creates a new `UseTree` object.
+    pub fn new(location: Span, path: Vec<Identifier>, kind: UseTreeKind) -> Self {
+        Self {
+            location,
+            path,
+            kind,
+            trailing_comma: None,
+        }
+    }
+
+    /// Sets the location of the comma after the last tree in a group.
+    pub fn with_trailing_comma(mut self, trailing_comma: Option<Span>) -> Self {
+        self.trailing_comma = trailing_comma;
+        self
+    }
+}
+
+impl FstNode for UseTree {
+    fn location(&self) -> &Span {
+        &self.location
+    }
+}
+
+/// The kinds of use-trees we can have.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UseTreeKind {
+    /// A simple import, like `a::b` or `a::b as c`.
+    Simple {
+        /// The name the thing is imported as, if it's renamed.
+        alias: Option<Identifier>,
+    },
+
+    /// A group of imports, like `a::{b, c}`.
+    Group(Vec<UseTree>),
+}
@@ -3,18 +3,34 @@ use errgonomic::parser::input::Span;
 
 /// A value in the AST.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Value {
     /// The type of value it is.
     pub kind: ValueKind,
 
     /// The location where the value was found.
+    #[cfg_attr(feature = "serde", serde(with = "crate::repr::fst::json::span"))]
     pub location: Span,
+
+    /// The exact text of the value, if it's different from how the value is normally written. For
+    /// example, this is `Some("1.50")` for the floating value `1.50`, but `None` for `1.5`.
+    pub raw: Option<String>,
 }
 
 impl Value {
     /// Creates a new `Value` object.
     pub fn new(location: Span, kind: ValueKind) -> Self {
-        Self { kind, location }
+        Self {
+            kind,
+            location,
+            raw: None,
+        }
+    }
+
+    /// Sets the exact text of the value, if it's different from how the value is normally written.
+    pub fn with_raw(mut self, raw: &str) -> Self {
+        self.raw = (self.kind.literal().as_deref() != Some(raw)).then(|| raw.to_string());
+        self
     }
 }
 
@@ -26,11 +42,13 @@ impl FstNode for Value {
 
 /// The kinds of values that can be in the AST.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValueKind {
-    /// An integer value, without any type determination.
-    Integer(i64),
+    /// An integer value. Its type is inferred from how it's used (see `compile::infer`), which is
+    /// also where it's checked to fit into that type. It's never negative, as `-1` is `1` negated.
+    Integer(u64),
 
-    /// A floating-point value, without any type determination.
+    /// A floating-point value. Its type is inferred from how it's used (see `compile::infer`).
     Floating(f64),
 
     /// A boolean value.
@@ -43,5 +61,19 @@ pub enum ValueKind {
     Function(Function),
 }
 
+impl ValueKind {
+    /// Gets how the value is normally written, if it's a literal (a number or a boolean).
+    pub fn literal(&self) -> Option<String> {
+        match self {
+            ValueKind::Integer(i) => Some(i.to_string()),
+            // NOTE: The debug format always has a `.`, unlike the display format.
+            ValueKind::Floating(f) => Some(format!("{:?}", f)),
+            ValueKind::Boolean(true) => Some("True".into()),
+            ValueKind::Boolean(false) => Some("False".into()),
+            ValueKind::Identifier(_) | ValueKind::Function(_) => None,
+        }
+    }
+}
+
 // NOTE: Have to do this b/c of `f64` not implementing `Eq`.
 impl Eq for ValueKind {}
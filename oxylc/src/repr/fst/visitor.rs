@@ -1,8 +1,28 @@
 //! The visitor pattern to walk along the FST, and return some "thing" which we want.
+//!
+//! There are three kinds of visitors here:
+//! - `FstVisitor`, which reads the FST and returns something for every node it visits.
+//! - `FstVisitorMut`, which edits the FST in place.
+//! - `Folder`, which takes the FST apart and builds a new one from it.
+//!
+//! Each method of each visitor has a default, which walks into the children of the node with the
+//! matching `walk_*` (or `walk_*_mut`, or `fold_*`) function. Leaves like identifiers have
+//! nothing to walk into, so their defaults do nothing. A visitor only has to implement the methods
+//! for the nodes it cares about, and can call the walk function itself to keep going deeper.
 
 use super::{
-    block::Block, expression::Expression, function::Function, identifier::Identifier,
-    statement::Statement, value::Value, File,
+    attribute::{Attribute, AttributeArg, AttributeArgKind},
+    block::Block,
+    doc_comment::DocComment,
+    expression::{Expression, ExpressionKind, Operator},
+    function::Function,
+    identifier::Identifier,
+    pattern::{Arm, Match, Pattern, PatternKind},
+    statement::{Statement, StatementKind},
+    type_expr::{Constructor, Signature, TypeExpr, TypeExprKind, TypeParam, TypeParams},
+    use_tree::{UseTree, UseTreeKind},
+    value::{Value, ValueKind},
+    File,
 };
 
 /// The `FstVisitor` allows for a program to visit the Fst and do things on it. Here's how it
@@ -14,12 +34,1251 @@ use super::{
 ///        manner you choose!
 ///
 /// NOTE: The `FstVisitor` should call itself to visit to nodes inside whatever we're visiting.
-pub trait FstVisitor<T> {
-    fn visit_file(&mut self, file: &File) -> T;
-    fn visit_statement(&mut self, statement: &Statement) -> T;
-    fn visit_expression(&mut self, expression: &Expression) -> T;
-    fn visit_value(&mut self, value: &Value) -> T;
-    fn visit_ident(&mut self, ident: &Identifier) -> T;
-    fn visit_function(&mut self, function: &Function) -> T;
-    fn visit_block(&mut self, block: &Block) -> T;
+/// The default methods do this, and return `T::default()`.
+pub trait FstVisitor<T: Default> {
+    fn visit_file(&mut self, file: &File) -> T {
+        walk_file(self, file)
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) -> T {
+        walk_statement(self, statement)
+    }
+
+    /// Visits an expression. By default, this calls the method for the kind of expression it is,
+    /// and returns what that returns.
+    fn visit_expression(&mut self, expression: &Expression) -> T {
+        walk_expression(self, expression)
+    }
+
+    fn visit_parenthesized(&mut self, inner: &Expression) -> T {
+        walk_parenthesized(self, inner)
+    }
+
+    fn visit_tuple(&mut self, items: &[Expression]) -> T {
+        walk_tuple(self, items)
+    }
+
+    fn visit_match(&mut self, m: &Match) -> T {
+        walk_match(self, m)
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) -> T {
+        walk_pattern(self, pattern)
+    }
+
+    fn visit_infix(&mut self, operator: &Operator, lhs: &Expression, rhs: &Expression) -> T {
+        walk_infix(self, operator, lhs, rhs)
+    }
+
+    fn visit_prefix(&mut self, operator: &Operator, rhs: &Expression) -> T {
+        walk_prefix(self, operator, rhs)
+    }
+
+    fn visit_postfix(&mut self, operator: &Operator, lhs: &Expression) -> T {
+        walk_postfix(self, operator, lhs)
+    }
+
+    fn visit_application(&mut self, function: &Expression, arg: &Expression) -> T {
+        walk_application(self, function, arg)
+    }
+
+    fn visit_value(&mut self, value: &Value) -> T {
+        walk_value(self, value)
+    }
+
+    fn visit_ident(&mut self, ident: &Identifier) -> T {
+        walk_ident(self, ident)
+    }
+
+    fn visit_function(&mut self, function: &Function) -> T {
+        walk_function(self, function)
+    }
+
+    fn visit_block(&mut self, block: &Block) -> T {
+        walk_block(self, block)
+    }
+
+    fn visit_attribute(&mut self, attribute: &Attribute) -> T {
+        walk_attribute(self, attribute)
+    }
+
+    fn visit_doc_comment(&mut self, doc_comment: &DocComment) -> T {
+        walk_doc_comment(self, doc_comment)
+    }
+
+    fn visit_use_tree(&mut self, tree: &UseTree) -> T {
+        walk_use_tree(self, tree)
+    }
+
+    fn visit_type_expr(&mut self, ty: &TypeExpr) -> T {
+        walk_type_expr(self, ty)
+    }
+
+    fn visit_type_params(&mut self, params: &TypeParams) -> T {
+        walk_type_params(self, params)
+    }
+}
+
+pub fn walk_file<T: Default, V: FstVisitor<T> + ?Sized>(visitor: &mut V, file: &File) -> T {
+    for statement in &file.statements {
+        visitor.visit_statement(statement);
+    }
+    T::default()
+}
+
+pub fn walk_statement<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    statement: &Statement,
+) -> T {
+    for doc in &statement.docs {
+        visitor.visit_doc_comment(doc);
+    }
+    for attribute in &statement.attributes {
+        visitor.visit_attribute(attribute);
+    }
+
+    match &statement.kind {
+        StatementKind::Let {
+            ident,
+            signature,
+            expression,
+            ..
+        } => {
+            visitor.visit_ident(ident);
+            if let Some(signature) = signature {
+                if let Some(params) = &signature.params {
+                    visitor.visit_type_params(params);
+                }
+                visitor.visit_type_expr(&signature.ty);
+            }
+            visitor.visit_expression(expression);
+        }
+        StatementKind::Mod { ident } => {
+            visitor.visit_ident(ident);
+        }
+        StatementKind::Use { tree } => {
+            visitor.visit_use_tree(tree);
+        }
+        StatementKind::Impl {
+            trait_ident,
+            type_ident,
+            expression,
+        } => {
+            visitor.visit_ident(trait_ident);
+            visitor.visit_ident(type_ident);
+            visitor.visit_expression(expression);
+        }
+        StatementKind::Type {
+            ident,
+            params,
+            constructors,
+        } => {
+            visitor.visit_ident(ident);
+            if let Some(params) = params {
+                visitor.visit_type_params(params);
+            }
+            for constructor in constructors {
+                visitor.visit_ident(&constructor.ident);
+                for field in &constructor.fields {
+                    visitor.visit_type_expr(field);
+                }
+            }
+        }
+    }
+    T::default()
+}
+
+pub fn walk_expression<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    expression: &Expression,
+) -> T {
+    match &expression.kind {
+        ExpressionKind::Parenthesized { inner, .. } => visitor.visit_parenthesized(inner),
+        ExpressionKind::Tuple { items, .. } => visitor.visit_tuple(items),
+        ExpressionKind::Value(value) => visitor.visit_value(value),
+        ExpressionKind::Block(block) => visitor.visit_block(block),
+        ExpressionKind::Match(m) => visitor.visit_match(m),
+        ExpressionKind::Infix { operator, lhs, rhs } => visitor.visit_infix(operator, lhs, rhs),
+        ExpressionKind::Prefix { operator, rhs } => visitor.visit_prefix(operator, rhs),
+        ExpressionKind::Postfix { operator, lhs } => visitor.visit_postfix(operator, lhs),
+        ExpressionKind::Application { function, arg } => visitor.visit_application(function, arg),
+    }
+}
+
+pub fn walk_parenthesized<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    inner: &Expression,
+) -> T {
+    visitor.visit_expression(inner);
+    T::default()
+}
+
+pub fn walk_tuple<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    items: &[Expression],
+) -> T {
+    for item in items {
+        visitor.visit_expression(item);
+    }
+    T::default()
+}
+
+pub fn walk_match<T: Default, V: FstVisitor<T> + ?Sized>(visitor: &mut V, m: &Match) -> T {
+    visitor.visit_expression(&m.scrutinee);
+    for arm in &m.arms {
+        visitor.visit_pattern(&arm.pattern);
+        visitor.visit_expression(&arm.expression);
+    }
+    T::default()
+}
+
+pub fn walk_pattern<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    pattern: &Pattern,
+) -> T {
+    match &pattern.kind {
+        PatternKind::Named { ident, args } => {
+            visitor.visit_ident(ident);
+            for arg in args {
+                visitor.visit_pattern(arg);
+            }
+        }
+        PatternKind::Value(value) => {
+            visitor.visit_value(value);
+        }
+        PatternKind::Parenthesized { inner, .. } => {
+            visitor.visit_pattern(inner);
+        }
+        PatternKind::Tuple { items, .. } => {
+            for item in items {
+                visitor.visit_pattern(item);
+            }
+        }
+    }
+    T::default()
+}
+
+pub fn walk_infix<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    _: &Operator,
+    lhs: &Expression,
+    rhs: &Expression,
+) -> T {
+    visitor.visit_expression(lhs);
+    visitor.visit_expression(rhs);
+    T::default()
+}
+
+pub fn walk_prefix<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    _: &Operator,
+    rhs: &Expression,
+) -> T {
+    visitor.visit_expression(rhs);
+    T::default()
+}
+
+pub fn walk_postfix<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    _: &Operator,
+    lhs: &Expression,
+) -> T {
+    visitor.visit_expression(lhs);
+    T::default()
+}
+
+pub fn walk_application<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    function: &Expression,
+    arg: &Expression,
+) -> T {
+    visitor.visit_expression(function);
+    visitor.visit_expression(arg);
+    T::default()
+}
+
+pub fn walk_value<T: Default, V: FstVisitor<T> + ?Sized>(visitor: &mut V, value: &Value) -> T {
+    match &value.kind {
+        ValueKind::Identifier(ident) => {
+            visitor.visit_ident(ident);
+        }
+        ValueKind::Function(function) => {
+            visitor.visit_function(function);
+        }
+        ValueKind::Integer(_) | ValueKind::Floating(_) | ValueKind::Boolean(_) => {}
+    }
+    T::default()
+}
+
+pub fn walk_ident<T: Default, V: FstVisitor<T> + ?Sized>(_: &mut V, _: &Identifier) -> T {
+    T::default()
+}
+
+pub fn walk_function<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    function: &Function,
+) -> T {
+    for arg in &function.args {
+        visitor.visit_ident(arg);
+    }
+    visitor.visit_expression(&function.expression);
+    T::default()
+}
+
+pub fn walk_block<T: Default, V: FstVisitor<T> + ?Sized>(visitor: &mut V, block: &Block) -> T {
+    for statement in &block.statements {
+        visitor.visit_statement(statement);
+    }
+    visitor.visit_expression(&block.expression);
+    T::default()
+}
+
+pub fn walk_attribute<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    attribute: &Attribute,
+) -> T {
+    visitor.visit_ident(&attribute.name);
+    for arg in &attribute.args {
+        if let AttributeArgKind::Value(value) = &arg.kind {
+            visitor.visit_value(value);
+        }
+    }
+    T::default()
+}
+
+pub fn walk_doc_comment<T: Default, V: FstVisitor<T> + ?Sized>(_: &mut V, _: &DocComment) -> T {
+    T::default()
+}
+
+pub fn walk_use_tree<T: Default, V: FstVisitor<T> + ?Sized>(visitor: &mut V, tree: &UseTree) -> T {
+    for ident in &tree.path {
+        visitor.visit_ident(ident);
+    }
+    match &tree.kind {
+        UseTreeKind::Simple { alias } => {
+            if let Some(alias) = alias {
+                visitor.visit_ident(alias);
+            }
+        }
+        UseTreeKind::Group(trees) => {
+            for tree in trees {
+                visitor.visit_use_tree(tree);
+            }
+        }
+    }
+    T::default()
+}
+
+pub fn walk_type_expr<T: Default, V: FstVisitor<T> + ?Sized>(visitor: &mut V, ty: &TypeExpr) -> T {
+    match &ty.kind {
+        TypeExprKind::Named { ident, args } => {
+            visitor.visit_ident(ident);
+            for arg in args {
+                visitor.visit_type_expr(arg);
+            }
+        }
+        TypeExprKind::Unit => {}
+        TypeExprKind::Parenthesized { inner, .. } => {
+            visitor.visit_type_expr(inner);
+        }
+        TypeExprKind::Tuple { items, .. } => {
+            for item in items {
+                visitor.visit_type_expr(item);
+            }
+        }
+        TypeExprKind::Function { param, result, .. } => {
+            visitor.visit_type_expr(param);
+            visitor.visit_type_expr(result);
+        }
+    }
+    T::default()
+}
+
+pub fn walk_type_params<T: Default, V: FstVisitor<T> + ?Sized>(
+    visitor: &mut V,
+    params: &TypeParams,
+) -> T {
+    for param in &params.params {
+        visitor.visit_ident(&param.ident);
+        for bound in &param.bounds {
+            visitor.visit_ident(bound);
+        }
+    }
+    T::default()
+}
+
+/// Like `FstVisitor`, but edits the nodes it visits in place.
+pub trait FstVisitorMut {
+    fn visit_file_mut(&mut self, file: &mut File) {
+        walk_file_mut(self, file)
+    }
+
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement)
+    }
+
+    /// Visits an expression. By default, this calls the method for the kind of expression it is.
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression)
+    }
+
+    fn visit_parenthesized_mut(&mut self, inner: &mut Expression) {
+        walk_parenthesized_mut(self, inner)
+    }
+
+    fn visit_tuple_mut(&mut self, items: &mut [Expression]) {
+        walk_tuple_mut(self, items)
+    }
+
+    fn visit_match_mut(&mut self, m: &mut Match) {
+        walk_match_mut(self, m)
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        walk_pattern_mut(self, pattern)
+    }
+
+    fn visit_infix_mut(
+        &mut self,
+        operator: &mut Operator,
+        lhs: &mut Expression,
+        rhs: &mut Expression,
+    ) {
+        walk_infix_mut(self, operator, lhs, rhs)
+    }
+
+    fn visit_prefix_mut(&mut self, operator: &mut Operator, rhs: &mut Expression) {
+        walk_prefix_mut(self, operator, rhs)
+    }
+
+    fn visit_postfix_mut(&mut self, operator: &mut Operator, lhs: &mut Expression) {
+        walk_postfix_mut(self, operator, lhs)
+    }
+
+    fn visit_application_mut(&mut self, function: &mut Expression, arg: &mut Expression) {
+        walk_application_mut(self, function, arg)
+    }
+
+    fn visit_value_mut(&mut self, value: &mut Value) {
+        walk_value_mut(self, value)
+    }
+
+    /// Visits an identifier. There's nothing in it to walk into, so this does nothing by default.
+    fn visit_ident_mut(&mut self, _: &mut Identifier) {}
+
+    fn visit_function_mut(&mut self, function: &mut Function) {
+        walk_function_mut(self, function)
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        walk_block_mut(self, block)
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut Attribute) {
+        walk_attribute_mut(self, attribute)
+    }
+
+    /// Visits a doc comment. There's nothing in it to walk into, so this does nothing by default.
+    fn visit_doc_comment_mut(&mut self, _: &mut DocComment) {}
+
+    fn visit_use_tree_mut(&mut self, tree: &mut UseTree) {
+        walk_use_tree_mut(self, tree)
+    }
+
+    fn visit_type_expr_mut(&mut self, ty: &mut TypeExpr) {
+        walk_type_expr_mut(self, ty)
+    }
+
+    fn visit_type_params_mut(&mut self, params: &mut TypeParams) {
+        walk_type_params_mut(self, params)
+    }
+}
+
+pub fn walk_file_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, file: &mut File) {
+    for statement in &mut file.statements {
+        visitor.visit_statement_mut(statement);
+    }
+}
+
+pub fn walk_statement_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    for doc in &mut statement.docs {
+        visitor.visit_doc_comment_mut(doc);
+    }
+    for attribute in &mut statement.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+
+    match &mut statement.kind {
+        StatementKind::Let {
+            ident,
+            signature,
+            expression,
+            ..
+        } => {
+            visitor.visit_ident_mut(ident);
+            if let Some(signature) = signature {
+                if let Some(params) = &mut signature.params {
+                    visitor.visit_type_params_mut(params);
+                }
+                visitor.visit_type_expr_mut(&mut signature.ty);
+            }
+            visitor.visit_expression_mut(expression);
+        }
+        StatementKind::Mod { ident } => visitor.visit_ident_mut(ident),
+        StatementKind::Use { tree } => visitor.visit_use_tree_mut(tree),
+        StatementKind::Impl {
+            trait_ident,
+            type_ident,
+            expression,
+        } => {
+            visitor.visit_ident_mut(trait_ident);
+            visitor.visit_ident_mut(type_ident);
+            visitor.visit_expression_mut(expression);
+        }
+        StatementKind::Type {
+            ident,
+            params,
+            constructors,
+        } => {
+            visitor.visit_ident_mut(ident);
+            if let Some(params) = params {
+                visitor.visit_type_params_mut(params);
+            }
+            for constructor in constructors {
+                visitor.visit_ident_mut(&mut constructor.ident);
+                for field in &mut constructor.fields {
+                    visitor.visit_type_expr_mut(field);
+                }
+            }
+        }
+    }
+}
+
+pub fn walk_expression_mut<V: FstVisitorMut + ?Sized>(
+    visitor: &mut V,
+    expression: &mut Expression,
+) {
+    match &mut expression.kind {
+        ExpressionKind::Parenthesized { inner, .. } => visitor.visit_parenthesized_mut(inner),
+        ExpressionKind::Tuple { items, .. } => visitor.visit_tuple_mut(items),
+        ExpressionKind::Value(value) => visitor.visit_value_mut(value),
+        ExpressionKind::Block(block) => visitor.visit_block_mut(block),
+        ExpressionKind::Match(m) => visitor.visit_match_mut(m),
+        ExpressionKind::Infix { operator, lhs, rhs } => visitor.visit_infix_mut(operator, lhs, rhs),
+        ExpressionKind::Prefix { operator, rhs } => visitor.visit_prefix_mut(operator, rhs),
+        ExpressionKind::Postfix { operator, lhs } => visitor.visit_postfix_mut(operator, lhs),
+        ExpressionKind::Application { function, arg } => {
+            visitor.visit_application_mut(function, arg)
+        }
+    }
+}
+
+pub fn walk_parenthesized_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, inner: &mut Expression) {
+    visitor.visit_expression_mut(inner);
+}
+
+pub fn walk_tuple_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, items: &mut [Expression]) {
+    for item in items {
+        visitor.visit_expression_mut(item);
+    }
+}
+
+pub fn walk_match_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, m: &mut Match) {
+    visitor.visit_expression_mut(&mut m.scrutinee);
+    for arm in &mut m.arms {
+        visitor.visit_pattern_mut(&mut arm.pattern);
+        visitor.visit_expression_mut(&mut arm.expression);
+    }
+}
+
+pub fn walk_pattern_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, pattern: &mut Pattern) {
+    match &mut pattern.kind {
+        PatternKind::Named { ident, args } => {
+            visitor.visit_ident_mut(ident);
+            for arg in args {
+                visitor.visit_pattern_mut(arg);
+            }
+        }
+        PatternKind::Value(value) => visitor.visit_value_mut(value),
+        PatternKind::Parenthesized { inner, .. } => visitor.visit_pattern_mut(inner),
+        PatternKind::Tuple { items, .. } => {
+            for item in items {
+                visitor.visit_pattern_mut(item);
+            }
+        }
+    }
+}
+
+pub fn walk_infix_mut<V: FstVisitorMut + ?Sized>(
+    visitor: &mut V,
+    _: &mut Operator,
+    lhs: &mut Expression,
+    rhs: &mut Expression,
+) {
+    visitor.visit_expression_mut(lhs);
+    visitor.visit_expression_mut(rhs);
+}
+
+pub fn walk_prefix_mut<V: FstVisitorMut + ?Sized>(
+    visitor: &mut V,
+    _: &mut Operator,
+    rhs: &mut Expression,
+) {
+    visitor.visit_expression_mut(rhs);
+}
+
+pub fn walk_postfix_mut<V: FstVisitorMut + ?Sized>(
+    visitor: &mut V,
+    _: &mut Operator,
+    lhs: &mut Expression,
+) {
+    visitor.visit_expression_mut(lhs);
+}
+
+pub fn walk_application_mut<V: FstVisitorMut + ?Sized>(
+    visitor: &mut V,
+    function: &mut Expression,
+    arg: &mut Expression,
+) {
+    visitor.visit_expression_mut(function);
+    visitor.visit_expression_mut(arg);
+}
+
+pub fn walk_value_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, value: &mut Value) {
+    match &mut value.kind {
+        ValueKind::Identifier(ident) => visitor.visit_ident_mut(ident),
+        ValueKind::Function(function) => visitor.visit_function_mut(function),
+        ValueKind::Integer(_) | ValueKind::Floating(_) | ValueKind::Boolean(_) => {}
+    }
+}
+
+pub fn walk_function_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, function: &mut Function) {
+    for arg in &mut function.args {
+        visitor.visit_ident_mut(arg);
+    }
+    visitor.visit_expression_mut(&mut function.expression);
+}
+
+pub fn walk_block_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, block: &mut Block) {
+    for statement in &mut block.statements {
+        visitor.visit_statement_mut(statement);
+    }
+    visitor.visit_expression_mut(&mut block.expression);
+}
+
+pub fn walk_attribute_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, attribute: &mut Attribute) {
+    visitor.visit_ident_mut(&mut attribute.name);
+    for arg in &mut attribute.args {
+        if let AttributeArgKind::Value(value) = &mut arg.kind {
+            visitor.visit_value_mut(value);
+        }
+    }
+}
+
+pub fn walk_use_tree_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, tree: &mut UseTree) {
+    for ident in &mut tree.path {
+        visitor.visit_ident_mut(ident);
+    }
+    match &mut tree.kind {
+        UseTreeKind::Simple { alias } => {
+            if let Some(alias) = alias {
+                visitor.visit_ident_mut(alias);
+            }
+        }
+        UseTreeKind::Group(trees) => {
+            for tree in trees {
+                visitor.visit_use_tree_mut(tree);
+            }
+        }
+    }
+}
+
+pub fn walk_type_expr_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, ty: &mut TypeExpr) {
+    match &mut ty.kind {
+        TypeExprKind::Named { ident, args } => {
+            visitor.visit_ident_mut(ident);
+            for arg in args {
+                visitor.visit_type_expr_mut(arg);
+            }
+        }
+        TypeExprKind::Unit => {}
+        TypeExprKind::Parenthesized { inner, .. } => visitor.visit_type_expr_mut(inner),
+        TypeExprKind::Tuple { items, .. } => {
+            for item in items {
+                visitor.visit_type_expr_mut(item);
+            }
+        }
+        TypeExprKind::Function { param, result, .. } => {
+            visitor.visit_type_expr_mut(param);
+            visitor.visit_type_expr_mut(result);
+        }
+    }
+}
+
+pub fn walk_type_params_mut<V: FstVisitorMut + ?Sized>(visitor: &mut V, params: &mut TypeParams) {
+    for param in &mut params.params {
+        visitor.visit_ident_mut(&mut param.ident);
+        for bound in &mut param.bounds {
+            visitor.visit_ident_mut(bound);
+        }
+    }
+}
+
+/// Takes the FST apart and builds a new one, one node at a time. Unlike `FstVisitorMut`, this can
+/// change the kind of a node, like replacing an expression with its value.
+pub trait Folder {
+    fn fold_file(&mut self, file: File) -> File {
+        fold_file(self, file)
+    }
+
+    fn fold_statement(&mut self, statement: Statement) -> Statement {
+        fold_statement(self, statement)
+    }
+
+    /// Folds an expression. By default, this calls the method for the kind of expression it is,
+    /// and keeps the location of the expression.
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        fold_expression(self, expression)
+    }
+
+    /// Folds the expression inside of parentheses. The parentheses themselves stay.
+    fn fold_parenthesized(&mut self, inner: Expression) -> Expression {
+        fold_parenthesized(self, inner)
+    }
+
+    fn fold_match(&mut self, m: Match) -> Match {
+        fold_match(self, m)
+    }
+
+    fn fold_pattern(&mut self, pattern: Pattern) -> Pattern {
+        fold_pattern(self, pattern)
+    }
+
+    fn fold_infix(
+        &mut self,
+        operator: Operator,
+        lhs: Expression,
+        rhs: Expression,
+    ) -> ExpressionKind {
+        fold_infix(self, operator, lhs, rhs)
+    }
+
+    fn fold_prefix(&mut self, operator: Operator, rhs: Expression) -> ExpressionKind {
+        fold_prefix(self, operator, rhs)
+    }
+
+    fn fold_postfix(&mut self, operator: Operator, lhs: Expression) -> ExpressionKind {
+        fold_postfix(self, operator, lhs)
+    }
+
+    fn fold_application(&mut self, function: Expression, arg: Expression) -> ExpressionKind {
+        fold_application(self, function, arg)
+    }
+
+    fn fold_value(&mut self, value: Value) -> Value {
+        fold_value(self, value)
+    }
+
+    /// Folds an identifier. There's nothing in it to fold, so it's kept as it is by default.
+    fn fold_ident(&mut self, ident: Identifier) -> Identifier {
+        ident
+    }
+
+    fn fold_function(&mut self, function: Function) -> Function {
+        fold_function(self, function)
+    }
+
+    fn fold_block(&mut self, block: Block) -> Block {
+        fold_block(self, block)
+    }
+
+    fn fold_attribute(&mut self, attribute: Attribute) -> Attribute {
+        fold_attribute(self, attribute)
+    }
+
+    /// Folds a doc comment. There's nothing in it to fold, so it's kept as it is by default.
+    fn fold_doc_comment(&mut self, doc_comment: DocComment) -> DocComment {
+        doc_comment
+    }
+
+    fn fold_use_tree(&mut self, tree: UseTree) -> UseTree {
+        fold_use_tree(self, tree)
+    }
+
+    fn fold_type_expr(&mut self, ty: TypeExpr) -> TypeExpr {
+        fold_type_expr(self, ty)
+    }
+
+    fn fold_type_params(&mut self, params: TypeParams) -> TypeParams {
+        fold_type_params(self, params)
+    }
+}
+
+pub fn fold_file<F: Folder + ?Sized>(folder: &mut F, file: File) -> File {
+    File {
+        statements: file
+            .statements
+            .into_iter()
+            .map(|statement| folder.fold_statement(statement))
+            .collect(),
+        ..file
+    }
+}
+
+pub fn fold_statement<F: Folder + ?Sized>(folder: &mut F, statement: Statement) -> Statement {
+    let kind = match statement.kind {
+        StatementKind::Let {
+            is_mutable,
+            ident,
+            signature,
+            expression,
+        } => StatementKind::Let {
+            is_mutable,
+            ident: folder.fold_ident(ident),
+            signature: signature.map(|signature| Signature {
+                params: signature
+                    .params
+                    .map(|params| folder.fold_type_params(params)),
+                ty: folder.fold_type_expr(signature.ty),
+                ..signature
+            }),
+            expression: folder.fold_expression(expression),
+        },
+        StatementKind::Mod { ident } => StatementKind::Mod {
+            ident: folder.fold_ident(ident),
+        },
+        StatementKind::Use { tree } => StatementKind::Use {
+            tree: folder.fold_use_tree(tree),
+        },
+        StatementKind::Impl {
+            trait_ident,
+            type_ident,
+            expression,
+        } => StatementKind::Impl {
+            trait_ident: folder.fold_ident(trait_ident),
+            type_ident: folder.fold_ident(type_ident),
+            expression: folder.fold_expression(expression),
+        },
+        StatementKind::Type {
+            ident,
+            params,
+            constructors,
+        } => StatementKind::Type {
+            ident: folder.fold_ident(ident),
+            params: params.map(|params| folder.fold_type_params(params)),
+            constructors: constructors
+                .into_iter()
+                .map(|constructor| Constructor {
+                    ident: folder.fold_ident(constructor.ident),
+                    fields: constructor
+                        .fields
+                        .into_iter()
+                        .map(|field| folder.fold_type_expr(field))
+                        .collect(),
+                    ..constructor
+                })
+                .collect(),
+        },
+    };
+
+    Statement {
+        kind,
+        docs: statement
+            .docs
+            .into_iter()
+            .map(|doc| folder.fold_doc_comment(doc))
+            .collect(),
+        attributes: statement
+            .attributes
+            .into_iter()
+            .map(|attribute| folder.fold_attribute(attribute))
+            .collect(),
+        ..statement
+    }
+}
+
+pub fn fold_expression<F: Folder + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+    let kind = match expression.kind {
+        ExpressionKind::Parenthesized {
+            lparen_location,
+            rparen_location,
+            inner,
+        } => ExpressionKind::Parenthesized {
+            lparen_location,
+            rparen_location,
+            inner: Box::new(folder.fold_parenthesized(*inner)),
+        },
+        ExpressionKind::Tuple {
+            lparen_location,
+            rparen_location,
+            items,
+            trailing_comma,
+        } => ExpressionKind::Tuple {
+            lparen_location,
+            rparen_location,
+            items: items
+                .into_iter()
+                .map(|item| folder.fold_expression(item))
+                .collect(),
+            trailing_comma,
+        },
+        ExpressionKind::Value(value) => ExpressionKind::Value(folder.fold_value(value)),
+        ExpressionKind::Block(block) => ExpressionKind::Block(folder.fold_block(block)),
+        ExpressionKind::Match(m) => ExpressionKind::Match(folder.fold_match(m)),
+        ExpressionKind::Infix { operator, lhs, rhs } => folder.fold_infix(operator, *lhs, *rhs),
+        ExpressionKind::Prefix { operator, rhs } => folder.fold_prefix(operator, *rhs),
+        ExpressionKind::Postfix { operator, lhs } => folder.fold_postfix(operator, *lhs),
+        ExpressionKind::Application { function, arg } => folder.fold_application(*function, *arg),
+    };
+
+    Expression::new(expression.location, kind)
+}
+
+pub fn fold_parenthesized<F: Folder + ?Sized>(folder: &mut F, inner: Expression) -> Expression {
+    folder.fold_expression(inner)
+}
+
+pub fn fold_match<F: Folder + ?Sized>(folder: &mut F, m: Match) -> Match {
+    Match {
+        scrutinee: Box::new(folder.fold_expression(*m.scrutinee)),
+        arms: m
+            .arms
+            .into_iter()
+            .map(|arm| Arm {
+                pattern: folder.fold_pattern(arm.pattern),
+                expression: folder.fold_expression(arm.expression),
+                ..arm
+            })
+            .collect(),
+        ..m
+    }
+}
+
+pub fn fold_pattern<F: Folder + ?Sized>(folder: &mut F, pattern: Pattern) -> Pattern {
+    let kind = match pattern.kind {
+        PatternKind::Named { ident, args } => PatternKind::Named {
+            ident: folder.fold_ident(ident),
+            args: args
+                .into_iter()
+                .map(|arg| folder.fold_pattern(arg))
+                .collect(),
+        },
+        PatternKind::Value(value) => PatternKind::Value(folder.fold_value(value)),
+        PatternKind::Parenthesized {
+            lparen_location,
+            rparen_location,
+            inner,
+        } => PatternKind::Parenthesized {
+            lparen_location,
+            rparen_location,
+            inner: Box::new(folder.fold_pattern(*inner)),
+        },
+        PatternKind::Tuple {
+            lparen_location,
+            rparen_location,
+            items,
+            trailing_comma,
+        } => PatternKind::Tuple {
+            lparen_location,
+            rparen_location,
+            items: items
+                .into_iter()
+                .map(|item| folder.fold_pattern(item))
+                .collect(),
+            trailing_comma,
+        },
+    };
+
+    Pattern::new(pattern.location, kind)
+}
+
+pub fn fold_infix<F: Folder + ?Sized>(
+    folder: &mut F,
+    operator: Operator,
+    lhs: Expression,
+    rhs: Expression,
+) -> ExpressionKind {
+    ExpressionKind::Infix {
+        operator,
+        lhs: Box::new(folder.fold_expression(lhs)),
+        rhs: Box::new(folder.fold_expression(rhs)),
+    }
+}
+
+pub fn fold_prefix<F: Folder + ?Sized>(
+    folder: &mut F,
+    operator: Operator,
+    rhs: Expression,
+) -> ExpressionKind {
+    ExpressionKind::Prefix {
+        operator,
+        rhs: Box::new(folder.fold_expression(rhs)),
+    }
+}
+
+pub fn fold_postfix<F: Folder + ?Sized>(
+    folder: &mut F,
+    operator: Operator,
+    lhs: Expression,
+) -> ExpressionKind {
+    ExpressionKind::Postfix {
+        operator,
+        lhs: Box::new(folder.fold_expression(lhs)),
+    }
+}
+
+pub fn fold_application<F: Folder + ?Sized>(
+    folder: &mut F,
+    function: Expression,
+    arg: Expression,
+) -> ExpressionKind {
+    ExpressionKind::Application {
+        function: Box::new(folder.fold_expression(function)),
+        arg: Box::new(folder.fold_expression(arg)),
+    }
+}
+
+pub fn fold_value<F: Folder + ?Sized>(folder: &mut F, value: Value) -> Value {
+    let kind = match value.kind {
+        ValueKind::Identifier(ident) => ValueKind::Identifier(folder.fold_ident(ident)),
+        ValueKind::Function(function) => ValueKind::Function(folder.fold_function(function)),
+        kind => kind,
+    };
+
+    Value { kind, ..value }
+}
+
+pub fn fold_function<F: Folder + ?Sized>(folder: &mut F, function: Function) -> Function {
+    Function {
+        args: function
+            .args
+            .into_iter()
+            .map(|arg| folder.fold_ident(arg))
+            .collect(),
+        expression: Box::new(folder.fold_expression(*function.expression)),
+        ..function
+    }
+}
+
+pub fn fold_block<F: Folder + ?Sized>(folder: &mut F, block: Block) -> Block {
+    Block::new(
+        block.location,
+        block
+            .statements
+            .into_iter()
+            .map(|statement| folder.fold_statement(statement))
+            .collect(),
+        Box::new(folder.fold_expression(*block.expression)),
+    )
+}
+
+pub fn fold_attribute<F: Folder + ?Sized>(folder: &mut F, attribute: Attribute) -> Attribute {
+    Attribute {
+        name: folder.fold_ident(attribute.name),
+        args: attribute
+            .args
+            .into_iter()
+            .map(|arg| {
+                let kind = match arg.kind {
+                    AttributeArgKind::Value(value) => {
+                        AttributeArgKind::Value(folder.fold_value(value))
+                    }
+                    AttributeArgKind::String(string) => AttributeArgKind::String(string),
+                };
+                AttributeArg::new(arg.location, kind)
+            })
+            .collect(),
+        ..attribute
+    }
+}
+
+pub fn fold_use_tree<F: Folder + ?Sized>(folder: &mut F, tree: UseTree) -> UseTree {
+    let kind = match tree.kind {
+        UseTreeKind::Simple { alias } => UseTreeKind::Simple {
+            alias: alias.map(|alias| folder.fold_ident(alias)),
+        },
+        UseTreeKind::Group(trees) => UseTreeKind::Group(
+            trees
+                .into_iter()
+                .map(|tree| folder.fold_use_tree(tree))
+                .collect(),
+        ),
+    };
+
+    UseTree {
+        path: tree
+            .path
+            .into_iter()
+            .map(|ident| folder.fold_ident(ident))
+            .collect(),
+        kind,
+        ..tree
+    }
+}
+
+pub fn fold_type_expr<F: Folder + ?Sized>(folder: &mut F, ty: TypeExpr) -> TypeExpr {
+    let kind = match ty.kind {
+        TypeExprKind::Named { ident, args } => TypeExprKind::Named {
+            ident: folder.fold_ident(ident),
+            args: args
+                .into_iter()
+                .map(|arg| folder.fold_type_expr(arg))
+                .collect(),
+        },
+        TypeExprKind::Unit => TypeExprKind::Unit,
+        TypeExprKind::Parenthesized {
+            lparen_location,
+            rparen_location,
+            inner,
+        } => TypeExprKind::Parenthesized {
+            lparen_location,
+            rparen_location,
+            inner: Box::new(folder.fold_type_expr(*inner)),
+        },
+        TypeExprKind::Tuple {
+            lparen_location,
+            rparen_location,
+            items,
+            trailing_comma,
+        } => TypeExprKind::Tuple {
+            lparen_location,
+            rparen_location,
+            items: items
+                .into_iter()
+                .map(|item| folder.fold_type_expr(item))
+                .collect(),
+            trailing_comma,
+        },
+        TypeExprKind::Function {
+            arrow_location,
+            param,
+            result,
+        } => TypeExprKind::Function {
+            arrow_location,
+            param: Box::new(folder.fold_type_expr(*param)),
+            result: Box::new(folder.fold_type_expr(*result)),
+        },
+    };
+
+    TypeExpr::new(ty.location, kind)
+}
+
+pub fn fold_type_params<F: Folder + ?Sized>(folder: &mut F, params: TypeParams) -> TypeParams {
+    TypeParams {
+        params: params
+            .params
+            .into_iter()
+            .map(|param| TypeParam {
+                ident: folder.fold_ident(param.ident),
+                bounds: param
+                    .bounds
+                    .into_iter()
+                    .map(|bound| folder.fold_ident(bound))
+                    .collect(),
+                ..param
+            })
+            .collect(),
+        ..params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compile::{parser::parse, printer::print},
+        repr::fst::expression::OperatorKind,
+    };
+
+    const SOURCE: &str = "#[a(b)]\nlet f = |x| { let y = x * 2\n(y) + -x }\nuse a::{b as c}\n";
+
+    /// Collects the names of every identifier it sees.
+    #[derive(Default)]
+    struct Names(Vec<String>);
+
+    impl FstVisitor<()> for Names {
+        fn visit_ident(&mut self, ident: &Identifier) {
+            self.0.push(ident.name.to_string());
+        }
+    }
+
+    /// Counts the infix operators in expressions.
+    #[derive(Default)]
+    struct Infixes(usize);
+
+    impl FstVisitor<()> for Infixes {
+        fn visit_infix(&mut self, operator: &Operator, lhs: &Expression, rhs: &Expression) {
+            self.0 += 1;
+            walk_infix(self, operator, lhs, rhs)
+        }
+    }
+
+    #[test]
+    fn can_walk_by_default() {
+        let file = parse(SOURCE).unwrap();
+
+        let mut names = Names::default();
+        names.visit_file(&file);
+        assert_eq!(
+            names.0,
+            ["a", "b", "f", "x", "y", "x", "y", "x", "a", "b", "c"]
+        );
+
+        let mut infixes = Infixes::default();
+        infixes.visit_file(&file);
+        assert_eq!(infixes.0, 2);
+    }
+
+    /// Renames every identifier called `x` to `z`, keeping its location.
+    struct Rename;
+
+    impl FstVisitorMut for Rename {
+        fn visit_ident_mut(&mut self, ident: &mut Identifier) {
+            if ident.name.as_str() == "x" {
+                ident.name = "z".into();
+            }
+        }
+    }
+
+    #[test]
+    fn can_edit_in_place() {
+        let mut file = parse(SOURCE).unwrap();
+        Rename.visit_file_mut(&mut file);
+
+        let mut names = Names::default();
+        names.visit_file(&file);
+        assert_eq!(
+            names.0,
+            ["a", "b", "f", "z", "y", "z", "y", "z", "a", "b", "c"]
+        );
+    }
+
+    /// Replaces negated expressions with the expression in them.
+    struct Unnegate;
+
+    impl Folder for Unnegate {
+        fn fold_prefix(&mut self, operator: Operator, rhs: Expression) -> ExpressionKind {
+            match operator.kind() {
+                OperatorKind::Dash => self.fold_expression(rhs).kind,
+                _ => fold_prefix(self, operator, rhs),
+            }
+        }
+    }
+
+    #[test]
+    fn can_fold_tree() {
+        let file = parse(SOURCE).unwrap();
+        let folded = Unnegate.fold_file(file.clone());
+        assert_ne!(folded, file);
+
+        let mut infixes = Infixes::default();
+        infixes.visit_file(&folded);
+        assert_eq!(infixes.0, 2);
+
+        // NOTE: Folding with the defaults gives back exactly the same tree.
+        struct Identity;
+        impl Folder for Identity {}
+        assert_eq!(Identity.fold_file(file.clone()), file);
+        assert_eq!(print(&Identity.fold_file(file)), SOURCE);
+    }
 }
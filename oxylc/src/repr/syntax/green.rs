@@ -0,0 +1,244 @@
+//! The green tree, which is immutable and has no absolute positions. Nodes only know their kind,
+//! their length and their children, so they can be shared (with `Arc`) between trees.
+
+use super::SyntaxKind;
+use crate::compile::lexer::token::TokenKind;
+use std::sync::Arc;
+
+/// A node in the green tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+
+    /// The length of the text of the node, in bytes.
+    len: usize,
+
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    /// Creates a new `GreenNode`.
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let len = children.iter().map(GreenElement::len).sum();
+        Self {
+            kind,
+            len,
+            children,
+        }
+    }
+
+    /// Gets the kind of node it is.
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// Gets the length of the text of the node, in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks if the node has no text.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the children of the node, in order.
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// Gets the text of the node, exactly as it was in the source.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.len);
+        self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, out: &mut String) {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.write_text(out),
+                GreenElement::Token(token) => out.push_str(&token.text),
+            }
+        }
+    }
+
+    /// Creates a copy of the node with the child at `index` replaced. The other children are
+    /// shared, not copied.
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> Self {
+        let mut children = self.children.clone();
+        children[index] = child;
+        Self::new(self.kind, children)
+    }
+
+    /// Creates a copy of the node with the children in `range` replaced.
+    pub fn splice_children(
+        &self,
+        range: std::ops::Range<usize>,
+        children: impl IntoIterator<Item = GreenElement>,
+    ) -> Self {
+        let mut spliced = self.children.clone();
+        spliced.splice(range, children);
+        Self::new(self.kind, spliced)
+    }
+}
+
+/// A token in the green tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: TokenKind,
+    text: String,
+}
+
+impl GreenToken {
+    /// Creates a new `GreenToken`.
+    pub fn new(kind: TokenKind, text: impl Into<String>) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    /// Gets the kind of token it is.
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    /// Gets the text of the token.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Gets the length of the text of the token, in bytes.
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// Checks if the token has no text.
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// Either a node or a token in the green tree.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenElement {
+    /// Gets the kind of the node or token.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind(),
+            GreenElement::Token(token) => SyntaxKind::Token(token.kind()),
+        }
+    }
+
+    /// Gets the length of the text of the node or token, in bytes.
+    pub fn len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.len(),
+            GreenElement::Token(token) => token.len(),
+        }
+    }
+
+    /// Checks if the node or token has no text.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<GreenNode> for GreenElement {
+    fn from(node: GreenNode) -> Self {
+        GreenElement::Node(Arc::new(node))
+    }
+}
+
+impl From<GreenToken> for GreenElement {
+    fn from(token: GreenToken) -> Self {
+        GreenElement::Token(Arc::new(token))
+    }
+}
+
+/// Builds a green tree from the top down, like a parser would: nodes are started, filled with
+/// tokens and other nodes, and then finished.
+#[derive(Clone, Debug, Default)]
+pub struct GreenBuilder {
+    /// The nodes that have been started but not finished, with the children they have so far.
+    stack: Vec<(SyntaxKind, Vec<GreenElement>)>,
+}
+
+impl GreenBuilder {
+    /// Creates a new `GreenBuilder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new node, as a child of the current one.
+    pub fn start_node(&mut self, kind: SyntaxKind) {
+        self.stack.push((kind, vec![]));
+    }
+
+    /// Adds a token to the current node.
+    pub fn token(&mut self, kind: TokenKind, text: &str) {
+        self.push(GreenToken::new(kind, text).into());
+    }
+
+    /// Adds an already built node or token to the current node.
+    pub fn push(&mut self, element: GreenElement) {
+        self.stack
+            .last_mut()
+            .expect("tokens can only be added to a started node")
+            .1
+            .push(element);
+    }
+
+    /// Finishes the current node. If it's the root, it's returned.
+    pub fn finish_node(&mut self) -> Option<GreenNode> {
+        let (kind, children) = self.stack.pop().expect("there's no node to finish");
+        let node = GreenNode::new(kind, children);
+
+        match self.stack.last_mut() {
+            Some((_, siblings)) => {
+                siblings.push(node.into());
+                None
+            }
+            None => Some(node),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_build_green_tree() {
+        let mut builder = GreenBuilder::new();
+        builder.start_node(SyntaxKind::File);
+        builder.start_node(SyntaxKind::ModStatement);
+        builder.token(TokenKind::Keyword, "mod");
+        builder.token(TokenKind::Whitespace, " ");
+        builder.token(TokenKind::Identifier, "a");
+        assert_eq!(builder.finish_node(), None);
+        builder.token(TokenKind::Newline, "\n");
+        let file = builder.finish_node().unwrap();
+
+        assert_eq!(file.kind(), SyntaxKind::File);
+        assert_eq!(file.len(), 6);
+        assert_eq!(file.text(), "mod a\n");
+        assert_eq!(file.children()[0].kind(), SyntaxKind::ModStatement);
+
+        let token = GreenToken::new(TokenKind::Identifier, "bc");
+        let edited = file.children()[0].clone();
+        let GreenElement::Node(statement) = edited else {
+            panic!("expected a node");
+        };
+        let statement = statement.replace_child(2, token.into());
+        let file = file.replace_child(0, statement.into());
+        assert_eq!(file.text(), "mod bc\n");
+        assert_eq!(file.len(), 7);
+    }
+}
@@ -0,0 +1,92 @@
+//! The `syntax` module is responsible for representing the syntax tree of a file in a way that's
+//! cheap to edit. It has the same shape as the FST, and is just as lossless, but is split in two:
+//! - The *green* tree (see `green`) is immutable, and only knows the kind and length of every
+//!   node and the text of every token. It has no absolute positions, so an unchanged subtree can
+//!   be shared between the tree before and after an edit, even if it moved.
+//! - The *red* tree (see `red`) is a view over the green tree, made on demand, which knows the
+//!   parent and the absolute position of every node.
+//!
+//! On top of the red tree, `nodes` has typed wrappers with the same accessors as the FST.
+
+pub mod green;
+pub mod nodes;
+pub mod red;
+
+use crate::compile::lexer::token::TokenKind;
+
+/// The kinds of nodes and tokens in the syntax tree.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    /// A whole file.
+    File,
+
+    /// A `let` statement, including its doc comments, attributes and visibility.
+    LetStatement,
+
+    /// A `mod` statement, including its doc comments, attributes and visibility.
+    ModStatement,
+
+    /// A `use` statement, including its doc comments, attributes and visibility.
+    UseStatement,
+
+    /// An `impl` statement, including its doc comments, attributes and visibility.
+    ImplStatement,
+
+    /// A `type` statement, including its doc comments, attributes and visibility.
+    TypeStatement,
+
+    /// An attribute, like `#[inline]`.
+    Attribute,
+
+    /// An argument to an attribute.
+    AttributeArg,
+
+    /// The tree of a `use` statement, or a tree in a group of one.
+    UseTree,
+
+    /// A parenthesized expression.
+    Parenthesized,
+
+    /// A tuple, like `(a, b)`.
+    Tuple,
+
+    /// A value, like `1`, `x` or a function.
+    Value,
+
+    /// A block.
+    Block,
+
+    /// An infix expression, like `a + b`.
+    Infix,
+
+    /// A prefix expression, like `-a`.
+    Prefix,
+
+    /// A postfix expression.
+    Postfix,
+
+    /// An application of a function to an argument, like `f x`.
+    Application,
+
+    /// A function, like `|x| x`.
+    Function,
+
+    /// A `match` expression.
+    Match,
+
+    /// An arm of a `match`, like `Cons x _ => x`.
+    MatchArm,
+
+    /// A pattern, like `Cons x _`.
+    Pattern,
+
+    /// A token, from the lexer.
+    Token(TokenKind),
+}
+
+impl SyntaxKind {
+    /// Checks if this is the kind of a token.
+    pub fn is_token(&self) -> bool {
+        matches!(self, SyntaxKind::Token(_))
+    }
+}
@@ -0,0 +1,388 @@
+//! Typed wrappers over the red tree, with the same accessors as the FST. Every wrapper is a red
+//! node of a known kind, so they're just as cheap to make. Children that are missing (which can't
+//! happen for trees built from the parser) are `None`.
+
+use super::{
+    red::{SyntaxNode, SyntaxToken},
+    SyntaxKind,
+};
+use crate::compile::lexer::token::TokenKind;
+use errgonomic::parser::input::Span;
+
+/// A red node of a known kind.
+pub trait TypedNode: Sized {
+    /// Wraps a node, if it's the right kind.
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    /// Gets the node being wrapped.
+    fn syntax(&self) -> &SyntaxNode;
+
+    /// Gets the absolute location of the node.
+    fn location(&self) -> Span {
+        self.syntax().location()
+    }
+}
+
+/// Defines a wrapper around the node kinds given.
+macro_rules! typed_node {
+    ($(#[$meta:meta])* $name:ident: $($kind:ident)|+) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct $name(SyntaxNode);
+
+        impl TypedNode for $name {
+            fn cast(node: SyntaxNode) -> Option<Self> {
+                matches!(node.kind(), $(SyntaxKind::$kind)|+).then_some(Self(node))
+            }
+
+            fn syntax(&self) -> &SyntaxNode {
+                &self.0
+            }
+        }
+    };
+}
+
+typed_node!(
+    /// A whole file.
+    File: File
+);
+typed_node!(
+    /// A statement, along with its doc comments, attributes and visibility.
+    Statement: LetStatement | ModStatement | UseStatement | ImplStatement | TypeStatement
+);
+typed_node!(
+    /// An attribute, like `#[inline]`.
+    Attribute: Attribute
+);
+typed_node!(
+    /// An argument to an attribute.
+    AttributeArg: AttributeArg
+);
+typed_node!(
+    /// The tree of a `use` statement.
+    UseTree: UseTree
+);
+typed_node!(
+    /// An expression.
+    Expression: Parenthesized | Tuple | Value | Block | Match | Infix | Prefix | Postfix | Application
+);
+typed_node!(
+    /// A value.
+    Value: Value
+);
+typed_node!(
+    /// A block.
+    Block: Block
+);
+typed_node!(
+    /// A function.
+    Function: Function
+);
+typed_node!(
+    /// A `match` expression.
+    Match: Match
+);
+typed_node!(
+    /// An arm of a `match`.
+    MatchArm: MatchArm
+);
+typed_node!(
+    /// A pattern.
+    Pattern: Pattern
+);
+
+/// Gets the children of `node` of type `T`.
+fn children<'a, T: TypedNode + 'a>(node: &'a SyntaxNode) -> impl Iterator<Item = T> + 'a {
+    node.children().filter_map(T::cast)
+}
+
+/// Gets the first child of `node` of type `T`.
+fn child<T: TypedNode>(node: &SyntaxNode) -> Option<T> {
+    children(node).next()
+}
+
+/// Gets the tokens directly in `node` of the given kind.
+fn tokens(node: &SyntaxNode, kind: TokenKind) -> impl Iterator<Item = SyntaxToken> + '_ {
+    node.child_tokens()
+        .filter(move |token| token.kind() == kind)
+}
+
+impl File {
+    /// Gets the statements in the file.
+    pub fn statements(&self) -> impl Iterator<Item = Statement> + '_ {
+        children(&self.0)
+    }
+}
+
+/// The kind of statement something is, and what's in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StatementKind {
+    Let {
+        ident: Option<SyntaxToken>,
+        expression: Option<Expression>,
+    },
+    Mod {
+        ident: Option<SyntaxToken>,
+    },
+    Use {
+        tree: Option<UseTree>,
+    },
+    Impl {
+        trait_ident: Option<SyntaxToken>,
+        type_ident: Option<SyntaxToken>,
+        expression: Option<Expression>,
+    },
+    Type {
+        ident: Option<SyntaxToken>,
+    },
+}
+
+impl Statement {
+    /// Gets the kind of statement it is.
+    pub fn kind(&self) -> StatementKind {
+        let ident = || tokens(&self.0, TokenKind::Identifier).next();
+
+        match self.0.kind() {
+            SyntaxKind::LetStatement => StatementKind::Let {
+                ident: ident(),
+                expression: child(&self.0),
+            },
+            SyntaxKind::ModStatement => StatementKind::Mod { ident: ident() },
+            SyntaxKind::ImplStatement => {
+                let mut idents = tokens(&self.0, TokenKind::Identifier);
+                StatementKind::Impl {
+                    trait_ident: idents.next(),
+                    type_ident: idents.next(),
+                    expression: child(&self.0),
+                }
+            }
+            SyntaxKind::TypeStatement => StatementKind::Type { ident: ident() },
+            _ => StatementKind::Use {
+                tree: child(&self.0),
+            },
+        }
+    }
+
+    /// Checks if the statement is marked `pub`.
+    pub fn is_public(&self) -> bool {
+        tokens(&self.0, TokenKind::Keyword).any(|token| token.text() == "pub")
+    }
+
+    /// Gets the doc comments attached to the statement.
+    pub fn docs(&self) -> impl Iterator<Item = SyntaxToken> + '_ {
+        tokens(&self.0, TokenKind::DocComment)
+    }
+
+    /// Gets the attributes attached to the statement.
+    pub fn attributes(&self) -> impl Iterator<Item = Attribute> + '_ {
+        children(&self.0)
+    }
+}
+
+impl Attribute {
+    /// Gets the name of the attribute.
+    pub fn name(&self) -> Option<SyntaxToken> {
+        tokens(&self.0, TokenKind::Identifier).next()
+    }
+
+    /// Gets the arguments of the attribute.
+    pub fn args(&self) -> impl Iterator<Item = AttributeArg> + '_ {
+        children(&self.0)
+    }
+}
+
+impl AttributeArg {
+    /// Gets the string the argument is, if it's one.
+    pub fn string(&self) -> Option<SyntaxToken> {
+        tokens(&self.0, TokenKind::String).next()
+    }
+
+    /// Gets the value the argument is, if it's one.
+    pub fn value(&self) -> Option<Value> {
+        child(&self.0)
+    }
+}
+
+impl UseTree {
+    /// Gets the path of the tree, before any alias or group.
+    pub fn path(&self) -> Vec<SyntaxToken> {
+        let mut path = vec![];
+        for token in self.0.child_tokens() {
+            match (token.kind(), token.text()) {
+                (TokenKind::Keyword, "as") => break,
+                (TokenKind::Identifier, _) => path.push(token),
+                _ => {}
+            }
+        }
+        path
+    }
+
+    /// Gets the alias of the tree, if it has one, like `b` in `a as b`.
+    pub fn alias(&self) -> Option<SyntaxToken> {
+        self.0
+            .child_tokens()
+            .skip_while(|token| token.text() != "as")
+            .find(|token| token.kind() == TokenKind::Identifier)
+    }
+
+    /// Gets the trees in the group of the tree, if it has one.
+    pub fn group(&self) -> Option<Vec<UseTree>> {
+        self.0
+            .child_tokens()
+            .any(|token| token.text() == "{")
+            .then(|| children(&self.0).collect())
+    }
+}
+
+/// The kind of expression something is, and what's in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExpressionKind {
+    Parenthesized {
+        inner: Option<Expression>,
+    },
+    Tuple {
+        items: Vec<Expression>,
+    },
+    Value(Value),
+    Block(Block),
+    Match(Match),
+    Infix {
+        operator: Option<SyntaxToken>,
+        lhs: Option<Expression>,
+        rhs: Option<Expression>,
+    },
+    Prefix {
+        operator: Option<SyntaxToken>,
+        rhs: Option<Expression>,
+    },
+    Postfix {
+        operator: Option<SyntaxToken>,
+        lhs: Option<Expression>,
+    },
+    Application {
+        function: Option<Expression>,
+        arg: Option<Expression>,
+    },
+}
+
+impl Expression {
+    /// Gets the kind of expression it is.
+    pub fn kind(&self) -> ExpressionKind {
+        let mut operands = children::<Expression>(&self.0);
+        let operator = || tokens(&self.0, TokenKind::Operator).next();
+
+        match self.0.kind() {
+            SyntaxKind::Parenthesized => ExpressionKind::Parenthesized {
+                inner: operands.next(),
+            },
+            SyntaxKind::Tuple => ExpressionKind::Tuple {
+                items: operands.collect(),
+            },
+            SyntaxKind::Value => ExpressionKind::Value(Value(self.0.clone())),
+            SyntaxKind::Block => ExpressionKind::Block(Block(self.0.clone())),
+            SyntaxKind::Match => ExpressionKind::Match(Match(self.0.clone())),
+            SyntaxKind::Infix => ExpressionKind::Infix {
+                operator: operator(),
+                lhs: operands.next(),
+                rhs: operands.next(),
+            },
+            SyntaxKind::Prefix => ExpressionKind::Prefix {
+                operator: operator(),
+                rhs: operands.next(),
+            },
+            SyntaxKind::Postfix => ExpressionKind::Postfix {
+                operator: operator(),
+                lhs: operands.next(),
+            },
+            _ => ExpressionKind::Application {
+                function: operands.next(),
+                arg: operands.next(),
+            },
+        }
+    }
+}
+
+/// The kind of value something is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueKind {
+    /// A number, like `1` or `1.5`.
+    Number(SyntaxToken),
+
+    /// `True` or `False`.
+    Boolean(SyntaxToken),
+
+    Identifier(SyntaxToken),
+    Function(Function),
+}
+
+impl Value {
+    /// Gets the kind of value it is.
+    pub fn kind(&self) -> Option<ValueKind> {
+        if let Some(function) = child(&self.0) {
+            return Some(ValueKind::Function(function));
+        }
+
+        self.0.child_tokens().find_map(|token| match token.kind() {
+            TokenKind::Number => Some(ValueKind::Number(token)),
+            TokenKind::Keyword => Some(ValueKind::Boolean(token)),
+            TokenKind::Identifier => Some(ValueKind::Identifier(token)),
+            _ => None,
+        })
+    }
+}
+
+impl Block {
+    /// Gets the statements in the block.
+    pub fn statements(&self) -> impl Iterator<Item = Statement> + '_ {
+        children(&self.0)
+    }
+
+    /// Gets the expression the block evaluates to.
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl Function {
+    /// Gets the arguments of the function.
+    pub fn args(&self) -> impl Iterator<Item = SyntaxToken> + '_ {
+        tokens(&self.0, TokenKind::Identifier)
+    }
+
+    /// Gets the body of the function.
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl Match {
+    /// Gets the expression being matched on.
+    pub fn scrutinee(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+
+    /// Gets the arms of the `match`.
+    pub fn arms(&self) -> impl Iterator<Item = MatchArm> + '_ {
+        children(&self.0)
+    }
+}
+
+impl MatchArm {
+    /// Gets the pattern of the arm.
+    pub fn pattern(&self) -> Option<Pattern> {
+        child(&self.0)
+    }
+
+    /// Gets the expression the arm evaluates to.
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl Pattern {
+    /// Gets the patterns directly inside of the pattern, like the arguments of a constructor or the
+    /// items of a tuple.
+    pub fn patterns(&self) -> impl Iterator<Item = Pattern> + '_ {
+        children(&self.0)
+    }
+}
@@ -0,0 +1,300 @@
+//! The red tree, which is a view over the green tree with parents and absolute positions. Red
+//! nodes are made on demand while walking the tree, and are cheap to make: they only point at
+//! their green node and their parent.
+
+use super::{
+    green::{GreenElement, GreenNode, GreenToken},
+    SyntaxKind,
+};
+use crate::compile::lexer::token::TokenKind;
+use errgonomic::parser::input::Span;
+use std::{fmt, rc::Rc, sync::Arc};
+
+/// A node in the red tree.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+    green: Arc<GreenNode>,
+
+    /// The parent of the node, and the index of the node in it.
+    parent: Option<(SyntaxNode, usize)>,
+
+    /// The absolute offset of the start of the node.
+    offset: usize,
+}
+
+impl SyntaxNode {
+    /// Creates the root of a red tree over a green tree.
+    pub fn new_root(green: Arc<GreenNode>) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            parent: None,
+            offset: 0,
+        }))
+    }
+
+    /// Gets the kind of node it is.
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+
+    /// Gets the green node this is a view of.
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.0.green
+    }
+
+    /// Gets the absolute location of the node.
+    pub fn location(&self) -> Span {
+        (self.0.offset..self.0.offset + self.0.green.len()).into()
+    }
+
+    /// Gets the text of the node, exactly as it was in the source.
+    pub fn text(&self) -> String {
+        self.0.green.text()
+    }
+
+    /// Gets the parent of the node, if it isn't the root.
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.as_ref().map(|(parent, _)| parent.clone())
+    }
+
+    /// Gets the index of the node in its parent's children (including tokens).
+    pub fn index(&self) -> usize {
+        self.0.parent.as_ref().map_or(0, |(_, index)| *index)
+    }
+
+    /// Gets the node and all of its ancestors, innermost first.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        std::iter::successors(Some(self.clone()), SyntaxNode::parent)
+    }
+
+    /// Gets the root of the tree the node is in.
+    pub fn root(&self) -> SyntaxNode {
+        self.ancestors().last().expect("there's always a root")
+    }
+
+    /// Gets the children of the node, both nodes and tokens, in order.
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.0.offset;
+        self.0
+            .green
+            .children()
+            .iter()
+            .enumerate()
+            .map(move |(index, child)| {
+                let start = offset;
+                offset += child.len();
+
+                match child {
+                    GreenElement::Node(green) => {
+                        SyntaxElement::Node(SyntaxNode(Rc::new(NodeData {
+                            green: green.clone(),
+                            parent: Some((self.clone(), index)),
+                            offset: start,
+                        })))
+                    }
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        green: green.clone(),
+                        parent: self.clone(),
+                        index,
+                        offset: start,
+                    }),
+                }
+            })
+    }
+
+    /// Gets the children of the node which are nodes, in order.
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children_with_tokens()
+            .filter_map(|child| child.into_node())
+    }
+
+    /// Gets the children of the node which are tokens, in order.
+    pub fn child_tokens(&self) -> impl Iterator<Item = SyntaxToken> + '_ {
+        self.children_with_tokens()
+            .filter_map(|child| child.into_token())
+    }
+
+    /// Gets the node and all of the nodes inside of it, in order (parents before children).
+    pub fn descendants(&self) -> Vec<SyntaxNode> {
+        let mut descendants = vec![self.clone()];
+        for child in self.children() {
+            descendants.extend(child.descendants());
+        }
+        descendants
+    }
+
+    /// Gets every token inside of the node, in order.
+    pub fn tokens(&self) -> Vec<SyntaxToken> {
+        let mut tokens = vec![];
+        for child in self.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    /// Gets the innermost node which covers all of `range`, which must be in this node.
+    pub fn covering_node(&self, range: std::ops::Range<usize>) -> SyntaxNode {
+        let inner = self.children().find(|child| {
+            let location = child.location();
+            location.start() <= range.start && range.end <= location.end()
+        });
+
+        match inner {
+            Some(child) => child.covering_node(range),
+            None => self.clone(),
+        }
+    }
+
+    /// Gets the token that contains `offset`. At the boundary between two tokens, the one after
+    /// it is picked.
+    pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+        for child in self.children_with_tokens() {
+            let location = child.location();
+            if location.start() <= offset && offset < location.end() {
+                return match child {
+                    SyntaxElement::Node(node) => node.token_at_offset(offset),
+                    SyntaxElement::Token(token) => Some(token),
+                };
+            }
+        }
+        None
+    }
+
+    /// Replaces this node with `green`, returning the green tree of the new root. Only the nodes
+    /// from this one up to the root are copied, as everything else is shared.
+    pub fn replace_with(&self, green: GreenNode) -> Arc<GreenNode> {
+        match &self.0.parent {
+            Some((parent, index)) => {
+                let replaced = parent.green().replace_child(*index, green.into());
+                parent.replace_with(replaced)
+            }
+            None => Arc::new(green),
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.location();
+        write!(
+            f,
+            "{:?}@{}..{}",
+            self.kind(),
+            location.start(),
+            location.end()
+        )
+    }
+}
+
+/// A token in the red tree.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    green: Arc<GreenToken>,
+    parent: SyntaxNode,
+
+    /// The index of the token in its parent's children.
+    index: usize,
+
+    /// The absolute offset of the start of the token.
+    offset: usize,
+}
+
+impl SyntaxToken {
+    /// Gets the kind of token it is.
+    pub fn kind(&self) -> TokenKind {
+        self.green.kind()
+    }
+
+    /// Gets the green token this is a view of.
+    pub fn green(&self) -> &Arc<GreenToken> {
+        &self.green
+    }
+
+    /// Gets the text of the token.
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    /// Gets the absolute location of the token.
+    pub fn location(&self) -> Span {
+        (self.offset..self.offset + self.green.len()).into()
+    }
+
+    /// Gets the node the token is in.
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+
+    /// Gets the index of the token in its parent's children.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.location();
+        write!(
+            f,
+            "{:?}@{}..{} {:?}",
+            self.kind(),
+            location.start(),
+            location.end(),
+            self.text()
+        )
+    }
+}
+
+/// Either a node or a token in the red tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    /// Gets the kind of the node or token.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => SyntaxKind::Token(token.kind()),
+        }
+    }
+
+    /// Gets the absolute location of the node or token.
+    pub fn location(&self) -> Span {
+        match self {
+            SyntaxElement::Node(node) => node.location(),
+            SyntaxElement::Token(token) => token.location(),
+        }
+    }
+
+    /// Gets the node, if this is one.
+    pub fn into_node(self) -> Option<SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    /// Gets the token, if this is one.
+    pub fn into_token(self) -> Option<SyntaxToken> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Runs the `oxylc` and `oxylfmt` binaries, and checks their exit codes and output.
+
+use assert_cmd::Command;
+use predicates::str::contains;
+use std::{fs, path::Path};
+use tempfile::TempDir;
+
+fn oxylc() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_oxylc"))
+}
+
+fn oxylfmt() -> Command {
+    Command::new(env!("CARGO_BIN_EXE_oxylfmt"))
+}
+
+/// Makes a directory with `files` in it, as pairs of paths and contents.
+fn files(files: &[(&str, &str)]) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    for (path, contents) in files {
+        let path = dir.path().join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+/// Makes a package called `app` with `main` as its root file.
+fn package(main: &str) -> TempDir {
+    files(&[
+        (
+            "Oxyl.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        ),
+        ("src/main.oxyl", main),
+    ])
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn can_check_files() {
+    let dir = files(&[("good.oxyl", "let x = 1\n"), ("bad.oxyl", "let x = y\n")]);
+
+    oxylc()
+        .arg("check")
+        .arg(dir.path().join("good.oxyl"))
+        .assert()
+        .code(0);
+    oxylc()
+        .arg("check")
+        .arg(dir.path().join("bad.oxyl"))
+        .assert()
+        .code(1)
+        .stderr(contains("error: aborting due to 1 previous error"));
+    oxylc()
+        .arg("check")
+        .arg(dir.path().join("missing.oxyl"))
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn can_check_packages() {
+    let dir = package("let x = 1\n");
+    oxylc().arg("build").arg(dir.path()).assert().code(0);
+    oxylc()
+        .arg("check")
+        .current_dir(dir.path())
+        .assert()
+        .code(0);
+
+    let dir = package("let x = True + 1\n");
+    oxylc().arg("build").arg(dir.path()).assert().code(1);
+}
+
+#[test]
+fn cant_check_types_across_modules() {
+    // NOTE: Each module is type checked on its own for now (see `oxylc check --help`), so a name
+    // used from another module can be used as any type.
+    let dir = files(&[
+        (
+            "Oxyl.toml",
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n",
+        ),
+        (
+            "src/main.oxyl",
+            "mod a\nuse a::x\nlet y: Bool = x\nlet z: Bool = a::x\n",
+        ),
+        ("src/a.oxyl", "pub let x = 1\n"),
+    ]);
+    oxylc().arg("check").arg(dir.path()).assert().code(0);
+    oxylc()
+        .args(["check", "--help"])
+        .assert()
+        .code(0)
+        .stdout(contains("isn't type checked yet"));
+
+    let dir = package("let x = 1\nlet y: Bool = x\n");
+    oxylc().arg("check").arg(dir.path()).assert().code(1);
+}
+
+#[test]
+fn cant_use_unsupported_commands() {
+    let dir = package("let x = 1\n");
+    oxylc()
+        .arg("run")
+        .arg(dir.path())
+        .assert()
+        .code(2)
+        .stderr(contains("there is no backend"));
+    oxylc()
+        .args(["dump", "--emit", "ir"])
+        .arg(dir.path().join("src/main.oxyl"))
+        .assert()
+        .code(2);
+    oxylc().arg("frobnicate").assert().code(2);
+}
+
+#[test]
+fn can_dump_files() {
+    let dir = files(&[("main.oxyl", "let x = 1\n"), ("bad.oxyl", "let = 1\n")]);
+
+    oxylc()
+        .args(["dump", "--emit", "types"])
+        .arg(dir.path().join("main.oxyl"))
+        .assert()
+        .code(0)
+        .stdout(contains("I64"));
+    oxylc()
+        .args(["dump", "--emit", "fst"])
+        .arg(dir.path().join("bad.oxyl"))
+        .assert()
+        .code(1)
+        .stderr(contains("Couldn't parse"));
+}
+
+#[test]
+fn can_format_files() {
+    let dir = files(&[("main.oxyl", "let x=1\n")]);
+    let path = dir.path().join("main.oxyl");
+
+    oxylc()
+        .args(["fmt", "--check"])
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stderr(contains("isn't formatted"));
+    assert_eq!(read(&path), "let x=1\n");
+
+    oxylc().arg("fmt").arg(&path).assert().code(0);
+    assert_eq!(read(&path), "let x = 1\n");
+    oxylc().args(["fmt", "--check"]).arg(&path).assert().code(0);
+}
+
+#[test]
+fn can_format_standard_input() {
+    oxylfmt()
+        .write_stdin("let x=1\n")
+        .assert()
+        .code(0)
+        .stdout("let x = 1\n");
+    oxylfmt()
+        .arg("--check")
+        .write_stdin("let x=1\n")
+        .assert()
+        .code(1)
+        .stderr(contains("Standard input isn't formatted"));
+    oxylfmt()
+        .arg("--check")
+        .write_stdin("let x = 1\n")
+        .assert()
+        .code(0);
+    oxylfmt().write_stdin("let = 1\n").assert().code(1);
+}
+
+#[test]
+fn can_format_ranges() {
+    let dir = files(&[("a.oxyl", "let a=1\nlet b=2\n"), ("b.oxyl", "let c=3\n")]);
+    let a = dir.path().join("a.oxyl");
+    let b = dir.path().join("b.oxyl");
+
+    oxylfmt()
+        .args(["--check", "--range", "9..10"])
+        .arg(&a)
+        .assert()
+        .code(1);
+    oxylfmt()
+        .args(["--range", "9..10"])
+        .arg(&a)
+        .assert()
+        .code(0);
+    assert_eq!(read(&a), "let a=1\nlet b = 2\n");
+
+    oxylfmt()
+        .args(["--range", "0..1"])
+        .write_stdin("let a=1\nlet b=2\n")
+        .assert()
+        .code(0)
+        .stdout("let a = 1\nlet b=2\n");
+    oxylfmt()
+        .args(["--range", "0..1"])
+        .args([&a, &b])
+        .assert()
+        .code(2);
+    oxylfmt()
+        .args(["--range", "ten"])
+        .write_stdin("")
+        .assert()
+        .code(2);
+    oxylfmt()
+        .args(["--range", "5..6"])
+        .write_stdin("let é = 1\n")
+        .assert()
+        .code(1);
+}
@@ -0,0 +1,119 @@
+//! Renders modules into static HTML pages.
+
+use crate::{item::Module, link::cross_link};
+use pulldown_cmark::{html::push_html, Parser};
+use std::path::PathBuf;
+
+/// Renders the modules into HTML pages, as pairs of (relative) paths and contents. There is an
+/// `index.html` listing every module, and a page per module.
+pub fn render(modules: &[Module]) -> Vec<(PathBuf, String)> {
+    let mut files = vec![];
+    let mut index = String::from("<h1>Modules</h1>\n<ul>\n");
+
+    for module in modules {
+        index.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a></li>\n",
+            escape(&module.name),
+            escape(&module.name)
+        ));
+        files.push((
+            PathBuf::from(format!("{}.html", module.name)),
+            page(&format!("Module {}", module.name), &render_module(module)),
+        ));
+    }
+
+    index.push_str("</ul>\n");
+    files.push((PathBuf::from("index.html"), page("Modules", &index)));
+    files
+}
+
+/// Renders the body of a single module's page.
+fn render_module(module: &Module) -> String {
+    let names = module
+        .items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect::<Vec<_>>();
+    let mut body = format!("<h1>Module <code>{}</code></h1>\n", escape(&module.name));
+
+    for item in &module.items {
+        body.push_str(&format!(
+            "<section id=\"{}\">\n<h2><code>{}</code></h2>\n<pre><code>{}</code></pre>\n",
+            escape(&item.name),
+            escape(&item.name),
+            escape(&item.signature)
+        ));
+
+        if let Some(reason) = &item.deprecated {
+            body.push_str("<p class=\"deprecated\"><strong>Deprecated:</strong> ");
+            body.push_str(&escape(reason));
+            body.push_str("</p>\n");
+        }
+
+        let docs = cross_link(&item.docs, &names, |name| format!("#{}", name));
+        push_html(&mut body, Parser::new(&docs));
+        body.push_str("</section>\n");
+    }
+
+    body
+}
+
+/// Wraps a body in a full HTML page.
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        body
+    )
+}
+
+/// Escapes text so it can be put inside HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Item;
+
+    #[test]
+    fn can_render_module() {
+        let module = Module {
+            name: "math".into(),
+            items: vec![Item {
+                name: "double".into(),
+                signature: "let double = |y|".into(),
+                docs: "Doubles, see [`double`].".into(),
+                deprecated: None,
+            }],
+        };
+
+        assert_eq!(
+            render_module(&module),
+            "<h1>Module <code>math</code></h1>\n<section id=\"double\">\n<h2><code>double</code></h2>\n<pre><code>let double = |y|</code></pre>\n<p>Doubles, see <a href=\"#double\"><code>double</code></a>.</p>\n</section>\n"
+        );
+    }
+
+    #[test]
+    fn can_escape_names() {
+        let module = Module {
+            name: "a\"<b>".into(),
+            items: vec![Item {
+                name: "x&y".into(),
+                signature: "let x&y".into(),
+                docs: String::new(),
+                deprecated: None,
+            }],
+        };
+
+        let files = render(std::slice::from_ref(&module));
+        assert!(files[1]
+            .1
+            .contains("<li><a href=\"a&quot;&lt;b&gt;.html\">a&quot;&lt;b&gt;</a></li>"));
+        assert!(render_module(&module).contains("<section id=\"x&amp;y\">"));
+    }
+}
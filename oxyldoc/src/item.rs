@@ -0,0 +1,355 @@
+//! Collects the documentable items out of an Oxyl file.
+
+use oxylc::repr::fst::{
+    attribute::{Attribute, AttributeArgKind},
+    block::Block,
+    doc_comment::{DocComment, DocCommentKind},
+    expression::{Expression, ExpressionKind},
+    function::Function,
+    identifier::Identifier,
+    statement::{Statement, StatementKind},
+    type_expr::{TypeExpr, TypeExprKind, TypeParams},
+    value::{Value, ValueKind},
+    visitor::FstVisitor,
+    File,
+};
+
+/// A documented module, which is a single Oxyl file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Module {
+    /// The name of the module.
+    pub name: String,
+
+    /// The items in the module, in the order they were declared.
+    pub items: Vec<Item>,
+}
+
+/// A single documentable item, which is a top-level `let`, `mod` or `type`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    /// The name of the item.
+    pub name: String,
+
+    /// The signature of the item, e.g. `pub let f = |x, y|`.
+    pub signature: String,
+
+    /// The (Markdown) documentation of the item.
+    pub docs: String,
+
+    /// The reason the item is deprecated, if it is. An empty string means it is deprecated, but
+    /// without a reason.
+    pub deprecated: Option<String>,
+}
+
+impl Module {
+    /// Collects all the items in the given `File` into a `Module`. Private items are only collected
+    /// if `document_private` is set.
+    pub fn collect(name: String, file: &File, document_private: bool) -> Self {
+        let mut collector = ItemCollector {
+            items: vec![],
+            document_private,
+        };
+        collector.visit_file(file);
+        Self {
+            name,
+            items: collector.items,
+        }
+    }
+}
+
+/// Walks the FST and collects the items at the top-level of a file. Note that we don't walk into
+/// expressions, as anything declared in there isn't visible outside of it.
+struct ItemCollector {
+    items: Vec<Item>,
+    document_private: bool,
+}
+
+impl FstVisitor<()> for ItemCollector {
+    fn visit_statement(&mut self, statement: &Statement) {
+        if !statement.visibility.is_public() && !self.document_private {
+            return;
+        }
+
+        let visibility = match statement.visibility.is_public() {
+            true => "pub ",
+            false => "",
+        };
+        let (ident, signature) = match &statement.kind {
+            StatementKind::Let {
+                ident,
+                signature,
+                expression,
+                ..
+            } => {
+                let signature = match signature {
+                    Some(signature) => format!(
+                        "{}: {}",
+                        signature
+                            .params
+                            .as_ref()
+                            .map(|params| SignatureRenderer.visit_type_params(params))
+                            .unwrap_or_default(),
+                        SignatureRenderer.visit_type_expr(&signature.ty)
+                    ),
+                    None => String::new(),
+                };
+                (
+                    ident,
+                    format!(
+                        "{}let {}{}{}",
+                        visibility,
+                        ident.name,
+                        signature,
+                        SignatureRenderer.visit_expression(expression)
+                    ),
+                )
+            }
+            StatementKind::Mod { ident } => (ident, format!("{}mod {}", visibility, ident.name)),
+            StatementKind::Type {
+                ident,
+                params,
+                constructors,
+            } => {
+                let constructors = constructors
+                    .iter()
+                    .map(|constructor| {
+                        let fields = constructor
+                            .fields
+                            .iter()
+                            .map(|field| format!(" {}", SignatureRenderer.visit_type_expr(field)));
+                        format!("{}{}", constructor.ident.name, fields.collect::<String>())
+                    })
+                    .collect::<Vec<_>>();
+                let params = params
+                    .as_ref()
+                    .map(|params| SignatureRenderer.visit_type_params(params))
+                    .unwrap_or_default();
+                (
+                    ident,
+                    format!(
+                        "{}type {}{} = {}",
+                        visibility,
+                        ident.name,
+                        params,
+                        constructors.join(" | ")
+                    ),
+                )
+            }
+            // NOTE: Imports and implementations aren't items of their own.
+            StatementKind::Use { .. } | StatementKind::Impl { .. } => return,
+        };
+        let deprecated = statement
+            .attributes
+            .iter()
+            .find(|attribute| attribute.name.name.as_str() == "deprecated")
+            .map(deprecation_reason);
+
+        self.items.push(Item {
+            name: ident.name.to_string(),
+            signature,
+            docs: docs_to_markdown(&statement.docs),
+            deprecated,
+        });
+    }
+}
+
+/// Renders the part of the signature after the name of an item. Functions show their arguments,
+/// but everything else is hidden, as the value itself is an implementation detail.
+struct SignatureRenderer;
+
+impl FstVisitor<String> for SignatureRenderer {
+    fn visit_file(&mut self, _: &File) -> String {
+        String::new()
+    }
+
+    fn visit_statement(&mut self, _: &Statement) -> String {
+        String::new()
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) -> String {
+        match &expression.kind {
+            ExpressionKind::Value(value) => self.visit_value(value),
+            _ => String::new(),
+        }
+    }
+
+    fn visit_value(&mut self, value: &Value) -> String {
+        match &value.kind {
+            ValueKind::Function(function) => self.visit_function(function),
+            _ => String::new(),
+        }
+    }
+
+    fn visit_ident(&mut self, ident: &Identifier) -> String {
+        ident.name.to_string()
+    }
+
+    fn visit_function(&mut self, function: &Function) -> String {
+        let args = function
+            .args
+            .iter()
+            .map(|arg| self.visit_ident(arg))
+            .collect::<Vec<_>>();
+        format!(" = |{}|", args.join(", "))
+    }
+
+    fn visit_type_expr(&mut self, ty: &TypeExpr) -> String {
+        match &ty.kind {
+            TypeExprKind::Named { ident, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| format!(" {}", self.visit_type_expr(arg)));
+                format!("{}{}", ident.name, args.collect::<String>())
+            }
+            TypeExprKind::Unit => "()".into(),
+            TypeExprKind::Parenthesized { inner, .. } => {
+                format!("({})", self.visit_type_expr(inner))
+            }
+            TypeExprKind::Tuple { items, .. } => {
+                let items = items.iter().map(|item| self.visit_type_expr(item));
+                format!("({})", items.collect::<Vec<_>>().join(", "))
+            }
+            TypeExprKind::Function { param, result, .. } => format!(
+                "{} -> {}",
+                self.visit_type_expr(param),
+                self.visit_type_expr(result)
+            ),
+        }
+    }
+
+    fn visit_type_params(&mut self, params: &TypeParams) -> String {
+        let params = params
+            .params
+            .iter()
+            .map(|param| {
+                let bounds = param
+                    .bounds
+                    .iter()
+                    .map(|bound| bound.name.as_str())
+                    .collect::<Vec<_>>();
+                match bounds.is_empty() {
+                    true => param.ident.name.to_string(),
+                    false => format!("{}: {}", param.ident.name, bounds.join(" + ")),
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", params.join(", "))
+    }
+
+    fn visit_block(&mut self, _: &Block) -> String {
+        String::new()
+    }
+
+    fn visit_attribute(&mut self, _: &Attribute) -> String {
+        String::new()
+    }
+
+    fn visit_doc_comment(&mut self, _: &DocComment) -> String {
+        String::new()
+    }
+}
+
+/// Gets the reason out of a `#[deprecated("...")]` attribute.
+fn deprecation_reason(attribute: &Attribute) -> String {
+    attribute
+        .args
+        .iter()
+        .find_map(|arg| match &arg.kind {
+            AttributeArgKind::String(reason) => Some(reason.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// Turns the doc comments of an item into a single Markdown document. Like rustdoc, we strip the
+/// first space after a `///`, and the leading `*`s of each line in a `/** */`.
+fn docs_to_markdown(docs: &[DocComment]) -> String {
+    let mut lines = vec![];
+
+    for doc in docs {
+        match doc.kind {
+            DocCommentKind::Line => {
+                lines.push(doc.content.strip_prefix(' ').unwrap_or(&doc.content));
+            }
+            DocCommentKind::Block => {
+                for line in doc.content.trim_matches('\n').lines() {
+                    let trimmed = line.trim_start();
+                    let line = match trimmed.strip_prefix('*') {
+                        Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                        None => trimmed,
+                    };
+                    lines.push(line.trim_end());
+                }
+            }
+        }
+    }
+
+    lines.join("\n").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use oxylc::compile::parser;
+
+    #[test]
+    fn can_collect_items() {
+        let file = parser::parse(
+            "/// The answer.\npub let x = 42\n/**\n * Doubles `y`.\n */\n#[deprecated(\"use x\")]\nlet double = |y, z| y\n/// Hidden.\nmod hidden\n",
+        )
+        .unwrap();
+        let module = Module::collect("test".into(), &file, false);
+
+        assert_eq!(module.items.len(), 1);
+        assert_eq!(module.items[0].signature, "pub let x");
+
+        let module = Module::collect("test".into(), &file, true);
+
+        assert_eq!(
+            module.items,
+            [
+                Item {
+                    name: "x".into(),
+                    signature: "pub let x".into(),
+                    docs: "The answer.".into(),
+                    deprecated: None,
+                },
+                Item {
+                    name: "double".into(),
+                    signature: "let double = |y, z|".into(),
+                    docs: "Doubles `y`.".into(),
+                    deprecated: Some("use x".into()),
+                },
+                Item {
+                    name: "hidden".into(),
+                    signature: "mod hidden".into(),
+                    docs: "Hidden.".into(),
+                    deprecated: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_collect_types_and_signatures() {
+        let file = parser::parse(
+            "/// A list.\npub type List[a] = Nil | Cons a (List a)\npub let map[a, b: Eq]: (a -> b) -> List a -> List b = |f, xs| xs\n",
+        )
+        .unwrap();
+        let module = Module::collect("test".into(), &file, false);
+
+        let signatures = module
+            .items
+            .iter()
+            .map(|item| item.signature.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signatures,
+            [
+                "pub type List[a] = Nil | Cons a (List a)",
+                "pub let map[a, b: Eq]: (a -> b) -> List a -> List b = |f, xs|",
+            ]
+        );
+        assert_eq!(module.items[0].docs, "A list.");
+    }
+}
@@ -0,0 +1,55 @@
+//! Cross-linking of items inside doc comments.
+
+/// Turns every intra-doc link in some Markdown, like `` [`foo`] `` or `[foo]`, into an actual link
+/// to the item `foo`, as long as `foo` is one of the given `items`. The destination of the link is
+/// given by `href`. Anything else in brackets (including normal Markdown links) is left alone.
+pub fn cross_link(markdown: &str, items: &[&str], href: impl Fn(&str) -> String) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find('[') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+
+        let inner = &rest[1..end];
+        let after = &rest[end + 1..];
+        let name = inner
+            .strip_prefix('`')
+            .and_then(|x| x.strip_suffix('`'))
+            .unwrap_or(inner);
+        let is_link = after.starts_with('(') || after.starts_with('[');
+
+        if !is_link && items.contains(&name) {
+            out.push_str(&format!("[{}]({})", inner, href(name)));
+        } else {
+            out.push_str(&rest[..=end]);
+        }
+
+        rest = after;
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_cross_link() {
+        let linked = cross_link(
+            "See [`foo`], [bar], [baz], [foo](elsewhere) and [x",
+            &["foo", "bar"],
+            |name| format!("#{}", name),
+        );
+        assert_eq!(
+            linked,
+            "See [`foo`](#foo), [bar](#bar), [baz], [foo](elsewhere) and [x"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+mod html;
+mod item;
+mod link;
+mod markdown;
+
+use clap::{Parser, ValueEnum};
+use item::Module;
+use oxylc::compile::parser;
+use std::{fs, path::PathBuf, process::ExitCode};
+
+/// Generates documentation for Oxyl source files.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// The Oxyl files to document. Each file becomes its own module.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// The directory to write the documentation to.
+    #[arg(short, long, default_value = "doc")]
+    out: PathBuf,
+
+    /// The format to write the documentation in.
+    #[arg(short, long, value_enum, default_value_t = Format::Html)]
+    format: Format,
+
+    /// Also document items that aren't `pub`.
+    #[arg(long)]
+    document_private_items: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Static HTML pages.
+    Html,
+
+    /// An mdBook-compatible Markdown tree.
+    Markdown,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum DocError {
+    #[error("Error reading/writing `{0}`: {1}")]
+    Io(PathBuf, std::io::Error),
+
+    #[error("Error parsing `{0}`: {1}")]
+    Parse(PathBuf, String),
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), DocError> {
+    let mut modules = vec![];
+
+    for path in &args.files {
+        let source = fs::read_to_string(path).map_err(|err| DocError::Io(path.clone(), err))?;
+        let file = parser::parse(&source)
+            .map_err(|err| DocError::Parse(path.clone(), format!("{:?}", err)))?;
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        modules.push(Module::collect(name, &file, args.document_private_items));
+    }
+
+    let files = match args.format {
+        Format::Html => html::render(&modules),
+        Format::Markdown => markdown::render(&modules),
+    };
+
+    for (path, contents) in files {
+        let path = args.out.join(path);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| DocError::Io(parent.to_path_buf(), err))?;
+        }
+
+        fs::write(&path, contents).map_err(|err| DocError::Io(path.clone(), err))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Renders modules into an mdBook-compatible Markdown tree, like the one in `spec/`.
+
+use crate::{item::Module, link::cross_link};
+use std::path::PathBuf;
+
+/// Renders the modules into the files of an mdBook, as pairs of (relative) paths and contents.
+pub fn render(modules: &[Module]) -> Vec<(PathBuf, String)> {
+    let mut files = vec![(PathBuf::from("book.toml"), book_toml())];
+    let mut summary = String::from("# Summary\n\n");
+
+    for module in modules {
+        summary.push_str(&format!("- [{}](./{}.md)\n", module.name, module.name));
+        files.push((
+            PathBuf::from("src").join(format!("{}.md", module.name)),
+            render_module(module),
+        ));
+    }
+
+    files.push((PathBuf::from("src").join("SUMMARY.md"), summary));
+    files
+}
+
+/// Renders a single module into a Markdown page.
+fn render_module(module: &Module) -> String {
+    let names = module
+        .items
+        .iter()
+        .map(|item| item.name.as_str())
+        .collect::<Vec<_>>();
+    let mut page = format!("# Module `{}`\n", module.name);
+
+    for item in &module.items {
+        // NOTE: mdBook gives the heading "`foo`" the id `foo`, which is what we link to.
+        page.push_str(&format!("\n## `{}`\n\n", item.name));
+        page.push_str(&format!("```oxyl\n{}\n```\n", item.signature));
+
+        if let Some(reason) = &item.deprecated {
+            page.push_str(&format!("\n> **Deprecated:** {}\n", reason));
+        }
+
+        if !item.docs.is_empty() {
+            page.push('\n');
+            page.push_str(&cross_link(&item.docs, &names, |name| format!("#{}", name)));
+            page.push('\n');
+        }
+    }
+
+    page
+}
+
+fn book_toml() -> String {
+    "[book]\n    src   = \"src\"\n    title = \"Oxyl API Documentation\"\n".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::Item;
+
+    #[test]
+    fn can_render_module() {
+        let module = Module {
+            name: "math".into(),
+            items: vec![Item {
+                name: "double".into(),
+                signature: "let double = |y|".into(),
+                docs: "Doubles, see [`double`].".into(),
+                deprecated: Some("use `x`".into()),
+            }],
+        };
+
+        assert_eq!(
+            render_module(&module),
+            "# Module `math`\n\n## `double`\n\n```oxyl\nlet double = |y|\n```\n\n> **Deprecated:** use `x`\n\nDoubles, see [`double`](#double).\n"
+        );
+    }
+}